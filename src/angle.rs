@@ -0,0 +1,186 @@
+//! Angles that wrap around
+//!
+//! An [`Angle`] holds radians in (−π, π], and its arithmetic wraps back
+//! into that range after every operation, so that phase accumulated over
+//! many steps, as in a phase-locked loop, never drifts away from the
+//! principal range or loses precision as a raw float would.  Differences
+//! are the smallest signed angle from one to the other.
+//!
+//! # Example
+//! ```
+//! use imaginary::Complex;
+//! use imaginary::angle::Angle;
+//! // phases either side of the negative real axis are close
+//! let a = Complex::<f64>::cis(3.0).angle_typed();
+//! let b = Complex::<f64>::cis(-3.0).angle_typed();
+//! assert!(((a - b).radians() - (6.0 - std::f64::consts::TAU)).abs() < 1e-12);
+//! assert!(((b - a).degrees() - 16.225).abs() < 1e-3);
+//! ```
+
+use crate::Complex;
+use core::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+/// An angle in radians, kept in (−π, π]
+#[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+pub struct Angle<T: Copy>(T);
+
+impl<T: Copy> Angle<T> {
+    /// The angle in radians, in (−π, π]
+    pub fn radians(self) -> T {
+        self.0
+    }
+}
+
+macro_rules! impl_angle {
+    ($t: ident) => {
+        impl Angle<$t> {
+            /// The angle `radians`, wrapped into (−π, π]
+            ///
+            /// # Example
+            /// ```
+            /// use imaginary::angle::Angle;
+            #[doc = concat!("use std::", stringify!($t), "::consts::PI;")]
+            #[doc = concat!("assert_eq!(Angle::<", stringify!($t), ">::new(-PI).radians(), PI);")]
+            #[doc = concat!("assert!((Angle::<", stringify!($t), ">::new(7.0).radians() - (7.0 - 2.0 * PI)).abs() < 1e-6);")]
+            /// ```
+            pub fn new(radians: $t) -> Angle<$t> {
+                use core::$t::consts::{PI, TAU};
+                if -PI < radians && radians <= PI {
+                    return Angle(radians);
+                }
+                // the Euclidean remainder, which is not in core
+                let rem = (radians + PI) % TAU;
+                let wrapped = if rem < 0.0 { rem + TAU } else { rem } - PI;
+                Angle(if wrapped <= -PI { PI } else { wrapped })
+            }
+
+            /// The angle `degrees`, wrapped into (−180°, 180°]
+            pub fn from_degrees(degrees: $t) -> Angle<$t> {
+                Angle::<$t>::new(degrees.to_radians())
+            }
+
+            /// The angle in degrees, in (−180°, 180°]
+            pub fn degrees(self) -> $t {
+                self.0.to_degrees()
+            }
+
+            /// The point e<sup>iθ</sup> on the unit circle
+            pub fn cis(self) -> Complex<$t> {
+                Complex::<$t>::cis(self.0)
+            }
+
+            /// Brings a sum or difference of two wrapped angles, in
+            /// (−2π, 2π], back into (−π, π]
+            fn rewrap(radians: $t) -> Angle<$t> {
+                use core::$t::consts::{PI, TAU};
+                if radians > PI {
+                    Angle(radians - TAU)
+                } else if radians <= -PI {
+                    Angle(radians + TAU)
+                } else {
+                    Angle(radians)
+                }
+            }
+        }
+
+        impl From<Angle<$t>> for $t {
+            fn from(angle: Angle<$t>) -> $t {
+                angle.0
+            }
+        }
+
+        impl Add for Angle<$t> {
+            type Output = Angle<$t>;
+            fn add(self, rhs: Angle<$t>) -> Angle<$t> {
+                Angle::<$t>::rewrap(self.0 + rhs.0)
+            }
+        }
+
+        /// The smallest signed angle from `rhs` to `self`
+        impl Sub for Angle<$t> {
+            type Output = Angle<$t>;
+            fn sub(self, rhs: Angle<$t>) -> Angle<$t> {
+                Angle::<$t>::rewrap(self.0 - rhs.0)
+            }
+        }
+
+        impl Neg for Angle<$t> {
+            type Output = Angle<$t>;
+            fn neg(self) -> Angle<$t> {
+                // −π is outside the range
+                Angle::<$t>::rewrap(-self.0)
+            }
+        }
+
+        impl AddAssign for Angle<$t> {
+            fn add_assign(&mut self, rhs: Angle<$t>) {
+                *self = *self + rhs;
+            }
+        }
+
+        impl SubAssign for Angle<$t> {
+            fn sub_assign(&mut self, rhs: Angle<$t>) {
+                *self = *self - rhs;
+            }
+        }
+
+        impl Complex<$t> {
+            /// The phase angle as an [`Angle`], in (−π, π]
+            pub fn angle_typed(self) -> Angle<$t> {
+                Angle::<$t>::new(self.angle())
+            }
+        }
+    }
+}
+impl_angle!(f32);
+impl_angle!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    #[test]
+    fn wrapping() {
+        assert_eq!(Angle::<f64>::new(PI).radians(), PI);
+        assert_eq!(Angle::<f64>::new(-PI).radians(), PI);
+        assert_eq!(Angle::<f64>::new(0.5).radians(), 0.5);
+        for k in -5..=5 {
+            let x = 0.3 + 2.0 * PI * k as f64;
+            assert!((Angle::<f64>::new(x).radians() - 0.3).abs() < 1e-14, "{k}");
+        }
+        assert_eq!(Angle::<f64>::from_degrees(270.0).degrees(), -90.0);
+        assert_eq!(Angle::<f32>::from_degrees(-180.0).degrees(), 180.0);
+        assert_eq!(f64::from(Angle::<f64>::new(FRAC_PI_2)), FRAC_PI_2);
+    }
+
+    #[test]
+    fn arithmetic() {
+        let (a, b) = (Angle::<f64>::new(3.0), Angle::<f64>::new(-3.0));
+        assert!(((a + a).radians() - (6.0 - 2.0 * PI)).abs() < 1e-15);
+        assert!(((a - b).radians() - (6.0 - 2.0 * PI)).abs() < 1e-15);
+        assert!(((b - a).radians() - (2.0 * PI - 6.0)).abs() < 1e-15);
+        assert_eq!(-Angle::<f64>::new(PI), Angle::<f64>::new(PI));
+        assert_eq!((-a).radians(), -3.0);
+        // a phase accumulator stays in range and agrees with the exact phase
+        let step = Angle::<f64>::new(0.1234);
+        let mut phase = Angle::<f64>::default();
+        for _ in 0..100_000 {
+            phase += step;
+            assert!(-PI < phase.radians() && phase.radians() <= PI);
+        }
+        assert!((phase - Angle::<f64>::new(0.1234 * 100_000.0)).radians().abs() < 1e-9);
+        phase -= phase;
+        assert_eq!(phase.radians(), 0.0);
+    }
+
+    #[test]
+    fn complex() {
+        let z = Complex::<f64>::new(-1.0, -1e-3);
+        assert!((z.angle_typed().radians() + PI - 1e-3).abs() < 1e-9);
+        assert_eq!(Complex::<f64>::new(-1.0, -0.0).angle_typed().radians(), PI);
+        let w = Complex::<f64>::new(0.6, 0.8);
+        assert_eq!(w.angle_typed().cis(), Complex::<f64>::cis(w.angle()));
+        assert!((w.angle_typed() - z.angle_typed()).radians() < 0.0);
+    }
+}
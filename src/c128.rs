@@ -0,0 +1,259 @@
+//! Complex numbers with quadruple precision components, with the `f128`
+//! feature on a nightly compiler
+//!
+//! `f128` is [`Float`], so `Complex<f128>` has all of the elementary
+//! functions, computed by the standard library's quadruple precision
+//! functions.  As `f128` has no [`Display`](core::fmt::Display) of its own,
+//! numbers are written through [`display`](Complex::display), which gives
+//! 36 significant digits, enough to tell every `f128` apart, or the
+//! precision of the format.
+//!
+//! # Example
+//! ```
+//! #![feature(f128)]
+//! use imaginary::{Complex, c128};
+//! let z: Complex<f128> = c128::I.exp();
+//! assert_eq!(format!("{:.30}", z.display()), "0.540302305868139717400936607443 + 0.841470984807896506652502321630*i");
+//! assert_eq!(Complex::<f128>::new(2.0, 0.0).sqrt().display().to_string(), "1.41421356237309504880168872420969798");
+//! ```
+
+use crate::float::Float;
+use crate::{Complex, One};
+use core::fmt::{self, Write};
+
+/// The imaginary number, `I` =
+/// <math> <msqrt> <mn> -1 </mn> </msqrt> </math>
+pub const I: Complex<f128> = Complex::new(0.0, 1.0);
+
+impl One for f128 {
+    const ONE: f128 = 1.0;
+}
+
+macro_rules! impl_float {
+    ($($unary: ident),*; $($binary: ident($arg: ident)),*) => {
+        impl Float for f128 {
+            const ZERO: f128 = 0.0;
+            const INFINITY: f128 = f128::INFINITY;
+            const PI: f128 = core::f128::consts::PI;
+            const FRAC_1_SQRT_2: f128 = core::f128::consts::FRAC_1_SQRT_2;
+            const LOG2_E: f128 = core::f128::consts::LOG2_E;
+            const LOG10_E: f128 = core::f128::consts::LOG10_E;
+
+            fn from_f64(x: f64) -> f128 {
+                x as f128
+            }
+            fn copysign(self, sign: f128) -> f128 {
+                <f128>::copysign(self, sign)
+            }
+            fn is_nan(self) -> bool {
+                <f128>::is_nan(self)
+            }
+            fn is_infinite(self) -> bool {
+                <f128>::is_infinite(self)
+            }
+            $(
+                fn $unary(self) -> f128 {
+                    <f128>::$unary(self)
+                }
+            )*
+            $(
+                fn $binary(self, $arg: f128) -> f128 {
+                    <f128>::$binary(self, $arg)
+                }
+            )*
+        }
+    }
+}
+impl_float!(abs, sqrt, cbrt, exp, ln, log2, log10, sin, cos, tan, sinh, cosh, tanh,
+            round, floor, ceil, trunc; powf(n), hypot(other), atan2(x));
+
+/// Significant digits written without a precision, enough that every
+/// `f128` reads back as itself
+const DIGITS: usize = 36;
+
+/// Writes `x` in decimal with `precision` places after the point, or
+/// with [`DIGITS`] significant digits and no trailing zeros, rounded half
+/// to even from the exact value as the standard library rounds floats
+pub(crate) fn write_decimal(w: &mut dyn Write, x: f128, precision: Option<usize>, plus: bool) -> fmt::Result {
+    if x.is_nan() {
+        return w.write_str("NaN");
+    }
+    if x.is_sign_negative() {
+        w.write_char('-')?;
+    } else if plus {
+        w.write_char('+')?;
+    }
+    if x.is_infinite() {
+        return w.write_str("inf");
+    }
+    let (mut digits, mut frac) = exact_digits(x.abs());
+    let keep = precision.unwrap_or_else(|| (DIGITS as isize - (digits.len() as isize - frac as isize)).max(0) as usize);
+    if keep < frac {
+        round_off(&mut digits, frac - keep);
+    } else {
+        digits.resize(digits.len() + keep - frac, 0);
+    }
+    frac = keep;
+    if precision.is_none() {
+        while frac > 0 && digits.last() == Some(&0) {
+            digits.pop();
+            frac -= 1;
+        }
+    }
+    let int_len = digits.len().saturating_sub(frac);
+    if int_len == 0 {
+        w.write_char('0')?;
+    }
+    for &d in &digits[..int_len] {
+        w.write_char(char::from(b'0' + d))?;
+    }
+    if frac > 0 {
+        w.write_char('.')?;
+        for _ in digits.len()..frac {
+            w.write_char('0')?;
+        }
+        for &d in &digits[int_len..] {
+            w.write_char(char::from(b'0' + d))?;
+        }
+    }
+    Ok(())
+}
+
+/// The decimal digits of a finite, non-negative `x`, exactly, and how many
+/// of them are after the point
+///
+/// x = m 2<sup>e</sup> is m 2<sup>e</sup> for e ≥ 0, or
+/// m 5<sup>−e</sup> / 10<sup>−e</sup> otherwise, so it is worked out in
+/// base 10<sup>9</sup> limbs by small multiplications.
+fn exact_digits(x: f128) -> (Vec<u8>, usize) {
+    const BASE: u64 = 1_000_000_000;
+    let bits = x.to_bits();
+    let biased = (bits >> 112) as i32;
+    let fraction = bits & ((1 << 112) - 1);
+    let (mut m, mut e) = if biased == 0 {
+        (fraction, -16494)
+    } else {
+        (fraction | 1 << 112, biased - 16495)
+    };
+    if m == 0 {
+        return (vec![0], 0);
+    }
+    while m & 1 == 0 && e < 0 {
+        m >>= 1;
+        e += 1;
+    }
+    let mut limbs = Vec::new();
+    while m > 0 {
+        limbs.push((m % BASE as u128) as u32);
+        m /= BASE as u128;
+    }
+    let mut mul = |k: u64| {
+        let mut carry = 0;
+        for limb in limbs.iter_mut() {
+            let t = *limb as u64 * k + carry;
+            *limb = (t % BASE) as u32;
+            carry = t / BASE;
+        }
+        while carry > 0 {
+            limbs.push((carry % BASE) as u32);
+            carry /= BASE;
+        }
+    };
+    let (factor, batch, count) = if e >= 0 { (2u64, 29, e as u32) } else { (5, 13, e.unsigned_abs()) };
+    for _ in 0..count / batch {
+        mul(factor.pow(batch));
+    }
+    mul(factor.pow(count % batch));
+    let mut digits = Vec::with_capacity(9 * limbs.len());
+    for (k, &limb) in limbs.iter().rev().enumerate() {
+        let mut chunk = [0; 9];
+        let mut n = limb;
+        for d in chunk.iter_mut().rev() {
+            *d = (n % 10) as u8;
+            n /= 10;
+        }
+        let skip = if k == 0 { chunk.iter().take_while(|&&d| d == 0).count() } else { 0 };
+        digits.extend_from_slice(&chunk[skip..]);
+    }
+    (digits, if e >= 0 { 0 } else { count as usize })
+}
+
+/// Drops the last `drop` digits, rounding half to even
+fn round_off(digits: &mut Vec<u8>, drop: usize) {
+    if drop > digits.len() {
+        // below half of the last place kept
+        digits.clear();
+        return;
+    }
+    let cut = digits.len() - drop;
+    let (first, rest) = (digits[cut], digits[cut + 1..].iter().any(|&d| d != 0));
+    let odd = cut > 0 && digits[cut - 1] % 2 == 1;
+    digits.truncate(cut);
+    if first > 5 || first == 5 && (rest || odd) {
+        let mut k = cut;
+        loop {
+            if k == 0 {
+                digits.insert(0, 1);
+                break;
+            }
+            k -= 1;
+            if digits[k] == 9 {
+                digits[k] = 0;
+            } else {
+                digits[k] += 1;
+                break;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal(x: f128, precision: Option<usize>) -> String {
+        let mut s = String::new();
+        write_decimal(&mut s, x, precision, false).unwrap();
+        s
+    }
+
+    #[test]
+    fn decimals() {
+        assert_eq!(decimal(0.0, None), "0");
+        assert_eq!(decimal(-0.0, Some(2)), "-0.00");
+        assert_eq!(decimal(1.5, None), "1.5");
+        assert_eq!(decimal(0.1, None), "0.100000000000000000000000000000000005");
+        assert_eq!(decimal(1.0 / 3.0, Some(5)), "0.33333");
+        assert_eq!(decimal(2.0 / 3.0, Some(0)), "1");
+        assert_eq!(decimal(0.125, Some(2)), "0.12");
+        assert_eq!(decimal(0.375, Some(2)), "0.38");
+        assert_eq!(decimal(9.96, Some(1)), "10.0");
+        assert_eq!(decimal(1e-40, Some(3)), "0.000");
+        assert_eq!(decimal(1e40, None), "10000000000000000000000000000000000000000");
+        assert_eq!(decimal(-1.0 / 1024.0, None), "-0.0009765625");
+        assert_eq!(decimal(f128::MAX, None).len(), 4933);
+        let min = decimal(f128::from_bits(1), None);
+        assert!(min.starts_with(&format!("0.{}6475", "0".repeat(4965))));
+        assert_eq!(min.len(), 2 + 4965 + DIGITS);
+        assert_eq!(decimal(f128::NAN, None), "NaN");
+        assert_eq!(decimal(f128::NEG_INFINITY, None), "-inf");
+        let mut s = String::new();
+        write_decimal(&mut s, 2.5, Some(1), true).unwrap();
+        assert_eq!(s, "+2.5");
+    }
+
+    #[test]
+    fn quadruple_precision() {
+        let z = Complex::<f128>::new(3.0, 4.0);
+        assert_eq!(z.abs(), 5.0);
+        assert_eq!(I * I, Complex::new(-1.0, 0.0));
+        assert_eq!(format!("{:.3}", z.display()), "3.000 + 4.000*i");
+        assert_eq!(format!("{:.3}", z.display().polar(crate::format::PolarStyle::Angle)), "5.000∠0.927");
+        // more precise than f64
+        let w = Complex::<f128>::new(0.0, 1e-20).exp() - Complex::new(1.0, 0.0);
+        assert!((w.i - 1e-20).abs() < 1e-50);
+        assert!((Complex::<f128>::new(1.0, 1e-10).abs() - 1.0 - 5e-21).abs() < 1e-33);
+        let pi = Complex::<f128>::new(-1.0, 0.0).ln().i;
+        assert_eq!(decimal(pi, None), "3.1415926535897932384626433832795028");
+    }
+}
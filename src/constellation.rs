@@ -0,0 +1,219 @@
+//! Gray coded QAM constellations
+//!
+//! A square M-QAM constellation places M = L² points on an L × L grid.  The
+//! upper half of the bits of a symbol select the in-phase level and the lower
+//! half the quadrature level, each Gray coded so that neighbouring points
+//! differ in a single bit.  Points are scaled to unit average energy.
+//!
+//! # Example
+//! ```
+//! use imaginary::{Complex, constellation::Constellation};
+//! let qam = Constellation::<f64>::qam16();
+//! let symbols = [3, 9, 14, 0];
+//! let mut points = qam.map_symbols(&symbols);
+//! // small errors are corrected by the nearest point decision
+//! points[1] += Complex::new(0.1, -0.05);
+//! assert_eq!(qam.demap_symbols(&points), symbols);
+//! ```
+
+use crate::Complex;
+
+/// A square QAM constellation
+#[derive(Clone, Debug, PartialEq)]
+pub struct Constellation<T: Copy> {
+    /// points indexed by symbol
+    points: Vec<Complex<T>>,
+    /// bits per axis
+    bits: u32,
+    /// spacing between adjacent levels
+    spacing: T
+}
+
+impl<T: Copy> Constellation<T> {
+    /// Points indexed by symbol
+    pub fn points(&self) -> &[Complex<T>] {
+        &self.points
+    }
+
+    /// Number of points
+    pub fn order(&self) -> usize {
+        self.points.len()
+    }
+
+    /// Bits carried by each symbol
+    pub fn bits_per_symbol(&self) -> u32 {
+        2 * self.bits
+    }
+}
+
+/// Gray code of `n`
+fn gray(n: usize) -> usize {
+    n ^ (n >> 1)
+}
+
+/// Inverse of the Gray code
+fn gray_inverse(mut g: usize) -> usize {
+    let mut n = g;
+    while g > 1 {
+        g >>= 1;
+        n ^= g;
+    }
+    n
+}
+
+macro_rules! impl_constellation {
+    ($t: ident) => {
+        impl Constellation<$t> {
+            /// Square QAM constellation with `order` points
+            ///
+            /// # Panics
+            /// Panics if `order` is not a power of 4 of at least 4.
+            pub fn qam(order: usize) -> Constellation<$t> {
+                assert!(order >= 4 && order.is_power_of_two() && order.trailing_zeros() % 2 == 0,
+                    "QAM order must be a power of 4, order = {order}"
+                );
+                let bits = order.trailing_zeros() / 2;
+                let levels = 1usize << bits;
+                // average energy of levels ±1, ±3, … on both axes
+                let energy = 2.0 * (levels * levels - 1) as $t / 3.0;
+                let scale = 1.0 / energy.sqrt();
+                let level = |g: usize| (2.0 * gray_inverse(g) as $t - (levels - 1) as $t) * scale;
+                let points = (0..order)
+                    .map(|symbol| Complex::new(level(symbol >> bits), level(symbol & (levels - 1))))
+                    .collect();
+                Constellation { points, bits, spacing: 2.0 * scale }
+            }
+
+            /// QPSK, the 4 point constellation (±1 ± i) / √2
+            pub fn qpsk() -> Constellation<$t> {
+                Self::qam(4)
+            }
+
+            /// 16-QAM
+            pub fn qam16() -> Constellation<$t> {
+                Self::qam(16)
+            }
+
+            /// 64-QAM
+            pub fn qam64() -> Constellation<$t> {
+                Self::qam(64)
+            }
+
+            /// The point for `symbol`
+            ///
+            /// # Panics
+            /// Panics if `symbol` is not less than the order.
+            pub fn map(&self, symbol: usize) -> Complex<$t> {
+                self.points[symbol]
+            }
+
+            /// The symbol of the point nearest to `z`
+            pub fn demap(&self, z: Complex<$t>) -> usize {
+                let max = (1usize << self.bits) - 1;
+                let offset = max as $t / 2.0;
+                let slice = |x: $t| {
+                    let k = (x / self.spacing + offset).round();
+                    // NaN slices to 0
+                    gray(if k >= max as $t { max } else if k > 0.0 { k as usize } else { 0 })
+                };
+                (slice(z.r) << self.bits) | slice(z.i)
+            }
+
+            /// Maps each symbol to its point
+            pub fn map_symbols(&self, symbols: &[usize]) -> Vec<Complex<$t>> {
+                symbols.iter().map(|&s| self.map(s)).collect()
+            }
+
+            /// Hard decision of the nearest symbol to each point
+            pub fn demap_symbols(&self, points: &[Complex<$t>]) -> Vec<usize> {
+                points.iter().map(|&z| self.demap(z)).collect()
+            }
+
+            /// Maps a bit stream, one bit per byte with the most significant
+            /// bit of each symbol first
+            ///
+            /// # Panics
+            /// Panics if the number of bits is not a multiple of
+            /// [`bits_per_symbol`](Self::bits_per_symbol) or a byte is
+            /// greater than 1.
+            pub fn map_bits(&self, bits: &[u8]) -> Vec<Complex<$t>> {
+                let n = self.bits_per_symbol() as usize;
+                assert_eq!(bits.len() % n, 0, "bit count must be a multiple of {n}");
+                bits.chunks(n).map(|chunk| {
+                    let symbol = chunk.iter().fold(0, |s, &b| {
+                        assert!(b <= 1, "bits must be 0 or 1, found {b}");
+                        (s << 1) | b as usize
+                    });
+                    self.map(symbol)
+                }).collect()
+            }
+
+            /// Hard decision bits of each point, one bit per byte with the
+            /// most significant bit of each symbol first
+            pub fn demap_bits(&self, points: &[Complex<$t>]) -> Vec<u8> {
+                let n = self.bits_per_symbol();
+                points.iter().flat_map(|&z| {
+                    let symbol = self.demap(z);
+                    (0..n).rev().map(move |k| (symbol >> k & 1) as u8)
+                }).collect()
+            }
+        }
+    }
+}
+impl_constellation!(f32);
+impl_constellation!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_energy() {
+        for qam in [Constellation::<f64>::qpsk(), Constellation::<f64>::qam16(), Constellation::<f64>::qam64()] {
+            let energy = qam.points().iter().map(|z| z.abs().powi(2)).sum::<f64>() / qam.order() as f64;
+            assert!((energy - 1.0).abs() < 1e-14);
+        }
+        let s = std::f64::consts::FRAC_1_SQRT_2;
+        let expected = [Complex::new(-s, -s), Complex::new(-s, s), Complex::new(s, -s), Complex::new(s, s)];
+        for (&z, &e) in Constellation::<f64>::qpsk().points().iter().zip(&expected) {
+            assert!((z - e).abs() < 1e-15);
+        }
+    }
+
+    #[test]
+    fn gray_neighbours() {
+        for qam in [Constellation::<f64>::qpsk(), Constellation::<f64>::qam16(), Constellation::<f64>::qam64()] {
+            let min = 2.0 / (2.0 * (qam.order() - 1) as f64 / 3.0).sqrt();
+            for (a, &za) in qam.points().iter().enumerate() {
+                for (b, &zb) in qam.points().iter().enumerate() {
+                    if ((za - zb).abs() - min).abs() < 1e-12 {
+                        assert_eq!((a ^ b).count_ones(), 1, "{a} {b}");
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn demap() {
+        let qam = Constellation::<f64>::qam64();
+        for symbol in 0..64 {
+            let z = qam.map(symbol);
+            assert_eq!(qam.demap(z), symbol);
+            assert_eq!(qam.demap(z + Complex::new(0.06, -0.06)), symbol);
+        }
+        // far outside points saturate to the corners
+        assert_eq!(qam.demap(Complex::new(10.0, 10.0)), qam.demap(qam.map(0) * -1.0));
+        let bits = [1, 0, 1, 1, 0, 0, 0, 1, 1, 1, 1, 0];
+        assert_eq!(qam.demap_bits(&qam.map_bits(&bits)), bits);
+        assert_eq!(Constellation::<f64>::qpsk().bits_per_symbol(), 2);
+    }
+
+    #[test]
+    fn gray_code() {
+        for n in 0..256 {
+            assert_eq!(gray_inverse(gray(n)), n);
+            assert_eq!((gray(n) ^ gray(n + 1)).count_ones(), 1);
+        }
+    }
+}
@@ -0,0 +1,721 @@
+//! Contour integrals of analytic functions
+//!
+//! Numerical inverse Laplace transforms sum samples of the transform along
+//! a contour in the s-plane.  The fixed Talbot method deforms the Bromwich
+//! line into a contour that wraps around the negative real axis, where the
+//! integrand decays quickly, and the Gaver–Stehfest method uses only samples
+//! on the positive real axis.  The inverse Z-transform samples X(z) on a
+//! circle and recovers x<sub>n</sub> with a fast Fourier transform, and
+//! Taylor coefficients come the same way from Cauchy's integral formula.
+//!
+//! Integrals along segments and arcs use adaptive 15 point Gauss–Kronrod
+//! quadrature, bisecting the piece with the largest error estimate, the
+//! difference from the embedded 7 point Gauss rule, until the total error
+//! estimate is small.  The nodes avoid the end points, so integrable end point
+//! singularities are handled by repeated bisection towards them.  A
+//! [`Path`] strings segments, arcs and parameterized curves together into a
+//! contour to integrate along.
+
+use crate::Complex;
+use std::fmt;
+use std::rc::Rc;
+
+/// Nodes of the 15 point Gauss–Kronrod rule on [−1, 1], the odd ones
+/// shared with the 7 point Gauss rule
+const KRONROD_NODES: [f64; 8] = [
+    0.9914553711208126, 0.9491079123427585, 0.8648644233597691, 0.7415311855993945,
+    0.5860872354676911, 0.4058451513773972, 0.20778495500789848, 0.0
+];
+const KRONROD_WEIGHTS: [f64; 8] = [
+    0.022935322010529224, 0.06309209262997856, 0.10479001032225019, 0.14065325971552592,
+    0.1690047266392679, 0.19035057806478542, 0.20443294007529889, 0.20948214108472782
+];
+const GAUSS_WEIGHTS: [f64; 4] = [0.1294849661688697, 0.27970539148927664, 0.3818300505051189, 0.4179591836734694];
+
+/// The value of an integral with an estimate of its absolute error
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Integral<T: Copy> {
+    /// the estimated integral
+    pub value: Complex<T>,
+    /// the estimated absolute error
+    pub error: T
+}
+
+/// A piece of a [`Path`]
+#[derive(Clone)]
+pub enum Piece<T: Copy> {
+    /// the straight segment from `start` to `end`
+    Segment {
+        /// first point
+        start: Complex<T>,
+        /// last point
+        end: Complex<T>
+    },
+    /// the arc center + radius e<sup>iθ</sup> from θ = `theta0` to `theta1`
+    Arc {
+        /// centre of the circle
+        center: Complex<T>,
+        /// radius of the circle
+        radius: T,
+        /// starting angle
+        theta0: T,
+        /// final angle
+        theta1: T
+    },
+    /// the curve z(t) from t = `t0` to `t1`
+    Curve {
+        /// the point at parameter t
+        z: Rc<dyn Fn(T) -> Complex<T>>,
+        /// the derivative of `z`
+        dz: Rc<dyn Fn(T) -> Complex<T>>,
+        /// starting parameter
+        t0: T,
+        /// final parameter
+        t1: T,
+        /// arc length of the curve
+        length: T
+    }
+}
+
+impl<T: Copy + fmt::Debug> fmt::Debug for Piece<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Piece::Segment { start, end } => f.debug_struct("Segment")
+                .field("start", start).field("end", end).finish(),
+            Piece::Arc { center, radius, theta0, theta1 } => f.debug_struct("Arc")
+                .field("center", center).field("radius", radius)
+                .field("theta0", theta0).field("theta1", theta1).finish(),
+            Piece::Curve { t0, t1, length, .. } => f.debug_struct("Curve")
+                .field("t0", t0).field("t1", t1).field("length", length).finish_non_exhaustive()
+        }
+    }
+}
+
+impl<T: Copy> Piece<T> {
+    /// The same piece traversed in the opposite direction
+    pub fn reversed(&self) -> Piece<T> {
+        match self.clone() {
+            Piece::Segment { start, end } => Piece::Segment { start: end, end: start },
+            Piece::Arc { center, radius, theta0, theta1 } =>
+                Piece::Arc { center, radius, theta0: theta1, theta1: theta0 },
+            Piece::Curve { z, dz, t0, t1, length } => Piece::Curve { z, dz, t0: t1, t1: t0, length }
+        }
+    }
+}
+
+/// A contour made of segments, circular arcs and parameterized curves
+///
+/// The pieces are traversed in order.  They usually join end to end, but
+/// nothing requires it; a gap is simply not part of the path.
+///
+/// # Example
+/// ```
+/// use imaginary::{Complex, contour::Path};
+/// use std::f64::consts::PI;
+/// // a D shaped contour around the upper half of the unit disk
+/// let path = Path::segment(Complex::new(-1.0, 0.0), Complex::new(1.0, 0.0))
+///     .then(Path::arc(Complex::new(0.0, 0.0), 1.0, 0.0, PI));
+/// assert!((path.length() - (2.0 + PI)).abs() < 1e-12);
+/// assert!((path.end() - path.start()).abs() < 1e-12);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Path<T: Copy> {
+    pieces: Vec<Piece<T>>
+}
+
+impl<T: Copy> Path<T> {
+    /// The straight segment from `start` to `end`
+    pub fn segment(start: Complex<T>, end: Complex<T>) -> Path<T> {
+        Path { pieces: vec![Piece::Segment { start, end }] }
+    }
+
+    /// The arc center + radius e<sup>iθ</sup> from θ = `theta0` to
+    /// `theta1`, counterclockwise when `theta1 > theta0`
+    pub fn arc(center: Complex<T>, radius: T, theta0: T, theta1: T) -> Path<T> {
+        Path { pieces: vec![Piece::Arc { center, radius, theta0, theta1 }] }
+    }
+
+    /// The closed polygon through `vertices`, returning to the first
+    ///
+    /// # Panics
+    /// Panics if there are fewer than 2 vertices.
+    pub fn polygon(vertices: &[Complex<T>]) -> Path<T> {
+        assert!(vertices.len() >= 2, "a polygon needs at least 2 vertices");
+        let pieces = vertices.iter().zip(vertices.iter().cycle().skip(1))
+            .map(|(&start, &end)| Piece::Segment { start, end })
+            .collect();
+        Path { pieces }
+    }
+
+    /// The pieces of the path, in order
+    pub fn pieces(&self) -> &[Piece<T>] {
+        &self.pieces
+    }
+
+    /// This path followed by `other`
+    pub fn then(mut self, other: Path<T>) -> Path<T> {
+        self.pieces.extend(other.pieces);
+        self
+    }
+
+    /// The same path traversed in the opposite direction
+    pub fn reversed(&self) -> Path<T> {
+        Path { pieces: self.pieces.iter().rev().map(Piece::reversed).collect() }
+    }
+}
+
+macro_rules! contour_mod {
+    ($m: ident for $t: ident, talbot: $talbot: expr, stehfest: $stehfest: expr) => {
+        /// Contour integrals of analytic functions
+        pub mod $m {
+            use crate::Complex;
+            use crate::fft::$m::{fft, ifft};
+            use super::{GAUSS_WEIGHTS, Integral, KRONROD_NODES, KRONROD_WEIGHTS, Path, Piece};
+            use std::rc::Rc;
+
+            /// The inverse Laplace transform f(t) of F(s) by the fixed Talbot
+            /// method
+            ///
+            /// F must be analytic to the right of its singularities, which
+            /// should lie near or left of the imaginary axis, and f real, so
+            /// that F(s̄) is the conjugate of F(s).  The contour is
+            /// s(θ) = r θ (cot θ + i) for −π < θ < π, with r = 2M / 5t, and
+            #[doc = concat!("M = ", stringify!($talbot), " points give a relative error well within ")]
+            /// the square root of the machine epsilon for smooth f.  Poles of F
+            /// near the imaginary axis, making f oscillate, lose accuracy as t
+            /// grows.
+            ///
+            /// # Panics
+            /// Panics if `t` is not positive.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, contour::", stringify!($m), "::invert_laplace};")]
+            /// // F(s) = 1 / (s² + 1) is the transform of sin t
+            /// let f = invert_laplace(|s| (s * s + 1.0).recip(), 2.0);
+            #[doc = concat!("assert!((f - 2.0", stringify!($t), ".sin()).abs() < 1e-3);")]
+            /// ```
+            pub fn invert_laplace(transform: impl Fn(Complex<$t>) -> Complex<$t>, t: $t) -> $t {
+                assert!(t > 0.0, "t must be positive, t = {t}");
+                const M: usize = $talbot;
+                let r = 2.0 * M as $t / (5.0 * t);
+                let mut sum = 0.5 * (transform(Complex::new(r, 0.0)) * crate::math::$m::exp(r * t)).r;
+                for k in 1..M {
+                    let theta = k as $t * std::$t::consts::PI / M as $t;
+                    let cot = crate::math::$m::cos(theta) / crate::math::$m::sin(theta);
+                    let s = Complex::new(r * theta * cot, r * theta);
+                    let sigma = theta + (theta * cot - 1.0) * cot;
+                    sum += ((s * t).exp() * transform(s) * Complex::new(1.0, sigma)).r;
+                }
+                r / M as $t * sum
+            }
+
+            /// The inverse Laplace transform f(t) of F(s) by the
+            /// Gaver–Stehfest method
+            ///
+            /// Only samples at real s = k ln 2 / t, for k from 1 to
+            #[doc = concat!(stringify!($stehfest), ", are used, so F may be ")]
+            /// given only on the positive real axis.  The weights alternate in
+            /// sign and grow quickly, so the result is accurate only for
+            /// smooth, non-oscillating f.
+            ///
+            /// # Panics
+            /// Panics if `t` is not positive.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, contour::", stringify!($m), "::invert_laplace_stehfest};")]
+            /// // F(s) = 1 / (s + 1) is the transform of e^−t
+            /// let f = invert_laplace_stehfest(|s| (s + 1.0).recip(), 0.5);
+            #[doc = concat!("assert!((f - (-0.5", stringify!($t), ").exp()).abs() < 1e-3);")]
+            /// ```
+            pub fn invert_laplace_stehfest(transform: impl Fn(Complex<$t>) -> Complex<$t>, t: $t) -> $t {
+                assert!(t > 0.0, "t must be positive, t = {t}");
+                const N: usize = $stehfest;
+                let half = N / 2;
+                let factorial = |n: usize| (1..=n).fold(1.0, |p, k| p * k as $t);
+                let ln2t = std::$t::consts::LN_2 / t;
+                let mut sum = 0.0;
+                for k in 1..=N {
+                    let mut weight = 0.0;
+                    for j in k.div_ceil(2)..=k.min(half) {
+                        weight += (j as $t).powi(half as i32) * factorial(2 * j)
+                            / (factorial(half - j) * factorial(j) * factorial(j - 1)
+                                * factorial(k - j) * factorial(2 * j - k));
+                    }
+                    if (k + half) % 2 == 1 {
+                        weight = -weight;
+                    }
+                    sum += weight * transform(Complex::new(k as $t * ln2t, 0.0)).r;
+                }
+                ln2t * sum
+            }
+
+            /// The first `len` samples x<sub>n</sub> of the sequence with Z-transform
+            /// X(z) = Σ x<sub>n</sub> z<sup>−n</sup>
+            ///
+            /// The samples are (r<sup>n</sup>/2πi) ∮ X(z) z<sup>n−1</sup> dz
+            /// over the circle |z| = r, evaluated by the trapezoid rule with an
+            /// inverse FFT.  The circle must lie in the region of convergence,
+            /// outside every pole for a causal sequence.  The result also holds
+            /// x<sub>n+N</sub> r<sup>−N</sup> and later aliases, where N, at least
+            /// twice `len`, is the number of points, so a larger `radius`
+            /// suppresses aliasing but scales rounding errors by
+            /// r<sup>n</sup>.
+            ///
+            /// # Panics
+            /// Panics if `radius` is not positive.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, contour::", stringify!($m), "::invert_z};")]
+            /// // X(z) = 1 / (1 − 0.5 / z) is the transform of 0.5ⁿ
+            /// let x = invert_z(|z| (1.0 - 0.5 / z).recip(), 8, 1.0);
+            /// for (n, x) in x.iter().enumerate() {
+            #[doc = concat!("    assert!((x.r - 0.5", stringify!($t), ".powi(n as i32)).abs() < 1e-4 && x.i.abs() < 1e-4);")]
+            /// }
+            /// ```
+            pub fn invert_z(transform: impl Fn(Complex<$t>) -> Complex<$t>, len: usize, radius: $t) -> Vec<Complex<$t>> {
+                assert!(radius > 0.0, "radius must be positive, radius = {radius}");
+                let n = (2 * len).next_power_of_two();
+                let mut samples: Vec<Complex<$t>> = (0..n)
+                    .map(|k| transform(Complex::<$t>::cis(std::$t::consts::TAU * k as $t / n as $t) * radius))
+                    .collect();
+                ifft(&mut samples);
+                samples.truncate(len);
+                let mut scale = 1.0;
+                for x in &mut samples {
+                    *x *= scale;
+                    scale *= radius;
+                }
+                samples
+            }
+
+            /// The first `k` Taylor coefficients of f about `center`,
+            /// a<sub>j</sub> = f<sup>(j)</sup>(center) / j!
+            ///
+            /// The coefficients are (1/2πi) ∮ f(z) (z − center)<sup>−j−1</sup>
+            /// dz over the circle of `radius` about `center`, evaluated by the
+            /// trapezoid rule with an FFT.  f must be analytic on and inside the
+            /// circle.  The error in a<sub>j</sub> is about the rounding error of
+            /// the largest f on the circle divided by radius<sup>j</sup>, plus
+            /// aliasing from a<sub>j+N</sub> radius<sup>N</sup> with N at least
+            /// 4k points, so the radius should be as large as the nearest
+            /// singularity allows without making f large on the circle.
+            ///
+            /// # Panics
+            /// Panics if `radius` is not positive.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, contour::", stringify!($m), "::derivatives_cauchy};")]
+            /// // the Taylor series of e^z about 0 has the coefficients 1 / j!
+            /// let a = derivatives_cauchy(|z| z.exp(), Complex::new(0.0, 0.0), 1.0, 6);
+            /// assert!((a[5] - Complex::new(1.0 / 120.0, 0.0)).abs() < 1e-6);
+            /// ```
+            pub fn derivatives_cauchy(
+                f: impl Fn(Complex<$t>) -> Complex<$t>, center: Complex<$t>, radius: $t, k: usize
+            ) -> Vec<Complex<$t>> {
+                assert!(radius > 0.0, "radius must be positive, radius = {radius}");
+                let n = (4 * k).next_power_of_two().max(16);
+                let mut samples: Vec<Complex<$t>> = (0..n)
+                    .map(|m| f(center + Complex::<$t>::cis(std::$t::consts::TAU * m as $t / n as $t) * radius))
+                    .collect();
+                fft(&mut samples);
+                samples.truncate(k);
+                let mut scale = 1.0 / n as $t;
+                for a in &mut samples {
+                    *a *= scale;
+                    scale /= radius;
+                }
+                samples
+            }
+
+            /// The 15 point Kronrod and 7 point Gauss estimates of the integral
+            /// of `f` over [a, b], with the Kronrod estimate of the integral of
+            /// |f|
+            pub(crate) fn gauss_kronrod(
+                f: &impl Fn($t) -> Complex<$t>, a: $t, b: $t
+            ) -> (Complex<$t>, Complex<$t>, $t) {
+                let (c, h) = (0.5 * (a + b), 0.5 * (b - a));
+                let fc = f(c);
+                let mut kronrod = fc * KRONROD_WEIGHTS[7] as $t;
+                let mut gauss = fc * GAUSS_WEIGHTS[3] as $t;
+                let mut magnitude = fc.abs() * KRONROD_WEIGHTS[7] as $t;
+                for j in 0..7 {
+                    let x = h * KRONROD_NODES[j] as $t;
+                    let (f0, f1) = (f(c - x), f(c + x));
+                    kronrod += (f0 + f1) * KRONROD_WEIGHTS[j] as $t;
+                    magnitude += (f0.abs() + f1.abs()) * KRONROD_WEIGHTS[j] as $t;
+                    if j % 2 == 1 {
+                        gauss += (f0 + f1) * GAUSS_WEIGHTS[j / 2] as $t;
+                    }
+                }
+                (kronrod * h, gauss * h, magnitude * h.abs())
+            }
+
+            /// Integrates `f` over [a, b], bisecting the piece with the
+            /// largest error estimate until the total is within `tol` of the
+            /// integral of |f|, after 2000 bisections, or once a node lands on
+            /// a singularity
+            fn integrate(f: impl Fn($t) -> Complex<$t>, a: $t, b: $t, tol: $t) -> Integral<$t> {
+                let (k, g, m) = gauss_kronrod(&f, a, b);
+                let mut parts = vec![(a, b, k, m, (k - g).abs())];
+                let (mut value, mut magnitude) = (k, m);
+                for _ in 0..2000 {
+                    let error: $t = parts.iter().map(|p| p.4).sum();
+                    if error <= tol * magnitude {
+                        break;
+                    }
+                    let worst = (0..parts.len()).max_by(|&i, &j| parts[i].4.total_cmp(&parts[j].4)).unwrap();
+                    let (a, b, k, m, error) = parts.swap_remove(worst);
+                    let mid = 0.5 * (a + b);
+                    let (k1, g1, m1) = gauss_kronrod(&f, a, mid);
+                    let (k2, g2, m2) = gauss_kronrod(&f, mid, b);
+                    if !(k1 + k2).abs().is_finite() {
+                        // the nodes have reached a singular end point
+                        parts.push((a, b, k, m, error));
+                        break;
+                    }
+                    value += k1 + k2 - k;
+                    magnitude += m1 + m2 - m;
+                    parts.push((a, mid, k1, m1, (k1 - g1).abs()));
+                    parts.push((mid, b, k2, m2, (k2 - g2).abs()));
+                }
+                Integral { value, error: parts.iter().map(|p| p.4).sum() }
+            }
+
+            /// The integral of f(z) dz along the straight segment from `a` to
+            /// `b`
+            ///
+            /// The segment is bisected until the error estimate is within
+            /// `tol` of the integral of |f(z)| |dz|, so that integrals that
+            /// cancel to zero still finish.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, contour::", stringify!($m), "::integrate_segment};")]
+            /// // z^(−1/2) is singular at the start, and its integral is 2 √z
+            /// let b = Complex::new(0.0, 1.0);
+            /// let integral = integrate_segment(|z| z.sqrt().recip(), Complex::new(0.0, 0.0), b, 1e-6);
+            /// assert!((integral.value - 2.0 * b.sqrt()).abs() < 1e-5);
+            /// ```
+            pub fn integrate_segment(
+                f: impl Fn(Complex<$t>) -> Complex<$t>, a: Complex<$t>, b: Complex<$t>, tol: $t
+            ) -> Integral<$t> {
+                let dz = b - a;
+                integrate(|t| f(a + dz * t) * dz, 0.0, 1.0, tol)
+            }
+
+            /// The integral of f(z) dz along the arc z = center +
+            /// radius e<sup>iθ</sup> from θ = `theta0` to `theta1`
+            ///
+            /// The arc runs counterclockwise when `theta1 > theta0`, and is
+            /// a full circle when they differ by 2π.  The tolerance is as for
+            /// [`integrate_segment`].
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, contour::", stringify!($m), "::integrate_arc};")]
+            #[doc = concat!("use std::", stringify!($t), "::consts::{PI, TAU};")]
+            /// // the residue of e^z / z³ at 0 is 1/2
+            /// let integral = integrate_arc(|z| z.exp() / (z * z * z), Complex::new(0.0, 0.0), 1.0, 0.0, TAU, 1e-6);
+            /// assert!((integral.value - Complex::new(0.0, PI)).abs() < 1e-5);
+            /// ```
+            pub fn integrate_arc(
+                f: impl Fn(Complex<$t>) -> Complex<$t>, center: Complex<$t>, radius: $t,
+                theta0: $t, theta1: $t, tol: $t
+            ) -> Integral<$t> {
+                integrate(|theta| {
+                    let w = Complex::<$t>::cis(theta) * radius;
+                    f(center + w) * Complex::new(-w.i, w.r)
+                }, theta0, theta1, tol)
+            }
+
+            impl Piece<$t> {
+                /// The arc length of the piece
+                pub fn length(&self) -> $t {
+                    match self {
+                        Piece::Segment { start, end } => (*end - *start).abs(),
+                        Piece::Arc { radius, theta0, theta1, .. } => (radius * (theta1 - theta0)).abs(),
+                        Piece::Curve { length, .. } => *length
+                    }
+                }
+
+                /// The point a fraction `u` of the way along the parameter of
+                /// the piece, which is arc length for segments and arcs
+                pub fn point(&self, u: $t) -> Complex<$t> {
+                    match self {
+                        Piece::Segment { start, end } => *start + (*end - *start) * u,
+                        Piece::Arc { center, radius, theta0, theta1 } =>
+                            *center + Complex::<$t>::cis(theta0 + (theta1 - theta0) * u) * *radius,
+                        Piece::Curve { z, t0, t1, .. } => z(t0 + (t1 - t0) * u)
+                    }
+                }
+            }
+
+            impl Path<$t> {
+                /// The full circle of `radius` about `center`, counterclockwise
+                /// from angle 0
+                pub fn circle(center: Complex<$t>, radius: $t) -> Path<$t> {
+                    Path::arc(center, radius, 0.0, std::$t::consts::TAU)
+                }
+
+                /// The curve z(t) from t = `t0` to `t1`, with its derivative
+                /// `dz`
+                ///
+                /// The arc length is found by quadrature when the curve is
+                /// created.
+                ///
+                /// # Example
+                /// ```
+                /// use imaginary::{Complex, contour::Path};
+                #[doc = concat!("use std::", stringify!($t), "::consts::TAU;")]
+                /// // an ellipse with semi-axes 2 and 1
+                #[doc = concat!("let ellipse = Path::<", stringify!($t), ">::curve(")]
+                ///     |t| Complex::new(2.0 * t.cos(), t.sin()),
+                ///     |t| Complex::new(-2.0 * t.sin(), t.cos()),
+                ///     0.0, TAU
+                /// );
+                /// assert!((ellipse.length() - 9.688448).abs() < 1e-5);
+                /// ```
+                pub fn curve(
+                    z: impl Fn($t) -> Complex<$t> + 'static, dz: impl Fn($t) -> Complex<$t> + 'static,
+                    t0: $t, t1: $t
+                ) -> Path<$t> {
+                    let tol = 1e2 * $t::EPSILON;
+                    let length = integrate(|t| Complex::new(dz(t).abs(), 0.0), t0, t1, tol).value.r.abs();
+                    Path { pieces: vec![Piece::Curve { z: Rc::new(z), dz: Rc::new(dz), t0, t1, length }] }
+                }
+
+                /// The arc length of the path
+                pub fn length(&self) -> $t {
+                    self.pieces.iter().map(Piece::<$t>::length).sum()
+                }
+
+                /// The first point of the path
+                pub fn start(&self) -> Complex<$t> {
+                    self.pieces[0].point(0.0)
+                }
+
+                /// The last point of the path
+                pub fn end(&self) -> Complex<$t> {
+                    self.pieces[self.pieces.len() - 1].point(1.0)
+                }
+
+                /// The point a fraction `t` of the way along the path
+                ///
+                /// Each piece takes a share of [0, 1] in proportion to its
+                /// length, and within a curve the point moves linearly in the
+                /// curve's own parameter rather than in arc length.  `t` is
+                /// clamped to [0, 1].
+                pub fn point(&self, t: $t) -> Complex<$t> {
+                    let lengths: Vec<$t> = self.pieces.iter().map(Piece::<$t>::length).collect();
+                    let mut s = t.clamp(0.0, 1.0) * lengths.iter().sum::<$t>();
+                    let last = self.pieces.len() - 1;
+                    for (k, (piece, length)) in self.pieces.iter().zip(lengths).enumerate() {
+                        if s <= length || k == last {
+                            let u = if length > 0.0 { (s / length).min(1.0) } else { 0.0 };
+                            return piece.point(u);
+                        }
+                        s -= length;
+                    }
+                    unreachable!()
+                }
+
+                /// `n` points evenly spread over the path from its start to its
+                /// end, as given by [`point`](Path::point)
+                pub fn sample(&self, n: usize) -> Vec<Complex<$t>> {
+                    let step = 1.0 / (n.max(2) - 1) as $t;
+                    (0..n).map(|k| self.point(k as $t * step)).collect()
+                }
+
+                /// The integral of f(z) dz along the path
+                ///
+                /// Each piece is integrated adaptively to `tol` as in
+                /// [`integrate_segment`], and the values and error estimates
+                /// are summed.
+                ///
+                /// # Example
+                /// ```
+                /// use imaginary::{Complex, contour::Path};
+                #[doc = concat!("use std::", stringify!($t), "::consts::TAU;")]
+                /// // a square around the pole of 1 / z
+                #[doc = concat!("let square = Path::<", stringify!($t), ">::polygon(&[")]
+                ///     Complex::new(-1.0, -1.0), Complex::new(1.0, -1.0),
+                ///     Complex::new(1.0, 1.0), Complex::new(-1.0, 1.0)
+                /// ]);
+                /// let integral = square.integrate(|z| z.recip(), 1e-6);
+                /// assert!((integral.value - Complex::new(0.0, TAU)).abs() < 1e-5);
+                /// ```
+                pub fn integrate(&self, f: impl Fn(Complex<$t>) -> Complex<$t>, tol: $t) -> Integral<$t> {
+                    let mut total = Integral { value: Complex::new(0.0, 0.0), error: 0.0 };
+                    for piece in &self.pieces {
+                        let integral = match piece {
+                            Piece::Segment { start, end } => integrate_segment(&f, *start, *end, tol),
+                            Piece::Arc { center, radius, theta0, theta1 } =>
+                                integrate_arc(&f, *center, *radius, *theta0, *theta1, tol),
+                            Piece::Curve { z, dz, t0, t1, .. } => integrate(|t| f(z(t)) * dz(t), *t0, *t1, tol)
+                        };
+                        total.value += integral.value;
+                        total.error += integral.error;
+                    }
+                    total
+                }
+            }
+
+            #[cfg(test)]
+            mod test {
+                use super::*;
+
+                #[test]
+                fn check_invert_laplace() {
+                    // Euler's constant
+                    const GAMMA: $t = 0.5772156649015329f64 as $t;
+                    let ep = $t::EPSILON.sqrt();
+                    let cases: [(fn(Complex<$t>) -> Complex<$t>, fn($t) -> $t); 4] = [
+                        (|s| (s + 1.0).recip(), |t| (-t).exp()),
+                        (|s| (s * s + 1.0).recip(), |t| t.sin()),
+                        (|s| s.sqrt().recip(), |t| (std::$t::consts::PI * t).sqrt().recip()),
+                        (|s| (s.ln() + GAMMA) / -s, |t| t.ln()),
+                    ];
+                    for (transform, f) in cases {
+                        for t in [0.1, 0.5, 1.0, 3.0] {
+                            let g = invert_laplace(transform, t);
+                            assert!((g - f(t)).abs() <= 10.0 * ep * f(t).abs().max(1.0), "{t} {g} {}", f(t));
+                        }
+                    }
+                    // Stehfest for the smooth cases
+                    for (transform, f) in [cases[0], cases[2], cases[3]] {
+                        for t in [0.1, 1.0, 3.0] {
+                            let g = invert_laplace_stehfest(transform, t);
+                            assert!((g - f(t)).abs() <= ep.sqrt() * f(t).abs().max(1.0), "{t} {g} {}", f(t));
+                        }
+                    }
+                }
+
+                #[test]
+                fn check_invert_z() {
+                    let ep = 1e2 * $t::EPSILON;
+                    // a complex pole, where rounding grows as radiusⁿ
+                    let a = Complex::new(0.4, 0.5);
+                    for radius in [1.2, 2.0] {
+                        let x = invert_z(|z| (1.0 - a / z).recip(), 20, radius);
+                        let (mut power, mut scale) = (Complex::new(1.0, 0.0), 1.0);
+                        for x in x {
+                            assert!((x - power).abs() < ep * scale, "{radius} {x:?} {power:?}");
+                            power *= a;
+                            scale *= radius;
+                        }
+                    }
+                    // aliasing from a pole close to the circle
+                    let a = 0.9;
+                    let x = invert_z(|z| (1.0 - a / z).recip(), 8, 1.0);
+                    assert!((x[0].r - 1.0 / (1.0 - a.powi(16))).abs() < ep);
+                    // e^(1/z) is the transform of 1 / n!
+                    let x = invert_z(|z| z.recip().exp(), 12, 1.0);
+                    let mut factorial = 1.0;
+                    for (n, x) in x.into_iter().enumerate() {
+                        factorial *= (n as $t).max(1.0);
+                        assert!((x - Complex::new(factorial.recip(), 0.0)).abs() < ep, "{n} {x:?}");
+                    }
+                }
+
+                #[test]
+                fn check_derivatives_cauchy() {
+                    let ep = 1e2 * $t::EPSILON;
+                    // e^z about 1 + i has the derivatives e^(1 + i), and the
+                    // error grows as max |f| / radius^j
+                    let center = Complex::new(1.0, 1.0);
+                    let a = derivatives_cauchy(|z| z.exp(), center, 2.0, 12);
+                    let (mut factorial, mut bound) = (1.0, ep * (center.r + 2.0).exp());
+                    for (j, a) in a.into_iter().enumerate() {
+                        factorial *= (j as $t).max(1.0);
+                        assert!((a - center.exp() / factorial).abs() < bound, "{j} {a:?}");
+                        bound /= 2.0;
+                    }
+                    // 1 / (1 − z) about 0, with the singularity outside the circle
+                    let a = derivatives_cauchy(|z| (1.0 - z).recip(), Complex::new(0.0, 0.0), 0.5, 10);
+                    for (j, a) in a.into_iter().enumerate() {
+                        assert!((a - Complex::new(1.0, 0.0)).abs() < 2.0 * ep * (2.0 as $t).powi(j as i32), "{j} {a:?}");
+                    }
+                }
+
+                #[test]
+                fn check_integrate() {
+                    let ep = 1e2 * $t::EPSILON;
+                    use std::$t::consts::{PI, TAU};
+                    let (zero, i) = (Complex::new(0.0, 0.0), Complex::new(0.0, 1.0));
+                    // a polynomial, exact with the Kronrod rule
+                    let b = Complex::new(1.0, 1.0);
+                    let integral = integrate_segment(|z| z * z, zero, b, ep);
+                    assert!((integral.value - b * b * b / 3.0).abs() < ep && integral.error < ep);
+                    // an end point singularity, and then one at each end, where
+                    // the nodes cannot get close enough for full accuracy
+                    let integral = integrate_segment(|z| z.sqrt().recip(), zero, i, ep);
+                    assert!((integral.value - 2.0 * i.sqrt()).abs() < 10.0 * ep, "{integral:?}");
+                    let f = |z: Complex<$t>| (z * (i - z)).sqrt().recip();
+                    let integral = integrate_segment(f, zero, i, ep.sqrt());
+                    assert!((integral.value - Complex::new(PI, 0.0)).abs() < 10.0 * ep.sqrt(), "{integral:?}");
+                    // the reversed segment has the opposite sign
+                    let reversed = integrate_segment(f, i, zero, ep.sqrt());
+                    assert!((integral.value + reversed.value).abs() < 10.0 * ep.sqrt());
+                    // closed contours, with and without an enclosed pole
+                    let center = Complex::new(0.5, -0.5);
+                    let integral = integrate_arc(|z| (z - center).recip(), center, 2.0, 0.0, TAU, ep);
+                    assert!((integral.value - Complex::new(0.0, TAU)).abs() < ep, "{integral:?}");
+                    let integral = integrate_arc(|z| z.exp(), center, 2.0, -PI, PI, ep);
+                    assert!(integral.value.abs() < 10.0 * ep, "{integral:?}");
+                    // a half circle, clockwise
+                    let integral = integrate_arc(|z| z.recip(), zero, 3.0, PI, 0.0, ep);
+                    assert!((integral.value - Complex::new(0.0, -PI)).abs() < ep, "{integral:?}");
+                }
+
+                #[test]
+                fn check_path() {
+                    let ep = 1e2 * $t::EPSILON;
+                    use std::$t::consts::{PI, TAU};
+                    let (zero, i) = (Complex::new(0.0, 0.0), Complex::new(0.0, 1.0));
+                    // a closed quarter disk, where z² integrates to zero
+                    let sector = Path::segment(zero, Complex::new(2.0, 0.0))
+                        .then(Path::arc(zero, 2.0, 0.0, PI / 2.0))
+                        .then(Path::segment(2.0 * i, zero));
+                    assert_eq!(sector.pieces().len(), 3);
+                    assert!((sector.length() - (4.0 + PI)).abs() < 10.0 * ep);
+                    assert!((sector.end() - sector.start()).abs() < ep);
+                    let integral = sector.integrate(|z| z * z, ep);
+                    assert!(integral.value.abs() < 10.0 * ep && integral.error < 10.0 * ep, "{integral:?}");
+                    // a curve and a circle agree
+                    let circle = Path::<$t>::circle(i, 2.0);
+                    let curve = Path::<$t>::curve(move |t: $t| i + Complex::<$t>::cis(t) * 2.0,
+                        |t: $t| Complex::<$t>::cis(t) * Complex::new(0.0, 2.0), 0.0, TAU);
+                    assert!((curve.length() - circle.length()).abs() < 10.0 * ep);
+                    let f = |z: Complex<$t>| z.exp() / (z - 0.5);
+                    let (c, d) = (circle.integrate(f, ep), curve.integrate(f, ep));
+                    let exact = Complex::new(0.0, TAU) * (0.5 as $t).exp();
+                    assert!((c.value - exact).abs() < 10.0 * ep && (d.value - exact).abs() < 10.0 * ep, "{c:?} {d:?}");
+                    // reversal
+                    let reversed = circle.reversed().integrate(f, ep);
+                    assert!((reversed.value + exact).abs() < 10.0 * ep);
+                    // sampling a square at its corners and midpoints
+                    let corners = [zero, Complex::new(1.0, 0.0), Complex::new(1.0, 1.0), i];
+                    let square = Path::polygon(&corners);
+                    assert_eq!((square.start(), square.end()), (zero, zero));
+                    let points = square.sample(9);
+                    assert_eq!(points.len(), 9);
+                    for (k, point) in points.iter().enumerate() {
+                        let expected = if k % 2 == 0 {
+                            corners[k / 2 % 4]
+                        } else {
+                            (corners[k / 2] + corners[(k / 2 + 1) % 4]) * 0.5
+                        };
+                        assert!((*point - expected).abs() < ep, "{k} {point:?}");
+                    }
+                    assert_eq!(square.reversed().sample(3)[1], Complex::new(1.0, 1.0));
+                }
+            }
+        }
+    }
+}
+contour_mod!(c32 for f32, talbot: 10, stehfest: 8);
+contour_mod!(c64 for f64, talbot: 24, stehfest: 14);
@@ -0,0 +1,168 @@
+//! Fixed-point CORDIC versions of [`cis`](crate::Complex::cis),
+//! [`abs`](crate::Complex::abs) and [`angle`](crate::Complex::angle)
+//!
+//! These use only integer shifts and adds, for targets without a floating
+//! point unit.  Angles are binary angles in an `i32`, where the full circle
+//! is 2³², so that `i32::MIN` is −π and wrapping arithmetic wraps the angle.
+//! Unit magnitude is Q1.30 fixed point, 1.0 = 2³⁰ = [`ONE`].
+//!
+//! The components from [`cis`] are within 20 units of 2⁻³⁰ of the exact
+//! ones, about 2 × 10⁻⁸.  Magnitudes are within 1 + 10⁻⁸|z| of the exact
+//! ones, and angles within 1.1 × 10⁻⁸ radians, or 8 units of the binary
+//! angle, plus 10⁻⁴/|z| radians for the coarse directions of small `z`.
+//!
+//! Requires the `cordic` feature.
+//!
+//! # Example
+//! ```
+//! use imaginary::{Complex, cordic};
+//! // 60° is a sixth of the circle
+//! let z = cordic::cis((1i64 << 32).div_euclid(6) as i32);
+//! assert!((z.r - cordic::ONE / 2).abs() < 8);
+//! assert_eq!(cordic::abs(Complex::new(3000, -4000)), 5000);
+//! ```
+
+use crate::Complex;
+
+/// 1.0 in Q1.30 fixed point
+pub const ONE: i32 = 1 << 30;
+
+/// atan(2⁻ᵏ) as binary angles
+const ATAN: [i32; 31] = [
+    536870912, 316933406, 167458907, 85004756, 42667331, 21354465, 10679838,
+    5340245, 2670163, 1335087, 667544, 333772, 166886, 83443, 41722, 20861,
+    10430, 5215, 2608, 1304, 652, 326, 163, 81, 41, 20, 10, 5, 3, 1, 1
+];
+
+/// Inverse of the CORDIC gain, ∏ 1/√(1 + 2⁻²ᵏ), in Q1.30
+const INV_GAIN: i64 = 652032874;
+
+/// e<sup>iθ</sup> in Q1.30 for the binary angle `angle`
+pub fn cis(angle: i32) -> Complex<i32> {
+    // rotate by π when outside [-π/2, π/2]
+    let flip = angle.wrapping_add(1 << 30) < 0;
+    let mut z = if flip { angle.wrapping_sub(i32::MIN) } else { angle };
+    let mut x = INV_GAIN;
+    let mut y = 0i64;
+    for (k, &atan) in ATAN.iter().enumerate() {
+        let (dx, dy) = (y >> k, x >> k);
+        if z >= 0 {
+            x -= dx;
+            y += dy;
+            z -= atan;
+        } else {
+            x += dx;
+            y -= dy;
+            z += atan;
+        }
+    }
+    let (x, y) = (x.clamp(-ONE as i64, ONE as i64) as i32, y.clamp(-ONE as i64, ONE as i64) as i32);
+    if flip {
+        Complex::new(-x, -y)
+    } else {
+        Complex::new(x, y)
+    }
+}
+
+/// Magnitude and binary angle of `z`, computed together
+///
+/// The magnitude has the same scale as the components of `z`, and the angle
+/// of zero is 0.
+pub fn abs_angle(z: Complex<i32>) -> (u32, i32) {
+    let (mut x, mut y) = (z.r as i64, z.i as i64);
+    let mut angle = 0i32;
+    // rotate into the right half plane
+    if x < 0 {
+        x = -x;
+        y = -y;
+        angle = i32::MIN;
+    }
+    // extra fractional bits keep the shifts of small inputs accurate
+    const FRAC: u32 = 16;
+    x <<= FRAC;
+    y <<= FRAC;
+    for (k, &atan) in ATAN.iter().enumerate() {
+        let (dx, dy) = (y >> k, x >> k);
+        if y > 0 {
+            x += dx;
+            y -= dy;
+            angle = angle.wrapping_add(atan);
+        } else {
+            x -= dx;
+            y += dy;
+            angle = angle.wrapping_sub(atan);
+        }
+    }
+    // x is at most 2³¹ √2 · 1.65 after dropping the fractional bits, which
+    // leaves room in an i64 to apply the gain
+    let x = (x + (1 << (FRAC - 1))) >> FRAC;
+    let abs = ((x * INV_GAIN + (1 << 29)) >> 30) as u32;
+    if z.r == 0 && z.i == 0 {
+        (0, 0)
+    } else {
+        (abs, angle)
+    }
+}
+
+/// Magnitude of `z`, with the same scale as its components
+pub fn abs(z: Complex<i32>) -> u32 {
+    abs_angle(z).0
+}
+
+/// Binary angle of `z`, 0 for zero
+pub fn angle(z: Complex<i32>) -> i32 {
+    abs_angle(z).1
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    fn to_radians(angle: i32) -> f64 {
+        angle as f64 * PI / (1u64 << 31) as f64
+    }
+
+    /// Pseudorandom 32-bit values, by xorshift
+    fn random() -> impl Iterator<Item = u32> {
+        core::iter::successors(Some(0x9e3779b97f4a7c15u64), |&x| {
+            let x = x ^ x << 13;
+            let x = x ^ x >> 7;
+            Some(x ^ x << 17)
+        }).map(|x| (x >> 32) as u32)
+    }
+
+    #[test]
+    fn cis_accuracy() {
+        let steps = (-1000..=1000).map(|k| (k as i64 * ((1i64 << 31) / 1000)) as i32);
+        for angle in steps.chain(random().take(100_000).map(|x| x as i32)) {
+            let z = cis(angle);
+            let expected = Complex::<f64>::cis(to_radians(angle)) * ONE as f64;
+            assert!((z.r as f64 - expected.r).abs() < 20.0, "{angle} {:?} {}", z, expected);
+            assert!((z.i as f64 - expected.i).abs() < 20.0, "{angle} {:?} {}", z, expected);
+        }
+    }
+
+    #[test]
+    fn abs_angle_accuracy() {
+        let edges = [(1, 0), (0, -1), (3, 4), (-5, 12), (i32::MIN, i32::MIN), (i32::MAX, -7),
+                     (-123456, 654321), (ONE, ONE), (-1, 1)];
+        // parts of every size, from the bits shifted out of random values
+        let mut bits = random();
+        let random_parts = core::iter::from_fn(|| {
+            let (r, i, shift) = (bits.next()? as i32, bits.next()? as i32, bits.next()? % 32);
+            Some((r >> shift, i >> shift))
+        });
+        for (r, i) in edges.into_iter().chain(random_parts.take(100_000)) {
+            if (r, i) == (0, 0) {
+                continue;
+            }
+            let (abs, angle) = abs_angle(Complex::new(r, i));
+            let z = Complex::new(r as f64, i as f64);
+            assert!((abs as f64 - z.abs()).abs() <= 1.0 + 1e-8 * z.abs(), "{r} {i} {abs}");
+            let da = (to_radians(angle) - z.angle() + PI).rem_euclid(2.0 * PI) - PI;
+            assert!(da.abs() <= 1.1e-8 + 1e-4 / z.abs(), "{r} {i} {angle}");
+        }
+        assert_eq!(abs_angle(Complex::new(0, 0)), (0, 0));
+    }
+}
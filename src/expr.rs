@@ -0,0 +1,274 @@
+//! Lazy element-wise arithmetic on slices of complex numbers
+//!
+//! Operators on [`Lazy`] expressions build a tree of the operations instead
+//! of computing them, and [`eval_into`](Lazy::eval_into) then evaluates the
+//! whole tree element by element in a single pass over the operands.  An
+//! expression such as `a * b + c * d` over large buffers thus reads each
+//! input once and writes the output once, without the temporary buffer per
+//! operator that eager slice arithmetic needs, which matters when memory
+//! bandwidth rather than arithmetic is the limit.
+//!
+//! Slices enter expressions through [`lazy`], and complex scalars can be
+//! combined with expressions directly.  The operands of a binary operation
+//! must have the same length.
+//!
+//! # Example
+//! ```
+//! use imaginary::Complex;
+//! use imaginary::expr::lazy;
+//! let a = vec![Complex::new(1.0, 1.0); 1000];
+//! let b = vec![Complex::new(0.0, 2.0); 1000];
+//! let c = vec![Complex::new(3.0, 0.0); 1000];
+//! let d = vec![Complex::new(0.5, 0.0); 1000];
+//! let mut z = vec![Complex::new(0.0, 0.0); 1000];
+//! (lazy(&a) * lazy(&b) + lazy(&c) * lazy(&d)).eval_into(&mut z);
+//! assert_eq!(z[0], Complex::new(-0.5, 2.0));
+//! ```
+
+use crate::Complex;
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+/// An element-wise expression of a given length over complex numbers
+pub trait Expr {
+    /// the type of the real and imaginary parts
+    type Item: Copy;
+    /// Number of elements
+    fn len(&self) -> usize;
+    /// Whether the expression has no elements
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Element `k`, for `k < self.len()`
+    fn at(&self, k: usize) -> Complex<Self::Item>;
+}
+
+impl<T: Copy> Expr for &[Complex<T>] {
+    type Item = T;
+    fn len(&self) -> usize {
+        <[Complex<T>]>::len(self)
+    }
+    fn at(&self, k: usize) -> Complex<T> {
+        self[k]
+    }
+}
+
+/// A lazily evaluated element-wise expression
+///
+/// Built by [`lazy`] and by the arithmetic operators, which take other
+/// expressions or complex scalars as operands.
+#[derive(Copy, Clone, Debug)]
+pub struct Lazy<E>(E);
+
+/// Starts a lazy expression from the elements of `slice`
+pub fn lazy<T: Copy>(slice: &[Complex<T>]) -> Lazy<&[Complex<T>]> {
+    Lazy(slice)
+}
+
+impl<E: Expr> Lazy<E> {
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Whether the expression has no elements
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Element `k` of the expression
+    ///
+    /// # Panics
+    /// Panics if `k` is out of range.
+    pub fn at(&self, k: usize) -> Complex<E::Item> {
+        assert!(k < self.len(), "index {k} out of range for an expression of length {}", self.len());
+        self.0.at(k)
+    }
+
+    /// Evaluates the expression into `out` in a single pass
+    ///
+    /// # Panics
+    /// Panics if `out` differs in length from the expression.
+    pub fn eval_into(&self, out: &mut [Complex<E::Item>]) {
+        assert_eq!(out.len(), self.len(), "output length differs from the expression");
+        for (k, out) in out.iter_mut().enumerate() {
+            *out = self.0.at(k);
+        }
+    }
+
+    /// Evaluates the expression into a new vector
+    pub fn eval(&self) -> Vec<Complex<E::Item>> {
+        (0..self.len()).map(|k| self.0.at(k)).collect()
+    }
+
+    /// Complex conjugate of each element
+    pub fn conj(self) -> Lazy<Conj<E>> where E::Item: Neg<Output=E::Item> {
+        Lazy(Conj(self.0))
+    }
+
+    /// Applies `f` to each element
+    ///
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// use imaginary::expr::lazy;
+    /// let z = [Complex::new(0.0, 0.0), Complex::new(2.0, 0.0)];
+    /// let w = (lazy(&z).map(|z: Complex<f64>| z.exp()) * Complex::new(2.0, 0.0)).eval();
+    /// assert_eq!(w[0], Complex::new(2.0, 0.0));
+    /// ```
+    pub fn map<F: Fn(Complex<E::Item>) -> Complex<E::Item>>(self, f: F) -> Lazy<Map<E, F>> {
+        Lazy(Map(self.0, f))
+    }
+}
+
+/// A complex scalar repeated to the length of the other operand
+#[derive(Copy, Clone, Debug)]
+pub struct Splat<T: Copy> {
+    value: Complex<T>,
+    len: usize
+}
+
+impl<T: Copy> Expr for Splat<T> {
+    type Item = T;
+    fn len(&self) -> usize {
+        self.len
+    }
+    fn at(&self, _k: usize) -> Complex<T> {
+        self.value
+    }
+}
+
+/// The complex conjugates of an expression
+#[derive(Copy, Clone, Debug)]
+pub struct Conj<A>(A);
+
+impl<A: Expr> Expr for Conj<A> where A::Item: Neg<Output=A::Item> {
+    type Item = A::Item;
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+    fn at(&self, k: usize) -> Complex<A::Item> {
+        self.0.at(k).conj()
+    }
+}
+
+/// The negation of an expression
+#[derive(Copy, Clone, Debug)]
+pub struct Negation<A>(A);
+
+impl<A: Expr> Expr for Negation<A> where Complex<A::Item>: Neg<Output=Complex<A::Item>> {
+    type Item = A::Item;
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+    fn at(&self, k: usize) -> Complex<A::Item> {
+        -self.0.at(k)
+    }
+}
+
+impl<E: Expr> Neg for Lazy<E> where Complex<E::Item>: Neg<Output=Complex<E::Item>> {
+    type Output = Lazy<Negation<E>>;
+    fn neg(self) -> Lazy<Negation<E>> {
+        Lazy(Negation(self.0))
+    }
+}
+
+/// A function applied to each element of an expression
+#[derive(Copy, Clone, Debug)]
+pub struct Map<A, F>(A, F);
+
+impl<A: Expr, F: Fn(Complex<A::Item>) -> Complex<A::Item>> Expr for Map<A, F> {
+    type Item = A::Item;
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+    fn at(&self, k: usize) -> Complex<A::Item> {
+        (self.1)(self.0.at(k))
+    }
+}
+
+/// Defines the node type `$node` for the element-wise operator `$op`, and
+/// the operator between expressions and with complex scalars on either side
+macro_rules! expr_op {
+    ($node: ident, $op: ident, $f: ident, $doc: literal) => {
+        #[doc = $doc]
+        #[derive(Copy, Clone, Debug)]
+        pub struct $node<A, B>(A, B);
+
+        impl<A: Expr, B: Expr<Item = A::Item>> Expr for $node<A, B>
+        where Complex<A::Item>: $op<Output=Complex<A::Item>> {
+            type Item = A::Item;
+            fn len(&self) -> usize {
+                self.0.len()
+            }
+            fn at(&self, k: usize) -> Complex<A::Item> {
+                self.0.at(k).$f(self.1.at(k))
+            }
+        }
+
+        impl<A: Expr, B: Expr<Item = A::Item>> $op<Lazy<B>> for Lazy<A>
+        where Complex<A::Item>: $op<Output=Complex<A::Item>> {
+            type Output = Lazy<$node<A, B>>;
+            fn $f(self, rhs: Lazy<B>) -> Lazy<$node<A, B>> {
+                assert_eq!(self.len(), rhs.len(), "expression lengths differ");
+                Lazy($node(self.0, rhs.0))
+            }
+        }
+
+        impl<A: Expr> $op<Complex<A::Item>> for Lazy<A>
+        where Complex<A::Item>: $op<Output=Complex<A::Item>> {
+            type Output = Lazy<$node<A, Splat<A::Item>>>;
+            fn $f(self, rhs: Complex<A::Item>) -> Lazy<$node<A, Splat<A::Item>>> {
+                let len = self.len();
+                Lazy($node(self.0, Splat { value: rhs, len }))
+            }
+        }
+
+        impl<T: Copy, B: Expr<Item = T>> $op<Lazy<B>> for Complex<T>
+        where Complex<T>: $op<Output=Complex<T>> {
+            type Output = Lazy<$node<Splat<T>, B>>;
+            fn $f(self, rhs: Lazy<B>) -> Lazy<$node<Splat<T>, B>> {
+                let len = rhs.len();
+                Lazy($node(Splat { value: self, len }, rhs.0))
+            }
+        }
+    }
+}
+expr_op!(Sum, Add, add, "The element-wise sum of two expressions");
+expr_op!(Difference, Sub, sub, "The element-wise difference of two expressions");
+expr_op!(Product, Mul, mul, "The element-wise product of two expressions");
+expr_op!(Quotient, Div, div, "The element-wise quotient of two expressions");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn buffer(n: usize, seed: f64) -> Vec<Complex<f64>> {
+        (0..n).map(|k| Complex::new((k as f64 * seed).sin(), (k as f64 * seed * 0.7).cos() + 1.5)).collect()
+    }
+
+    #[test]
+    fn fused_matches_eager() {
+        let n = 257;
+        let (a, b, c, d) = (buffer(n, 0.3), buffer(n, 1.1), buffer(n, 2.3), buffer(n, 0.9));
+        let s = Complex::new(0.5, -2.0);
+        let mut out = vec![Complex::new(0.0, 0.0); n];
+        ((lazy(&a) * lazy(&b) + lazy(&c) * lazy(&d)) / s - lazy(&a).conj()).eval_into(&mut out);
+        for k in 0..n {
+            assert_eq!(out[k], (a[k] * b[k] + c[k] * d[k]) / s - a[k].conj());
+        }
+        let out = (s * -lazy(&a) - s + lazy(&b).map(|z| z * z) / lazy(&d)).eval();
+        for k in 0..n {
+            assert_eq!(out[k], s * -a[k] - s + b[k] * b[k] / d[k]);
+        }
+        let expr = s + lazy(&c) * s;
+        assert_eq!((expr.len(), expr.at(3)), (n, s + c[3] * s));
+        assert!(lazy::<f64>(&[]).is_empty());
+        assert_eq!((lazy::<f64>(&[]) + Complex::new(1.0, 0.0)).eval(), []);
+    }
+
+    #[test]
+    #[should_panic]
+    fn length_mismatch() {
+        let _ = lazy(&buffer(4, 0.1)) + lazy(&buffer(5, 0.1));
+    }
+}
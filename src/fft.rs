@@ -0,0 +1,162 @@
+//! Fast Fourier transforms of [`Complex`](crate::Complex) buffers
+//!
+//! Power of 2 lengths use an iterative radix-2 transform, other lengths are
+//! handled with Bluestein's chirp-z algorithm.
+
+macro_rules! fft_mod {
+    ($m: ident for $t: ident) => {
+        /// Fast Fourier transforms of [`Complex`](crate::Complex) buffers
+        pub mod $m {
+            use crate::Complex;
+
+            /// In-place forward discrete Fourier transform
+            ///
+            /// X<sub>k</sub> = Σ x<sub>n</sub> e<sup>-2πi kn/N</sup>
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, fft::", stringify!($m), "::fft};")]
+            /// let mut buf = [Complex::new(1.0, 0.0); 4];
+            /// fft(&mut buf);
+            /// assert_eq!(buf, [
+            ///     Complex::new(4.0, 0.0), Complex::new(0.0, 0.0),
+            ///     Complex::new(0.0, 0.0), Complex::new(0.0, 0.0)
+            /// ]);
+            /// ```
+            pub fn fft(buf: &mut [Complex<$t>]) {
+                transform(buf, false);
+            }
+
+            /// In-place inverse discrete Fourier transform
+            ///
+            /// x<sub>n</sub> = (1/N) Σ X<sub>k</sub> e<sup>2πi kn/N</sup>
+            pub fn ifft(buf: &mut [Complex<$t>]) {
+                transform(buf, true);
+                let scale = 1.0 / buf.len() as $t;
+                for x in buf.iter_mut() {
+                    *x *= scale;
+                }
+            }
+
+            fn transform(buf: &mut [Complex<$t>], inverse: bool) {
+                let n = buf.len();
+                if n <= 1 {
+                    return;
+                }
+                if n.is_power_of_two() {
+                    radix_2(buf, inverse);
+                } else {
+                    bluestein(buf, inverse);
+                }
+            }
+
+            /// Unnormalized transform of a power of 2 length buffer
+            fn radix_2(buf: &mut [Complex<$t>], inverse: bool) {
+                use std::$t::consts::TAU;
+                let n = buf.len();
+                let bits = n.trailing_zeros();
+                for k in 0..n {
+                    let j = k.reverse_bits() >> (usize::BITS - bits);
+                    if k < j {
+                        buf.swap(k, j);
+                    }
+                }
+                let sign = if inverse { 1.0 } else { -1.0 };
+                let twiddles: Vec<Complex<$t>> = (0..n / 2)
+                    .map(|k| Complex::<$t>::cis(sign * TAU * k as $t / n as $t))
+                    .collect();
+                let mut len = 2;
+                while len <= n {
+                    let half = len / 2;
+                    let stride = n / len;
+                    for chunk in buf.chunks_exact_mut(len) {
+                        let (lo, hi) = chunk.split_at_mut(half);
+                        for (k, (a, b)) in lo.iter_mut().zip(hi.iter_mut()).enumerate() {
+                            let t = *b * twiddles[k * stride];
+                            *b = *a - t;
+                            *a += t;
+                        }
+                    }
+                    len *= 2;
+                }
+            }
+
+            /// Unnormalized transform of any length using Bluestein's
+            /// algorithm, a convolution with a chirp done with radix-2
+            /// transforms
+            fn bluestein(buf: &mut [Complex<$t>], inverse: bool) {
+                use std::$t::consts::PI;
+                let n = buf.len();
+                let m = (2 * n - 1).next_power_of_two();
+                let sign = if inverse { 1.0 } else { -1.0 };
+                // chirp e^(∓πi k²/n), with k² reduced mod 2n to keep the
+                // angle accurate
+                let chirp: Vec<Complex<$t>> = (0..n)
+                    .map(|k| {
+                        let k_sq = (k as u128 * k as u128 % (2 * n as u128)) as $t;
+                        Complex::<$t>::cis(sign * PI * k_sq / n as $t)
+                    })
+                    .collect();
+                let zero = Complex::new(0.0, 0.0);
+                let mut a = vec![zero; m];
+                for (a, (&x, &w)) in a.iter_mut().zip(buf.iter().zip(&chirp)) {
+                    *a = x * w;
+                }
+                let mut b = vec![zero; m];
+                b[0] = chirp[0].conj();
+                for k in 1..n {
+                    b[k] = chirp[k].conj();
+                    b[m - k] = b[k];
+                }
+                radix_2(&mut a, false);
+                radix_2(&mut b, false);
+                for (a, &b) in a.iter_mut().zip(&b) {
+                    *a *= b;
+                }
+                radix_2(&mut a, true);
+                let scale = 1.0 / m as $t;
+                for (x, (&a, &w)) in buf.iter_mut().zip(a.iter().zip(&chirp)) {
+                    *x = a * w * scale;
+                }
+            }
+
+            #[cfg(test)]
+            mod test {
+                use super::*;
+
+                fn dft(x: &[Complex<$t>]) -> Vec<Complex<$t>> {
+                    use std::$t::consts::TAU;
+                    let n = x.len();
+                    (0..n).map(|k| {
+                        x.iter().enumerate().fold(Complex::new(0.0, 0.0), |sum, (j, &x)| {
+                            let angle = -TAU * ((j * k) % n) as $t / n as $t;
+                            sum + x * Complex::<$t>::cis(angle)
+                        })
+                    }).collect()
+                }
+
+                #[test]
+                fn check_fft() {
+                    for n in [1, 2, 3, 5, 8, 12, 16, 17, 64, 100] {
+                        let x: Vec<Complex<$t>> = (0..n)
+                            .map(|k| Complex::new((k as $t * 0.7).sin(), (k as $t * 1.3).cos()))
+                            .collect();
+                        let mut y = x.clone();
+                        fft(&mut y);
+                        let expected = dft(&x);
+                        let ep = 100.0 * n as $t * $t::EPSILON;
+                        for (&y, &e) in y.iter().zip(&expected) {
+                            assert!((y - e).abs() <= ep, "n = {n}, {y} != {e}");
+                        }
+                        ifft(&mut y);
+                        for (&y, &x) in y.iter().zip(&x) {
+                            assert!((y - x).abs() <= ep, "n = {n}, {y} != {x}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+fft_mod!(c32 for f32);
+fft_mod!(c64 for f64);
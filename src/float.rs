@@ -0,0 +1,239 @@
+//! The real number types of the elementary functions of [`Complex`](crate::Complex)
+//!
+//! The absolute value, phase angle, exponentials, logarithms, powers, roots
+//! and trigonometric functions of `Complex<T>` are implemented once for any
+//! `T` that is [`Float`].  Other float types, such as double-double,
+//! software or automatic differentiation floats, get all of them by
+//! implementing the trait.  `f32` and `f64` implement it with the same
+//! functions as the rest of the crate, so from `libm` with the
+//! `deterministic` feature or without `std`.
+//!
+//! # Example
+//! ```
+//! use imaginary::Complex;
+//! use imaginary::float::Float;
+//! // rotation by an angle, for any float type
+//! fn rotate<T: Float>(z: Complex<T>, theta: T) -> Complex<T> {
+//!     z * Complex::cis(theta)
+//! }
+//! let z = rotate(Complex::new(2.0f32, 0.0), 0.5);
+//! assert!((z - 2.0 * Complex::<f32>::cis(0.5)).abs() < 1e-6);
+//! ```
+
+use crate::{math, One};
+use core::ops::{Add, Div, Mul, Neg, Sub};
+
+/// A real floating-point type, with the constants and elementary functions
+/// that the methods of [`Complex`](crate::Complex) are built from
+///
+/// The functions should follow IEEE 754 and the C standard library for
+/// special values, such as infinities, `NAN`s and signed zeros, as the
+/// complex functions rely on them for their branch cuts and limits.
+pub trait Float: One + Default + PartialOrd + Neg<Output=Self> +
+                 Add<Output=Self> + Sub<Output=Self> + Mul<Output=Self> + Div<Output=Self> {
+    /// zero
+    const ZERO: Self;
+    /// positive infinity
+    const INFINITY: Self;
+    /// π
+    const PI: Self;
+    /// 1/√2
+    const FRAC_1_SQRT_2: Self;
+    /// log₂ e
+    const LOG2_E: Self;
+    /// log₁₀ e
+    const LOG10_E: Self;
+
+    /// The nearest value to `x`, for the constants of approximations
+    fn from_f64(x: f64) -> Self;
+    /// absolute value
+    fn abs(self) -> Self;
+    /// the magnitude of `self` with the sign of `sign`
+    fn copysign(self, sign: Self) -> Self;
+    /// whether `self` is `NAN`
+    fn is_nan(self) -> bool;
+    /// whether `self` is positive or negative infinity
+    fn is_infinite(self) -> bool;
+    /// square root
+    fn sqrt(self) -> Self;
+    /// cube root
+    fn cbrt(self) -> Self;
+    /// exponential, e<sup>x</sup>
+    fn exp(self) -> Self;
+    /// natural logarithm
+    fn ln(self) -> Self;
+    /// base 2 logarithm
+    fn log2(self) -> Self;
+    /// base 10 logarithm
+    fn log10(self) -> Self;
+    /// power, x<sup>n</sup>
+    fn powf(self, n: Self) -> Self;
+    /// √(x² + y²) without overflow or underflow
+    fn hypot(self, other: Self) -> Self;
+    /// the four-quadrant arctangent of `self` / `x`
+    fn atan2(self, x: Self) -> Self;
+    /// sine
+    fn sin(self) -> Self;
+    /// cosine
+    fn cos(self) -> Self;
+    /// tangent
+    fn tan(self) -> Self;
+    /// hyperbolic sine
+    fn sinh(self) -> Self;
+    /// hyperbolic cosine
+    fn cosh(self) -> Self;
+    /// hyperbolic tangent
+    fn tanh(self) -> Self;
+    /// nearest integer, halfway cases away from zero
+    fn round(self) -> Self;
+    /// largest integer not above `self`
+    fn floor(self) -> Self;
+    /// smallest integer not below `self`
+    fn ceil(self) -> Self;
+    /// integer part
+    fn trunc(self) -> Self;
+}
+
+macro_rules! impl_float {
+    ($m: ident for $t: ident) => {
+        impl Float for $t {
+            const ZERO: $t = 0.0;
+            const INFINITY: $t = $t::INFINITY;
+            const PI: $t = core::$t::consts::PI;
+            const FRAC_1_SQRT_2: $t = core::$t::consts::FRAC_1_SQRT_2;
+            const LOG2_E: $t = core::$t::consts::LOG2_E;
+            const LOG10_E: $t = core::$t::consts::LOG10_E;
+
+            fn from_f64(x: f64) -> $t {
+                x as $t
+            }
+            fn abs(self) -> $t {
+                <$t>::abs(self)
+            }
+            fn copysign(self, sign: $t) -> $t {
+                <$t>::copysign(self, sign)
+            }
+            fn is_nan(self) -> bool {
+                <$t>::is_nan(self)
+            }
+            fn is_infinite(self) -> bool {
+                <$t>::is_infinite(self)
+            }
+            impl_float!(@math $m $t, sqrt, cbrt, exp, ln, log2, log10, sin, cos, tan, sinh, cosh, tanh,
+                        round, floor, ceil, trunc; powf(n), hypot(other), atan2(x));
+        }
+    };
+    (@math $m: ident $t: ident, $($unary: ident),*; $($binary: ident($arg: ident)),*) => {
+        $(
+            #[inline]
+            fn $unary(self) -> $t {
+                math::$m::$unary(self)
+            }
+        )*
+        $(
+            #[inline]
+            fn $binary(self, $arg: $t) -> $t {
+                math::$m::$binary(self, $arg)
+            }
+        )*
+    }
+}
+impl_float!(c32 for f32);
+impl_float!(c64 for f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Complex;
+
+    /// An f64 behind a wrapper, standing in for a float type from
+    /// another crate
+    #[derive(Copy, Clone, Debug, Default, PartialEq, PartialOrd)]
+    struct Wrapped(f64);
+
+    impl core::fmt::Display for Wrapped {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result { self.0.fmt(f) }
+    }
+    impl Neg for Wrapped {
+        type Output = Wrapped;
+        fn neg(self) -> Wrapped { Wrapped(-self.0) }
+    }
+    impl Add for Wrapped {
+        type Output = Wrapped;
+        fn add(self, rhs: Wrapped) -> Wrapped { Wrapped(self.0 + rhs.0) }
+    }
+    impl Sub for Wrapped {
+        type Output = Wrapped;
+        fn sub(self, rhs: Wrapped) -> Wrapped { Wrapped(self.0 - rhs.0) }
+    }
+    impl Mul for Wrapped {
+        type Output = Wrapped;
+        fn mul(self, rhs: Wrapped) -> Wrapped { Wrapped(self.0 * rhs.0) }
+    }
+    impl Div for Wrapped {
+        type Output = Wrapped;
+        fn div(self, rhs: Wrapped) -> Wrapped { Wrapped(self.0 / rhs.0) }
+    }
+    impl One for Wrapped {
+        const ONE: Wrapped = Wrapped(1.0);
+    }
+
+    impl Float for Wrapped {
+        const ZERO: Wrapped = Wrapped(0.0);
+        const INFINITY: Wrapped = Wrapped(f64::INFINITY);
+        const PI: Wrapped = Wrapped(core::f64::consts::PI);
+        const FRAC_1_SQRT_2: Wrapped = Wrapped(core::f64::consts::FRAC_1_SQRT_2);
+        const LOG2_E: Wrapped = Wrapped(core::f64::consts::LOG2_E);
+        const LOG10_E: Wrapped = Wrapped(core::f64::consts::LOG10_E);
+
+        fn from_f64(x: f64) -> Wrapped { Wrapped(x) }
+        fn abs(self) -> Wrapped { Wrapped(Float::abs(self.0)) }
+        fn copysign(self, sign: Wrapped) -> Wrapped { Wrapped(Float::copysign(self.0, sign.0)) }
+        fn is_nan(self) -> bool { self.0.is_nan() }
+        fn is_infinite(self) -> bool { self.0.is_infinite() }
+        fn sqrt(self) -> Wrapped { Wrapped(Float::sqrt(self.0)) }
+        fn cbrt(self) -> Wrapped { Wrapped(Float::cbrt(self.0)) }
+        fn exp(self) -> Wrapped { Wrapped(Float::exp(self.0)) }
+        fn ln(self) -> Wrapped { Wrapped(Float::ln(self.0)) }
+        fn log2(self) -> Wrapped { Wrapped(Float::log2(self.0)) }
+        fn log10(self) -> Wrapped { Wrapped(Float::log10(self.0)) }
+        fn powf(self, n: Wrapped) -> Wrapped { Wrapped(Float::powf(self.0, n.0)) }
+        fn hypot(self, other: Wrapped) -> Wrapped { Wrapped(Float::hypot(self.0, other.0)) }
+        fn atan2(self, x: Wrapped) -> Wrapped { Wrapped(Float::atan2(self.0, x.0)) }
+        fn sin(self) -> Wrapped { Wrapped(Float::sin(self.0)) }
+        fn cos(self) -> Wrapped { Wrapped(Float::cos(self.0)) }
+        fn tan(self) -> Wrapped { Wrapped(Float::tan(self.0)) }
+        fn sinh(self) -> Wrapped { Wrapped(Float::sinh(self.0)) }
+        fn cosh(self) -> Wrapped { Wrapped(Float::cosh(self.0)) }
+        fn tanh(self) -> Wrapped { Wrapped(Float::tanh(self.0)) }
+        fn round(self) -> Wrapped { Wrapped(Float::round(self.0)) }
+        fn floor(self) -> Wrapped { Wrapped(Float::floor(self.0)) }
+        fn ceil(self) -> Wrapped { Wrapped(Float::ceil(self.0)) }
+        fn trunc(self) -> Wrapped { Wrapped(Float::trunc(self.0)) }
+    }
+
+    #[test]
+    fn custom_float() {
+        let wrap = |z: Complex<f64>| Complex::new(Wrapped(z.r), Wrapped(z.i));
+        let same = |a: f64, b: f64| a == b || a.is_nan() && b.is_nan();
+        type Function<T> = fn(Complex<T>) -> Complex<T>;
+        let functions: [(Function<f64>, Function<Wrapped>); 12] = [
+            (Complex::exp, Complex::exp), (Complex::ln, Complex::ln), (Complex::log10, Complex::log10),
+            (Complex::sqrt, Complex::sqrt), (Complex::cbrt, Complex::cbrt), (Complex::sign, Complex::sign),
+            (Complex::sin, Complex::sin), (Complex::tan, Complex::tan), (Complex::coth, Complex::coth),
+            (|z| z.powi(-3), |z| z.powi(-3)), (|z| z.powf(0.7), |z| z.powf(Wrapped(0.7))),
+            (Complex::round, Complex::round)
+        ];
+        for z in [Complex::new(0.5, -1.25), Complex::new(-3.0, 0.0), Complex::new(-2.5, -0.0), Complex::new(f64::INFINITY, 1.0)] {
+            for (f, g) in functions {
+                let (expected, got) = (f(z), g(wrap(z)));
+                assert!(same(expected.r, got.r.0) && same(expected.i, got.i.0), "{z:?}: {expected:?} {got:?}");
+            }
+            assert_eq!(Wrapped(z.abs()), wrap(z).abs());
+            assert_eq!(Wrapped(z.angle_approx()), wrap(z).angle_approx());
+        }
+        // displayed as for f64
+        let z = Complex::new(-3.0, 4.0);
+        assert_eq!(format!("{:.3}", wrap(z)), format!("{z:.3}"));
+    }
+}
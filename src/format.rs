@@ -0,0 +1,177 @@
+//! Options for formatting complex numbers
+//!
+//! [`Complex::display`] wraps a number with options for how
+//! [`Display`](core::fmt::Display) writes it, such as in polar form, or
+//! with `j` for the imaginary unit as electrical engineers write.  The
+//! flags of the format, precision, `+`, width, fill and alignment, apply
+//! as they do to the number itself.  The alternate flag, as in `{:#}`,
+//! writes a wrapped number in the default [`PolarStyle`] unless a form has
+//! been chosen.
+//!
+//! # Example
+//! ```
+//! use imaginary::Complex;
+//! use imaginary::format::PolarStyle;
+//! let z = Complex::<f64>::new(3.0, 4.0);
+//! assert_eq!(format!("{:#.3}", z.display()), "5.000∠0.927");
+//! assert_eq!(format!("{:.3}", z.display().polar(PolarStyle::Exp)), "5.000*e^(0.927i)");
+//! assert_eq!(z.display().unit('j').mul_sign("").to_string(), "3 + 4j");
+//! ```
+
+use crate::Complex;
+
+/// How polar form is written
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum PolarStyle {
+    /// The magnitude and the angle in radians, `5∠0.927`
+    #[default]
+    Angle,
+    /// As an exponential, `5*e^(0.927i)`
+    Exp
+}
+
+/// A complex number with options for how it is displayed
+///
+/// Made by [`Complex::display`], and written in rectangular form as
+/// `a + b*i` by default, or in polar form with the alternate flag.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Formatted<T: Copy> {
+    z: Complex<T>,
+    polar: Option<PolarStyle>,
+    unit: char,
+    mul_sign: &'static str
+}
+
+impl<T: Copy> Formatted<T> {
+    /// Writes the number in polar form, in the given style
+    pub fn polar(self, style: PolarStyle) -> Formatted<T> {
+        Formatted { polar: Some(style), ..self }
+    }
+
+    /// Writes the number in rectangular form, `a + b*i`
+    pub fn rectangular(self) -> Formatted<T> {
+        Formatted { polar: None, ..self }
+    }
+
+    /// Writes the imaginary unit as `unit`, such as `'j'`, rather than `i`
+    ///
+    /// Both `i` and `j` are accepted when parsing.
+    pub fn unit(self, unit: char) -> Formatted<T> {
+        Formatted { unit, ..self }
+    }
+
+    /// Writes `mul_sign` between a component and the imaginary unit,
+    /// rather than `*`, such as `""` for `4j` or `"·"`
+    pub fn mul_sign(self, mul_sign: &'static str) -> Formatted<T> {
+        Formatted { mul_sign, ..self }
+    }
+}
+
+/// Writes the magnitude `abs` and the angle `angle` in polar form, with
+/// `part` writing each as for [`Complex::write_parts`]
+#[allow(clippy::too_many_arguments)]
+fn write_polar<T>(
+    w: &mut dyn core::fmt::Write, abs: T, angle: T, style: PolarStyle, plus: bool, unit: char, mul_sign: &str,
+    part: &dyn Fn(&mut dyn core::fmt::Write, &T, bool) -> core::fmt::Result
+) -> core::fmt::Result {
+    part(w, &abs, plus)?;
+    match style {
+        PolarStyle::Angle => w.write_str("∠")?,
+        PolarStyle::Exp => {
+            w.write_str(mul_sign)?;
+            w.write_str("e^(")?
+        }
+    }
+    part(w, &angle, false)?;
+    match style {
+        PolarStyle::Angle => Ok(()),
+        PolarStyle::Exp => {
+            w.write_char(unit)?;
+            w.write_char(')')
+        }
+    }
+}
+
+/// Writes `x` with the precision and `+` flag of a format
+fn write_part<T: core::fmt::Display>(w: &mut dyn core::fmt::Write, x: T, precision: Option<usize>, plus: bool) -> core::fmt::Result {
+    match (precision, plus) {
+        (Some(p), true) => write!(w, "{x:+.p$}"),
+        (Some(p), false) => write!(w, "{x:.p$}"),
+        (None, true) => write!(w, "{x:+}"),
+        (None, false) => write!(w, "{x}")
+    }
+}
+
+macro_rules! impl_formatted {
+    ($t: ty, $write: path) => {
+        impl Complex<$t> {
+            /// Wraps `self` with options for how it is displayed, see the
+            /// [`format`](mod@crate::format) module
+            pub fn display(self) -> Formatted<$t> {
+                Formatted { z: self, polar: None, unit: 'i', mul_sign: "*" }
+            }
+        }
+
+        impl core::fmt::Display for Formatted<$t> {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                let (precision, plus) = (f.precision(), f.sign_plus());
+                let part = |w: &mut dyn core::fmt::Write, x: &$t, plus: bool| $write(w, *x, precision, plus);
+                let Formatted { z, unit, mul_sign, .. } = *self;
+                match self.polar.or(f.alternate().then_some(PolarStyle::default())) {
+                    None => {
+                        let is_unit = |x: &$t| crate::writes_unit("1", |w| $write(w, *x, None, false));
+                        crate::pad(f, |w| z.write_parts(w, plus, unit, mul_sign, &part, &is_unit))
+                    }
+                    Some(style) => crate::pad(f, |w| write_polar(w, z.abs(), z.angle(), style, plus, unit, mul_sign, &part))
+                }
+            }
+        }
+    }
+}
+impl_formatted!(f32, write_part);
+impl_formatted!(f64, write_part);
+#[cfg(feature = "half")]
+impl_formatted!(::half::f16, write_part);
+#[cfg(feature = "half")]
+impl_formatted!(::half::bf16, write_part);
+#[cfg(feature = "f128")]
+impl_formatted!(f128, crate::c128::write_decimal);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::string::ToString;
+
+    #[test]
+    fn polar() {
+        let z = Complex::<f64>::new(-1.0, -1.0);
+        assert_eq!(format!("{:.2}", z.display().polar(PolarStyle::Angle)), "1.41∠-2.36");
+        assert_eq!(format!("{:.2}", z.display().polar(PolarStyle::Exp)), "1.41*e^(-2.36i)");
+        assert_eq!(format!("{:+.1}", z.display().polar(PolarStyle::Exp)), "+1.4*e^(-2.4i)");
+        assert_eq!(format!("{:>12.1}|", z.display().polar(PolarStyle::Angle)), "    1.4∠-2.4|");
+        assert_eq!(format!("{:#}", Complex::<f32>::new(2.0, 0.0).display()), "2∠0");
+        assert_eq!(format!("{:#.2}", z.display()), format!("{:.2}", z.display().polar(PolarStyle::default())));
+        assert_eq!(format!("{:#.2}", z.display().polar(PolarStyle::Exp)), "1.41*e^(-2.36i)");
+        assert_eq!(z.display().polar(PolarStyle::Exp).rectangular().to_string(), z.to_string());
+        assert_eq!(z.display().to_string(), "-1 - i");
+        // the alternate flag leaves unwrapped numbers in rectangular form
+        assert_eq!(format!("{z:#}"), z.to_string());
+        assert_eq!(format!("{:#}", Complex::new(3, 4)), "3 + 4*i");
+    }
+
+    #[test]
+    fn unit() {
+        let z = Complex::<f64>::new(3.0, -4.0);
+        let engineer = |z: Complex<f64>| z.display().unit('j').mul_sign("");
+        assert_eq!(engineer(z).to_string(), "3 - 4j");
+        assert_eq!(format!("{:+.1}", engineer(Complex::new(0.0, 1.0))), "+j");
+        assert_eq!(format!("{:>6}", engineer(Complex::new(0.0, -2.5))), " -2.5j");
+        assert_eq!(engineer(Complex::new(-1.0, 1.0)).to_string(), "-1 + j");
+        assert_eq!(format!("{:.1}", engineer(z).polar(PolarStyle::Exp)), "5.0e^(-0.9j)");
+        assert_eq!(z.display().mul_sign("·").to_string(), "3 - 4·i");
+        for z in [z, Complex::new(0.0, 1.0), Complex::new(-0.5, -1.0), Complex::new(2.0, 0.0)] {
+            assert_eq!(engineer(z).to_string().parse(), Ok(z));
+            assert_eq!(z.display().unit('j').to_string().parse(), Ok(z));
+        }
+    }
+}
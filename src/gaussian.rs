@@ -0,0 +1,371 @@
+//! Gaussian integers, the complex numbers with integer parts
+//!
+//! `Complex<i32>` and `Complex<i64>` are the ring Z\[i\] with the generic
+//! arithmetic of [`Complex`], and this module adds its number theory: the
+//! [`norm`](Complex::norm), Euclidean division with the smallest remainder
+//! by [`div_rem`](Complex::div_rem), the [`gcd`](Complex::gcd), the
+//! normalization of associates into the first quadrant, and a test for
+//! Gaussian primes.  The norm is in the next wider unsigned integer type,
+//! so it does not overflow for any parts.
+//!
+//! # Example
+//! ```
+//! use imaginary::Complex;
+//! // 5 = (2 + i)(2 - i) shares the factor 2 - i with 3 + i = (1 + i)(2 - i)
+//! let gcd = Complex::<i64>::new(5, 0).gcd(Complex::new(3, 1));
+//! assert_eq!(gcd, Complex::new(1, 2));
+//! assert_eq!(gcd.norm(), 5);
+//! assert!(gcd.is_gaussian_prime());
+//! assert!(!Complex::<i64>::new(5, 0).is_gaussian_prime());
+//! ```
+
+use crate::Complex;
+
+macro_rules! impl_gaussian {
+    ($t: ident as $w: ident, $u: ident) => {
+        impl Complex<$t> {
+            /// The norm, r² + i², the square of the absolute value, in the
+            /// wider unsigned integer type
+            ///
+            /// # Example
+            /// ```
+            /// use imaginary::Complex;
+            #[doc = concat!("let z = Complex::<", stringify!($t), ">::new(", stringify!($t), "::MIN, 1);")]
+            #[doc = concat!("assert_eq!(z.norm(), (", stringify!($t), "::MIN.unsigned_abs() as ", stringify!($u), ").pow(2) + 1);")]
+            /// ```
+            pub fn norm(self) -> $u {
+                let (r, i) = (self.r.unsigned_abs() as $u, self.i.unsigned_abs() as $u);
+                r * r + i * i
+            }
+
+            /// Whether `self` is one of the units, 1, i, −1 and −i
+            pub fn is_unit(self) -> bool {
+                self.norm() == 1
+            }
+
+            /// The associate of `self` in the first quadrant, with a
+            /// positive real part and a non-negative imaginary part, which
+            /// is the product of `self` with one of the units
+            ///
+            /// Zero is its own only associate.
+            /// # Example
+            /// ```
+            /// use imaginary::Complex;
+            #[doc = concat!("assert_eq!(Complex::<", stringify!($t), ">::new(-2, 3).normalize(), Complex::new(3, 2));")]
+            #[doc = concat!("assert_eq!(Complex::<", stringify!($t), ">::new(0, -4).normalize(), Complex::new(4, 0));")]
+            /// ```
+            pub fn normalize(self) -> Complex<$t> {
+                match (self.r, self.i) {
+                    (r, i) if r > 0 && i >= 0 => self,
+                    (r, i) if r <= 0 && i > 0 => self.mul_neg_i(),
+                    (r, i) if r < 0 && i <= 0 => -self,
+                    (0, 0) => self,
+                    _ => self.mul_i()
+                }
+            }
+
+            /// Euclidean division, the quotient `q` and the remainder
+            /// `self - rhs * q`, with `q` the nearest Gaussian integer to
+            /// `self / rhs` so that the norm of the remainder is at most
+            /// half the norm of `rhs`
+            ///
+            /// The `%` operator of the `num-traits` feature truncates the
+            /// quotient instead, and its remainder can be larger.
+            ///
+            /// # Panics
+            /// `div_rem` panics when `rhs` is zero, and on overflow as integer
+            /// arithmetic does, which only parts of `MIN` can cause.
+            ///
+            /// # Example
+            /// ```
+            /// use imaginary::Complex;
+            #[doc = concat!("let (a, b) = (Complex::<", stringify!($t), ">::new(27, -4), Complex::new(3, 5));")]
+            /// let (q, r) = a.div_rem(b);
+            /// assert_eq!((q, r), (Complex::new(2, -4), Complex::new(1, -2)));
+            /// assert_eq!(b * q + r, a);
+            /// assert!(2 * r.norm() <= b.norm());
+            /// ```
+            pub fn div_rem(self, rhs: Complex<$t>) -> (Complex<$t>, Complex<$t>) {
+                let (a, b, c, e) = (self.r as $w, self.i as $w, rhs.r as $w, rhs.i as $w);
+                let d = c * c + e * e;
+                // the nearest integer to n / d, for d > 0
+                let nearest = |n: $w| {
+                    let (q, r) = (n.div_euclid(d), n.rem_euclid(d));
+                    (if r > d - r { q + 1 } else { q }) as $t
+                };
+                let q = Complex::new(nearest(a * c + b * e), nearest(b * c - a * e));
+                (q, self - rhs * q)
+            }
+
+            /// The greatest common divisor of `self` and `other`, by
+            /// Euclid's algorithm, normalized into the first quadrant as by
+            /// [`normalize`](Complex::normalize)
+            ///
+            /// The gcd of zero and zero is zero.
+            /// # Example
+            /// ```
+            /// use imaginary::Complex;
+            #[doc = concat!("let a = Complex::<", stringify!($t), ">::new(2, 1) * Complex::new(3, 2);")]
+            /// let b = Complex::new(2, 1) * Complex::new(1, -4);
+            /// assert_eq!(a.gcd(b), Complex::new(2, 1));
+            /// assert_eq!(a.gcd(Complex::new(0, 0)), a.normalize());
+            /// ```
+            pub fn gcd(self, other: Complex<$t>) -> Complex<$t> {
+                let (mut a, mut b) = (self, other);
+                while b != Complex::new(0, 0) {
+                    (a, b) = (b, a.div_rem(b).1);
+                }
+                a.normalize()
+            }
+
+            /// Whether `self` is a Gaussian prime, with no factors other
+            /// than the units and its associates
+            ///
+            /// These are the numbers with a prime norm, and the associates
+            /// of the primes of the integers that are 3 mod 4.  The norms
+            /// are tested by the Baillie–PSW test, which has no known
+            /// counterexample and is certain for norms below 2⁶⁴, and so
+            /// for every `Complex<i32>`.
+            /// # Example
+            /// ```
+            /// use imaginary::Complex;
+            #[doc = concat!("assert!(Complex::<", stringify!($t), ">::new(1, 1).is_gaussian_prime());")]
+            #[doc = concat!("assert!(Complex::<", stringify!($t), ">::new(0, -7).is_gaussian_prime());")]
+            #[doc = concat!("assert!(!Complex::<", stringify!($t), ">::new(13, 0).is_gaussian_prime());")]
+            /// ```
+            pub fn is_gaussian_prime(self) -> bool {
+                match (self.r, self.i) {
+                    (0, n) | (n, 0) => {
+                        let n = n.unsigned_abs() as u128;
+                        n % 4 == 3 && is_prime(n)
+                    }
+                    _ => is_prime(self.norm() as u128)
+                }
+            }
+        }
+    };
+}
+impl_gaussian!(i32 as i64, u64);
+impl_gaussian!(i64 as i128, u128);
+
+/// The small primes, by which `n` is divided before the probable prime
+/// tests
+const SMALL_PRIMES: [u128; 13] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41];
+
+/// Whether `n` is prime, by the Baillie–PSW test: trial division by the
+/// [`SMALL_PRIMES`], then the strong probable prime test to base 2 and the
+/// strong Lucas probable prime test
+///
+/// No composite is known to pass both tests, and there is none below 2⁶⁴.
+/// `n` must be at most 2¹²⁷, as the norms are.
+fn is_prime(n: u128) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for p in SMALL_PRIMES {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    is_strong_probable_prime(n, 2) && is_strong_lucas_probable_prime(n)
+}
+
+/// The strong probable prime test of odd `n` to base `a`, of Miller–Rabin
+fn is_strong_probable_prime(n: u128, a: u128) -> bool {
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    let mut x = pow_mod(a, d, n);
+    if x == 1 || x == n - 1 {
+        return true;
+    }
+    for _ in 1..s {
+        x = mul_mod(x, x, n);
+        if x == n - 1 {
+            return true;
+        }
+    }
+    false
+}
+
+/// The strong Lucas probable prime test of odd `n` with no small factors,
+/// with the parameters P = 1 and Q = (1 − D)/4 of Selfridge, D the first
+/// of 5, −7, 9, −11, … with the Jacobi symbol (D/n) = −1
+fn is_strong_lucas_probable_prime(n: u128) -> bool {
+    // there is no such D for a square
+    if n.isqrt().pow(2) == n {
+        return false;
+    }
+    // D and Q as residues mod n
+    let residue = |x: i64| if x < 0 { n - x.unsigned_abs() as u128 % n } else { x as u128 % n };
+    let mut d = 5i64;
+    while jacobi(residue(d), n) != -1 {
+        d = if d > 0 { -(d + 2) } else { 2 - d };
+    }
+    let (d, q) = (residue(d), residue((1 - d) / 4));
+    // x/2 mod n, for odd n
+    let half = |x: u128| if x & 1 == 0 { x >> 1 } else { (x >> 1) + (n >> 1) + 1 };
+    let add_mod = |a: u128, b: u128| if a >= n - b { a - (n - b) } else { a + b };
+    let sub_mod = |a: u128, b: u128| if a >= b { a - b } else { a + (n - b) };
+    // U_k, V_k and Q^k for k the leading bits of n + 1 = d 2^s, d odd
+    let s = (n + 1).trailing_zeros();
+    let odd = (n + 1) >> s;
+    let (mut u, mut v, mut qk) = (1, 1, q);
+    for bit in (0..odd.ilog2()).rev() {
+        (u, v, qk) = (mul_mod(u, v, n), sub_mod(mul_mod(v, v, n), add_mod(qk, qk)), mul_mod(qk, qk, n));
+        if odd >> bit & 1 == 1 {
+            (u, v, qk) = (half(add_mod(u, v)), half(add_mod(mul_mod(d, u, n), v)), mul_mod(qk, q, n));
+        }
+    }
+    if u == 0 || v == 0 {
+        return true;
+    }
+    // V_(d 2^r) for r < s
+    for _ in 1..s {
+        (v, qk) = (sub_mod(mul_mod(v, v, n), add_mod(qk, qk)), mul_mod(qk, qk, n));
+        if v == 0 {
+            return true;
+        }
+    }
+    false
+}
+
+/// The Jacobi symbol (a/n) of odd n
+fn jacobi(mut a: u128, mut n: u128) -> i32 {
+    let mut sign = 1;
+    a %= n;
+    while a != 0 {
+        let twos = a.trailing_zeros();
+        a >>= twos;
+        if twos & 1 == 1 && matches!(n % 8, 3 | 5) {
+            sign = -sign;
+        }
+        if a % 4 == 3 && n % 4 == 3 {
+            sign = -sign;
+        }
+        (a, n) = (n % a, a);
+    }
+    if n == 1 { sign } else { 0 }
+}
+
+/// a<sup>e</sup> mod n, by repeated squaring
+fn pow_mod(mut a: u128, mut e: u128, n: u128) -> u128 {
+    let mut result = 1;
+    while e > 0 {
+        if e & 1 == 1 {
+            result = mul_mod(result, a, n);
+        }
+        a = mul_mod(a, a, n);
+        e >>= 1;
+    }
+    result
+}
+
+/// a b mod n for a, b < n, by doubling and adding when the product could
+/// overflow
+fn mul_mod(mut a: u128, mut b: u128, n: u128) -> u128 {
+    if n <= u64::MAX as u128 {
+        return a * b % n;
+    }
+    // a + b mod n without overflow, for a, b < n
+    let add_mod = |a: u128, b: u128| if a >= n - b { a - (n - b) } else { a + b };
+    let mut product = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            product = add_mod(product, a);
+        }
+        a = add_mod(a, a);
+        b >>= 1;
+    }
+    product
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::vec::Vec;
+
+    #[test]
+    fn euclidean_division() {
+        for a in [Complex::new(27i64, -4), Complex::new(-13, 8), Complex::new(0, 0), Complex::new(i64::MAX >> 2, i64::MIN >> 2)] {
+            for b in [Complex::new(3i64, 5), Complex::new(-2, 0), Complex::new(0, -1), Complex::new(1 << 40, 7)] {
+                let (q, r) = a.div_rem(b);
+                assert_eq!(b * q + r, a, "{a} / {b}");
+                assert!(2 * r.norm() <= b.norm(), "{a} / {b}");
+            }
+        }
+        // the remainder of the operator, with a truncated quotient, is
+        // larger
+        let (a, b) = (Complex::new(7i32, 7), Complex::new(2, 0));
+        #[cfg(feature = "num-traits")]
+        assert_eq!(a % b, Complex::new(1, 1));
+        assert_eq!(a.div_rem(b).1.norm(), 2);
+        assert_eq!(Complex::new(5i32, 0).div_rem(Complex::new(2, 0)), (Complex::new(2, 0), Complex::new(1, 0)));
+        assert_eq!(Complex::new(i32::MIN, i32::MIN).norm(), 1 << 63);
+    }
+
+    #[test]
+    fn normalize_and_gcd() {
+        let z = Complex::new(3i32, 1);
+        for k in 0..4 {
+            assert_eq!(z.rotate_quarter_turns(k).normalize(), z);
+            assert_eq!(Complex::new(0i32, 5).rotate_quarter_turns(k).normalize(), Complex::new(5, 0));
+        }
+        assert!(Complex::new(0i64, -1).is_unit());
+        assert!(!Complex::new(1i64, 1).is_unit());
+        assert_eq!(Complex::new(0i32, 0).gcd(Complex::new(0, 0)), Complex::new(0, 0));
+        assert_eq!(Complex::new(0i32, 0).gcd(Complex::new(0, -3)), Complex::new(3, 0));
+        assert_eq!(Complex::new(4i32, 0).gcd(Complex::new(6, 0)), Complex::new(2, 0));
+        // coprime factors
+        let (p, q) = (Complex::new(3i64, 2), Complex::new(2, 3));
+        assert!(p.gcd(q).is_unit());
+        assert_eq!((p * p * q).gcd(p * q * q), (p * q).normalize());
+    }
+
+    #[test]
+    fn gaussian_primes() {
+        let primes: Vec<_> = (0..=6).flat_map(|r| (0..=6).map(move |i| Complex::new(r, i)))
+            .filter(|z: &Complex<i32>| z.is_gaussian_prime()).collect();
+        assert_eq!(primes, [(0, 3), (1, 1), (1, 2), (1, 4), (1, 6), (2, 1), (2, 3), (2, 5), (3, 0), (3, 2),
+                            (4, 1), (4, 5), (5, 2), (5, 4), (5, 6), (6, 1), (6, 5)].map(Complex::from));
+        for z in primes {
+            assert!(z.conj().is_gaussian_prime() && (-z).mul_i().is_gaussian_prime());
+        }
+        assert!(!Complex::new(0i32, 0).is_gaussian_prime() && !Complex::new(-1i32, 0).is_gaussian_prime());
+        assert!(Complex::new(i32::MAX, 0).is_gaussian_prime());
+        // norms of 125 bits
+        let p = Complex::new(4611686018427387911i64, 1490116119384765650);
+        assert_eq!(p.norm(), 23488093981808967186377586527888866421);
+        assert!(p.is_gaussian_prime());
+        assert!(!Complex::new(p.r, p.i + 2).is_gaussian_prime());
+        assert!(Complex::new(0i64, (1 << 61) - 1).is_gaussian_prime());
+        // a strong pseudoprime to bases 2 to 37, though not to 41
+        assert!(!is_prime(318665857834031151167461));
+        // a strong pseudoprime to the first 13 prime bases
+        assert!(!is_prime(3317044064679887385961981));
+    }
+
+    #[test]
+    fn baillie_psw() {
+        let mut sieve = vec![true; 100_000];
+        sieve[..2].fill(false);
+        for p in 2..sieve.len() {
+            if sieve[p] {
+                (p * p..sieve.len()).step_by(p).for_each(|k| sieve[k] = false);
+            }
+        }
+        assert!(sieve.iter().enumerate().all(|(n, &prime)| is_prime(n as u128) == prime));
+        // each test rejects the strong pseudoprimes of the other
+        for n in [2047, 3277, 4033, 3215031751, 2152302898747, 318665857834031151167461] {
+            assert!(is_strong_probable_prime(n, 2) && !is_strong_lucas_probable_prime(n), "{n}");
+        }
+        for n in [5459, 5777, 10877, 16109, 18971] {
+            assert!(is_strong_lucas_probable_prime(n) && !is_strong_probable_prime(n, 2), "{n}");
+        }
+        // squares, and products of primes of 61 and 63 bits
+        let (p, q) = ((1u128 << 61) - 1, 9223372036854775783);
+        assert!(is_prime(p) && is_prime(q));
+        assert!(!is_prime(p * p) && !is_prime(p * q) && !is_prime(q * q));
+        assert!(is_prime(170141183460469231731687303715884105727));
+        assert!(!is_prime(1 << 127));
+    }
+}
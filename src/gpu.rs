@@ -0,0 +1,585 @@
+//! Bulk operations on complex buffers on the GPU
+//!
+//! A [`Gpu`] holds a device and the compute pipelines for its operations.
+//! Data is uploaded once into a [`GpuBuffer`], any number of operations run
+//! on the device without copying back, and the result is downloaded at the
+//! end.  The operations mirror those of the [`slice`](crate::slice) and
+//! [`fft`](crate::fft) modules: element-wise products, `cis` ramps, sums and
+//! power of 2 Fourier transforms.  Operations are queued and run in order,
+//! and only downloads and sums wait for the device.
+//!
+//! GPUs work in single precision, so buffers hold `Complex<f32>`.  Twiddle
+//! factors and ramp phases are computed exactly enough that the results
+//! match the CPU versions to the accuracy of the device's `sin` and `cos`.
+//!
+//! Requires the `wgpu` feature.
+//!
+//! # Example
+//! ```
+//! use imaginary::Complex;
+//! use imaginary::gpu::Gpu;
+//! // skip where there is no GPU, or software renderer, to run on
+//! let Ok(gpu) = Gpu::new() else { return };
+//! let mut signal = gpu.zeros(1 << 16);
+//! gpu.cis_ramp(&mut signal, 0.25, 0.0);
+//! gpu.fft(&mut signal);
+//! let spectrum = gpu.download(&signal);
+//! // a tone in bin 2¹⁶ / 8π, between 2607 and 2608
+//! let peak = (0..spectrum.len()).max_by(|&j, &k| spectrum[j].abs().total_cmp(&spectrum[k].abs())).unwrap();
+//! assert!(peak == 2607 || peak == 2608);
+//! ```
+
+use crate::Complex;
+use std::sync::Mutex;
+use wgpu::util::DeviceExt;
+
+/// Threads per workgroup of the element-wise kernels
+const WORKGROUP: u32 = 64;
+
+/// Elements summed by each workgroup of the reduction kernel
+const REDUCE: u32 = 512;
+
+/// Definitions shared by the kernels
+const COMMON: &str = "
+struct Params { n: u32, u0: u32, u1: u32, u2: u32, u3: u32, f0: f32, f1: f32, f2: f32 }
+
+@group(0) @binding(0) var<uniform> p: Params;
+
+fn cmul(a: vec2<f32>, b: vec2<f32>) -> vec2<f32> {
+    return vec2<f32>(a.x * b.x - a.y * b.y, a.x * b.y + a.y * b.x);
+}
+
+// workgroups are laid out in two dimensions for more than 65535 of them
+fn index(id: vec3<u32>, groups: vec3<u32>) -> u32 {
+    return id.x + id.y * groups.x * 64u;
+}
+";
+
+/// `out[k] = a[k] * b[k]` for `k < n`
+const MUL: &str = "
+@group(0) @binding(1) var<storage, read> a: array<vec2<f32>>;
+@group(0) @binding(2) var<storage, read> b: array<vec2<f32>>;
+@group(0) @binding(3) var<storage, read_write> out: array<vec2<f32>>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>, @builtin(num_workgroups) groups: vec3<u32>) {
+    let k = index(id, groups);
+    if (k < p.n) {
+        out[k] = cmul(a[k], b[k]);
+    }
+}
+";
+
+/// `out[k] = cis(2π (start + k inc) / 2⁶⁴)` for `k < n`, with the 64 bit
+/// phases `inc` = (u0, u1) and `start` = (u2, u3), high word first
+const CIS: &str = "
+@group(0) @binding(1) var<storage, read_write> out: array<vec2<f32>>;
+
+// the high word of the 64 bit product a b
+fn mulhi(a: u32, b: u32) -> u32 {
+    let a0 = a & 0xffffu;
+    let a1 = a >> 16u;
+    let b0 = b & 0xffffu;
+    let b1 = b >> 16u;
+    let p01 = a0 * b1;
+    let p10 = a1 * b0;
+    let mid = ((a0 * b0) >> 16u) + (p01 & 0xffffu) + (p10 & 0xffffu);
+    return a1 * b1 + (p01 >> 16u) + (p10 >> 16u) + (mid >> 16u);
+}
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>, @builtin(num_workgroups) groups: vec3<u32>) {
+    let k = index(id, groups);
+    if (k < p.n) {
+        let lo = k * p.u1 + p.u3;
+        let carry = select(0u, 1u, lo < p.u3);
+        let hi = k * p.u0 + mulhi(k, p.u1) + p.u2 + carry;
+        // the high word as a signed fraction of a turn, in [−π, π)
+        let theta = f32(bitcast<i32>(hi)) * 1.4629180792671596e-9;
+        out[k] = vec2<f32>(cos(theta), sin(theta));
+    }
+}
+";
+
+/// Sums of blocks of 512 elements of `input`, for `n` elements
+const SUM: &str = "
+@group(0) @binding(1) var<storage, read> input: array<vec2<f32>>;
+@group(0) @binding(2) var<storage, read_write> out: array<vec2<f32>>;
+
+var<workgroup> partial: array<vec2<f32>, 256>;
+
+@compute @workgroup_size(256)
+fn main(
+    @builtin(local_invocation_id) local: vec3<u32>,
+    @builtin(workgroup_id) group: vec3<u32>,
+    @builtin(num_workgroups) groups: vec3<u32>
+) {
+    let block = group.x + group.y * groups.x;
+    let k = block * 512u + local.x;
+    var s = vec2<f32>(0.0, 0.0);
+    if (k < p.n) {
+        s = input[k];
+    }
+    if (k + 256u < p.n) {
+        s = s + input[k + 256u];
+    }
+    partial[local.x] = s;
+    workgroupBarrier();
+    for (var stride = 128u; stride > 0u; stride = stride >> 1u) {
+        if (local.x < stride) {
+            partial[local.x] = partial[local.x] + partial[local.x + stride];
+        }
+        workgroupBarrier();
+    }
+    if (local.x == 0u) {
+        out[block] = partial[0];
+    }
+}
+";
+
+/// One radix-2 Stockham pass of a transform of length 2 `n`, combining
+/// transforms of length `u0` with the twiddles e<sup>∓πi t/n</sup>, for the
+/// inverse if `u1` is 1, and scaling by `f0`
+const FFT: &str = "
+@group(0) @binding(1) var<storage, read> input: array<vec2<f32>>;
+@group(0) @binding(2) var<storage, read_write> out: array<vec2<f32>>;
+@group(0) @binding(3) var<storage, read> twiddle: array<vec2<f32>>;
+
+@compute @workgroup_size(64)
+fn main(@builtin(global_invocation_id) id: vec3<u32>, @builtin(num_workgroups) groups: vec3<u32>) {
+    let j = index(id, groups);
+    if (j < p.n) {
+        let span = p.u0;
+        let k = j & (span - 1u);
+        var w = twiddle[k * (p.n / span)];
+        if (p.u1 == 1u) {
+            w.y = -w.y;
+        }
+        let a = input[j];
+        let b = cmul(input[j + p.n], w);
+        let at = (j - k) * 2u + k;
+        out[at] = (a + b) * p.f0;
+        out[at + span] = (a - b) * p.f0;
+    }
+}
+";
+
+/// The reasons a [`Gpu`] can't be created
+#[derive(Clone, Debug, PartialEq)]
+pub enum GpuError {
+    /// no adapter, hardware or software, is available
+    NoAdapter(String),
+    /// the adapter could not open a device
+    Device(String)
+}
+
+impl std::fmt::Display for GpuError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GpuError::NoAdapter(reason) => write!(f, "no GPU adapter is available: {reason}"),
+            GpuError::Device(reason) => write!(f, "could not open a GPU device: {reason}")
+        }
+    }
+}
+
+impl std::error::Error for GpuError {}
+
+/// A buffer of `Complex<f32>` on the GPU
+#[derive(Debug)]
+pub struct GpuBuffer {
+    buffer: wgpu::Buffer,
+    len: usize
+}
+
+impl GpuBuffer {
+    /// Number of elements
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether the buffer has no elements
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+/// A GPU device with the pipelines for operations on [`GpuBuffer`]s
+#[derive(Debug)]
+pub struct Gpu {
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    info: wgpu::AdapterInfo,
+    mul: wgpu::ComputePipeline,
+    cis: wgpu::ComputePipeline,
+    sum: wgpu::ComputePipeline,
+    fft: wgpu::ComputePipeline,
+    /// twiddle factors e<sup>−2πi t/N</sup>, t < N/2, by transform length N
+    twiddles: Mutex<Vec<(usize, wgpu::Buffer)>>
+}
+
+/// The size in bytes of `len` elements, at least one so that empty
+/// buffers can still be created
+fn byte_size(len: usize) -> u64 {
+    (len.max(1) * std::mem::size_of::<Complex<f32>>()) as u64
+}
+
+/// The bytes of `data`
+fn as_bytes(data: &[Complex<f32>]) -> &[u8] {
+    // SAFETY: Complex<f32> is repr(C), two f32 without padding, so every
+    // byte is initialized, and u8 has no alignment requirement
+    unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, std::mem::size_of_val(data)) }
+}
+
+/// Workgroup counts covering `groups` workgroups, in two dimensions if
+/// there are more than one dimension allows
+fn layout(groups: u32) -> (u32, u32) {
+    const MAX: u32 = 65535;
+    if groups <= MAX { (groups, 1) } else { (MAX, groups.div_ceil(MAX)) }
+}
+
+impl Gpu {
+    /// Opens the default GPU adapter, which may be a software renderer
+    ///
+    /// Blocks until the device is ready.
+    pub fn new() -> Result<Gpu, GpuError> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            power_preference: wgpu::PowerPreference::HighPerformance,
+            ..Default::default()
+        })).map_err(|e| GpuError::NoAdapter(e.to_string()))?;
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor {
+            label: Some("imaginary"),
+            required_limits: adapter.limits(),
+            ..Default::default()
+        })).map_err(|e| GpuError::Device(e.to_string()))?;
+        let pipeline = |name: &str, source: &str| {
+            let module = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+                label: Some(name),
+                source: wgpu::ShaderSource::Wgsl(format!("{COMMON}{source}").into())
+            });
+            device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+                label: Some(name),
+                layout: None,
+                module: &module,
+                entry_point: Some("main"),
+                compilation_options: Default::default(),
+                cache: None
+            })
+        };
+        Ok(Gpu {
+            mul: pipeline("mul", MUL),
+            cis: pipeline("cis", CIS),
+            sum: pipeline("sum", SUM),
+            fft: pipeline("fft", FFT),
+            info: adapter.get_info(),
+            device,
+            queue,
+            twiddles: Mutex::new(Vec::new())
+        })
+    }
+
+    /// The name, backend and type of the adapter in use
+    pub fn info(&self) -> &wgpu::AdapterInfo {
+        &self.info
+    }
+
+    /// A new buffer for `len` elements without initial contents
+    fn buffer(&self, len: usize) -> GpuBuffer {
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: None,
+            size: byte_size(len),
+            usage: wgpu::BufferUsages::STORAGE | wgpu::BufferUsages::COPY_SRC | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false
+        });
+        GpuBuffer { buffer, len }
+    }
+
+    /// A buffer of `len` zeros on the device
+    pub fn zeros(&self, len: usize) -> GpuBuffer {
+        // buffers are zeroed on creation
+        self.buffer(len)
+    }
+
+    /// Copies `data` into a new buffer on the device
+    pub fn upload(&self, data: &[Complex<f32>]) -> GpuBuffer {
+        let buffer = self.buffer(data.len());
+        self.queue.write_buffer(&buffer.buffer, 0, as_bytes(data));
+        buffer
+    }
+
+    /// Overwrites the contents of `buffer` with `data`
+    ///
+    /// # Panics
+    /// Panics if `data` differs in length from `buffer`.
+    pub fn write(&self, buffer: &mut GpuBuffer, data: &[Complex<f32>]) {
+        assert_eq!(buffer.len, data.len(), "data length differs from the buffer");
+        self.queue.write_buffer(&buffer.buffer, 0, as_bytes(data));
+    }
+
+    /// Copies the contents of `buffer` back from the device, waiting for
+    /// the operations queued on it to finish
+    pub fn download(&self, buffer: &GpuBuffer) -> Vec<Complex<f32>> {
+        let mut out = vec![Complex::new(0.0, 0.0); buffer.len];
+        self.download_into(buffer, &mut out);
+        out
+    }
+
+    /// Copies the contents of `buffer` into `out`, waiting for the
+    /// operations queued on it to finish
+    ///
+    /// # Panics
+    /// Panics if `out` differs in length from `buffer`.
+    pub fn download_into(&self, buffer: &GpuBuffer, out: &mut [Complex<f32>]) {
+        assert_eq!(buffer.len, out.len(), "output length differs from the buffer");
+        if out.is_empty() {
+            return;
+        }
+        let size = byte_size(out.len());
+        let staging = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("download"),
+            size,
+            usage: wgpu::BufferUsages::MAP_READ | wgpu::BufferUsages::COPY_DST,
+            mapped_at_creation: false
+        });
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        encoder.copy_buffer_to_buffer(&buffer.buffer, 0, &staging, 0, size);
+        self.queue.submit([encoder.finish()]);
+        let slice = staging.slice(..);
+        slice.map_async(wgpu::MapMode::Read, |result| result.expect("mapping a download buffer failed"));
+        self.device.poll(wgpu::PollType::Wait).expect("waiting for the GPU failed");
+        for (z, bytes) in out.iter_mut().zip(slice.get_mapped_range().chunks_exact(8)) {
+            let part = |k: usize| f32::from_ne_bytes(bytes[k..k + 4].try_into().unwrap());
+            *z = Complex::new(part(0), part(4));
+        }
+    }
+
+    /// A uniform buffer of kernel parameters
+    fn params(&self, n: u32, u: [u32; 4], f: [f32; 3]) -> wgpu::Buffer {
+        let words = [n, u[0], u[1], u[2], u[3], f[0].to_bits(), f[1].to_bits(), f[2].to_bits()];
+        let bytes: Vec<u8> = words.iter().flat_map(|w| w.to_ne_bytes()).collect();
+        self.device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+            label: Some("params"),
+            contents: &bytes,
+            usage: wgpu::BufferUsages::UNIFORM
+        })
+    }
+
+    /// Records a dispatch of `pipeline` over `groups` workgroups, binding
+    /// `buffers` from binding 0
+    fn dispatch(&self, encoder: &mut wgpu::CommandEncoder, pipeline: &wgpu::ComputePipeline, buffers: &[&wgpu::Buffer], groups: u32) {
+        let entries: Vec<wgpu::BindGroupEntry> = buffers.iter().enumerate()
+            .map(|(k, buffer)| wgpu::BindGroupEntry { binding: k as u32, resource: buffer.as_entire_binding() })
+            .collect();
+        let bind_group = self.device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: None,
+            layout: &pipeline.get_bind_group_layout(0),
+            entries: &entries
+        });
+        let mut pass = encoder.begin_compute_pass(&Default::default());
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        let (x, y) = layout(groups);
+        pass.dispatch_workgroups(x, y, 1);
+    }
+
+    /// Element-wise product, `out[k] = a[k] * b[k]`
+    ///
+    /// # Panics
+    /// Panics if the buffers differ in length.
+    pub fn mul(&self, a: &GpuBuffer, b: &GpuBuffer, out: &mut GpuBuffer) {
+        assert!(a.len == b.len && a.len == out.len, "buffer lengths differ");
+        if out.len == 0 {
+            return;
+        }
+        let n = out.len as u32;
+        let params = self.params(n, [0; 4], [0.0; 3]);
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        self.dispatch(&mut encoder, &self.mul, &[&params, &a.buffer, &b.buffer, &out.buffer], n.div_ceil(WORKGROUP));
+        self.queue.submit([encoder.finish()]);
+    }
+
+    /// Fills `out` with the ramp `out[k] = cis(phase0 + w k)`
+    ///
+    /// The phase is accumulated in 64 bit fixed point, so that it does not
+    /// drift over long buffers.
+    pub fn cis_ramp(&self, out: &mut GpuBuffer, w: f32, phase0: f32) {
+        use std::f64::consts::TAU;
+        if out.len == 0 {
+            return;
+        }
+        // fractions of a turn in units of 2⁻⁶⁴
+        let fixed = |angle: f32| ((angle as f64 / TAU).rem_euclid(1.0) * 2f64.powi(64)) as u64;
+        let (inc, start) = (fixed(w), fixed(phase0));
+        let n = out.len as u32;
+        let words = [(inc >> 32) as u32, inc as u32, (start >> 32) as u32, start as u32];
+        let params = self.params(n, words, [0.0; 3]);
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        self.dispatch(&mut encoder, &self.cis, &[&params, &out.buffer], n.div_ceil(WORKGROUP));
+        self.queue.submit([encoder.finish()]);
+    }
+
+    /// Sum of the elements of `a`, by a tree reduction on the device
+    pub fn sum(&self, a: &GpuBuffer) -> Complex<f32> {
+        if a.len == 0 {
+            return Complex::new(0.0, 0.0);
+        }
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        let mut n = a.len as u32;
+        let mut partial = None;
+        while n > 1 || partial.is_none() {
+            let groups = n.div_ceil(REDUCE);
+            let out = self.buffer(groups as usize);
+            let params = self.params(n, [0; 4], [0.0; 3]);
+            let input = partial.as_ref().unwrap_or(a);
+            self.dispatch(&mut encoder, &self.sum, &[&params, &input.buffer, &out.buffer], groups);
+            partial = Some(out);
+            n = groups;
+        }
+        self.queue.submit([encoder.finish()]);
+        self.download(&partial.unwrap())[0]
+    }
+
+    /// In-place forward discrete Fourier transform,
+    /// X<sub>k</sub> = Σ x<sub>n</sub> e<sup>-2πi kn/N</sup>
+    ///
+    /// # Panics
+    /// Panics if the length is not a power of 2.
+    pub fn fft(&self, buf: &mut GpuBuffer) {
+        self.transform(buf, false);
+    }
+
+    /// In-place inverse discrete Fourier transform,
+    /// x<sub>n</sub> = (1/N) Σ X<sub>k</sub> e<sup>2πi kn/N</sup>
+    ///
+    /// # Panics
+    /// Panics if the length is not a power of 2.
+    pub fn ifft(&self, buf: &mut GpuBuffer) {
+        self.transform(buf, true);
+    }
+
+    /// The radix-2 Stockham transform, which needs no bit reversal: each
+    /// pass reads one buffer and writes the other in order
+    fn transform(&self, buf: &mut GpuBuffer, inverse: bool) {
+        let len = buf.len;
+        assert!(len == 0 || len.is_power_of_two(), "GPU transforms need a power of 2 length, not {len}");
+        if len < 2 {
+            return;
+        }
+        let mut twiddles = self.twiddles.lock().unwrap();
+        if !twiddles.iter().any(|&(n, _)| n == len) {
+            let factors: Vec<Complex<f32>> = (0..len / 2).map(|t| {
+                let (s, c) = (-std::f64::consts::TAU * t as f64 / len as f64).sin_cos();
+                Complex::new(c as f32, s as f32)
+            }).collect();
+            twiddles.push((len, self.upload(&factors).buffer));
+        }
+        let twiddle = &twiddles.iter().find(|&&(n, _)| n == len).unwrap().1;
+        let scratch = self.buffer(len);
+        let half = (len / 2) as u32;
+        let passes = len.trailing_zeros();
+        let mut encoder = self.device.create_command_encoder(&Default::default());
+        for pass in 0..passes {
+            let scale = if inverse && pass + 1 == passes { 1.0 / len as f32 } else { 1.0 };
+            let params = self.params(half, [1 << pass, inverse as u32, 0, 0], [scale, 0.0, 0.0]);
+            let (input, out) = if pass % 2 == 0 { (&buf.buffer, &scratch.buffer) } else { (&scratch.buffer, &buf.buffer) };
+            self.dispatch(&mut encoder, &self.fft, &[&params, input, out, twiddle], half.div_ceil(WORKGROUP));
+        }
+        if passes % 2 == 1 {
+            encoder.copy_buffer_to_buffer(&scratch.buffer, 0, &buf.buffer, 0, byte_size(len));
+        }
+        self.queue.submit([encoder.finish()]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signal(n: usize) -> Vec<Complex<f32>> {
+        (0..n).map(|k| Complex::new((k as f32 * 0.37).sin(), (k as f32 * 0.11).cos() - 0.5)).collect()
+    }
+
+    /// The device, or `None` to skip the test where there is none
+    fn gpu() -> Option<Gpu> {
+        Gpu::new().map_err(|e| eprintln!("skipping: {e}")).ok()
+    }
+
+    #[test]
+    fn element_wise() {
+        let Some(gpu) = gpu() else { return };
+        let n = 70_001;
+        let (a, b) = (signal(n), signal(n + 1)[1..].to_vec());
+        let (ga, gb) = (gpu.upload(&a), gpu.upload(&b));
+        let mut out = gpu.zeros(n);
+        assert_eq!(gpu.download(&out), vec![Complex::new(0.0, 0.0); n]);
+        gpu.mul(&ga, &gb, &mut out);
+        let product = gpu.download(&out);
+        for k in 0..n {
+            assert!((product[k] - a[k] * b[k]).abs() < 1e-6, "{k}");
+        }
+        gpu.write(&mut out, &b);
+        assert_eq!(gpu.download(&out), b);
+        let empty = gpu.zeros(0);
+        assert_eq!(gpu.download(&empty), []);
+        assert_eq!(gpu.sum(&empty), Complex::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn ramp() {
+        let Some(gpu) = gpu() else { return };
+        let n = 1 << 20;
+        let (w, phase0) = (0.123_456_7f32, -2.5f32);
+        let mut out = gpu.zeros(n);
+        gpu.cis_ramp(&mut out, w, phase0);
+        let ramp = gpu.download(&out);
+        // the phase does not drift even a million samples in
+        for k in [0, 1, 1000, 123_457, n - 1] {
+            let exact = Complex::<f64>::cis(phase0 as f64 + w as f64 * k as f64);
+            let z = Complex::new(ramp[k].r as f64, ramp[k].i as f64);
+            assert!((z - exact).abs() < 1e-5, "{k} {z} {exact}");
+        }
+    }
+
+    #[test]
+    fn reduction() {
+        let Some(gpu) = gpu() else { return };
+        for n in [1, 511, 512, 513, 300_000] {
+            let a = signal(n);
+            let exact = a.iter().fold(Complex::new(0.0f64, 0.0), |s, z| s + Complex::new(z.r as f64, z.i as f64));
+            let sum = gpu.sum(&gpu.upload(&a));
+            let error = (Complex::new(sum.r as f64, sum.i as f64) - exact).abs();
+            assert!(error < 1e-6 * n as f64, "{n} {sum} {exact}");
+        }
+    }
+
+    #[test]
+    fn transforms() {
+        let Some(gpu) = gpu() else { return };
+        for n in [1, 2, 8, 1024, 1 << 17] {
+            let x = signal(n);
+            let mut buf = gpu.upload(&x);
+            gpu.fft(&mut buf);
+            let spectrum = gpu.download(&buf);
+            let mut expected = x.clone();
+            crate::fft::c32::fft(&mut expected);
+            let scale = (n as f32).sqrt();
+            for k in 0..n {
+                assert!((spectrum[k] - expected[k]).abs() < 1e-5 * scale * (n as f32).log2().max(1.0), "{n} {k}");
+            }
+            gpu.ifft(&mut buf);
+            let back = gpu.download(&buf);
+            for k in 0..n {
+                assert!((back[k] - x[k]).abs() < 1e-5, "{n} {k}");
+            }
+        }
+    }
+
+    // not #[should_panic], which would pass without a device
+    #[test]
+    fn transform_length() {
+        let Some(gpu) = gpu() else { return };
+        let panic = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| gpu.fft(&mut gpu.zeros(12))));
+        let message = panic.expect_err("a length of 12 should panic");
+        assert!(message.downcast_ref::<String>().is_some_and(|m| m.contains("power of 2")));
+    }
+}
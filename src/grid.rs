@@ -0,0 +1,213 @@
+//! Evenly spaced points on the complex plane
+//!
+//! The end points given are always included exactly, so that adjacent
+//! ranges can share their boundary samples.  Besides even spacing in the
+//! plane, points can be spaced geometrically, evenly in log magnitude and
+//! in phase along a logarithmic spiral, or along a ray as for the jω axis of
+//! a frequency sweep.
+//!
+//! A function is sampled over the points of a grid into a caller's buffer,
+//! in parallel with the `rayon` feature.
+
+macro_rules! grid_mod {
+    ($m: ident for $t: ident) => {
+        /// Evenly spaced points on the complex plane
+        pub mod $m {
+            use crate::Complex;
+            use std::ops::RangeInclusive;
+
+            /// `n` evenly spaced real numbers from `a` to `b` inclusive
+            fn spaced(a: $t, b: $t, n: usize) -> impl Iterator<Item = $t> {
+                let step = (b - a) / (n.max(2) - 1) as $t;
+                (0..n).map(move |k| if k + 1 == n && n > 1 { b } else { a + step * k as $t })
+            }
+
+            /// `n` evenly spaced points on the segment from `a` to `b`
+            ///
+            /// Both end points are included, exactly, for `n` ≥ 2, and `n` = 1
+            /// gives only `a`.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, grid::", stringify!($m), "::linspace};")]
+            /// let points = linspace(Complex::new(0.0, 0.0), Complex::new(1.0, 2.0), 3);
+            /// assert_eq!(points, [Complex::new(0.0, 0.0), Complex::new(0.5, 1.0), Complex::new(1.0, 2.0)]);
+            /// ```
+            pub fn linspace(a: Complex<$t>, b: Complex<$t>, n: usize) -> Vec<Complex<$t>> {
+                spaced(a.r, b.r, n).zip(spaced(a.i, b.i, n)).map(|(r, i)| Complex::new(r, i)).collect()
+            }
+
+            /// A grid of `nx` by `ny` points covering the rectangle
+            /// `re_range` × `im_range`, in row-major order
+            ///
+            /// Row `j` holds the points with the `j`th imaginary part, running
+            /// from the start of `im_range` to its end, so the point at
+            /// column `k` is at index `j * nx + k`.  A decreasing `im_range`
+            /// puts the top row first, as images are stored.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, grid::", stringify!($m), "::grid};")]
+            /// let points = grid(-1.0..=1.0, 1.0..=0.0, 3, 2);
+            /// assert_eq!(points.len(), 6);
+            /// assert_eq!(points[0], Complex::new(-1.0, 1.0));
+            /// assert_eq!(points[5], Complex::new(1.0, 0.0));
+            /// ```
+            pub fn grid(re_range: RangeInclusive<$t>, im_range: RangeInclusive<$t>, nx: usize, ny: usize) -> Vec<Complex<$t>> {
+                let re: Vec<$t> = spaced(*re_range.start(), *re_range.end(), nx).collect();
+                spaced(*im_range.start(), *im_range.end(), ny)
+                    .flat_map(|i| re.iter().map(move |&r| Complex::new(r, i)))
+                    .collect()
+            }
+
+            /// `n` points from `a` to `b` spaced evenly in log magnitude and in
+            /// phase, so that consecutive points have a constant ratio
+            ///
+            /// The points follow the logarithmic spiral a (b/a)<sup>t</sup>,
+            /// turning through the principal argument of b/a, which is at
+            /// most half a turn.  Both end points are included, exactly, for
+            /// `n` ≥ 2.
+            ///
+            /// # Panics
+            /// Panics if `a` or `b` is zero.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, grid::", stringify!($m), "::geomspace};")]
+            /// // 1, 2i, −4
+            /// let points = geomspace(Complex::new(1.0, 0.0), Complex::new(-4.0, 0.0), 3);
+            /// assert!((points[1] - Complex::new(0.0, 2.0)).abs() < 1e-6);
+            /// ```
+            pub fn geomspace(a: Complex<$t>, b: Complex<$t>, n: usize) -> Vec<Complex<$t>> {
+                let zero = Complex::new(0.0, 0.0);
+                assert!(a != zero && b != zero, "geomspace needs nonzero end points, a = {a:?}, b = {b:?}");
+                let turn = (b / a).ln();
+                spaced(0.0, 1.0, n).enumerate().map(|(k, t)| {
+                    if k + 1 == n && n > 1 { b } else { a * (turn * t).exp() }
+                }).collect()
+            }
+
+            /// `n` points 10<sup>x</sup> `ray` for x evenly spaced from `start`
+            /// to `stop`
+            ///
+            /// With `ray` = i these are the points jω of a logarithmic
+            /// frequency sweep.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, grid::", stringify!($m), "::logspace};")]
+            /// // jω from 0.1 to 1000 rad/s, five points
+            /// let s = logspace(-1.0, 3.0, 5, Complex::new(0.0, 1.0));
+            /// assert_eq!(s[2], Complex::new(0.0, 10.0));
+            /// ```
+            pub fn logspace(start: $t, stop: $t, n: usize, ray: Complex<$t>) -> Vec<Complex<$t>> {
+                spaced(start, stop, n).map(|x| ray * crate::math::$m::powf(10.0, x)).collect()
+            }
+
+            /// Evaluates `f` at each point of `grid`, writing the values to
+            /// the same positions of `out`
+            ///
+            /// Nothing is allocated, so a buffer can be reused between frames.
+            /// With the `rayon` feature the points are shared out in chunks
+            /// over the rayon thread pool.
+            ///
+            /// # Panics
+            /// Panics if `grid` and `out` differ in length.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, grid::", stringify!($m), "::{grid, sample_grid}};")]
+            /// let points = grid(-2.0..=2.0, -2.0..=2.0, 64, 64);
+            /// let mut values = vec![Complex::new(0.0, 0.0); points.len()];
+            /// sample_grid(|z| z * z, &points, &mut values);
+            /// assert_eq!(values[0], points[0] * points[0]);
+            /// ```
+            pub fn sample_grid(f: impl Fn(Complex<$t>) -> Complex<$t> + Sync, grid: &[Complex<$t>], out: &mut [Complex<$t>]) {
+                assert_eq!(grid.len(), out.len(), "grid and output lengths differ");
+                #[cfg(feature = "rayon")]
+                {
+                    use rayon::prelude::*;
+                    const CHUNK: usize = 1024;
+                    out.par_chunks_mut(CHUNK).zip(grid.par_chunks(CHUNK)).for_each(|(out, grid)| {
+                        for (value, &z) in out.iter_mut().zip(grid) {
+                            *value = f(z);
+                        }
+                    });
+                }
+                #[cfg(not(feature = "rayon"))]
+                for (value, &z) in out.iter_mut().zip(grid) {
+                    *value = f(z);
+                }
+            }
+
+            #[cfg(test)]
+            mod test {
+                use super::*;
+
+                #[test]
+                fn check_linspace() {
+                    let (a, b) = (Complex::new(0.1, -0.3), Complex::new(0.7, 0.3));
+                    assert_eq!(linspace(a, b, 0), []);
+                    assert_eq!(linspace(a, b, 1), [a]);
+                    assert_eq!(linspace(a, b, 2), [a, b]);
+                    let points = linspace(a, b, 7);
+                    assert_eq!((points[0], points[6]), (a, b));
+                    for pair in points.windows(2) {
+                        assert!((pair[1] - pair[0] - (b - a) / 6.0).abs() < 10.0 * $t::EPSILON);
+                    }
+                }
+
+                #[test]
+                fn check_grid() {
+                    let points = grid(0.0..=1.0, -2.0..=2.0, 5, 3);
+                    assert_eq!(points.len(), 15);
+                    for (j, row) in points.chunks(5).enumerate() {
+                        for (k, point) in row.iter().enumerate() {
+                            assert_eq!(*point, Complex::new(0.25 * k as $t, 2.0 * j as $t - 2.0));
+                        }
+                    }
+                    assert_eq!(grid(0.0..=1.0, 0.0..=1.0, 0, 4), []);
+                }
+
+                #[test]
+                fn check_geomspace() {
+                    let ep = 10.0 * $t::EPSILON;
+                    let (a, b) = (Complex::new(0.5, 0.5), Complex::new(-3.0, 4.0));
+                    let points = geomspace(a, b, 9);
+                    assert_eq!((points[0], points[8]), (a, b));
+                    let ratio = points[1] / points[0];
+                    for pair in points.windows(2) {
+                        assert!((pair[1] / pair[0] - ratio).abs() < ep, "{pair:?}");
+                    }
+                    // across the negative real axis the short way round
+                    let points = geomspace(Complex::new(-1.0, 0.1), Complex::new(-1.0, -0.1), 3);
+                    assert!(points[1].r < -1.0 && points[1].i.abs() < ep);
+                    assert_eq!(geomspace(a, b, 1), [a]);
+                }
+
+                #[test]
+                fn check_logspace() {
+                    let ray = Complex::new(0.6, 0.8);
+                    let points = logspace(0.0, 2.0, 3, ray);
+                    for (point, scale) in points.iter().zip([1.0, 10.0, 100.0]) {
+                        assert!((*point - ray * scale).abs() < 1e3 * $t::EPSILON, "{point:?}");
+                    }
+                    assert_eq!(logspace(1.0, 2.0, 0, ray), []);
+                }
+
+                #[test]
+                fn check_sample_grid() {
+                    let points = grid(-1.0..=1.0, -1.0..=1.0, 100, 50);
+                    let mut values = vec![Complex::new(0.0, 0.0); points.len()];
+                    sample_grid(|z| z.exp(), &points, &mut values);
+                    for (value, point) in values.iter().zip(&points) {
+                        assert_eq!(*value, point.exp());
+                    }
+                    sample_grid(|z| z, &[], &mut []);
+                }
+            }
+        }
+    }
+}
+grid_mod!(c32 for f32);
+grid_mod!(c64 for f64);
@@ -0,0 +1,108 @@
+//! Complex numbers with half precision components, with the `half` feature
+//!
+//! The `f16` and `bf16` types of the `half` crate are [`Float`], so
+//! `Complex<f16>` and `Complex<bf16>` have all of the elementary functions,
+//! along with the formatting and parsing of any `Complex`.  The functions
+//! are evaluated in `f32` and rounded to the component type, and the
+//! imaginary units are in the [`c16`] and [`cb16`] modules.
+//!
+//! # Example
+//! ```
+//! use imaginary::Complex;
+//! use imaginary::half::c16;
+//! use half::f16;
+//! let z = Complex::new(f16::from_f32(1.5), f16::from_f32(-2.0));
+//! assert_eq!((z * c16::I).to_string(), "2 + 1.5*i");
+//! assert_eq!(format!("{:.3}", z.exp()), "-1.865 - 4.074*i");
+//! ```
+
+use crate::float::Float;
+use crate::{math, Complex, One};
+use ::half::{bf16, f16};
+
+macro_rules! half_mod {
+    ($m: ident for $t: ident) => {
+        impl One for $t {
+            const ONE: $t = $t::ONE;
+        }
+
+        impl Float for $t {
+            const ZERO: $t = $t::ZERO;
+            const INFINITY: $t = $t::INFINITY;
+            const PI: $t = $t::PI;
+            const FRAC_1_SQRT_2: $t = $t::FRAC_1_SQRT_2;
+            const LOG2_E: $t = $t::LOG2_E;
+            const LOG10_E: $t = $t::LOG10_E;
+
+            fn from_f64(x: f64) -> $t {
+                $t::from_f64(x)
+            }
+            fn abs(self) -> $t {
+                self.copysign($t::ZERO)
+            }
+            fn copysign(self, sign: $t) -> $t {
+                <$t>::copysign(self, sign)
+            }
+            fn is_nan(self) -> bool {
+                <$t>::is_nan(self)
+            }
+            fn is_infinite(self) -> bool {
+                <$t>::is_infinite(self)
+            }
+            half_mod!(@f32 $t, sqrt, cbrt, exp, ln, log2, log10, sin, cos, tan, sinh, cosh, tanh,
+                      round, floor, ceil, trunc; powf(n), hypot(other), atan2(x));
+        }
+
+        #[doc = concat!("Constants of `Complex<", stringify!($t), ">`")]
+        pub mod $m {
+            use super::*;
+
+            /// The imaginary number, `I` =
+            /// <math> <msqrt> <mn> -1 </mn> </msqrt> </math>
+            pub const I: Complex<$t> = Complex::new($t::ZERO, $t::ONE);
+        }
+    };
+    (@f32 $t: ident, $($unary: ident),*; $($binary: ident($arg: ident)),*) => {
+        $(
+            fn $unary(self) -> $t {
+                $t::from_f32(math::c32::$unary(self.to_f32()))
+            }
+        )*
+        $(
+            fn $binary(self, $arg: $t) -> $t {
+                $t::from_f32(math::c32::$binary(self.to_f32(), $arg.to_f32()))
+            }
+        )*
+    }
+}
+half_mod!(c16 for f16);
+half_mod!(cb16 for bf16);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_precision() {
+        let z = Complex::new(f16::from_f32(3.0), f16::from_f32(4.0));
+        assert_eq!(z.abs(), f16::from_f32(5.0));
+        assert_eq!(c16::I * c16::I, Complex::new(-f16::ONE, f16::ZERO));
+        assert_eq!(z.sqrt(), Complex::new(f16::from_f32(2.0), f16::ONE));
+        assert_eq!(format!("{:.2}", z.ln()), "1.61 + 0.93*i");
+        assert_eq!(format!("{:#.2}", z.display()), "5.00∠0.93");
+        assert_eq!(z.display().unit('j').mul_sign("").to_string(), "3 + 4j");
+        assert_eq!("3 - 4i".parse(), Ok(z.conj()));
+        // close to the functions of f32, with rounding at each step
+        let to_f32 = |z: Complex<f16>| Complex::new(z.r.to_f32(), z.i.to_f32());
+        let w = Complex::new(f16::from_f32(0.3), f16::from_f32(-1.1));
+        for (got, expected) in [(w.tan(), to_f32(w).tan()), (w.cosh(), to_f32(w).cosh()), (w.powf(f16::from_f32(2.5)), to_f32(w).powf(2.5))] {
+            assert!((to_f32(got) - expected).abs() < 4e-3 * expected.abs(), "{got} {expected}");
+        }
+
+        let z = Complex::new(bf16::from_f32(-2.0), bf16::from_f32(0.5));
+        assert_eq!(cb16::I * z, Complex::new(bf16::from_f32(-0.5), bf16::from_f32(-2.0)));
+        let expected = Complex::new(-2.0f32, 0.5).exp();
+        let got = z.exp();
+        assert!((Complex::new(got.r.to_f32(), got.i.to_f32()) - expected).abs() < 2e-2 * expected.abs());
+    }
+}
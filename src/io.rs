@@ -0,0 +1,736 @@
+//! Reading and writing complex data files
+//!
+//! With the `csv` feature, complex arrays are read from and written to CSV
+//! files with a pair of columns per value, holding either the real and
+//! imaginary parts or the magnitude and the phase in degrees.  A first row
+//! that does not parse as numbers is taken to be a header and skipped, as
+//! are lines starting with `#`.
+//!
+//! With the `npy` feature, arrays of any shape are exchanged with NumPy as
+//! `.npy` files of dtype complex64 or complex128, and collections of them as
+//! `.npz` archives.
+//!
+//! Raw IQ recordings from software defined radios, interleaved in-phase and
+//! quadrature samples as 8 bit, 16 bit or floating point numbers, are
+//! streamed in chunks to and from `Complex<f32>` buffers by [`IqReader`]
+//! and [`IqWriter`], scaling integer samples to [−1, 1).
+
+use crate::Complex;
+#[cfg(any(feature = "csv", feature = "npy"))]
+use std::fmt;
+use std::io::{self, Read, Write};
+
+/// The sample format of a raw IQ file, each sample an in-phase value
+/// followed by a quadrature value
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IqFormat {
+    /// unsigned bytes offset by 127.5, as recorded by rtl-sdr (`cu8`)
+    U8,
+    /// signed bytes (`cs8`)
+    I8,
+    /// signed little endian 16 bit integers (`cs16`)
+    I16,
+    /// little endian 32 bit floats, as GNU Radio's `gr_complex` (`cf32`)
+    F32
+}
+
+impl IqFormat {
+    /// The number of bytes in one complex sample
+    pub fn sample_size(self) -> usize {
+        match self {
+            IqFormat::U8 | IqFormat::I8 => 2,
+            IqFormat::I16 => 4,
+            IqFormat::F32 => 8
+        }
+    }
+
+    /// Scales one component to a float
+    fn decode(self, bytes: &[u8]) -> f32 {
+        match self {
+            IqFormat::U8 => (bytes[0] as f32 - 127.5) / 128.0,
+            IqFormat::I8 => bytes[0] as i8 as f32 / 128.0,
+            IqFormat::I16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32768.0,
+            IqFormat::F32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+        }
+    }
+
+    /// Scales one component back to the format, rounding and saturating
+    fn encode(self, x: f32, bytes: &mut Vec<u8>) {
+        match self {
+            IqFormat::U8 => bytes.push((x * 128.0 + 127.5).round().clamp(0.0, 255.0) as u8),
+            IqFormat::I8 => bytes.push((x * 128.0).round().clamp(-128.0, 127.0) as i8 as u8),
+            IqFormat::I16 => bytes.extend_from_slice(&((x * 32768.0).round().clamp(-32768.0, 32767.0) as i16).to_le_bytes()),
+            IqFormat::F32 => bytes.extend_from_slice(&x.to_le_bytes())
+        }
+    }
+}
+
+/// Reads raw IQ samples in chunks
+///
+/// # Example
+/// ```
+/// use imaginary::{Complex, io::{IqFormat, IqReader}};
+/// // two rtl-sdr samples
+/// let mut reader = IqReader::new(&[255u8, 0, 128, 127][..], IqFormat::U8);
+/// let mut buf = [Complex::new(0.0, 0.0); 16];
+/// assert_eq!(reader.read(&mut buf).unwrap(), 2);
+/// assert_eq!(buf[0], Complex::new(127.5 / 128.0, -127.5 / 128.0));
+/// assert_eq!(reader.read(&mut buf).unwrap(), 0);
+/// ```
+#[derive(Debug)]
+pub struct IqReader<R> {
+    reader: R,
+    format: IqFormat,
+    bytes: Vec<u8>
+}
+
+impl<R: Read> IqReader<R> {
+    /// Reads samples of `format` from `reader`, which is best buffered
+    pub fn new(reader: R, format: IqFormat) -> IqReader<R> {
+        IqReader { reader, format, bytes: Vec::new() }
+    }
+
+    /// Fills `buf` with as many samples as remain, up to its length, and
+    /// returns how many were read
+    ///
+    /// Fewer samples than fit are read only at the end of the input, where
+    /// 0 is returned once there are none left.  A trailing partial sample
+    /// is ignored.
+    pub fn read(&mut self, buf: &mut [Complex<f32>]) -> io::Result<usize> {
+        let size = self.format.sample_size();
+        self.bytes.resize(buf.len() * size, 0);
+        let mut filled = 0;
+        while filled < self.bytes.len() {
+            match self.reader.read(&mut self.bytes[filled..]) {
+                Ok(0) => break,
+                Ok(n) => filled += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => return Err(e)
+            }
+        }
+        let half = size / 2;
+        let samples = filled / size;
+        for (z, bytes) in buf.iter_mut().zip(self.bytes[..samples * size].chunks_exact(size)) {
+            *z = Complex::new(self.format.decode(&bytes[..half]), self.format.decode(&bytes[half..]));
+        }
+        Ok(samples)
+    }
+
+    /// Reads all the remaining samples
+    pub fn read_to_end(&mut self) -> io::Result<Vec<Complex<f32>>> {
+        let mut samples = Vec::new();
+        let mut buf = vec![Complex::new(0.0, 0.0); 4096];
+        loop {
+            let n = self.read(&mut buf)?;
+            if n == 0 {
+                return Ok(samples);
+            }
+            samples.extend_from_slice(&buf[..n]);
+        }
+    }
+
+    /// The underlying reader
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// Writes raw IQ samples in chunks
+///
+/// Integer formats round to the nearest level and saturate outside
+/// [−1, 1).
+///
+/// # Example
+/// ```
+/// use imaginary::{Complex, io::{IqFormat, IqWriter}};
+/// let mut writer = IqWriter::new(Vec::new(), IqFormat::I8);
+/// writer.write(&[Complex::new(0.5, -2.0)]).unwrap();
+/// assert_eq!(writer.into_inner(), [64, 128]);
+/// ```
+#[derive(Debug)]
+pub struct IqWriter<W> {
+    writer: W,
+    format: IqFormat,
+    bytes: Vec<u8>
+}
+
+impl<W: Write> IqWriter<W> {
+    /// Writes samples of `format` to `writer`
+    pub fn new(writer: W, format: IqFormat) -> IqWriter<W> {
+        IqWriter { writer, format, bytes: Vec::new() }
+    }
+
+    /// Writes all of `samples`
+    pub fn write(&mut self, samples: &[Complex<f32>]) -> io::Result<()> {
+        self.bytes.clear();
+        for z in samples {
+            self.format.encode(z.r, &mut self.bytes);
+            self.format.encode(z.i, &mut self.bytes);
+        }
+        self.writer.write_all(&self.bytes)
+    }
+
+    /// Flushes the underlying writer
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    /// The underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// The meaning of the pair of CSV columns holding each complex value
+#[cfg(feature = "csv")]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CsvFormat {
+    /// real part, then imaginary part
+    ReIm,
+    /// magnitude, then phase in degrees
+    MagPhaseDeg
+}
+
+/// An error reading or writing complex data as CSV
+#[cfg(feature = "csv")]
+#[derive(Debug)]
+pub enum CsvError {
+    /// the underlying CSV reader or writer failed
+    Csv(csv::Error),
+    /// a field on this line is not a number
+    Number {
+        /// line number, starting from 1
+        line: u64
+    },
+    /// this line has too few fields
+    Columns {
+        /// line number, starting from 1
+        line: u64
+    }
+}
+
+#[cfg(feature = "csv")]
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Csv(error) => error.fmt(f),
+            CsvError::Number { line } => write!(f, "invalid number on line {line}"),
+            CsvError::Columns { line } => write!(f, "missing columns on line {line}")
+        }
+    }
+}
+
+#[cfg(feature = "csv")]
+impl std::error::Error for CsvError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CsvError::Csv(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+#[cfg(feature = "csv")]
+impl From<csv::Error> for CsvError {
+    fn from(error: csv::Error) -> CsvError {
+        CsvError::Csv(error)
+    }
+}
+
+/// A complex array as stored in a NumPy `.npy` file
+#[cfg(feature = "npy")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct NpyArray<T: Copy> {
+    /// length of each dimension, empty for a scalar
+    pub shape: Vec<usize>,
+    /// elements in row-major order
+    pub data: Vec<Complex<T>>
+}
+
+#[cfg(feature = "npy")]
+impl<T: Copy> From<Vec<Complex<T>>> for NpyArray<T> {
+    /// A one dimensional array
+    fn from(data: Vec<Complex<T>>) -> NpyArray<T> {
+        NpyArray { shape: vec![data.len()], data }
+    }
+}
+
+/// An error reading or writing NumPy files
+#[cfg(feature = "npy")]
+#[derive(Debug)]
+pub enum NpyError {
+    /// reading or writing failed
+    Io(std::io::Error),
+    /// the `.npz` archive is invalid
+    Zip(zip::result::ZipError),
+    /// the file is not in the `.npy` format
+    Header,
+    /// the elements are not complex64 or complex128
+    Dtype(String),
+    /// the array is stored in Fortran order, which is not supported
+    FortranOrder
+}
+
+#[cfg(feature = "npy")]
+impl fmt::Display for NpyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NpyError::Io(error) => error.fmt(f),
+            NpyError::Zip(error) => error.fmt(f),
+            NpyError::Header => write!(f, "invalid npy header"),
+            NpyError::Dtype(descr) => write!(f, "unsupported dtype {descr}"),
+            NpyError::FortranOrder => write!(f, "Fortran ordered arrays are not supported")
+        }
+    }
+}
+
+#[cfg(feature = "npy")]
+impl std::error::Error for NpyError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            NpyError::Io(error) => Some(error),
+            NpyError::Zip(error) => Some(error),
+            _ => None
+        }
+    }
+}
+
+#[cfg(feature = "npy")]
+impl From<std::io::Error> for NpyError {
+    fn from(error: std::io::Error) -> NpyError {
+        NpyError::Io(error)
+    }
+}
+
+#[cfg(feature = "npy")]
+impl From<zip::result::ZipError> for NpyError {
+    fn from(error: zip::result::ZipError) -> NpyError {
+        NpyError::Zip(error)
+    }
+}
+
+/// The dtype, byte order and shape from the header of a `.npy` file
+#[cfg(feature = "npy")]
+struct NpyHeader {
+    /// bytes per complex element, 8 or 16
+    size: usize,
+    big_endian: bool,
+    shape: Vec<usize>,
+    /// bytes of data, the product of the shape and `size`
+    len: usize
+}
+
+/// Reads exactly `len` bytes, as `read_exact` does, growing the buffer only
+/// as the bytes arrive, so that a length from an untrusted file cannot
+/// allocate more than the file holds
+#[cfg(feature = "npy")]
+fn read_bytes(reader: &mut impl std::io::Read, len: usize) -> std::io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() < len {
+        return Err(std::io::ErrorKind::UnexpectedEof.into());
+    }
+    Ok(bytes)
+}
+
+/// Reads the header of a `.npy` file, leaving `reader` at the data
+#[cfg(feature = "npy")]
+fn read_npy_header(reader: &mut impl std::io::Read) -> Result<NpyHeader, NpyError> {
+    let mut magic = [0; 8];
+    reader.read_exact(&mut magic)?;
+    if &magic[..6] != b"\x93NUMPY" {
+        return Err(NpyError::Header);
+    }
+    let len = if magic[6] == 1 {
+        let mut len = [0; 2];
+        reader.read_exact(&mut len)?;
+        u16::from_le_bytes(len) as usize
+    } else {
+        let mut len = [0; 4];
+        reader.read_exact(&mut len)?;
+        u32::from_le_bytes(len) as usize
+    };
+    let header = read_bytes(reader, len)?;
+    let header = String::from_utf8(header).map_err(|_| NpyError::Header)?;
+    // the value following a key of the header dictionary
+    let value = |key: &str| header.find(key).map(|at| header[at + key.len()..].trim_start_matches([' ', ':']));
+    let descr = value("'descr'").and_then(|v| v.strip_prefix('\'')?.split('\'').next()).ok_or(NpyError::Header)?;
+    let (big_endian, size) = match descr {
+        "<c8" => (false, 8),
+        "<c16" => (false, 16),
+        ">c8" => (true, 8),
+        ">c16" => (true, 16),
+        _ => return Err(NpyError::Dtype(descr.to_string()))
+    };
+    match value("'fortran_order'") {
+        Some(v) if v.starts_with("False") => {}
+        Some(v) if v.starts_with("True") => return Err(NpyError::FortranOrder),
+        _ => return Err(NpyError::Header)
+    }
+    let shape = value("'shape'").and_then(|v| v.strip_prefix('(')?.split(')').next()).ok_or(NpyError::Header)?;
+    let shape = shape.split(',').map(str::trim).filter(|d| !d.is_empty())
+        .map(|d| d.parse().map_err(|_| NpyError::Header))
+        .collect::<Result<Vec<usize>, _>>()?;
+    let len = shape.iter().try_fold(size, |len: usize, &n| len.checked_mul(n)).ok_or_else(|| {
+        std::io::Error::new(std::io::ErrorKind::InvalidData, "npy array size overflows usize")
+    })?;
+    Ok(NpyHeader { size, big_endian, shape, len })
+}
+
+/// Writes the header of a `.npy` file for complex elements of `size` bytes
+#[cfg(feature = "npy")]
+fn write_npy_header(writer: &mut impl std::io::Write, size: usize, shape: &[usize]) -> std::io::Result<()> {
+    let shape = match shape {
+        [n] => format!("({n},)"),
+        _ => format!("({})", shape.iter().map(usize::to_string).collect::<Vec<_>>().join(", "))
+    };
+    let mut header = format!("{{'descr': '<c{size}', 'fortran_order': False, 'shape': {shape}, }}");
+    // pad with spaces and a newline to a multiple of 64 bytes in all
+    let len = (10 + header.len() + 1).next_multiple_of(64) - 10;
+    while header.len() + 1 < len {
+        header.push(' ');
+    }
+    header.push('\n');
+    writer.write_all(b"\x93NUMPY\x01\x00")?;
+    writer.write_all(&(len as u16).to_le_bytes())?;
+    writer.write_all(header.as_bytes())
+}
+
+macro_rules! io_mod {
+    ($m: ident for $t: ident) => {
+        /// Reading and writing complex data files
+        pub mod $m {
+            #[cfg(any(feature = "csv", feature = "npy"))]
+            use crate::Complex;
+            #[cfg(feature = "csv")]
+            use super::{CsvError, CsvFormat};
+            #[cfg(feature = "npy")]
+            use super::{NpyArray, NpyError, read_npy_header, write_npy_header};
+
+            /// Reads complex values from CSV, one per row, from the pair of
+            /// columns starting at index `column`
+            ///
+            /// Fields are trimmed, rows may have differing numbers of fields,
+            /// and lines starting with `#` are skipped.  If the first row does
+            /// not parse as numbers it is taken as a header and skipped.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, io::{CsvFormat, ", stringify!($m), "::read_csv}};")]
+            /// let data = "freq, mag, phase\n1e9, 0.5, 90\n2e9, 0.25, 180\n";
+            /// let z = read_csv(data.as_bytes(), CsvFormat::MagPhaseDeg, 1).unwrap();
+            /// assert_eq!(z.len(), 2);
+            /// assert!((z[0] - Complex::new(0.0, 0.5)).abs() < 1e-6);
+            /// ```
+            #[cfg(feature = "csv")]
+            pub fn read_csv(reader: impl std::io::Read, format: CsvFormat, column: usize) -> Result<Vec<Complex<$t>>, CsvError> {
+                let mut reader = csv::ReaderBuilder::new()
+                    .has_headers(false)
+                    .flexible(true)
+                    .trim(csv::Trim::All)
+                    .comment(Some(b'#'))
+                    .from_reader(reader);
+                let mut values = Vec::new();
+                for (row, record) in reader.records().enumerate() {
+                    let record = record?;
+                    let line = record.position().map_or(0, |p| p.line());
+                    let (Some(a), Some(b)) = (record.get(column), record.get(column + 1)) else {
+                        return Err(CsvError::Columns { line });
+                    };
+                    let (a, b) = match (a.parse::<$t>(), b.parse::<$t>()) {
+                        (Ok(a), Ok(b)) => (a, b),
+                        _ if row == 0 => continue,
+                        _ => return Err(CsvError::Number { line })
+                    };
+                    values.push(match format {
+                        CsvFormat::ReIm => Complex::new(a, b),
+                        CsvFormat::MagPhaseDeg => Complex::<$t>::cis(b.to_radians()) * a
+                    });
+                }
+                Ok(values)
+            }
+
+            /// Writes complex values as CSV, one per row, after a header of
+            /// `re,im` or `mag,phase_deg`
+            ///
+            /// The numbers are written with the fewest digits that read back
+            /// to the same value.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, io::{CsvFormat, ", stringify!($m), "::write_csv}};")]
+            /// let mut out = Vec::new();
+            /// write_csv(&mut out, &[Complex::new(1.5, -2.0)], CsvFormat::ReIm).unwrap();
+            /// assert_eq!(String::from_utf8(out).unwrap(), "re,im\n1.5,-2\n");
+            /// ```
+            #[cfg(feature = "csv")]
+            pub fn write_csv(writer: impl std::io::Write, values: &[Complex<$t>], format: CsvFormat) -> Result<(), CsvError> {
+                let mut writer = csv::Writer::from_writer(writer);
+                match format {
+                    CsvFormat::ReIm => writer.write_record(["re", "im"])?,
+                    CsvFormat::MagPhaseDeg => writer.write_record(["mag", "phase_deg"])?
+                }
+                for z in values {
+                    let (a, b) = match format {
+                        CsvFormat::ReIm => (z.r, z.i),
+                        CsvFormat::MagPhaseDeg => (z.abs(), z.angle().to_degrees())
+                    };
+                    writer.write_record([a.to_string(), b.to_string()])?;
+                }
+                writer.flush().map_err(csv::Error::from)?;
+                Ok(())
+            }
+
+            /// Reads a complex array from a NumPy `.npy` file
+            ///
+            /// Elements of dtype complex64 or complex128, in either byte order,
+            #[doc = concat!("are converted to `Complex<", stringify!($t), ">`.")]
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, io::{NpyArray, ", stringify!($m), "::{read_npy, write_npy}}};")]
+            /// let array = NpyArray { shape: vec![2, 1], data: vec![Complex::new(1.0, 2.0), Complex::new(3.0, 4.0)] };
+            /// let mut file = Vec::new();
+            /// write_npy(&mut file, &array).unwrap();
+            /// assert_eq!(read_npy(file.as_slice()).unwrap(), array);
+            /// ```
+            #[cfg(feature = "npy")]
+            pub fn read_npy(mut reader: impl std::io::Read) -> Result<NpyArray<$t>, NpyError> {
+                let header = read_npy_header(&mut reader)?;
+                let bytes = super::read_bytes(&mut reader, header.len)?;
+                let half = header.size / 2;
+                let part = |bytes: &[u8]| -> $t {
+                    match (half, header.big_endian) {
+                        (4, false) => f32::from_le_bytes(bytes.try_into().unwrap()) as $t,
+                        (4, true) => f32::from_be_bytes(bytes.try_into().unwrap()) as $t,
+                        (_, false) => f64::from_le_bytes(bytes.try_into().unwrap()) as $t,
+                        (_, true) => f64::from_be_bytes(bytes.try_into().unwrap()) as $t
+                    }
+                };
+                let data = bytes.chunks_exact(header.size)
+                    .map(|z| Complex::new(part(&z[..half]), part(&z[half..])))
+                    .collect();
+                Ok(NpyArray { shape: header.shape, data })
+            }
+
+            /// Writes a complex array as a little endian NumPy `.npy` file, of
+            /// dtype complex64 from `c32` and complex128 from `c64`
+            ///
+            /// # Panics
+            /// Panics if the number of elements does not match the shape.
+            #[cfg(feature = "npy")]
+            pub fn write_npy(mut writer: impl std::io::Write, array: &NpyArray<$t>) -> Result<(), NpyError> {
+                assert_eq!(array.shape.iter().product::<usize>(), array.data.len(), "shape does not match the data");
+                let size = 2 * std::mem::size_of::<$t>();
+                write_npy_header(&mut writer, size, &array.shape)?;
+                let mut bytes = Vec::with_capacity(size * array.data.len());
+                for z in &array.data {
+                    bytes.extend_from_slice(&z.r.to_le_bytes());
+                    bytes.extend_from_slice(&z.i.to_le_bytes());
+                }
+                writer.write_all(&bytes)?;
+                Ok(())
+            }
+
+            /// Reads the complex arrays of a NumPy `.npz` archive, with their
+            /// names
+            ///
+            /// The names are those given to `numpy.savez`, without the
+            /// `.npy` extension of the archive members.  Archives from
+            /// `numpy.savez_compressed` are read too.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, io::{NpyArray, ", stringify!($m), "::{read_npz, write_npz}}};")]
+            /// use std::io::Cursor;
+            /// let x = NpyArray::from(vec![Complex::new(1.0, -1.0)]);
+            /// let mut file = Cursor::new(Vec::new());
+            /// write_npz(&mut file, &[("x", &x)]).unwrap();
+            /// assert_eq!(read_npz(file).unwrap(), [("x".to_string(), x)]);
+            /// ```
+            #[cfg(feature = "npy")]
+            pub fn read_npz(reader: impl std::io::Read + std::io::Seek) -> Result<Vec<(String, NpyArray<$t>)>, NpyError> {
+                let mut archive = zip::ZipArchive::new(reader)?;
+                let mut arrays = Vec::with_capacity(archive.len());
+                for k in 0..archive.len() {
+                    let file = archive.by_index(k)?;
+                    let name = file.name();
+                    let name = name.strip_suffix(".npy").unwrap_or(name).to_string();
+                    arrays.push((name, read_npy(file)?));
+                }
+                Ok(arrays)
+            }
+
+            /// Writes complex arrays to an uncompressed NumPy `.npz` archive,
+            /// as `numpy.savez` does, to be loaded under the given names
+            #[cfg(feature = "npy")]
+            pub fn write_npz(
+                writer: impl std::io::Write + std::io::Seek, arrays: &[(&str, &NpyArray<$t>)]
+            ) -> Result<(), NpyError> {
+                let mut archive = zip::ZipWriter::new(writer);
+                let options = zip::write::SimpleFileOptions::default()
+                    .compression_method(zip::CompressionMethod::Stored);
+                for (name, array) in arrays {
+                    archive.start_file(format!("{name}.npy"), options)?;
+                    write_npy(&mut archive, array)?;
+                }
+                archive.finish()?;
+                Ok(())
+            }
+
+            #[cfg(all(test, feature = "csv"))]
+            mod test_csv {
+                use super::*;
+
+                #[test]
+                fn check_csv() {
+                    let values = [Complex::new(1.0, -0.5), Complex::new(-3.25e-7, 1e10), Complex::new(0.1, 0.2)];
+                    for format in [CsvFormat::ReIm, CsvFormat::MagPhaseDeg] {
+                        let mut out = Vec::new();
+                        write_csv(&mut out, &values, format).unwrap();
+                        let read = read_csv(out.as_slice(), format, 0).unwrap();
+                        assert_eq!(read.len(), values.len());
+                        for (a, b) in read.iter().zip(&values) {
+                            if format == CsvFormat::ReIm {
+                                assert_eq!(a, b);
+                            } else {
+                                assert!((*a - *b).abs() <= 10.0 * $t::EPSILON * b.abs());
+                            }
+                        }
+                    }
+                    // no header, comments, and extra columns
+                    let data = "# measured\n0, 1, 2, x\n1, 3, 4, y\n";
+                    let read = read_csv(data.as_bytes(), CsvFormat::ReIm, 1).unwrap();
+                    assert_eq!(read, [Complex::new(1.0, 2.0), Complex::new(3.0, 4.0)]);
+                    // errors after the first row
+                    let data = "re,im\n1,2\n3,four\n";
+                    assert!(matches!(read_csv(data.as_bytes(), CsvFormat::ReIm, 0), Err(CsvError::Number { line: 3 })));
+                    let data = "1,2\n3\n";
+                    assert!(matches!(read_csv(data.as_bytes(), CsvFormat::ReIm, 0), Err(CsvError::Columns { line: 2 })));
+                }
+            }
+
+            #[cfg(all(test, feature = "npy"))]
+            mod test_npy {
+                use super::*;
+
+                #[test]
+                fn check_npy() {
+                    // numpy.save of np.array([[1+2j, -0.5j, 3], [0, 1e-300j, -7.25]]).astype(...)
+                    fn file(descr: &str, parts: &[f64]) -> Vec<u8> {
+                        let mut header = format!("{{'descr': '{descr}', 'fortran_order': False, 'shape': (2, 3), }}");
+                        while (10 + header.len() + 1) % 64 != 0 {
+                            header.push(' ');
+                        }
+                        header.push('\n');
+                        let mut file = b"\x93NUMPY\x01\x00".to_vec();
+                        file.extend_from_slice(&(header.len() as u16).to_le_bytes());
+                        file.extend_from_slice(header.as_bytes());
+                        for &x in parts {
+                            match descr {
+                                "<c8" => file.extend_from_slice(&(x as f32).to_le_bytes()),
+                                ">c8" => file.extend_from_slice(&(x as f32).to_be_bytes()),
+                                "<c16" => file.extend_from_slice(&x.to_le_bytes()),
+                                _ => file.extend_from_slice(&x.to_be_bytes())
+                            }
+                        }
+                        file
+                    }
+                    let parts = [1.0, 2.0, 0.0, -0.5, 3.0, 0.0, 0.0, 0.0, 0.0, 0.125, -7.25, 0.0];
+                    let data: Vec<Complex<$t>> = parts.chunks(2).map(|z| Complex::new(z[0] as $t, z[1] as $t)).collect();
+                    for descr in ["<c8", ">c8", "<c16", ">c16"] {
+                        let array = read_npy(file(descr, &parts).as_slice()).unwrap();
+                        assert_eq!(array.shape, [2, 3]);
+                        assert_eq!(array.data, data, "{descr}");
+                    }
+                    // round trip, including a scalar and an empty array
+                    for shape in [vec![2, 3], vec![6], vec![], vec![0, 4]] {
+                        let len: usize = shape.iter().product();
+                        let array = NpyArray { shape, data: data[..len].to_vec() };
+                        let mut file = Vec::new();
+                        write_npy(&mut file, &array).unwrap();
+                        assert_eq!((file.len() - len * 2 * std::mem::size_of::<$t>()) % 64, 0);
+                        assert_eq!(read_npy(file.as_slice()).unwrap(), array);
+                    }
+                    // unsupported files
+                    assert!(matches!(read_npy(file("<f8", &parts).as_slice()), Err(NpyError::Dtype(_))));
+                    let fortran = String::from_utf8_lossy(&file("<c16", &parts)).replace("False", "True ");
+                    assert!(matches!(read_npy(fortran.as_bytes()), Err(NpyError::FortranOrder | NpyError::Header)));
+                    assert!(matches!(read_npy(&b"PK\x03\x04"[..]), Err(NpyError::Io(_))));
+                    // sizes that overflow, or that the file does not hold
+                    let shaped = |shape: &str| {
+                        let header = format!("{{'descr': '<c16', 'fortran_order': False, 'shape': {shape}, }}{:>54}", "\n");
+                        let mut file = b"\x93NUMPY\x01\x00".to_vec();
+                        file.extend_from_slice(&(header.len() as u16).to_le_bytes());
+                        file.extend_from_slice(header.as_bytes());
+                        file.extend_from_slice(&[0; 96]);
+                        file
+                    };
+                    let error = |file: Vec<u8>| match read_npy(file.as_slice()) {
+                        Err(NpyError::Io(error)) => error.kind(),
+                        other => panic!("{other:?}")
+                    };
+                    assert_eq!(read_npy(shaped("(2, 3)").as_slice()).unwrap().data.len(), 6);
+                    assert_eq!(error(shaped("(4294967296, 4294967296)")), std::io::ErrorKind::InvalidData);
+                    assert_eq!(error(shaped("(1000000000000,)")), std::io::ErrorKind::UnexpectedEof);
+                    assert_eq!(error(shaped("(2, 4)")), std::io::ErrorKind::UnexpectedEof);
+                    let mut short = b"\x93NUMPY\x02\x00\xff\xff\xff\xff".to_vec();
+                    short.extend_from_slice(b"{'descr': '<c16', ");
+                    assert_eq!(error(short), std::io::ErrorKind::UnexpectedEof);
+                }
+
+                #[test]
+                fn check_npz() {
+                    let a = NpyArray::from(vec![Complex::new(1.0, 2.0), Complex::new(-3.0, 0.5)]);
+                    let b = NpyArray { shape: vec![1, 1], data: vec![Complex::new(0.25, -0.75)] };
+                    let mut file = std::io::Cursor::new(Vec::new());
+                    write_npz(&mut file, &[("a", &a), ("b", &b)]).unwrap();
+                    file.set_position(0);
+                    let arrays = read_npz(file).unwrap();
+                    assert_eq!(arrays, [("a".to_string(), a), ("b".to_string(), b)]);
+                }
+            }
+        }
+    }
+}
+io_mod!(c32 for f32);
+io_mod!(c64 for f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_iq() {
+        let samples = [Complex::new(0.5, -0.25), Complex::new(-1.0, 0.99), Complex::new(0.0, 0.0)];
+        for format in [IqFormat::U8, IqFormat::I8, IqFormat::I16, IqFormat::F32] {
+            let mut writer = IqWriter::new(Vec::new(), format);
+            writer.write(&samples).unwrap();
+            writer.write(&samples[..1]).unwrap();
+            let bytes = writer.into_inner();
+            assert_eq!(bytes.len(), 4 * format.sample_size());
+            // read back in chunks of 2, with a trailing partial sample
+            let mut reader = IqReader::new(&bytes[..bytes.len() - 1], format);
+            let mut buf = [Complex::new(0.0, 0.0); 2];
+            let mut read = Vec::new();
+            while let n @ 1.. = reader.read(&mut buf).unwrap() {
+                read.extend_from_slice(&buf[..n]);
+            }
+            assert_eq!(read.len(), 3);
+            let step = match format {
+                IqFormat::U8 | IqFormat::I8 => 1.0 / 128.0,
+                IqFormat::I16 => 1.0 / 32768.0,
+                IqFormat::F32 => 0.0
+            };
+            for (a, b) in read.iter().zip(&samples) {
+                assert!((a.r - b.r).abs() <= step && (a.i - b.i).abs() <= step, "{format:?} {a:?} {b:?}");
+            }
+        }
+        // full scale and saturation
+        let mut writer = IqWriter::new(Vec::new(), IqFormat::I16);
+        writer.write(&[Complex::new(-1.0, 1.0)]).unwrap();
+        assert_eq!(writer.into_inner(), [0x00, 0x80, 0xff, 0x7f]);
+        let all = IqReader::new(&[0u8, 0x80, 0xff, 0x7f][..], IqFormat::I16).read_to_end().unwrap();
+        assert_eq!(all, [Complex::new(-1.0, 32767.0 / 32768.0)]);
+    }
+}
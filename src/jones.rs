@@ -0,0 +1,232 @@
+//! Jones calculus for polarized light
+//!
+//! A fully polarized beam is a [`JonesVector`] of the complex amplitudes of
+//! its x and y field components, and linear optical elements are
+//! [`JonesMatrix`] values.  Elements compose by multiplication, with the
+//! element the light passes through first on the right.
+//!
+//! # Example
+//! ```
+//! use imaginary::jones::{JonesMatrix, JonesVector};
+//! use std::f64::consts::FRAC_PI_4;
+//!
+//! // crossed polarizers block all light, unless a polarizer at 45° is
+//! // placed between them
+//! let light = JonesVector::<f64>::linear(0.0);
+//! let crossed = JonesMatrix::<f64>::polarizer(0.0).then(JonesMatrix::<f64>::polarizer(FRAC_PI_4))
+//!     .then(JonesMatrix::<f64>::polarizer(2.0 * FRAC_PI_4));
+//! assert!(((crossed * light).intensity() - 0.25).abs() < 1e-12);
+//! ```
+
+use crate::{math, Complex};
+use core::ops::Mul;
+
+/// Polarization state, the complex amplitudes of the x and y field
+/// components
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JonesVector<T: Copy> {
+    /// x component
+    pub x: Complex<T>,
+    /// y component
+    pub y: Complex<T>
+}
+
+/// Linear optical element acting on [`JonesVector`]s
+///
+/// `m[row][column]`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct JonesMatrix<T: Copy> {
+    /// matrix elements
+    pub m: [[Complex<T>; 2]; 2]
+}
+
+impl<T: Copy> JonesVector<T> {
+    /// Creates a Jones vector from its components
+    pub fn new(x: Complex<T>, y: Complex<T>) -> JonesVector<T> {
+        JonesVector { x, y }
+    }
+}
+
+impl<T: Copy> JonesMatrix<T> {
+    /// Creates a Jones matrix from its rows
+    pub fn new(m: [[Complex<T>; 2]; 2]) -> JonesMatrix<T> {
+        JonesMatrix { m }
+    }
+}
+
+macro_rules! impl_jones {
+    ($m: ident for $t: ident) => {
+        impl JonesVector<$t> {
+            /// Unit intensity light linearly polarized at `angle` radians from
+            /// the x axis
+            pub fn linear(angle: $t) -> JonesVector<$t> {
+                JonesVector::new(math::$m::cos(angle).into(), math::$m::sin(angle).into())
+            }
+
+            /// Unit intensity circularly polarized light, right handed for
+            /// `right` and left handed otherwise
+            ///
+            /// Right handed light is (1, i) / √2 and has a positive
+            /// [`ellipticity`](Self::ellipticity).
+            pub fn circular(right: bool) -> JonesVector<$t> {
+                let s = core::$t::consts::FRAC_1_SQRT_2;
+                let y = if right { Complex::new(0.0, s) } else { Complex::new(0.0, -s) };
+                JonesVector::new(s.into(), y)
+            }
+
+            /// Intensity |x|² + |y|²
+            pub fn intensity(&self) -> $t {
+                self.x.r * self.x.r + self.x.i * self.x.i + self.y.r * self.y.r + self.y.i * self.y.i
+            }
+
+            /// Stokes parameters [S<sub>0</sub>, S<sub>1</sub>, S<sub>2</sub>,
+            /// S<sub>3</sub>]
+            ///
+            /// S<sub>0</sub> = |x|² + |y|², S<sub>1</sub> = |x|² − |y|²,
+            /// S<sub>2</sub> + i S<sub>3</sub> = 2 x̄ y
+            pub fn stokes(&self) -> [$t; 4] {
+                let xx = self.x.r * self.x.r + self.x.i * self.x.i;
+                let yy = self.y.r * self.y.r + self.y.i * self.y.i;
+                let xy = 2.0 * self.x.conj() * self.y;
+                [xx + yy, xx - yy, xy.r, xy.i]
+            }
+
+            /// Orientation ψ of the polarization ellipse's major axis in
+            /// (−π/2, π/2] radians from the x axis
+            pub fn orientation(&self) -> $t {
+                let [_, s1, s2, _] = self.stokes();
+                0.5 * math::$m::atan2(s2, s1)
+            }
+
+            /// Ellipticity angle χ in [−π/4, π/4] radians, where tan χ is the
+            /// ratio of the ellipse's minor to major axis, positive for right
+            /// handed light
+            pub fn ellipticity(&self) -> $t {
+                let [s0, _, _, s3] = self.stokes();
+                0.5 * math::$m::asin((s3 / s0).clamp(-1.0, 1.0))
+            }
+        }
+
+        impl JonesMatrix<$t> {
+            /// The identity, free space
+            pub fn identity() -> JonesMatrix<$t> {
+                let (one, zero) = (Complex::new(1.0, 0.0), Complex::new(0.0, 0.0));
+                JonesMatrix::new([[one, zero], [zero, one]])
+            }
+
+            /// Rotator turning the polarization by `angle` radians
+            /// counterclockwise
+            pub fn rotator(angle: $t) -> JonesMatrix<$t> {
+                let (s, c) = (math::$m::sin(angle), math::$m::cos(angle));
+                JonesMatrix::new([[c.into(), (-s).into()], [s.into(), c.into()]])
+            }
+
+            /// Ideal linear polarizer with its transmission axis at `angle`
+            /// radians from the x axis
+            pub fn polarizer(angle: $t) -> JonesMatrix<$t> {
+                let (s, c) = (math::$m::sin(angle), math::$m::cos(angle));
+                JonesMatrix::new([[(c * c).into(), (c * s).into()], [(c * s).into(), (s * s).into()]])
+            }
+
+            /// Waveplate delaying the slow axis by `retardance` radians
+            /// relative to the fast axis at `angle` radians from the x axis
+            pub fn waveplate(retardance: $t, angle: $t) -> JonesMatrix<$t> {
+                let zero = Complex::new(0.0, 0.0);
+                let plate = JonesMatrix::new([[Complex::new(1.0, 0.0), zero], [zero, Complex::<$t>::cis(retardance)]]);
+                Self::rotator(angle) * plate * Self::rotator(-angle)
+            }
+
+            /// Quarter wave plate with its fast axis at `angle` radians from
+            /// the x axis
+            pub fn quarter_wave(angle: $t) -> JonesMatrix<$t> {
+                Self::waveplate(core::$t::consts::FRAC_PI_2, angle)
+            }
+
+            /// Half wave plate with its fast axis at `angle` radians from the
+            /// x axis
+            pub fn half_wave(angle: $t) -> JonesMatrix<$t> {
+                Self::waveplate(core::$t::consts::PI, angle)
+            }
+
+            /// Applies the element to a polarization state
+            pub fn apply(&self, v: JonesVector<$t>) -> JonesVector<$t> {
+                let [[a, b], [c, d]] = self.m;
+                JonesVector::new(a * v.x + b * v.y, c * v.x + d * v.y)
+            }
+
+            /// The element `self` followed by `next`, `next * self`
+            pub fn then(self, next: JonesMatrix<$t>) -> JonesMatrix<$t> {
+                next * self
+            }
+        }
+
+        impl Mul<JonesVector<$t>> for JonesMatrix<$t> {
+            type Output = JonesVector<$t>;
+            fn mul(self, v: JonesVector<$t>) -> JonesVector<$t> {
+                self.apply(v)
+            }
+        }
+
+        impl Mul for JonesMatrix<$t> {
+            type Output = JonesMatrix<$t>;
+            fn mul(self, rhs: JonesMatrix<$t>) -> JonesMatrix<$t> {
+                let [[a, b], [c, d]] = self.m;
+                let [[e, f], [g, h]] = rhs.m;
+                JonesMatrix::new([[a * e + b * g, a * f + b * h], [c * e + d * g, c * f + d * h]])
+            }
+        }
+    }
+}
+impl_jones!(c32 for f32);
+impl_jones!(c64 for f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{FRAC_PI_4, FRAC_PI_8};
+
+    fn close(a: JonesVector<f64>, b: JonesVector<f64>) -> bool {
+        (a.x - b.x).abs() < 1e-12 && (a.y - b.y).abs() < 1e-12
+    }
+
+    #[test]
+    fn malus_law() {
+        for k in 0..10 {
+            let angle = 0.17 * k as f64 - 0.7;
+            let out = JonesMatrix::<f64>::polarizer(angle) * JonesVector::<f64>::linear(0.0);
+            assert!((out.intensity() - angle.cos().powi(2)).abs() < 1e-12);
+            assert!((out.orientation() - angle).abs() < 1e-12);
+        }
+    }
+
+    #[test]
+    fn waveplates() {
+        // a quarter wave plate at 45° turns x polarized light circular
+        let circ = JonesMatrix::<f64>::quarter_wave(FRAC_PI_4) * JonesVector::<f64>::linear(0.0);
+        assert!((circ.intensity() - 1.0).abs() < 1e-12);
+        assert!((circ.ellipticity().abs() - FRAC_PI_4).abs() < 1e-12);
+        // a half wave plate at θ reflects linear polarization about θ
+        let out = JonesMatrix::<f64>::half_wave(FRAC_PI_8) * JonesVector::<f64>::linear(0.0);
+        assert!((out.orientation() - FRAC_PI_4).abs() < 1e-12);
+        assert!(out.ellipticity().abs() < 1e-12);
+        // two quarter wave plates make a half wave plate
+        let two = JonesMatrix::<f64>::quarter_wave(0.3).then(JonesMatrix::<f64>::quarter_wave(0.3));
+        let v = JonesVector::new(Complex::new(0.6, 0.1), Complex::new(-0.2, 0.7));
+        assert!(close(two * v, JonesMatrix::<f64>::half_wave(0.3) * v));
+    }
+
+    #[test]
+    fn stokes() {
+        let right = JonesVector::<f64>::circular(true);
+        let [s0, s1, s2, s3] = right.stokes();
+        assert!((s0 - 1.0).abs() < 1e-15 && s1.abs() < 1e-15 && s2.abs() < 1e-15);
+        assert!((s3 - 1.0).abs() < 1e-15);
+        assert!((JonesVector::<f64>::circular(false).ellipticity() + FRAC_PI_4).abs() < 1e-7);
+        // rotators turn the ellipse without changing its shape
+        let v = JonesVector::new(Complex::new(0.8, 0.0), Complex::new(0.1, 0.3));
+        let turned = JonesMatrix::<f64>::rotator(0.2) * v;
+        assert!((turned.orientation() - v.orientation() - 0.2).abs() < 1e-12);
+        assert!((turned.ellipticity() - v.ellipticity()).abs() < 1e-12);
+        assert!(close(JonesMatrix::<f64>::identity() * v, v));
+    }
+}
@@ -4,12 +4,24 @@
 //! complex numbers and a more comprehensive implementation of complex numbers 
 //! for [`f32`] and [`f64`] floating-point types.  Complex number functionality
 //! is implemented by the [`Complex`] struct.  The generic implementation 
-//! includes overloaded operators (`+`, `-`, `*`, and `/`).  The float specific 
+//! includes overloaded operators (`+`, `-`, `*`, and `/`), on values and
+//! on references with any `Clone` components.  The float specific 
 //! implementations have overloaded operators for operations between floats and
 //! [`Complex`] and additional functionality; such as, [`abs`](Complex::abs), 
 //! [`sign`](Complex::sign), [`angle`](Complex::angle), [`cis`](Complex::cis), 
-//! [`exp`](Complex::cis), [`powf`](Complex::powf), [`powc`](Complex::powc), 
-//! [`sqrt`](Complex::sqrt), and [`cbrt`](Complex::cbrt).
+//! [`exp`](Complex::cis), [`powi`](Complex::powi), [`powf`](Complex::powf), [`powc`](Complex::powc), 
+//! [`sqrt`](Complex::sqrt), and [`cbrt`](Complex::cbrt), for any
+//! [`Float`] component type.
+//! 
+//! The modules build on [`Complex`], from polynomials and Fourier
+//! transforms to special functions.  Optional features, such as `serde`,
+//! `num-traits` and `rand`, are described where they apply.
+//! 
+//! The crate is `no_std` without its default `std` feature, keeping
+//! [`Complex`] and the modules that need no allocator, with the
+//! transcendental functions of the `libm` feature.  The `deterministic`
+//! feature uses `libm` with `std` too, for results that are bit-identical
+//! on every platform.
 //! 
 //! # Examples
 //! ```
@@ -35,30 +47,174 @@
 //! assert_eq!(z, 2.0 * f32::cos(3.1) + 2.0 * f32::sin(3.1) * c32::I);
 //! ```
 
+#![cfg_attr(not(feature = "std"), no_std)]
+#![cfg_attr(feature = "f128", feature(f128))]
+
+// the tests format and print with std, also when the crate is no_std
+#[cfg(all(test, not(feature = "std")))]
+#[macro_use]
+extern crate std;
+
+#[cfg(not(any(feature = "std", feature = "libm")))]
+compile_error!("imaginary needs either the `std` or the `libm` feature for its elementary functions");
+
 use core::ops::*;
+use core::str::FromStr;
+use float::Float;
+
+pub mod angle;
+#[cfg(feature = "f128")]
+pub mod c128;
+#[cfg(feature = "std")]
+pub mod constellation;
+#[cfg(feature = "std")]
+pub mod contour;
+#[cfg(feature = "cordic")]
+pub mod cordic;
+#[cfg(feature = "std")]
+pub mod expr;
+#[cfg(feature = "std")]
+pub mod fft;
+pub mod float;
+pub mod format;
+pub mod gaussian;
+#[cfg(feature = "wgpu")]
+pub mod gpu;
+#[cfg(feature = "std")]
+pub mod grid;
+#[cfg(feature = "half")]
+pub mod half;
+#[cfg(feature = "std")]
+pub mod io;
+pub mod jones;
+#[cfg(feature = "std")]
+pub mod linalg;
+mod math;
+#[cfg(feature = "rand")]
+pub mod noise;
+#[cfg(feature = "num-traits")]
+mod num;
+#[cfg(feature = "std")]
+pub mod ode;
+mod parse;
+pub mod polar;
+#[cfg(feature = "std")]
+pub mod poly;
+#[cfg(feature = "num-rational")]
+pub mod ratio;
+#[cfg(feature = "std")]
+pub mod rational;
+#[cfg(feature = "rand")]
+pub mod region;
+pub mod rf;
+#[cfg(feature = "std")]
+pub mod roots;
+pub mod rotator;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(feature = "std")]
+pub mod signal;
+#[cfg(feature = "std")]
+pub mod slice;
+#[cfg(feature = "std")]
+pub mod special;
 
 /// A struct for representing complex numbers
+///
+/// The layout is that of `[T; 2]`, the real part followed by the imaginary
+/// part.  The components need only be `Clone`, so that big-number types
+/// can be used; the operators take their operands by value, or by
+/// reference to keep them.
+///
+/// # Example
+/// ```
+/// use imaginary::Complex;
+/// use num_bigint::BigInt;
+/// let z = Complex::new(BigInt::from(3), BigInt::from(-4));
+/// let w = &(&z * &z) * &z;
+/// assert_eq!(w, Complex::new(BigInt::from(-117), BigInt::from(-44)));
+/// ```
+#[repr(C)]
 #[derive(Default, Copy, Clone, Debug, PartialEq)]
-pub struct Complex<T: Copy>{
+#[cfg_attr(feature = "serde", derive(::serde::Serialize, ::serde::Deserialize))]
+pub struct Complex<T: Clone>{
     /// real
     pub r: T,
     /// imaginary
     pub i: T
 }
 
-impl<T: Copy> Complex<T>{
+impl<T: Clone> Complex<T>{
     pub const fn new(real: T, imag:  T) -> Complex<T> {
         Complex { r: real, i: imag }
     }
 }
+
+/// The multiplicative identity of a component type
+///
+/// Implemented for the primitive integers and floats, so that generic code
+/// can build [`Complex::i`] without naming a float type.
+pub trait One: Copy {
+    /// one
+    const ONE: Self;
+}
+
+macro_rules! impl_one {
+    ($($t: ty = $one: expr),*) => {
+        $(impl One for $t {
+            const ONE: $t = $one;
+        })*
+    }
+}
+impl_one!(i8 = 1, i16 = 1, i32 = 1, i64 = 1, i128 = 1, isize = 1,
+          u8 = 1, u16 = 1, u32 = 1, u64 = 1, u128 = 1, usize = 1,
+          f32 = 1.0, f64 = 1.0);
+
+impl<T: Clone + Default> Complex<T> {
+    /// A real number, with a zero imaginary part
+    ///
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// assert_eq!(Complex::from_real(2), Complex::new(2, 0));
+    /// ```
+    pub fn from_real(r: T) -> Complex<T> {
+        Complex { r, i: T::default() }
+    }
+
+    /// An imaginary number, with a zero real part
+    ///
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// assert_eq!(Complex::from_imag(-0.5), Complex::new(0.0, -0.5));
+    /// ```
+    pub fn from_imag(i: T) -> Complex<T> {
+        Complex { r: T::default(), i }
+    }
+}
+
+impl<T: One + Default> Complex<T> {
+    /// The imaginary unit i
+    ///
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// assert_eq!(Complex::<i32>::i(), Complex::new(0, 1));
+    /// assert_eq!(Complex::<f64>::i() * Complex::i(), Complex::new(-1.0, 0.0));
+    /// ```
+    pub fn i() -> Complex<T> {
+        Complex::from_imag(T::ONE)
+    }
+}
 /// # Example
 /// ```
 /// use imaginary::Complex;
 /// assert_eq!(Complex::new(5.0, 0.0), 5.0.into());
 /// ```
-impl<T: Copy + Default> From<T> for Complex<T>{
+impl<T: Clone + Default> From<T> for Complex<T>{
     fn from(value: T) -> Complex<T>{
-        Complex { r: value, i: T::default() }
+        Complex::from_real(value)
     }
 }
 
@@ -67,7 +223,7 @@ impl<T: Copy + Default> From<T> for Complex<T>{
 /// use imaginary::Complex;
 /// assert_eq!(Complex::new(1.0, 2.0), (1.0, 2.0).into());
 /// ```
-impl<T: Copy> From<(T, T)> for Complex<T>{
+impl<T: Clone> From<(T, T)> for Complex<T>{
     fn from(value: (T, T)) -> Complex<T>{
         let (real, imag) = value;
         Complex { r: (real), i: (imag) }
@@ -79,14 +235,66 @@ impl<T: Copy> From<(T, T)> for Complex<T>{
 /// use imaginary::Complex;
 /// assert_eq!((1.0, 2.0), Complex::new(1.0, 2.0).into());
 /// ```
-impl<T: Copy> From<Complex<T>> for (T, T) {
+impl<T: Clone> From<Complex<T>> for (T, T) {
     fn from(value: Complex<T>) -> (T, T) {
         (value.r, value.i)
     }
 }
 
+/// # Example
+/// ```
+/// use imaginary::Complex;
+/// assert_eq!(Complex::new(1.0, 2.0), [1.0, 2.0].into());
+/// ```
+impl<T: Clone> From<[T; 2]> for Complex<T> {
+    fn from([r, i]: [T; 2]) -> Complex<T> {
+        Complex { r, i }
+    }
+}
+
+/// # Example
+/// ```
+/// use imaginary::Complex;
+/// assert_eq!([1.0, 2.0], <[f64; 2]>::from(Complex::new(1.0, 2.0)));
+/// ```
+impl<T: Clone> From<Complex<T>> for [T; 2] {
+    fn from(value: Complex<T>) -> [T; 2] {
+        [value.r, value.i]
+    }
+}
+
+impl<T: Clone> Complex<T> {
+    /// Views the real and imaginary parts as an array
+    ///
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// let z = Complex::new(3, 4);
+    /// assert_eq!(z.as_array(), &[3, 4]);
+    /// ```
+    pub fn as_array(&self) -> &[T; 2] {
+        // SAFETY: Complex<T> is repr(C) with two fields of type T, so it has
+        // the layout of [T; 2]
+        unsafe { &*(self as *const Complex<T> as *const [T; 2]) }
+    }
+
+    /// Views the real and imaginary parts as a mutable array
+    ///
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// let mut z = Complex::new(3, 4);
+    /// z.as_mut_array()[1] = -4;
+    /// assert_eq!(z, Complex::new(3, -4));
+    /// ```
+    pub fn as_mut_array(&mut self) -> &mut [T; 2] {
+        // SAFETY: as for as_array, and the borrow of self is exclusive
+        unsafe { &mut *(self as *mut Complex<T> as *mut [T; 2]) }
+    }
+}
+
 impl<T> Complex<T>
-where T: Neg<Output=T> + Copy {
+where T: Neg<Output=T> + Clone {
     /// Complex conjugate
     /// # Example
     /// ```
@@ -95,7 +303,140 @@ where T: Neg<Output=T> + Copy {
     /// assert_eq!(z.conj(), Complex::new(1.0, -2.0));
     /// ```
     pub fn conj(self) -> Complex<T> {
-        Complex { r: (self.r), i: (-self.i) }
+        Complex { r: self.r, i: -self.i }
+    }
+
+    /// Product with i, a quarter turn anticlockwise, by swapping the
+    /// parts and negating one
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// assert_eq!(Complex::new(1.0, 2.0).mul_i(), Complex::new(-2.0, 1.0));
+    /// ```
+    pub fn mul_i(self) -> Complex<T> {
+        Complex { r: -self.i, i: self.r }
+    }
+
+    /// Product with −i, a quarter turn clockwise, by swapping the parts
+    /// and negating one
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// assert_eq!(Complex::new(1.0, 2.0).mul_neg_i(), Complex::new(2.0, -1.0));
+    /// ```
+    pub fn mul_neg_i(self) -> Complex<T> {
+        Complex { r: self.i, i: -self.r }
+    }
+
+    /// Product with i<sup>k</sup>, `k` quarter turns anticlockwise, or
+    /// clockwise for a negative `k`, exactly
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// let z = Complex::new(3, 1);
+    /// assert_eq!(z.rotate_quarter_turns(2), -z);
+    /// assert_eq!(z.rotate_quarter_turns(-5), z.mul_neg_i());
+    /// ```
+    pub fn rotate_quarter_turns(self, k: i32) -> Complex<T> {
+        match k.rem_euclid(4) {
+            0 => self,
+            1 => self.mul_i(),
+            2 => Complex { r: -self.r, i: -self.i },
+            _ => self.mul_neg_i()
+        }
+    }
+}
+
+impl<T> Complex<T>
+where T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Clone {
+    /// Dot product of `self` and `other` as 2D vectors,
+    /// `self.r * other.r + self.i * other.i`
+    ///
+    /// This is the real part of `self.conj() * other`, and is zero for
+    /// perpendicular vectors.
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// assert_eq!(Complex::new(1, 2).dot(Complex::new(3, 4)), 11);
+    /// ```
+    pub fn dot(self, other: Complex<T>) -> T {
+        self.r * other.r + self.i * other.i
+    }
+
+    /// Wedge, or 2D cross, product of `self` and `other`,
+    /// `self.r * other.i - self.i * other.r`
+    ///
+    /// This is the imaginary part of `self.conj() * other`, the signed
+    /// area of the parallelogram spanned by the vectors, positive when
+    /// `other` lies anticlockwise of `self`.
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// assert_eq!(Complex::new(1.0, 0.0).wedge(Complex::new(0.0, 2.0)), 2.0);
+    /// assert_eq!(Complex::new(0.0, 2.0).wedge(Complex::new(1.0, 0.0)), -2.0);
+    /// ```
+    pub fn wedge(self, other: Complex<T>) -> T {
+        self.r * other.i - self.i * other.r
+    }
+}
+
+impl<T> Complex<T>
+where T: Mul<Output=T> + Clone {
+    /// Product with the real number `x`, `self * x` in two
+    /// multiplications instead of the four of a complex product
+    ///
+    /// `*` does not look for zero components at run time to take this
+    /// path itself.  The test would add a branch to every product to
+    /// save two multiplications in the rare real one, and would change
+    /// results with infinities, where the full product makes the
+    /// `0 × ∞` terms NaN.  Call these methods when the operand is known
+    /// to be real or imaginary, and likewise for `/` with
+    /// [`div_real`](Self::div_real).
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// assert_eq!(Complex::new(1, -2).mul_real(3), Complex::new(3, -6));
+    /// ```
+    pub fn mul_real(self, x: T) -> Complex<T> {
+        Complex { r: self.r * x.clone(), i: self.i * x }
+    }
+
+    /// Product with the imaginary number `y`i, in two multiplications
+    /// instead of the four of a complex product
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// assert_eq!(Complex::new(1, -2).mul_imag(3), Complex::new(6, 3));
+    /// ```
+    pub fn mul_imag(self, y: T) -> Complex<T>
+    where T: Neg<Output=T> {
+        Complex { r: -(self.i * y.clone()), i: self.r * y }
+    }
+}
+
+impl<T> Complex<T>
+where T: Div<Output=T> + Clone {
+    /// Quotient by the real number `x`, `self / x` in two divisions
+    /// without forming |x|²
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// assert_eq!(Complex::new(3.0, -6.0).div_real(3.0), Complex::new(1.0, -2.0));
+    /// ```
+    pub fn div_real(self, x: T) -> Complex<T> {
+        Complex { r: self.r / x.clone(), i: self.i / x }
+    }
+
+    /// Quotient by the imaginary number `y`i, in two divisions without
+    /// forming |y|²
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// assert_eq!(Complex::new(6.0, 3.0).div_imag(3.0), Complex::new(1.0, -2.0));
+    /// ```
+    pub fn div_imag(self, y: T) -> Complex<T>
+    where T: Neg<Output=T> {
+        Complex { r: self.i / y.clone(), i: -(self.r / y) }
     }
 }
 
@@ -106,7 +447,7 @@ where T: Neg<Output=T> + Copy {
 /// assert_eq!(-z, Complex::new(-1.0, -2.0));
 /// ```
 impl<T> Neg for Complex<T>
-where T: Neg<Output=T> + Copy {
+where T: Neg<Output=T> + Clone {
     type Output = Complex<T>;
     fn neg(self) -> Complex<T>{
         Complex { r: (-self.r), i: (-self.i) }
@@ -121,7 +462,7 @@ where T: Neg<Output=T> + Copy {
 /// assert_eq!(z + w, Complex::new(4.0, 2.0));
 /// ```
 impl<T> Add for Complex<T>
-where T: Add<Output=T> + Copy {
+where T: Add<Output=T> + Clone {
     type Output = Complex<T>;
     fn add(self, rhs: Complex<T>) -> Complex<T> {
         Complex { r: (self.r + rhs.r), i: (self.i + rhs.i) }
@@ -136,7 +477,7 @@ where T: Add<Output=T> + Copy {
 /// assert_eq!(z - w, Complex::new(-2.0, 6.0));
 /// ```
 impl<T> Sub for Complex<T>
-where T: Sub<Output=T> + Copy {
+where T: Sub<Output=T> + Clone {
     type Output = Complex<T>;
     fn sub(self, rhs: Complex<T>) -> Complex<T> {
         Complex { r: (self.r - rhs.r), i: (self.i - rhs.i) }
@@ -151,13 +492,10 @@ where T: Sub<Output=T> + Copy {
 /// assert_eq!(z * w, Complex::new(11.0, 10.0));
 /// ```
 impl<T> Mul for Complex<T>
-where T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Copy {
+where T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Clone {
     type Output = Complex<T>;
     fn mul(self, rhs: Complex<T>) -> Complex<T> {
-        Complex { 
-            r: (self.r * rhs.r - self.i * rhs.i), 
-            i: (self.r * rhs.i + self.i * rhs.r) 
-        }
+        &self * &rhs
     }
 }
 
@@ -170,13 +508,96 @@ where T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Copy {
 /// ```
 impl<T> Div for Complex<T>
 where T: Add<Output=T> + Sub<Output=T> + 
-         Mul<Output=T> + Div<Output=T> + Copy {
+         Mul<Output=T> + Div<Output=T> + Clone {
     type Output = Complex<T>;
     fn div(self, rhs: Complex<T>) -> Complex<T> {
-        let denom = rhs.r * rhs.r + rhs.i * rhs.i;
-        Complex { 
-            r: (self.r * rhs.r + self.i * rhs.i)/denom, 
-            i: (self.i * rhs.r - self.r * rhs.i)/denom
+        &self / &rhs
+    }
+}
+
+// Operators on references, cloning the components only as needed, for
+// types that are not Copy
+
+/// # Example
+/// ```
+/// use imaginary::Complex;
+/// let z = Complex::new(1.0, 2.0);
+/// assert_eq!(-&z, Complex::new(-1.0, -2.0));
+/// ```
+impl<T> Neg for &Complex<T>
+where T: Neg<Output=T> + Clone {
+    type Output = Complex<T>;
+    fn neg(self) -> Complex<T> {
+        Complex { r: -self.r.clone(), i: -self.i.clone() }
+    }
+}
+
+/// # Example
+/// ```
+/// use imaginary::Complex;
+/// let z = Complex::new(1.0,  4.0);
+/// let w = Complex::new(3.0, -2.0);
+/// assert_eq!(&z + &w, Complex::new(4.0, 2.0));
+/// ```
+impl<T> Add for &Complex<T>
+where T: Add<Output=T> + Clone {
+    type Output = Complex<T>;
+    fn add(self, rhs: &Complex<T>) -> Complex<T> {
+        Complex { r: self.r.clone() + rhs.r.clone(), i: self.i.clone() + rhs.i.clone() }
+    }
+}
+
+/// # Example
+/// ```
+/// use imaginary::Complex;
+/// let z = Complex::new(1.0,  4.0);
+/// let w = Complex::new(3.0, -2.0);
+/// assert_eq!(&z - &w, Complex::new(-2.0, 6.0));
+/// ```
+impl<T> Sub for &Complex<T>
+where T: Sub<Output=T> + Clone {
+    type Output = Complex<T>;
+    fn sub(self, rhs: &Complex<T>) -> Complex<T> {
+        Complex { r: self.r.clone() - rhs.r.clone(), i: self.i.clone() - rhs.i.clone() }
+    }
+}
+
+/// # Example
+/// ```
+/// use imaginary::Complex;
+/// let z = Complex::new(1.0,  4.0);
+/// let w = Complex::new(3.0, -2.0);
+/// assert_eq!(&z * &w, Complex::new(11.0, 10.0));
+/// ```
+impl<T> Mul for &Complex<T>
+where T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> + Clone {
+    type Output = Complex<T>;
+    fn mul(self, rhs: &Complex<T>) -> Complex<T> {
+        let (a, b, c, d) = (&self.r, &self.i, &rhs.r, &rhs.i);
+        Complex {
+            r: a.clone() * c.clone() - b.clone() * d.clone(),
+            i: a.clone() * d.clone() + b.clone() * c.clone()
+        }
+    }
+}
+
+/// # Example
+/// ```
+/// use imaginary::Complex;
+/// let z = Complex::new(1.0,  2.0);
+/// let w = Complex::new(3.0, -4.0);
+/// assert_eq!(&z / &w, Complex::new(-0.2, 0.4));
+/// ```
+impl<T> Div for &Complex<T>
+where T: Add<Output=T> + Sub<Output=T> +
+         Mul<Output=T> + Div<Output=T> + Clone {
+    type Output = Complex<T>;
+    fn div(self, rhs: &Complex<T>) -> Complex<T> {
+        let (a, b, c, d) = (&self.r, &self.i, &rhs.r, &rhs.i);
+        let denom = c.clone() * c.clone() + d.clone() * d.clone();
+        Complex {
+            r: (a.clone() * c.clone() + b.clone() * d.clone()) / denom.clone(),
+            i: (b.clone() * c.clone() - a.clone() * d.clone()) / denom
         }
     }
 }
@@ -242,47 +663,401 @@ where T: Add<Output=T> + Sub<Output=T> +
     }
 }
 
-macro_rules! impl_display_for_complex {
-    ($t: ty) => {
-        impl std::fmt::Display for Complex<$t> {
-            /// Provides simple display representation for the `Complex` struct
+/// A [`fmt::Write`](core::fmt::Write) sink filling a byte buffer, so that
+/// values can be formatted without an allocator
+struct ByteSink<'a> {
+    buf: &'a mut [u8],
+    len: usize
+}
+
+impl core::fmt::Write for ByteSink<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let end = self.len + s.len();
+        if end > self.buf.len() {
+            return Err(core::fmt::Error);
+        }
+        self.buf[self.len..end].copy_from_slice(s.as_bytes());
+        self.len = end;
+        Ok(())
+    }
+}
+
+/// Counts the characters written, to pad formatted values to a width
+struct CharCount(usize);
+
+impl core::fmt::Write for CharCount {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.0 += s.chars().count();
+        Ok(())
+    }
+}
+
+/// Writes to the inner writer without the leading minus sign of the text,
+/// to write the magnitude of a negative part without negating it, which
+/// overflows for the `MIN` of the integers
+struct Unsigned<'a>(&'a mut dyn core::fmt::Write, bool);
+
+impl core::fmt::Write for Unsigned<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let s = match self.1 {
+            false if !s.is_empty() => {
+                self.1 = true;
+                s.strip_prefix('-').unwrap_or(s)
+            }
+            _ => s
+        };
+        self.0.write_str(s)
+    }
+}
+
+/// Checks that the text written, after any leading minus sign, is the
+/// given text, failing as soon as it differs
+struct Matches<'a>(&'a str, bool);
+
+impl core::fmt::Write for Matches<'_> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let s = match self.1 {
+            false if !s.is_empty() => {
+                self.1 = true;
+                s.strip_prefix('-').unwrap_or(s)
+            }
+            _ => s
+        };
+        self.0 = self.0.strip_prefix(s).ok_or(core::fmt::Error)?;
+        Ok(())
+    }
+}
+
+/// Whether `body` writes `one`, or `one` with a minus sign, to tell unit
+/// imaginary parts without a constant one of the component type
+fn writes_unit(one: &str, body: impl FnOnce(&mut dyn core::fmt::Write) -> core::fmt::Result) -> bool {
+    let mut text = Matches(one, false);
+    body(&mut text).is_ok() && text.0.is_empty()
+}
+
+/// Writes the text written by `body` to `f`, padded to the width of `f`
+/// with its fill and alignment, to the right by default as for numbers
+fn pad(f: &mut core::fmt::Formatter<'_>, body: impl Fn(&mut dyn core::fmt::Write) -> core::fmt::Result) -> core::fmt::Result {
+    use core::fmt::{Alignment, Write};
+    let Some(width) = f.width() else {
+        return body(f);
+    };
+    let mut count = CharCount(0);
+    body(&mut count)?;
+    let padding = width.saturating_sub(count.0);
+    let (before, after) = match f.align() {
+        Some(Alignment::Left) => (0, padding),
+        Some(Alignment::Center) => (padding / 2, padding - padding / 2),
+        _ => (padding, 0)
+    };
+    let fill = f.fill();
+    for _ in 0..before {
+        f.write_char(fill)?;
+    }
+    body(f)?;
+    for _ in 0..after {
+        f.write_char(fill)?;
+    }
+    Ok(())
+}
+
+impl<T> Complex<T>
+where T: PartialOrd + Default + Clone {
+    /// Writes `self` as `a + b*i`, with `part` writing each component,
+    /// given whether to show a plus sign, and with `unit` for i and
+    /// `mul_sign` for the multiplication sign
+    ///
+    /// Zero parts are left out and imaginary parts for which `is_unit`
+    /// holds, those of magnitude one, are written as the unit alone.  The
+    /// sign between the parts is written separately from the imaginary
+    /// part, so `plus` only applies to the first part written.
+    fn write_parts(
+        &self, w: &mut dyn core::fmt::Write, plus: bool, unit: char, mul_sign: &str,
+        part: &dyn Fn(&mut dyn core::fmt::Write, &T, bool) -> core::fmt::Result,
+        is_unit: &dyn Fn(&T) -> bool
+    ) -> core::fmt::Result {
+        let zero = T::default();
+        if self.i == zero {
+            return part(w, &self.r, plus);
+        }
+        let unit_part = is_unit(&self.i);
+        if self.r == zero {
+            if unit_part && self.i > zero {
+                if plus {
+                    w.write_char('+')?;
+                }
+                w.write_char(unit)
+            } else if unit_part {
+                w.write_char('-')?;
+                w.write_char(unit)
+            } else {
+                part(w, &self.i, plus)?;
+                w.write_str(mul_sign)?;
+                w.write_char(unit)
+            }
+        } else {
+            part(w, &self.r, plus)?;
+            let negative = self.i < zero;
+            w.write_str(if negative { " - " } else { " + " })?;
+            if !unit_part {
+                part(&mut Unsigned(w, false), &self.i, false)?;
+                w.write_str(mul_sign)?;
+            }
+            w.write_char(unit)
+        }
+    }
+}
+
+/// Provides simple display representation for the `Complex` struct
+///
+/// Any component type that can be displayed and compared, and that
+/// defaults to zero, is supported, such as floats, integers and rationals.
+/// Imaginary parts displayed as `1` or `-1` are written as `i` or `-i`.
+///
+/// The precision and `+` flag of the format apply to each component, and
+/// the width, fill and alignment to the whole, so that columns of complex
+/// numbers line up.  Float components are written in polar form by
+/// [`display`](Complex::display), see the [`format`](mod@format) module.
+///
+/// # Examples
+/// ```
+/// use imaginary::Complex;
+/// let num = Complex::new(3.0, -4.0).to_string();
+/// assert_eq!("3 - 4*i", num);
+/// assert_eq!(Complex::new(0, -1).to_string(), "-i");
+/// assert_eq!(Complex::new(-2, 1).to_string(), "-2 + i");
+/// let z = Complex::new(1.0, -2.0 / 3.0);
+/// assert_eq!(format!("{z:.3}"), "1.000 - 0.667*i");
+/// assert_eq!(format!("[{z:>+16.2}]"), "[  +1.00 - 0.67*i]");
+/// assert_eq!(format!("[{z:_<13.1}]"), "[1.0 - 0.7*i__]");
+/// ```
+impl<T> core::fmt::Display for Complex<T>
+where T: core::fmt::Display + PartialOrd + Default + Clone {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let (precision, plus) = (f.precision(), f.sign_plus());
+        let part = |w: &mut dyn core::fmt::Write, x: &T, plus: bool| match (precision, plus) {
+            (Some(p), true) => write!(w, "{x:+.p$}"),
+            (Some(p), false) => write!(w, "{x:.p$}"),
+            (None, true) => write!(w, "{x:+}"),
+            (None, false) => write!(w, "{x}")
+        };
+        let is_unit = |x: &T| writes_unit("1", |w| write!(w, "{x}"));
+        pad(f, |w| self.write_parts(w, plus, 'i', "*", &part, &is_unit))
+    }
+}
+
+/// Writes the components in scientific notation, with the flags of the
+/// format applied as for [`Display`](core::fmt::Display)
+macro_rules! impl_exp_fmt_for_complex {
+    ($($fmt: ident, $e: literal, $one: literal, $plus: literal, $p: literal, $plus_p: literal, $example: literal);*) => {
+        $(
+            #[doc = concat!("# Example\n```\nuse imaginary::Complex;\n", $example, "\n```")]
+            impl<T> core::fmt::$fmt for Complex<T>
+            where T: core::fmt::$fmt + PartialOrd + Default + Clone {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    let (precision, plus) = (f.precision(), f.sign_plus());
+                    let part = |w: &mut dyn core::fmt::Write, x: &T, plus: bool| match (precision, plus) {
+                        (Some(p), true) => write!(w, $plus_p, x, p = p),
+                        (Some(p), false) => write!(w, $p, x, p = p),
+                        (None, true) => write!(w, $plus, x),
+                        (None, false) => write!(w, $e, x)
+                    };
+                    let is_unit = |x: &T| writes_unit($one, |w| write!(w, $e, x));
+                    pad(f, |w| self.write_parts(w, plus, 'i', "*", &part, &is_unit))
+                }
+            }
+        )*
+    }
+}
+impl_exp_fmt_for_complex!(
+    LowerExp, "{:e}", "1e0", "{:+e}", "{:.p$e}", "{:+.p$e}",
+    "assert_eq!(format!(\"{:e}\", Complex::new(1500.0, -2.1e-4)), \"1.5e3 - 2.1e-4*i\");";
+    UpperExp, "{:E}", "1E0", "{:+E}", "{:.p$E}", "{:+.p$E}",
+    "assert_eq!(format!(\"{:.2E}\", Complex::new(1500.0, -2.1e-4)), \"1.50E3 - 2.10E-4*i\");"
+);
+
+impl<T: Clone> Complex<T> where Complex<T>: core::fmt::Display {
+    /// Formats `self` as [`Display`](core::fmt::Display) does into `buf`,
+    /// returning the text written
+    ///
+    /// Only `core::fmt` is used and nothing is allocated, so values can be
+    /// printed on targets without an allocator.  Fails if the text does not
+    /// fit in `buf`.
+    ///
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// let mut buf = [0; 32];
+    /// let z = Complex::new(1.5, -2.0);
+    /// assert_eq!(z.format_into(&mut buf), Ok("1.5 - 2*i"));
+    /// assert!(z.format_into(&mut buf[..4]).is_err());
+    /// ```
+    pub fn format_into<'a>(&self, buf: &'a mut [u8]) -> Result<&'a str, core::fmt::Error> {
+        use core::fmt::Write;
+        let mut sink = ByteSink { buf, len: 0 };
+        write!(sink, "{self}")?;
+        let ByteSink { buf, len } = sink;
+        core::str::from_utf8(&buf[..len]).map_err(|_| core::fmt::Error)
+    }
+}
+
+/// An error which can be returned when parsing a [`Complex`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseComplexError {
+    pos: usize
+}
+
+impl core::fmt::Display for ParseComplexError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "invalid complex number syntax at byte {}", self.pos)
+    }
+}
+
+impl core::error::Error for ParseComplexError {}
+
+/// Parses a complex number in the format written by `Display`
+///
+/// A real part, an imaginary part or both, in that order, are accepted,
+/// with the imaginary part written as `bi` or `b*i`, or `i` alone for a
+/// unit, and `j` accepted for `i`.  Spaces around the sign between the
+/// parts, and around the whole, are optional.
+///
+/// # Examples
+/// ```
+/// use imaginary::Complex;
+/// assert_eq!("3 - 4*i".parse(), Ok(Complex::new(3.0, -4.0)));
+/// assert_eq!("3+4i".parse(), Ok(Complex::new(3.0, 4.0)));
+/// assert_eq!("3 + 4j".parse(), Ok(Complex::new(3.0, 4.0)));
+/// assert_eq!("-i".parse(), Ok(Complex::new(0, -1)));
+/// assert_eq!("2.5e-3".parse(), Ok(Complex::new(2.5e-3, 0.0)));
+/// assert!("3 + 4".parse::<Complex<f64>>().is_err());
+/// ```
+impl<T> FromStr for Complex<T>
+where T: Clone + Default + FromStr + Neg<Output=T> {
+    type Err = ParseComplexError;
+    fn from_str(s: &str) -> Result<Complex<T>, ParseComplexError> {
+        let mut scanner = parse::Scanner { s, pos: 0 };
+        scanner.skip_spaces();
+        let z = scanner.complex::<T>().ok_or(ParseComplexError { pos: scanner.pos })?;
+        scanner.skip_spaces();
+        if scanner.pos < s.len() {
+            return Err(ParseComplexError { pos: scanner.pos });
+        }
+        Ok(z)
+    }
+}
+
+/// Lossless conversions from integer to float components
+macro_rules! impl_from_int_for_complex {
+    ($($i: ty => $($f: ty),*);*) => {
+        $($(
+            /// Converts the components exactly
+            impl From<Complex<$i>> for Complex<$f> {
+                fn from(z: Complex<$i>) -> Complex<$f> {
+                    Complex { r: z.r.into(), i: z.i.into() }
+                }
+            }
+        )*)*
+    }
+}
+impl_from_int_for_complex!(i8 => f32, f64; i16 => f32, f64; i32 => f64;
+                           u8 => f32, f64; u16 => f32, f64; u32 => f64);
+
+/// An error which can be returned when converting a float [`Complex`] to an
+/// integer one, when a component is out of range or `NAN`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TryFromComplexError(());
+
+impl core::fmt::Display for TryFromComplexError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "complex component out of range of the integer type")
+    }
+}
+
+impl core::error::Error for TryFromComplexError {}
+
+/// Conversions from float to integer components, rounding to nearest
+macro_rules! impl_try_from_float_for_complex {
+    ($($f: ident => $($i: ty),*);*) => {
+        $($(
+            /// Rounds the components to the nearest integers, halfway cases
+            /// away from zero, failing if either is out of range or `NAN`
             ///
-            /// # Examples
-            /// ```
-            /// use imaginary::Complex;
-            /// let num = Complex::new(3.0, -4.0).to_string();
-            /// assert_eq!("3 - 4*i", num);
-            /// ```
-            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-                if self.i == 0.0 {
-                    write!(f, "{}", self.r)
-                } else if self.r == 0.0 {
-                    if self.i == 1.0 {
-                        write!(f, "i")
-                    } else if self.i == -1.0 {
-                        write!(f, "-i")
-                    } else {
-                        write!(f, "{}*i", self.i)
-                    }
-                } else if self.i < 0.0 {
-                    if self.i == -1.0 {
-                        write!(f, "{} - i", self.r)
-                    } else {
-                        write!(f, "{} - {}*i", self.r, -self.i)
-                    }
-                } else {
-                    if self.i == 1.0 {
-                        write!(f, "{} + i", self.r)
-                    } else {
-                        write!(f, "{} + {}*i", self.r, self.i)
-                    }
+            /// Other roundings are made by rounding first, as in
+            /// `z.floor().try_into()`.
+            impl TryFrom<Complex<$f>> for Complex<$i> {
+                type Error = TryFromComplexError;
+                fn try_from(z: Complex<$f>) -> Result<Complex<$i>, TryFromComplexError> {
+                    // both bounds are exact: zero or plus or minus a power of 2
+                    let (min, end) = (<$i>::MIN as $f, (<$i>::MAX / 2 + 1) as $f * 2.0);
+                    let convert = |x: $f| {
+                        if x >= min && x < end { Ok(x as $i) } else { Err(TryFromComplexError(())) }
+                    };
+                    let z = z.round();
+                    Ok(Complex { r: convert(z.r)?, i: convert(z.i)? })
                 }
             }
+        )*)*
+    }
+}
+impl_try_from_float_for_complex!(f32 => i8, i16, i32, i64, u8, u16, u32, u64;
+                                 f64 => i8, i16, i32, i64, u8, u16, u32, u64);
+
+#[cfg(feature = "defmt")]
+macro_rules! impl_defmt_for_complex {
+    ($t: ident, $format: literal) => {
+        impl defmt::Format for Complex<$t> {
+            /// Logs the two components as raw floats, formatted as
+            /// `r + i*i` by the host
+            fn format(&self, f: defmt::Formatter<'_>) {
+                defmt::write!(f, $format, self.r, self.i)
+            }
+        }
+    }
+}
+#[cfg(feature = "defmt")]
+impl_defmt_for_complex!(f32, "{=f32} + {=f32}*i");
+#[cfg(feature = "defmt")]
+impl_defmt_for_complex!(f64, "{=f64} + {=f64}*i");
+
+/// A [`fmt::Write`](core::fmt::Write) forwarding to a `ufmt` formatter,
+/// keeping the error of its writer
+#[cfg(feature = "ufmt")]
+struct UfmtSink<'a, 'w, W: ufmt::uWrite + ?Sized> {
+    f: &'a mut ufmt::Formatter<'w, W>,
+    error: Option<W::Error>
+}
+
+#[cfg(feature = "ufmt")]
+impl<W: ufmt::uWrite + ?Sized> core::fmt::Write for UfmtSink<'_, '_, W> {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.f.write_str(s).map_err(|e| {
+            self.error = Some(e);
+            core::fmt::Error
+        })
+    }
+}
+
+/// # Example
+/// ```
+/// use imaginary::Complex;
+/// let mut s = String::new();
+/// ufmt::uwrite!(s, "z = {}", Complex::new(1.5, -2.0)).unwrap();
+/// assert_eq!(s, "z = 1.5 - 2*i");
+/// ```
+#[cfg(feature = "ufmt")]
+impl<T: Clone> ufmt::uDisplay for Complex<T> where Complex<T>: core::fmt::Display {
+    /// Writes `self` as [`Display`](core::fmt::Display) does, streamed
+    /// through `core::fmt` into the writer, since `ufmt` formats no floats
+    fn fmt<W: ufmt::uWrite + ?Sized>(&self, f: &mut ufmt::Formatter<'_, W>) -> Result<(), W::Error> {
+        use core::fmt::Write;
+        let mut sink = UfmtSink { f, error: None };
+        match write!(sink, "{self}") {
+            Ok(()) => Ok(()),
+            Err(_) => Err(sink.error.expect("a Display implementation returned an error unexpectedly"))
         }
     }
 }
-impl_display_for_complex!(f32);
-impl_display_for_complex!(f64);
 
 macro_rules! impl_ops_for_complex {
     ($t: ty) => {
@@ -415,178 +1190,482 @@ macro_rules! impl_ops_for_complex {
                 }
             }
         }
+        /// # Example
+        /// ```
+        /// use imaginary::Complex;
+        /// let mut z = Complex::new(3.0, -2.0);
+        /// z += 1.0;
+        /// assert_eq!(z, Complex::new(4.0, -2.0));
+        /// ```
+        impl AddAssign<$t> for Complex<$t> {
+            fn add_assign(&mut self, rhs: $t) {
+                self.r += rhs;
+            }
+        }
+        /// # Example
+        /// ```
+        /// use imaginary::Complex;
+        /// let mut z = Complex::new(3.0, -2.0);
+        /// z -= 1.0;
+        /// assert_eq!(z, Complex::new(2.0, -2.0));
+        /// ```
+        impl SubAssign<$t> for Complex<$t> {
+            fn sub_assign(&mut self, rhs: $t) {
+                self.r -= rhs;
+            }
+        }
+        /// # Example
+        /// ```
+        /// use imaginary::Complex;
+        /// let mut z = Complex::new(1.0, -2.0);
+        /// z *= 3.0;
+        /// assert_eq!(z, Complex::new(3.0, -6.0));
+        /// ```
+        impl MulAssign<$t> for Complex<$t> {
+            fn mul_assign(&mut self, rhs: $t) {
+                self.r *= rhs;
+                self.i *= rhs;
+            }
+        }
+        /// # Example
+        /// ```
+        /// use imaginary::Complex;
+        /// let mut z = Complex::new(3.0, -4.0);
+        /// z /= 5.0;
+        /// assert_eq!(z, Complex::new(0.6, -0.8));
+        /// ```
+        impl DivAssign<$t> for Complex<$t> {
+            fn div_assign(&mut self, rhs: $t) {
+                self.r /= rhs;
+                self.i /= rhs;
+            }
+        }
     }
 }
 impl_ops_for_complex!(f32);
 impl_ops_for_complex!(f64);
 
-macro_rules! impl_complex {
-    ($t: ident) => {
-        impl Complex<$t>{
+/// The elementary functions, for any [`Float`] component type
+impl<T: Float> Complex<T> {
+
+    pub fn recip(self) -> Self {
+        self.conj().div_real(self.r * self.r + self.i * self.i)
+    }
+
+    /// The absolute value or complex modulus
+    ///
+    /// This is also known as the magnitude or norm of
+    /// the complex number
+    pub fn abs(self) -> T {
+        self.r.hypot(self.i)
+    }
 
-            pub fn recip(self) -> Self {
-                self.conj() / (self.r * self.r + self.i * self.i)
+    /// Fast approximate absolute value
+    ///
+    /// Uses the larger of two alpha max plus beta min estimates, with
+    /// a maximum relative error of 0.98%.  No square root is taken.
+    pub fn abs_approx(self) -> T {
+        let (x, y) = (self.r.abs(), self.i.abs());
+        let (max, min) = if x > y { (x, y) } else { (y, x) };
+        let near = T::from_f64(0.9902994) * max + T::from_f64(0.1969828) * min;
+        let far = T::from_f64(0.8395353) * max + T::from_f64(0.5609596) * min;
+        if near > far { near } else { far }
+    }
+
+    /// Fast approximate phase angle
+    ///
+    /// Reduces to an octant and evaluates a minimax polynomial for the
+    /// arctangent, with a maximum absolute error of 6.1 × 10⁻⁴ radians
+    /// (0.035°).  The angle of zero is 0.
+    pub fn angle_approx(self) -> T {
+        let (x, y) = (self.r.abs(), self.i.abs());
+        let (max, min) = if x > y { (x, y) } else { (y, x) };
+        if max == T::ZERO {
+            return T::ZERO;
+        }
+        let t = min / max;
+        let t2 = t * t;
+        let mut a = t * (T::from_f64(0.995358) + t2 * (T::from_f64(-0.2886902) + t2 * T::from_f64(0.07933904)));
+        if y > x {
+            a = T::PI / T::from_f64(2.0) - a;
+        }
+        if self.r < T::ZERO {
+            a = T::PI - a;
+        }
+        if self.i < T::ZERO {
+            a = -a;
+        }
+        a
+    }
+
+    /// Returns the direction with a absolute value of 1
+    pub fn sign(self) -> Complex<T> {
+        self.div_real(self.abs())
+    }
+
+    /// Rounds both components to the nearest integer, halfway cases
+    /// away from zero
+    pub fn round(self) -> Complex<T> {
+        Complex::new(self.r.round(), self.i.round())
+    }
+
+    /// Rounds both components down
+    pub fn floor(self) -> Complex<T> {
+        Complex::new(self.r.floor(), self.i.floor())
+    }
+
+    /// Rounds both components up
+    pub fn ceil(self) -> Complex<T> {
+        Complex::new(self.r.ceil(), self.i.ceil())
+    }
+
+    /// Rounds both components towards zero
+    pub fn trunc(self) -> Complex<T> {
+        Complex::new(self.r.trunc(), self.i.trunc())
+    }
+
+    /// Phase angle or argument
+    ///
+    /// The angle returned is in radians from the real axis
+    pub fn angle(self) -> T {
+        self.i.atan2(self.r)
+    }
+
+    /// Distance between `self` and `other` on the complex plane,
+    /// |`self` − `other`|
+    ///
+    /// Computed with `hypot`, so it neither overflows nor underflows
+    /// for distances that are representable.
+    pub fn distance(self, other: Complex<T>) -> T {
+        (self.r - other.r).hypot(self.i - other.i)
+    }
+
+    /// Smallest signed angle from `other` to `self`, in radians
+    ///
+    /// The difference of the phase angles is wrapped into (−π, π],
+    /// so phases either side of the negative real axis compare as
+    /// close.
+    ///
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// use std::f64::consts::TAU;
+    /// // 3 and −3 radians are 2π − 6 apart the short way round
+    /// let (a, b) = (Complex::cis(3.0), Complex::cis(-3.0));
+    /// assert!((a.arg_diff(b) - (6.0 - TAU)).abs() < 1e-12);
+    /// ```
+    pub fn arg_diff(self, other: Complex<T>) -> T {
+        let tau = T::PI + T::PI;
+        let d = self.angle() - other.angle();
+        if d > T::PI {
+            d - tau
+        } else if d <= -T::PI {
+            d + tau
+        } else {
+            d
+        }
+    }
+
+    /// Euler's formula
+    ///
+    /// `cis(θ)` = cos(θ) + i sin(θ) = e<sup>θ i</sup>
+    pub fn cis(theta: T) -> Complex<T> {
+        Complex::new(theta.cos(), theta.sin())
+    }
+
+    /// The exponential function, e<sup>z</sup>
+    pub fn exp(self) -> Complex<T> {
+        Complex::cis(self.i).mul_real(self.r.exp())
+    }
+
+    /// The natural logarithm
+    ///
+    /// The branch cut is along the negative real axis, and the sign
+    /// of a zero imaginary part picks the side of the cut, as for C's
+    /// `clog`: ln(−1 ± 0i) = ±πi.
+    pub fn ln(self) -> Complex<T> {
+        Complex::new(self.abs().ln(), self.angle())
+    }
+
+    /// Base 2 logarithm, with the branch cut of [`ln`](Self::ln)
+    pub fn log2(self) -> Complex<T> {
+        Complex::new(self.abs().log2(), self.angle() * T::LOG2_E)
+    }
+
+    /// Base 10 logarithm, with the branch cut of [`ln`](Self::ln)
+    ///
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// let z = Complex::new(0.0, 100.0).log10();
+    /// assert_eq!(z.r, 2.0);
+    /// assert!((z.i - std::f64::consts::FRAC_PI_2 * std::f64::consts::LOG10_E).abs() < 1e-15);
+    /// ```
+    pub fn log10(self) -> Complex<T> {
+        Complex::new(self.abs().log10(), self.angle() * T::LOG10_E)
+    }
+
+    /// Logarithm to a complex `base`, ln(z) / ln(`base`)
+    pub fn log(self, base: Complex<T>) -> Complex<T> {
+        self.ln() / base.ln()
+    }
+
+    /// Power, z<sup>n</sup> where n is an integer, by repeated
+    /// squaring
+    ///
+    /// Only complex multiplications are used, about 2 log₂|n| of
+    /// them, and one division for a negative `n`.  Prefer this to
+    /// [`powf`](Self::powf) for integer exponents: it is faster,
+    /// it is exact when the intermediate products are, as for
+    /// Gaussian integers, and it does not round the angle, so
+    /// that i<sup>2</sup> is exactly −1 rather than −1 + 1.2×10<sup>−16</sup>i.
+    /// Zero to the power zero is one.
+    ///
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// let z = Complex::new(1.0, 1.0);
+    /// assert_eq!(z.powi(8), Complex::new(16.0, 0.0));
+    /// assert_eq!(z.powi(-2), Complex::new(0.0, -0.5));
+    /// ```
+    pub fn powi(self, n: i32) -> Complex<T> {
+        let mut e = n.unsigned_abs();
+        // inverting first underflows to zero where inverting the
+        // power would divide by infinity
+        let mut base = if n < 0 { self.recip() } else { self };
+        let mut acc = Complex::new(T::ONE, T::ZERO);
+        while e > 0 {
+            if e & 1 == 1 {
+                acc *= base;
             }
-
-            /// The absolute value or complex modulus
-            ///
-            /// This is also known as the magnitude or norm of
-            /// the complex number
-            pub fn abs(self) -> $t {
-                self.r.hypot(self.i)
+            e >>= 1;
+            if e > 0 {
+                base = base * base;
             }
+        }
+        acc
+    }
 
-            /// Returns the direction with a absolute value of 1
-            pub fn sign(self) -> Complex<$t> {
-                self / self.abs()
-            }
+    /// Power, z<sup>n</sup> where n is a float
+    pub fn powf(self, n: T) -> Complex<T> {
+        let r = self.abs().powf(n);
+        let theta = n * self.angle();
+        Complex::cis(theta).mul_real(r)
+    }
+    /// Power, z<sup>n</sup> where n is complex
+    pub fn powc(self, n: Complex<T>) -> Complex<T> {
+        (n * self.ln()).exp()
+    }
 
-            /// Phase angle or argument
-            ///
-            /// The angle returned is in radians from the real axis
-            pub fn angle(self) -> $t {
-                self.i.atan2(self.r)
+    /// Square root, <math> <msqrt> <mi> z </mi> </msqrt> </math>
+    ///
+    /// The branch cut is along the negative real axis, and the sign
+    /// of a zero imaginary part picks the side of the cut.  Zeros,
+    /// infinities and `NAN`s follow C's `csqrt`, so that
+    /// sqrt(−1 ± 0i) = ±i and sqrt(x ± ∞i) = ∞ ± ∞i for any x.
+    pub fn sqrt(self) -> Complex<T> {
+        let (x, y) = self.into();
+        let half = T::from_f64(0.5);
+        if y.is_infinite() {
+            return Complex::new(T::INFINITY, y);
+        }
+        if x.is_infinite() {
+            // y - y is zero, or NAN for a NAN y
+            #[allow(clippy::eq_op)]
+            let zero = y - y;
+            return if x > T::ZERO {
+                Complex::new(x, zero.copysign(y))
+            } else {
+                Complex::new(zero.abs(), x.abs().copysign(y))
+            };
+        }
+        if y == T::ZERO {
+            if x >= T::ZERO {
+                // +0 for either zero
+                Complex::new(x.sqrt().abs(), y)
+            } else if x < T::ZERO {
+                Complex::new(T::ZERO, (-x).sqrt().copysign(y))
+            } else {
+                Complex::new(x, x)
             }
-
-            /// Euler's formula
-            ///
-            /// `cis(θ)` = cos(θ) + i sin(θ) = e<sup>θ i</sup>
-            pub fn cis(theta: $t) -> Complex<$t> {
-                Complex::new(theta.cos(), theta.sin())
+        } else {
+            let r = self.abs();
+            let x_num = r + x;
+            if x_num != T::ZERO {
+                let x_rt = x_num.sqrt();
+                Complex::new(x_rt, y / x_rt).mul_real(T::FRAC_1_SQRT_2)
+            } else {
+                let x_rt = (-x).sqrt();
+                Complex::new(half * y.abs() / x_rt, x_rt.copysign(y))
             }
+        }
+    }
 
-            /// The exponential function, e<sup>z</sup>
-            pub fn exp(self) -> Complex<$t> {
-                let r = self.r.exp();
-                r * Complex::<$t>::cis(self.i)
-            }
+    /// Cube root,
+    /// <math> <mroot> <mi> z </mi> <mn> 3 </mn> </mroot> </math>
+    pub fn cbrt(self) -> Complex<T> {
+        let three = T::from_f64(3.0);
+        let r = self.abs().cbrt();
+        if r == T::ZERO {
+            return Complex::new(T::ZERO, T::ZERO);
+        }
+        let theta = self.angle() / three;
+        let cbrt = Complex::cis(theta).mul_real(r);
+        let cbrt_sq = cbrt * cbrt;
+        cbrt - (cbrt * cbrt_sq - self) / cbrt_sq.mul_real(three)
+    }
 
-            /// The natural logarithm
-            pub fn ln(self) -> Complex<$t> {
-                Complex::new(self.abs().ln(), self.angle())
-            }
+    /// Cosine
+    pub fn cos(self) -> Complex<T> {
+        let (x, y) = self.into();
+        Complex::new(x.cos() * y.cosh(), -x.sin() * y.sinh())
+    }
+    /// Sine
+    pub fn sin(self) -> Complex<T> {
+        let (x, y) = self.into();
+        Complex::new(x.sin() * y.cosh(), x.cos() * y.sinh())
+    }
+    /// Tangent
+    pub fn tan(self) -> Complex<T> {
+        let (x, y) = self.into();
+        let num = Complex::new(x.tan(), y.tanh());
+        let dom = Complex::new(T::ONE, -x.tan() * y.tanh());
+        num / dom
+    }
+    /// Secant
+    pub fn sec(self) -> Complex<T> {
+        self.cos().recip()
+    }
+    /// Cosecant
+    pub fn csc(self) -> Complex<T> {
+        self.sin().recip()
+    }
+    /// Cotangent
+    pub fn cot(self) -> Complex<T> {
+        let (x, y) = self.into();
+        let num = Complex::new(T::ONE, -x.tan() * y.tanh());
+        let dom = Complex::new(x.tan(), y.tanh());
+        num / dom
+    }
+    /// Hyperbolic cosine
+    pub fn cosh(self) -> Complex<T> {
+        let (x, y) = self.into();
+        Complex::new(x.cosh() * y.cos(), x.sinh() * y.sin())
+    }
+    /// Hyperbolic sine
+    pub fn sinh(self) -> Complex<T> {
+        let (x, y) = self.into();
+        Complex::new(x.sinh() * y.cos(), x.cosh() * y.sin())
+    }
+    /// Hyperbolic tangent
+    pub fn tanh(self) -> Complex<T> {
+        let (x, y) = self.into();
+        let num = Complex::new(x.tanh(), y.tan());
+        let dom = Complex::new(T::ONE, x.tanh() * y.tan());
+        num / dom
+    }
+    /// Hyperbolic secant
+    pub fn sech(self) -> Complex<T> {
+        self.cosh().recip()
+    }
+    /// Hyperbolic cosecant
+    pub fn csch(self) -> Complex<T> {
+        self.sinh().recip()
+    }
+    /// Hyperbolic cotangent
+    pub fn coth(self) -> Complex<T> {
+        let (x, y) = self.into();
+        let num = Complex::new(T::ONE, x.tanh() * y.tan());
+        let dom = Complex::new(x.tanh(), y.tan());
+        num / dom
+    }
 
-            /// Power, z<sup>n</sup> where n is a float
-            pub fn powf(self, n: $t) -> Complex<$t> {
-                let r = self.abs().powf(n);
-                let theta = n * self.angle();
-                r * Complex::<$t>::cis(theta)
-            }
-            /// Power, z<sup>n</sup> where n is complex
-            pub fn powc(self, n: Complex<$t>) -> Complex<$t> {
-                (n * self.ln()).exp()
-            }
+    /// Absolute value without the scaling of `hypot`,
+    /// √(r² + i²)
+    ///
+    /// The squares overflow for components beyond about the square
+    /// root of the largest float, and lose precision below about the
+    /// square root of the smallest normal one.  Use
+    /// [`abs`](Self::abs) when the range is unknown.
+    pub fn abs_fast(self) -> T {
+        (self.r * self.r + self.i * self.i).sqrt()
+    }
 
-            /// Square root, <math> <msqrt> <mi> z </mi> </msqrt> </math>
-            pub fn sqrt(self) -> Complex<$t> {
-                let (x, y) = self.into();
-                if y == 0.0 {
-                    if x >= 0.0 {
-                        Complex::new(x.sqrt(), y)
-                    } else {
-                        Complex::new(0.0, (-x).sqrt().copysign(y))
-                    }
-                } else {
-                    let r = self.abs();
-                    let x_num = r + x;
-                    if x_num != 0.0 {
-                        use std::$t::consts::FRAC_1_SQRT_2;
-                        let x_rt = x_num.sqrt();
-                        Complex::new(x_rt, y / x_rt) * FRAC_1_SQRT_2
-                    } else {
-                        let x_rt = (-x).sqrt();
-                        Complex::new(0.5 * y.abs() / x_rt, x_rt.copysign(y))
-                    }
-                }
-            }
+    /// Multiplication, the same as `*`
+    ///
+    /// The product has no scaling to skip, so this is only for
+    /// symmetry with [`div_fast`](Self::div_fast) in code that spells
+    /// out the fast variants.
+    ///
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// let z = Complex::new(1.0, 2.0);
+    /// let w = Complex::new(3.0, -4.0);
+    /// assert_eq!(z.mul_fast(w), z * w);
+    /// ```
+    pub fn mul_fast(self, rhs: Complex<T>) -> Complex<T> {
+        self * rhs
+    }
 
-            /// Cube root,
-            /// <math> <mroot> <mi> z </mi> <mn> 3 </mn> </mroot> </math>
-            pub fn cbrt(self) -> Complex<$t> {
-                let r = self.abs().cbrt();
-                if r == 0.0 {
-                    return Complex::new(0.0, 0.0);
-                }
-                let theta = self.angle() / 3.0;
-                let cbrt = r * Complex::<$t>::cis(theta);
-                let cbrt_sq = cbrt * cbrt;
-                cbrt - (cbrt * cbrt_sq - self) / (3.0 * cbrt_sq)
-            }
+    /// Reciprocal with one real division of |z|² followed by
+    /// multiplications, rather than the two divisions of
+    /// [`recip`](Self::recip)
+    ///
+    /// Like `recip`, |z|² overflows for components beyond about the
+    /// square root of the largest float.
+    pub fn recip_fast(self) -> Complex<T> {
+        let scale = T::ONE / (self.r * self.r + self.i * self.i);
+        Complex::new(self.r * scale, -self.i * scale)
+    }
 
-            /// Cosine
-            pub fn cos(self) -> Complex<$t> {
-                let (x, y) = self.into();
-                Complex::new(x.cos() * y.cosh(), -x.sin() * y.sinh())
-            }
-            /// Sine
-            pub fn sin(self) -> Complex<$t> {
-                let (x, y) = self.into();
-                Complex::new(x.sin() * y.cosh(), x.cos() * y.sinh())
-            }
-            /// Tangent
-            pub fn tan(self) -> Complex<$t> {
-                let (x, y) = self.into();
-                let num = Complex::new(x.tan(), y.tanh());
-                let dom = Complex::new(1.0, -x.tan() * y.tanh());
-                num / dom
-            }
-            /// Secant
-            pub fn sec(self) -> Complex<$t> {
-                self.cos().recip()
-            }
-            /// Cosecant
-            pub fn csc(self) -> Complex<$t> {
-                self.sin().recip()
-            }
-            /// Cotangent
-            pub fn cot(self) -> Complex<$t> {
-                let (x, y) = self.into();
-                let num = Complex::new(1.0, -x.tan() * y.tanh());
-                let dom = Complex::new(x.tan(), y.tanh());
-                num / dom
-            }
-            /// Hyperbolic cosine
-            pub fn cosh(self) -> Complex<$t> {
-                let (x, y) = self.into();
-                Complex::new(x.cosh() * y.cos(), x.sinh() * y.sin())
-            }
-            /// Hyperbolic sine
-            pub fn sinh(self) -> Complex<$t> {
-                let (x, y) = self.into();
-                Complex::new(x.sinh() * y.cos(), x.cosh() * y.sin())
-            }
-            /// Hyperbolic tangent
-            pub fn tanh(self) -> Complex<$t> {
-                let (x, y) = self.into();
-                let num = Complex::new(x.tanh(), y.tan());
-                let dom = Complex::new(1.0, x.tanh() * y.tan());
-                num / dom
-            }
-            /// Hyperbolic secant
-            pub fn sech(self) -> Complex<$t> {
-                self.cosh().recip()
-            }
-            /// Hyperbolic cosecant
-            pub fn csch(self) -> Complex<$t> {
-                self.sinh().recip()
-            }
-            /// Hyperbolic cotangent
-            pub fn coth(self) -> Complex<$t> {
-                let (x, y) = self.into();
-                let num = Complex::new(1.0, x.tanh() * y.tan());
-                let dom = Complex::new(x.tanh(), y.tan());
-                num / dom
-            }
+    /// Division with one real division of |rhs|² followed by
+    /// multiplications, rather than the two divisions of `/`
+    ///
+    /// Multiplying by the rounded reciprocal can differ from `/` in
+    /// the last bit.  Neither guards against overflow or underflow of
+    /// |rhs|², so both need components within about the square root
+    /// of the float range.
+    ///
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// let z = Complex::new(1.0, 2.0);
+    /// let w = Complex::new(3.0, -4.0);
+    /// assert!((z.div_fast(w) - z / w).abs() < 1e-15);
+    /// ```
+    pub fn div_fast(self, rhs: Complex<T>) -> Complex<T> {
+        self * rhs.recip_fast()
+    }
+
+    /// Natural logarithm from ½ ln(r² + i²), without `hypot` and
+    /// with the range of [`abs_fast`](Self::abs_fast)
+    pub fn ln_fast(self) -> Complex<T> {
+        Complex::new(T::from_f64(0.5) * (self.r * self.r + self.i * self.i).ln(), self.angle())
+    }
+
+    /// Square root without the special cases of zeros, infinities
+    /// and `NAN`s, and with the range of [`abs_fast`](Self::abs_fast)
+    ///
+    /// The sign of the imaginary part still picks the side of the
+    /// branch cut, but zero gives `NAN`s rather than zero.
+    pub fn sqrt_fast(self) -> Complex<T> {
+        let (x, y) = self.into();
+        let half = T::from_f64(0.5);
+        let t = (half * (self.abs_fast() + x.abs())).sqrt();
+        if x >= T::ZERO {
+            Complex::new(t, half * y / t)
+        } else {
+            Complex::new(half * y.abs() / t, t.copysign(y))
         }
     }
 }
-impl_complex!(f32);
-impl_complex!(f64);
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::string::ToString;
 
     #[test]
     fn check_const_path(){
@@ -610,6 +1689,270 @@ mod tests {
         assert_eq!(a.conj(), Complex{r: 1.0, i: -2.0});
     }
 
+    // Tests the operators on references, with components that are not Copy
+    #[test]
+    fn reference_ops() {
+        use num_bigint::BigInt;
+        let a = Complex::new(2.5, -1.0);
+        let b = Complex::new(-0.5, 3.0);
+        let (x, y) = (&a, &b);
+        assert_eq!((-x, x + y, x - y, x * y, x / y), (-a, a + b, a - b, a * b, a / b));
+
+        let big = |r: i64, i: i64| Complex::new(BigInt::from(r), BigInt::from(i));
+        let z = big(i64::MAX, -3);
+        let w = big(2, i64::MIN);
+        assert_eq!(&z + &w, Complex::new(BigInt::from(i64::MAX) + 2, BigInt::from(i64::MIN) - 3));
+        assert_eq!(&z - &w, Complex::new(BigInt::from(i64::MAX) - 2, -BigInt::from(i64::MIN) - 3));
+        assert_eq!(-&z, big(-i64::MAX, 3));
+        let product = &z * &w;
+        assert_eq!(product.r, BigInt::from(i64::MAX) * 2 + BigInt::from(3) * BigInt::from(i64::MIN));
+        assert_eq!(&(&product / &w) - &z, big(0, 0));
+        assert_eq!(Complex::from((BigInt::from(7), BigInt::from(-1))), big(7, -1));
+        assert_eq!(<[BigInt; 2]>::from(big(1, 2)), [BigInt::from(1), BigInt::from(2)]);
+        assert_eq!(Complex::from_imag(BigInt::from(5)), big(0, 5));
+        assert_eq!(z.clone().conj(), big(i64::MAX, 3));
+        assert_eq!((z.clone().mul_i(), z.rotate_quarter_turns(-1)), (big(3, i64::MAX), big(-3, -i64::MAX)));
+        assert_eq!(big(1, 2).dot(big(3, 4)), BigInt::from(11));
+        assert_eq!(big(4, -6).div_real(BigInt::from(2)), big(2, -3));
+        assert_eq!("-170141183460469231731687303715884105728 + 2i".parse(), Ok(Complex::new(BigInt::from(i128::MIN), BigInt::from(2))));
+    }
+
+    #[test]
+    fn display_big() {
+        use num_bigint::BigInt;
+        assert_eq!(Complex::new(BigInt::from(3), BigInt::from(-4)).to_string(), "3 - 4*i");
+        let z = Complex::new(BigInt::from(7), -BigInt::from(u128::MAX) * 2);
+        assert_eq!(z.to_string(), "7 - 680564733841876926926749214863536422910*i");
+        assert_eq!(z.conj().to_string(), "7 + 680564733841876926926749214863536422910*i");
+        assert_eq!(format!("{:#}", Complex::new(BigInt::from(0), BigInt::from(-1))), "-i");
+        assert_eq!(Complex::new(BigInt::from(0), BigInt::from(5)).to_string(), "5*i");
+    }
+
+    #[test]
+    fn format_into() {
+        let mut buf = [0u8; 64];
+        for z in [Complex::new(0.0, -1.0), Complex::new(-0.25, 1e-3), Complex::new(f64::NAN, 2.0)] {
+            assert_eq!(z.format_into(&mut buf).unwrap(), z.to_string());
+        }
+        let z = Complex::new(1.0f32, 1.0);
+        assert_eq!(z.format_into(&mut buf[..5]), Ok("1 + i"));
+        assert!(z.format_into(&mut buf[..4]).is_err());
+        assert!(z.format_into(&mut []).is_err());
+    }
+
+    #[cfg(feature = "ufmt")]
+    #[test]
+    fn ufmt() {
+        // a writer of at most `room` bytes, failing with the length of what
+        // did not fit
+        struct Limited(String, usize);
+        impl ufmt::uWrite for Limited {
+            type Error = usize;
+            fn write_str(&mut self, s: &str) -> Result<(), usize> {
+                if self.0.len() + s.len() > self.1 {
+                    return Err(s.len());
+                }
+                self.0.push_str(s);
+                Ok(())
+            }
+        }
+        let z = Complex::new(-0.25f32, 3.0);
+        let mut w = Limited(String::new(), 64);
+        ufmt::uwrite!(w, "[{}] [{}]", z, Complex::new(2i64, -1)).unwrap();
+        assert_eq!(w.0, "[-0.25 + 3*i] [2 - i]");
+        assert_eq!(ufmt::uwrite!(Limited(String::new(), 6), "{}", z), Err(3));
+    }
+
+    #[test]
+    fn display_flags() {
+        let z = Complex::new(-1.25, 0.5);
+        assert_eq!(format!("{z:.1}"), "-1.2 + 0.5*i");
+        assert_eq!(format!("{z:+}"), "-1.25 + 0.5*i");
+        assert_eq!(format!("{:+.2}", Complex::new(1.0, -1.0)), "+1.00 - i");
+        assert_eq!(format!("{:+}", Complex::new(0.0, 1.0)), "+i");
+        assert_eq!(format!("{:+}", Complex::new(0.0, 2.0)), "+2*i");
+        assert_eq!(format!("{:+.1}", Complex::new(3.0, 0.0)), "+3.0");
+        assert_eq!(format!("{z:14}|"), " -1.25 + 0.5*i|");
+        assert_eq!(format!("{z:<15}|"), "-1.25 + 0.5*i  |");
+        assert_eq!(format!("{z:*^17}|"), "**-1.25 + 0.5*i**|");
+        assert_eq!(format!("{z:4}"), "-1.25 + 0.5*i");
+        assert_eq!(format!("{:>6}", Complex::new(0, -1)), "    -i");
+        assert_eq!(format!("{:>6.1}", Complex::new(f64::NAN, 0.0)), "   NaN");
+        // padding counts characters, not bytes
+        assert_eq!(format!("{:é>5}", Complex::new(0, 1)), "ééééi");
+    }
+
+    #[test]
+    fn exp_formats() {
+        let z = Complex::new(-1234.5, 6.7e-8);
+        assert_eq!(format!("{z:e}"), "-1.2345e3 + 6.7e-8*i");
+        assert_eq!(format!("{z:E}"), "-1.2345E3 + 6.7E-8*i");
+        assert_eq!(format!("{z:.2e}"), "-1.23e3 + 6.70e-8*i");
+        assert_eq!(format!("{:+.1e}", Complex::new(0.0, 2.5e10)), "+2.5e10*i");
+        assert_eq!(format!("{:e}", Complex::new(3.0f32, 1.0)), "3e0 + i");
+        assert_eq!(format!("{:>13.0e}|", Complex::new(-5.0, -50.0)), " -5e0 - 5e1*i|");
+        assert_eq!(format!("{z:e}").parse(), Ok(z));
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        let values = [0.0, -0.0, 1.0, -1.0, 2.5, -4.0, 1e-7, 6.02e23, f64::INFINITY, -f64::INFINITY, f64::NAN];
+        for r in values {
+            for i in values {
+                let z = Complex::new(r, i);
+                let text = z.to_string();
+                let w: Complex<f64> = text.parse().unwrap_or_else(|e| panic!("{text}: {e}"));
+                assert!((w.r == r || r.is_nan() && w.r.is_nan()) && (w.i == i || i.is_nan() && w.i.is_nan()), "{text}");
+                let z = Complex::new(r as f32, i as f32);
+                let w: Complex<f32> = z.to_string().parse().unwrap();
+                assert!(w == z || z.r.is_nan() || z.i.is_nan(), "{z}");
+            }
+        }
+        assert_eq!(Complex::new(-2, 1).to_string().parse(), Ok(Complex::new(-2, 1)));
+    }
+
+    #[test]
+    fn parse_forms() {
+        let parse = |s: &str| s.parse::<Complex<f64>>();
+        assert_eq!(parse("3"), Ok(Complex::new(3.0, 0.0)));
+        assert_eq!(parse("-i"), Ok(Complex::new(0.0, -1.0)));
+        assert_eq!(parse("4i"), Ok(Complex::new(0.0, 4.0)));
+        assert_eq!(parse("3+4i"), Ok(Complex::new(3.0, 4.0)));
+        assert_eq!(parse(" 3 - 4*i "), Ok(Complex::new(3.0, -4.0)));
+        assert_eq!(parse("-.5 + i"), Ok(Complex::new(-0.5, 1.0)));
+        assert_eq!(parse("1e3-2.5E-2i"), Ok(Complex::new(1e3, -2.5e-2)));
+        for bad in ["", "i3", "3 + 4", "3 4i", "4i + 3", "3 + 4i + 1", "1.5.2", "3 +- 4i"] {
+            assert!(parse(bad).is_err(), "{bad}");
+        }
+        assert_eq!(parse("3 + 4").unwrap_err().to_string(), "invalid complex number syntax at byte 5");
+        assert!("1.5".parse::<Complex<i32>>().is_err());
+    }
+
+    #[test]
+    fn unit_constructors() {
+        fn rotate_quarter<T: One + Default + Mul<Output=T> + Add<Output=T> + Sub<Output=T>>(z: Complex<T>) -> Complex<T> {
+            z * Complex::i()
+        }
+        assert_eq!(rotate_quarter(Complex::new(2i64, 3)), Complex::new(-3, 2));
+        assert_eq!(rotate_quarter(Complex::<f32>::from_real(1.5)), Complex::from_imag(1.5));
+        assert_eq!(Complex::<u8>::i(), Complex::new(0, 1));
+        assert_eq!(Complex::from_real(4.0), Complex::from(4.0));
+    }
+
+    #[test]
+    fn dot_wedge() {
+        let (a, b) = (Complex::new(3.0, -1.5), Complex::new(-0.5, 2.0));
+        let product = a.conj() * b;
+        assert_eq!((a.dot(b), a.wedge(b)), (product.r, product.i));
+        assert_eq!((a.dot(a), a.wedge(a)), (a.r * a.r + a.i * a.i, 0.0));
+        assert_eq!(b.wedge(a), -a.wedge(b));
+        assert_eq!(Complex::new(2, 5).wedge(Complex::new(-5, 2)), 29);
+    }
+
+    #[test]
+    fn quarter_turns() {
+        let z = Complex::new(1.5, -0.0);
+        assert_eq!(z.mul_i(), z * Complex::new(0.0, 1.0));
+        assert_eq!(z.mul_neg_i(), Complex::new(-0.0, -1.5));
+        for k in -9i32..9 {
+            let expected = (0..k.rem_euclid(4)).fold(z, |w, _| w.mul_i());
+            assert_eq!(z.rotate_quarter_turns(k), expected, "{k}");
+        }
+        assert_eq!(Complex::new(2i8, -3).rotate_quarter_turns(i32::MIN), Complex::new(2, -3));
+        assert_eq!(Complex::new(f64::INFINITY, 1.0).mul_i(), Complex::new(-1.0, f64::INFINITY));
+    }
+
+    #[test]
+    fn real_imag_operands() {
+        let z = Complex::new(1.5, -2.5);
+        let (x, y) = (0.75, -4.0);
+        assert_eq!(z.mul_real(x), z * Complex::new(x, 0.0));
+        assert_eq!(z.mul_imag(y), z * Complex::new(0.0, y));
+        assert_eq!(z.div_real(x), z / Complex::new(x, 0.0));
+        assert_eq!(z.div_imag(y), z / Complex::new(0.0, y));
+        // no overflow from squaring the divisor
+        let big = 1e200;
+        assert_eq!(Complex::new(1e200, 2e200).div_real(big), Complex::new(1.0, 2.0));
+        assert_eq!(Complex::new(1e200, 2e200).div_imag(big), Complex::new(2.0, -1.0));
+        assert_eq!(Complex::new(2, 3).mul_imag(2).mul_imag(-1), Complex::new(4, 6));
+        // unsigned components, which have no negation
+        assert_eq!(Complex::new(2u32, 3).mul_real(4).div_real(2), Complex::new(4, 6));
+    }
+
+    #[test]
+    fn scalar_assign_ops() {
+        let z = Complex::new(1.5f32, -2.5);
+        let x = 0.75;
+        let mut w = z;
+        w += x;
+        assert_eq!(w, z + x);
+        w -= 2.0 * x;
+        assert_eq!(w, z - x);
+        w *= x;
+        assert_eq!(w, (z - x) * x);
+        w /= x;
+        assert_eq!(w, z - x);
+        // each part is scaled on its own, as by a real number
+        let mut z = Complex::new(-0.0, 1e300);
+        z *= 1e10;
+        assert_eq!(z, Complex::new(-0.0, f64::INFINITY));
+        z /= f64::INFINITY;
+        assert!(z.r == 0.0 && z.r.is_sign_negative() && z.i.is_nan());
+    }
+
+    #[test]
+    fn array_conversions() {
+        let mut zs = [Complex::new(1.5f32, -2.0), Complex::new(0.0, 3.0)];
+        assert_eq!(<[f32; 2]>::from(zs[0]), *zs[0].as_array());
+        assert_eq!(Complex::from([0.0, 3.0]), zs[1]);
+        for z in &mut zs {
+            let [r, i] = z.as_mut_array();
+            core::mem::swap(r, i);
+        }
+        assert_eq!(zs, [Complex::new(-2.0, 1.5), Complex::new(3.0, 0.0)]);
+    }
+
+    #[test]
+    fn int_float_conversions() {
+        let adc = Complex::new(-32768i16, 32767);
+        assert_eq!(Complex::<f32>::from(adc), Complex::new(-32768.0, 32767.0));
+        assert_eq!(Complex::<f64>::from(Complex::new(u32::MAX, 0)), Complex::new(4294967295.0, 0.0));
+        let z = Complex::new(2.5f64, -1.4);
+        assert_eq!(Complex::<i32>::try_from(z), Ok(Complex::new(3, -1)));
+        assert_eq!(Complex::<i32>::try_from(z.floor()), Ok(Complex::new(2, -2)));
+        assert_eq!(Complex::<i32>::try_from(z.trunc()), Ok(Complex::new(2, -1)));
+        assert_eq!(Complex::<i32>::try_from(z.ceil()), Ok(Complex::new(3, -1)));
+        assert!(Complex::<u8>::try_from(z).is_err());
+        assert_eq!(Complex::<i8>::try_from(Complex::new(127.4f32, -128.0)), Ok(Complex::new(127, -128)));
+        assert!(Complex::<i8>::try_from(Complex::new(127.5f32, 0.0)).is_err());
+        assert!(Complex::<i16>::try_from(Complex::new(0.0, f64::NAN)).is_err());
+        // the end of the i64 range is a power of 2, exactly representable
+        assert!(Complex::<i64>::try_from(Complex::new(2f64.powi(63), 0.0)).is_err());
+        assert_eq!(Complex::<i64>::try_from(Complex::new(-2f64.powi(63), 0.0)), Ok(Complex::new(i64::MIN, 0)));
+    }
+
+    #[test]
+    fn display_generic() {
+        assert_eq!(Complex::new(3, -4).to_string(), "3 - 4*i");
+        assert_eq!(Complex::new(0, 7).to_string(), "7*i");
+        assert_eq!(Complex::new(5, 0).to_string(), "5");
+        assert_eq!(Complex::new(2, -1).to_string(), "2 - i");
+        assert_eq!(Complex::new(0, 1).to_string(), "i");
+        assert_eq!(Complex::new(0i64, i64::MIN).to_string(), format!("{}*i", i64::MIN));
+        assert_eq!(Complex::new(1i32, i32::MIN).to_string(), "1 - 2147483648*i");
+        assert_eq!(format!("{:+.1}", Complex::new(-1i8, i8::MIN)), "-1 - 128*i");
+        assert_eq!(format!("{:e}", Complex::new(2i64, i64::MIN)), format!("2e0 - {:e}*i", i64::MIN.unsigned_abs()));
+        assert_eq!(format!("{:.2}", Complex::new(1.0, -0.25)), "1.00 - 0.25*i");
+        assert_eq!(Complex::new(-0.5f32, 1.0).to_string(), "-0.5 + i");
+        assert_eq!(Complex::new(1.0, f64::NAN).to_string(), "1 + NaN*i");
+        // no negation or division needed
+        assert_eq!(Complex::new(3u8, 1).to_string(), "3 + i");
+        assert_eq!(format!("{:+}", Complex::new(0u64, 1)), "+i");
+        assert_eq!(format!("{:e}", Complex::new(0.0, -1.0)), "-i");
+        assert_eq!(format!("{:E}", Complex::new(2, 10)), "2E0 + 1E1*i");
+        assert_eq!(Complex::new(1.0, 1.0 + f64::EPSILON).to_string(), format!("1 + {}*i", 1.0 + f64::EPSILON));
+    }
+
     #[test]
     fn assign_ops() {
         let a_0 = Complex::from((1.0, 2.0));
@@ -625,11 +1968,46 @@ mod tests {
     }
 }
 
+/// A root of a polynomial with real coefficients, told apart as real or as
+/// one of a pair of complex conjugates
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ClassifiedRoot<T: Copy> {
+    /// A real root
+    Real {
+        /// the root
+        value: T,
+        /// number of times the root is repeated
+        multiplicity: usize
+    },
+    /// A pair of complex conjugate roots
+    ConjugatePair {
+        /// the root of the pair with a positive imaginary part
+        root: Complex<T>,
+        /// number of times each root of the pair is repeated
+        multiplicity: usize
+    }
+}
+
+impl<T: Copy> ClassifiedRoot<T> {
+    /// Whether the root is real
+    pub fn is_real(&self) -> bool {
+        matches!(self, ClassifiedRoot::Real { .. })
+    }
+
+    /// Number of times the root, or each root of the pair, is repeated
+    pub fn multiplicity(&self) -> usize {
+        match *self {
+            ClassifiedRoot::Real { multiplicity, .. } => multiplicity,
+            ClassifiedRoot::ConjugatePair { multiplicity, .. } => multiplicity
+        }
+    }
+}
+
 macro_rules! complex_mod {
     ($m: ident for $t: ident) => {
         /// Functions in this module take floats and return [`Complex`] values
         pub mod $m {
-            use std::assert_ne;
+            use core::assert_ne;
 use super::*;
 
             /// The imaginary number, `I` =
@@ -672,14 +2050,57 @@ use super::*;
             /// For the natural logarithm of a complex number use
             /// [`Complex::ln`]
             pub fn ln(exp: $t) -> Complex<$t> {
-                const PI: $t = std::$t::consts::PI;
+                const PI: $t = core::$t::consts::PI;
                 if exp >= 0.0 {
-                    Complex::new(exp.ln(), 0.0)
+                    Complex::new(crate::math::$m::ln(exp), 0.0)
+                } else {
+                    Complex::new(crate::math::$m::ln(-exp), PI)
+                }
+            }
+
+            /// Base 2 logarithm
+            ///
+            /// Returns a complex base 2 logarithm of any valid float.
+            /// For the base 2 logarithm of a complex number use
+            /// [`Complex::log2`]
+            pub fn log2(x: $t) -> Complex<$t> {
+                use core::$t::consts::{LOG2_E, PI};
+                if x >= 0.0 {
+                    Complex::new(crate::math::$m::log2(x), 0.0)
+                } else {
+                    Complex::new(crate::math::$m::log2(-x), PI * LOG2_E)
+                }
+            }
+
+            /// Base 10 logarithm
+            ///
+            /// Returns a complex base 10 logarithm of any valid float.
+            /// For the base 10 logarithm of a complex number use
+            /// [`Complex::log10`]
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::", stringify!($m), "::log10;")]
+            /// assert_eq!(log10(-1000.0).r, 3.0);
+            /// ```
+            pub fn log10(x: $t) -> Complex<$t> {
+                use core::$t::consts::{LOG10_E, PI};
+                if x >= 0.0 {
+                    Complex::new(crate::math::$m::log10(x), 0.0)
                 } else {
-                    Complex::new((-exp).ln(), PI)
+                    Complex::new(crate::math::$m::log10(-x), PI * LOG10_E)
                 }
             }
 
+            /// Logarithm to the base `base`, ln(`x`) / ln(`base`)
+            ///
+            /// Returns a complex logarithm of any valid floats, so either
+            /// may be negative.  For the logarithm of a complex number use
+            /// [`Complex::log`]
+            pub fn log(x: $t, base: $t) -> Complex<$t> {
+                ln(x) / ln(base)
+            }
+
             /// Square root
             ///
             /// Returns a complex square root of any valid float.
@@ -687,9 +2108,9 @@ use super::*;
             /// [`Complex::sqrt`]
             pub fn sqrt(sq: $t) -> Complex<$t> {
                 if sq >= 0.0 {
-                    Complex::<$t>::new(sq.sqrt(), 0.0)
+                    Complex::<$t>::new(math::$m::sqrt(sq), 0.0)
                 } else {
-                    Complex::<$t>::new(0.0, (-sq).sqrt())
+                    Complex::<$t>::new(0.0, math::$m::sqrt(-sq))
                 }
             }
 
@@ -762,10 +2183,390 @@ use super::*;
                 roots
             }
 
+            /// Quadratic roots, treating terms below a relative tolerance as
+            /// zero
+            ///
+            /// Returns the complex roots of: a x² + b x + c = 0
+            ///
+            /// The leading coefficient is taken as zero when |a| ≤ `tol`
+            /// max(|b|, |c|), and the equation is then solved as a linear
+            /// one, giving one root or, if b is negligible too, none.  A
+            /// discriminant within `tol` of the size of its terms gives a
+            /// double root, returned twice.  The coefficients are scaled
+            /// first and real roots are found without cancellation, so
+            /// extreme magnitudes such as a = 1e-300 are handled smoothly.
+            /// A `tol` of zero only treats exact zeros as degenerate.
+            ///
+            /// # Panics
+            ///
+            /// Panics if any coefficients are `NAN`
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::", stringify!($m), "::quad_tol;")]
+            /// // a leading coefficient left over from rounding
+            /// let roots = quad_tol(1e-30, 2.0, -4.0, 1e-12);
+            /// assert_eq!(roots.len(), 1);
+            /// assert_eq!(roots[0].r, 2.0);
+            /// ```
+            #[cfg(feature = "std")]
+            pub fn quad_tol(a: $t, b: $t, c: $t, tol: $t) -> Vec<Complex<$t>> {
+                assert!(!(a.is_nan() || b.is_nan() || c.is_nan()),
+                    "NAN term, a = {a}, b = {b}, c = {c}"
+                );
+                let scale = a.abs().max(b.abs()).max(c.abs());
+                if scale == 0.0 {
+                    return Vec::new();
+                }
+                let (a, b, c) = (a / scale, b / scale, c / scale);
+                if a.abs() <= tol * b.abs().max(c.abs()) {
+                    return if b.abs() <= tol * c.abs() {
+                        Vec::new()
+                    } else {
+                        vec![Complex::new(-c / b, 0.0)]
+                    };
+                }
+                let disc = b * b - 4.0 * a * c;
+                if disc.abs() <= tol * (b * b + (4.0 * a * c).abs()) {
+                    let root = Complex::new(-0.5 * b / a, 0.0);
+                    return vec![root, root];
+                }
+                if disc < 0.0 {
+                    let (re, im) = (-0.5 * b / a, 0.5 * (-disc).sqrt() / a);
+                    return vec![Complex::new(re, im), Complex::new(re, -im)];
+                }
+                let q = -0.5 * (b + disc.sqrt().copysign(b));
+                vec![Complex::new(q / a, 0.0), Complex::new(c / q, 0.0)]
+            }
+
+            /// Cubic roots, treating terms below a relative tolerance as zero
+            ///
+            /// Returns the complex roots of: a x³ + b x² + c x + d = 0
+            ///
+            /// The leading coefficient is taken as zero when |a| ≤ `tol`
+            /// max(|b|, |c|, |d|), and the remaining quadratic is solved by
+            /// [`quad_tol`].  A cubic whose depressed form vanishes within
+            /// `tol` gives its triple root, returned three times.  Otherwise
+            /// the roots are those of [`cubic`].
+            ///
+            /// # Panics
+            ///
+            /// Panics if any coefficients are `NAN`
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::", stringify!($m), "::cubic_tol;")]
+            /// // (x - 1)³
+            /// let roots = cubic_tol(1.0, -3.0, 3.0, -1.0, 1e-6);
+            /// assert!(roots.iter().all(|z| z.r == 1.0 && z.i == 0.0));
+            /// ```
+            #[cfg(feature = "std")]
+            pub fn cubic_tol(a: $t, b: $t, c: $t, d: $t, tol: $t) -> Vec<Complex<$t>> {
+                assert!(!(a.is_nan() || b.is_nan() || c.is_nan() || d.is_nan()),
+                    "NAN term, a = {a}, b = {b}, c = {c}, d = {d}"
+                );
+                let scale = a.abs().max(b.abs()).max(c.abs()).max(d.abs());
+                if scale == 0.0 {
+                    return Vec::new();
+                }
+                let (a, b, c, d) = (a / scale, b / scale, c / scale, d / scale);
+                if a.abs() <= tol * b.abs().max(c.abs()).max(d.abs()) {
+                    return quad_tol(b, c, d, tol);
+                }
+                let (a_2, a_1, a_0) = (b / a, c / a, d / a);
+                let p = a_1 - (a_2 * a_2) / 3.0;
+                let q = (9.0 * a_1 * a_2 - 2.0 * a_2 * a_2 * a_2) / 27.0 - a_0;
+                let p_scale = a_1.abs() + a_2 * a_2 / 3.0;
+                let q_scale = (9.0 * (a_1 * a_2).abs() + 2.0 * (a_2 * a_2 * a_2).abs()) / 27.0 + a_0.abs();
+                if p.abs() <= tol * p_scale && q.abs() <= tol * q_scale {
+                    return vec![Complex::new(-a_2 / 3.0, 0.0); 3];
+                }
+                cubic(a, b, c, d).to_vec()
+            }
+
+            /// Quadratic roots, classified as real roots or a conjugate pair
+            ///
+            /// Returns the roots of: a x² + b x + c = 0, as found by
+            /// [`quad_tol`], with roots that agree within `tol`, relative to
+            /// the larger root, merged into one of higher multiplicity.  A
+            /// double root is a single real root of multiplicity 2, with no
+            /// rounding noise in an imaginary part to test.
+            ///
+            /// # Panics
+            ///
+            /// Panics if any coefficients are `NAN`
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{ClassifiedRoot, Complex, ", stringify!($m), "::quad_classified};")]
+            /// let roots = quad_classified(1.0, 2.0, 5.0, 1e-6);
+            /// assert_eq!(roots, [ClassifiedRoot::ConjugatePair { root: Complex::new(-1.0, 2.0), multiplicity: 1 }]);
+            /// assert!(quad_classified(1.0, -0.2, 0.01, 1e-6)[0].is_real());
+            /// ```
+            #[cfg(feature = "std")]
+            pub fn quad_classified(a: $t, b: $t, c: $t, tol: $t) -> Vec<ClassifiedRoot<$t>> {
+                classify(quad_tol(a, b, c, tol), tol)
+            }
+
+            /// Cubic roots, classified as real roots or conjugate pairs
+            ///
+            /// Returns the roots of: a x³ + b x² + c x + d = 0, as found by
+            /// [`cubic_tol`], with roots whose imaginary parts are within
+            /// `tol` of zero taken as real, and roots that agree within `tol`
+            /// merged into one of higher multiplicity, where `tol` is
+            /// relative to the largest root.  Multiple roots found
+            /// by Cardano's formula are only accurate to about the square or
+            /// cube root of the precision, so `tol` should be at least that
+            /// to merge them.
+            ///
+            /// # Panics
+            ///
+            /// Panics if any coefficients are `NAN`
+            #[cfg(feature = "std")]
+            pub fn cubic_classified(a: $t, b: $t, c: $t, d: $t, tol: $t) -> Vec<ClassifiedRoot<$t>> {
+                classify(cubic_tol(a, b, c, d, tol), tol)
+            }
+
+            /// Merges roots of a real polynomial that agree within `tol`,
+            /// keeping one root of each conjugate pair
+            ///
+            /// The rounding in each root is relative to the largest root,
+            /// not to the root itself, so `tol` is scaled by that, and roots
+            /// at zero can be taken as real.
+            #[cfg(feature = "std")]
+            fn classify(roots: Vec<Complex<$t>>, tol: $t) -> Vec<ClassifiedRoot<$t>> {
+                let tol = tol * roots.iter().fold(0.0, |scale: $t, z| scale.max(z.abs()));
+                let near = |a: Complex<$t>, b: Complex<$t>| (a - b).abs() <= tol;
+                let mut classified: Vec<ClassifiedRoot<$t>> = Vec::new();
+                for z in roots {
+                    if z.i.abs() <= tol {
+                        let z = Complex::new(z.r, 0.0);
+                        match classified.iter_mut().find(|c| matches!(c, ClassifiedRoot::Real { value, .. } if near(Complex::new(*value, 0.0), z))) {
+                            Some(ClassifiedRoot::Real { multiplicity, .. }) => *multiplicity += 1,
+                            _ => classified.push(ClassifiedRoot::Real { value: z.r, multiplicity: 1 })
+                        }
+                    } else if z.i > 0.0 {
+                        match classified.iter_mut().find(|c| matches!(c, ClassifiedRoot::ConjugatePair { root, .. } if near(*root, z))) {
+                            Some(ClassifiedRoot::ConjugatePair { multiplicity, .. }) => *multiplicity += 1,
+                            _ => classified.push(ClassifiedRoot::ConjugatePair { root: z, multiplicity: 1 })
+                        }
+                    }
+                }
+                classified
+            }
+
             #[cfg(test)]
             mod test {
                 use super::*;
 
+                #[cfg(feature = "std")]
+                #[test]
+                fn check_quad_tol() {
+                    let ep = 4.0 * $t::EPSILON;
+                    // degenerate leading terms, at any magnitude
+                    assert_eq!(quad_tol(0.0, 2.0, -4.0, 0.0), [Complex::new(2.0, 0.0)]);
+                    assert_eq!(quad_tol($t::MIN_POSITIVE, 2.0, -4.0, ep), [Complex::new(2.0, 0.0)]);
+                    assert_eq!(quad_tol(1e-30, 1e-30, 1.0, 1e-6), []);
+                    assert_eq!(quad_tol(0.0, 0.0, 0.0, 0.0), []);
+                    // a double root despite rounding in the discriminant
+                    assert_eq!(quad_tol(1.0, -0.2, 0.01, ep), [Complex::new(0.1, 0.0); 2]);
+                    // both roots accurate when they differ greatly
+                    let roots = quad_tol(1.0, -1e6, 1.0, 0.0);
+                    assert!((roots[0].r * roots[1].r - 1.0).abs() < ep, "{roots:?}");
+                    assert!(((roots[0].r + roots[1].r) / 1e6 - 1.0).abs() < ep, "{roots:?}");
+                    // tiny coefficients are scaled rather than underflowing
+                    let roots = quad_tol(1e-30, 0.0, -4e-30, 0.0);
+                    assert_eq!(roots, [Complex::new(-2.0, 0.0), Complex::new(2.0, 0.0)]);
+                    let roots = quad_tol(2.0, 0.0, 8.0, ep);
+                    assert_eq!(roots, [Complex::new(0.0, 2.0), Complex::new(0.0, -2.0)]);
+                }
+
+                #[cfg(feature = "std")]
+                #[test]
+                fn check_cubic_tol() {
+                    let ep = 4.0 * $t::EPSILON;
+                    let roots = cubic_tol(1e-20, 1.0, -3.0, 2.0, 1e-6);
+                    assert_eq!(roots.len(), 2);
+                    assert!((roots[0] - 2.0).abs() < ep && (roots[1] - 1.0).abs() < ep, "{roots:?}");
+                    assert_eq!(cubic_tol(0.5, -3.0, 6.0, -4.0, ep), [Complex::new(2.0, 0.0); 3]);
+                    // (x - 1)(x² + 4), unaffected by the tolerance
+                    let mut roots = cubic_tol(1.0, -1.0, 4.0, -4.0, ep);
+                    roots.sort_by(|a, b| a.i.total_cmp(&b.i));
+                    for (root, expected) in roots.iter().zip([Complex::new(0.0, -2.0), Complex::new(1.0, 0.0), Complex::new(0.0, 2.0)]) {
+                        assert!((*root - expected).abs() < 10.0 * ep, "{roots:?}");
+                    }
+                }
+
+                #[cfg(feature = "std")]
+                #[test]
+                fn check_classified() {
+                    let ep = 4.0 * $t::EPSILON;
+                    let real = |value, multiplicity| ClassifiedRoot::Real { value, multiplicity };
+                    assert_eq!(quad_classified(1.0, -0.75, 0.125, ep), [real(0.5, 1), real(0.25, 1)]);
+                    assert_eq!(quad_classified(1.0, -0.2, 0.01, ep), [real(0.1, 2)]);
+                    assert_eq!(quad_classified(0.0, 2.0, 1.0, ep), [real(-0.5, 1)]);
+                    assert_eq!(cubic_classified(1.0, -3.0, 3.0, -1.0, ep), [real(1.0, 3)]);
+                    // (x + 2)(x² - 2x + 5)
+                    let roots = cubic_classified(1.0, 0.0, 1.0, 10.0, ep.sqrt());
+                    assert_eq!(roots.len(), 2, "{roots:?}");
+                    for root in roots {
+                        match root {
+                            ClassifiedRoot::Real { value, multiplicity } => {
+                                assert!((value + 2.0).abs() < 10.0 * ep && multiplicity == 1);
+                            }
+                            ClassifiedRoot::ConjugatePair { root, multiplicity } => {
+                                assert!((root - Complex::new(1.0, 2.0)).abs() < 10.0 * ep && multiplicity == 1);
+                            }
+                        }
+                    }
+                    // (x - 1)²(x + 1), the double root split by rounding
+                    let roots = cubic_classified(1.0, -1.0, -1.0, 1.0, ep.sqrt());
+                    assert_eq!(roots.len(), 2, "{roots:?}");
+                    assert!(roots.iter().all(|r| r.is_real()));
+                    assert_eq!(roots.iter().map(|r| r.multiplicity()).sum::<usize>(), 3);
+                    // multiple roots at zero, which have no relative tolerance
+                    // of their own
+                    let roots = cubic_classified(1.0, -1.0, 0.0, 0.0, 1e-4);
+                    assert_eq!(roots.len(), 2, "{roots:?}");
+                    assert_eq!(roots[0], real(1.0, 1));
+                    assert!(matches!(roots[1], ClassifiedRoot::Real { value, multiplicity: 2 } if value.abs() < 1e-4), "{roots:?}");
+                    let roots = cubic_classified(1.0, 3.0, 0.0, 0.0, 1e-4);
+                    assert_eq!(roots.len(), 2, "{roots:?}");
+                    assert!(roots.iter().all(|r| r.is_real()));
+                    assert!(roots.contains(&real(-3.0, 1)));
+                    assert_eq!(cubic_classified(1.0, 0.0, 0.0, 0.0, ep), [real(0.0, 3)]);
+                    assert_eq!(quad_classified(1.0, 0.0, 0.0, ep), [real(0.0, 2)]);
+                }
+
+                #[test]
+                fn check_distance_arg_diff() {
+                    use core::$t::consts::PI;
+                    let ep = 4.0 * $t::EPSILON;
+                    let (a, b) = (Complex::<$t>::new(1.0, 2.0), Complex::new(4.0, -2.0));
+                    assert_eq!(a.distance(b), 5.0);
+                    let big = Complex::<$t>::new($t::MAX / 2.0, 0.0);
+                    assert_eq!(big.distance(-big), $t::MAX);
+                    let tiny = Complex::<$t>::new(0.0, $t::MIN_POSITIVE);
+                    assert_eq!(tiny.distance(-tiny), 2.0 * $t::MIN_POSITIVE);
+                    // across the seam at ±π
+                    let (above, below) = (Complex::<$t>::cis(PI - 0.1), Complex::<$t>::cis(-PI + 0.1));
+                    assert!((above.arg_diff(below) + 0.2).abs() < 10.0 * ep);
+                    assert!((below.arg_diff(above) - 0.2).abs() < 10.0 * ep);
+                    // half a turn either way is +π
+                    assert_eq!(Complex::<$t>::new(-1.0, 0.0).arg_diff(Complex::new(1.0, 0.0)), PI);
+                    assert_eq!(Complex::<$t>::new(1.0, 0.0).arg_diff(Complex::new(-1.0, 0.0)), PI);
+                    assert_eq!(a.arg_diff(a * 3.0), 0.0);
+                }
+
+                #[test]
+                fn check_branch_cuts() {
+                    use core::$t::consts::{FRAC_PI_2, PI};
+                    let (inf, nan) = ($t::INFINITY, $t::NAN);
+                    // equal, with equal signs of zero, or both NAN
+                    let same = |a: $t, b: $t| (a.is_nan() && b.is_nan()) || (a == b && a.is_sign_negative() == b.is_sign_negative());
+                    let sqrt_table = [
+                        ((0.0, 0.0), (0.0, 0.0)),
+                        ((-0.0, 0.0), (0.0, 0.0)),
+                        ((0.0, -0.0), (0.0, -0.0)),
+                        ((-0.0, -0.0), (0.0, -0.0)),
+                        ((-1.0, 0.0), (0.0, 1.0)),
+                        ((-1.0, -0.0), (0.0, -1.0)),
+                        ((4.0, -0.0), (2.0, -0.0)),
+                        ((1.0, inf), (inf, inf)),
+                        ((nan, -inf), (inf, -inf)),
+                        ((-inf, 1.0), (0.0, inf)),
+                        ((-inf, -1.0), (0.0, -inf)),
+                        ((inf, 1.0), (inf, 0.0)),
+                        ((inf, -1.0), (inf, -0.0)),
+                        ((inf, nan), (inf, nan)),
+                        ((-inf, nan), (nan, inf)),
+                        ((nan, 0.0), (nan, nan)),
+                        ((1.0, nan), (nan, nan)),
+                        ((nan, 1.0), (nan, nan))
+                    ];
+                    for ((x, y), (r, i)) in sqrt_table {
+                        let z = Complex::<$t>::new(x, y).sqrt();
+                        assert!(same(z.r, r) && (same(z.i, i) || (x == -inf && y.is_nan() && z.i.is_infinite())), "sqrt({x}, {y}) = {z:?}");
+                    }
+                    let ln_table = [
+                        ((0.0, 0.0), (-inf, 0.0)),
+                        ((-0.0, 0.0), (-inf, PI)),
+                        ((-0.0, -0.0), (-inf, -PI)),
+                        ((-1.0, 0.0), (0.0, PI)),
+                        ((-1.0, -0.0), (0.0, -PI)),
+                        ((1.0, -0.0), (0.0, -0.0)),
+                        ((-2.0, inf), (inf, FRAC_PI_2)),
+                        ((-inf, 1.0), (inf, PI)),
+                        ((-inf, -1.0), (inf, -PI)),
+                        ((inf, nan), (inf, nan)),
+                        ((nan, -inf), (inf, nan)),
+                        ((nan, 1.0), (nan, nan))
+                    ];
+                    for ((x, y), (r, i)) in ln_table {
+                        let z = Complex::<$t>::new(x, y).ln();
+                        assert!(same(z.r, r) && same(z.i, i), "ln({x}, {y}) = {z:?}");
+                    }
+                    // either side of the cut, away from it
+                    let below = Complex::<$t>::new(-4.0, -1e-30).sqrt();
+                    assert!((below - Complex::new(0.0, -2.0)).abs() < 1e-6);
+                }
+
+                #[test]
+                fn check_fast() {
+                    let ep = 8.0 * $t::EPSILON;
+                    for k in 1..200 {
+                        let z = Complex::<$t>::cis(0.37 * k as $t) * (0.05 * k as $t);
+                        let w = Complex::<$t>::cis(-1.3 * k as $t) * (10.0 / k as $t);
+                        assert!((z.abs_fast() - z.abs()).abs() <= ep * z.abs());
+                        assert!((z.recip_fast() - z.recip()).abs() <= ep * z.recip().abs());
+                        assert!((z.div_fast(w) - z / w).abs() <= ep * (z / w).abs());
+                        assert_eq!(z.mul_fast(w), z * w);
+                        assert!((z.ln_fast() - z.ln()).abs() <= ep * z.ln().abs().max(1.0));
+                        let root = z.sqrt_fast();
+                        assert!((root * root - z).abs() <= ep * z.abs() && root.r >= 0.0, "{z}");
+                    }
+                    // out of range, where the default methods hold up
+                    let big = Complex::<$t>::new($t::MAX / 4.0, $t::MAX / 4.0);
+                    assert!(big.abs().is_finite() && big.abs_fast().is_infinite());
+                }
+
+                #[test]
+                fn check_logs() {
+                    let ep = 8.0 * $t::EPSILON;
+                    for k in 1..50 {
+                        let z = Complex::<$t>::cis(0.37 * k as $t - 9.0) * (0.3 * k as $t);
+                        let ln = z.ln();
+                        assert!((z.log2() - ln / $t::ln(2.0)).abs() <= ep * ln.abs().max(1.0), "{z}");
+                        assert!((z.log10() - ln / $t::ln(10.0)).abs() <= ep * ln.abs().max(1.0), "{z}");
+                        let base = Complex::<$t>::new(-2.0, 0.5);
+                        assert!((base.powc(z.log(base)) - z).abs() <= 64.0 * ep * z.abs(), "{z}");
+                    }
+                    assert_eq!(Complex::<$t>::new(1024.0, 0.0).log2(), Complex::new(10.0, 0.0));
+                    assert_eq!(Complex::<$t>::new(-1.0, -0.0).log10().i, -std::$t::consts::PI * std::$t::consts::LOG10_E);
+                    assert_eq!($m::log2(0.125), Complex::new(-3.0, 0.0));
+                    assert_eq!($m::log10(-0.01), Complex::new(-2.0, std::$t::consts::PI * std::$t::consts::LOG10_E));
+                    assert!(($m::log(-8.0, 2.0) - $m::log2(-8.0)).abs() <= ep * 4.0);
+                    assert!(($m::log(8.0, -2.0) * $m::ln(-2.0) - $m::ln(8.0)).abs() <= ep * 4.0);
+                }
+
+                #[test]
+                fn check_powi() {
+                    let z = Complex::<$t>::new(0.9, -0.6);
+                    let mut product = Complex::<$t>::new(1.0, 0.0);
+                    for n in 0..40 {
+                        let ep = 4.0 * n as $t * $t::EPSILON * product.abs();
+                        assert!((z.powi(n) - product).abs() <= ep, "{n}");
+                        assert!((z.powi(n) - z.powf(n as $t)).abs() <= 4.0 * ep, "{n}");
+                        assert!((z.powi(-n) * product - 1.0).abs() <= 2.0 * ep / product.abs(), "{n}");
+                        product *= z;
+                    }
+                    let i = $m::I;
+                    assert_eq!((i.powi(2), i.powi(3), i.powi(-1)), (Complex::new(-1.0, 0.0), -i, -i));
+                    assert_eq!(Complex::<$t>::new(2.0, -1.0).powi(5), Complex::new(-38.0, -41.0));
+                    assert_eq!(Complex::<$t>::new(0.0, 0.0).powi(0), Complex::new(1.0, 0.0));
+                    assert_eq!(Complex::<$t>::new(2.0, 0.0).powi(i32::MIN), Complex::new(0.0, 0.0));
+                }
+
                 #[test]
                 fn check_cis() {
                     let i: Complex<$t> = $m::I;
@@ -817,6 +2618,20 @@ use super::*;
                     }
                 }
 
+                #[test]
+                fn check_approx(){
+                    for k in 0..3600 {
+                        let z = (1.0 + k as $t * 0.01) * Complex::<$t>::cis(k as $t * 0.1);
+                        let rel = (z.abs_approx() - z.abs()) / z.abs();
+                        assert!(rel.abs() <= 0.0098, "{z} {rel}");
+                        let diff = z.angle_approx() - z.angle();
+                        let diff = diff - (diff / std::$t::consts::TAU).round() * std::$t::consts::TAU;
+                        assert!(diff.abs() <= 6.2e-4, "{z} {diff}");
+                    }
+                    let zero = Complex::<$t>::new(0.0, 0.0);
+                    assert_eq!((zero.abs_approx(), zero.angle_approx()), (0.0, 0.0));
+                }
+
                 #[test]
                 fn check_cbrt(){
                     let cubes = [
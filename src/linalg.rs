@@ -0,0 +1,567 @@
+//! Dense complex linear algebra
+//!
+//! Square [`Matrix`] values are factored by [`Lu`] decomposition with
+//! partial pivoting, to solve linear systems and to find determinants and
+//! condition numbers.  The QR and singular vector routines used by the
+//! other modules store matrices as a list of columns.
+//!
+//! # Example
+//! ```
+//! use imaginary::Complex;
+//! use imaginary::linalg::Matrix;
+//! // a two port impedance matrix driven by unit currents
+//! let z = Matrix::<f64>::from_rows(vec![
+//!     vec![Complex::new(50.0, 10.0), Complex::new(0.0, -20.0)],
+//!     vec![Complex::new(0.0, -20.0), Complex::new(75.0, 0.0)],
+//! ]);
+//! let v = z.mul_vec(&[Complex::new(1.0, 0.0), Complex::new(1.0, 0.0)]);
+//! let i = z.lu().solve(&v).unwrap();
+//! assert!((i[0] - Complex::new(1.0, 0.0)).abs() < 1e-12);
+//! ```
+
+use crate::Complex;
+use std::ops::{Index, IndexMut};
+
+/// A dense square matrix of complex numbers, stored by rows
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix<T: Copy> {
+    n: usize,
+    data: Vec<Complex<T>>
+}
+
+/// LU decomposition with partial pivoting, P A = L U, of a square
+/// [`Matrix`]
+///
+/// L has a unit diagonal and is stored below the diagonal of the same
+/// matrix as U.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lu<T: Copy> {
+    lu: Matrix<T>,
+    /// row `i` of P A is row `perm[i]` of A
+    perm: Vec<usize>,
+    /// whether P is an odd permutation
+    odd: bool,
+    /// 1-norm of A
+    norm: T
+}
+
+impl<T: Copy> Matrix<T> {
+    /// Creates an `n` × `n` matrix from its elements, row by row
+    ///
+    /// # Panics
+    /// Panics if `data` does not hold `n`² elements.
+    pub fn new(n: usize, data: Vec<Complex<T>>) -> Matrix<T> {
+        assert_eq!(data.len(), n * n, "a {n} × {n} matrix needs {} elements", n * n);
+        Matrix { n, data }
+    }
+
+    /// Creates a matrix from its rows
+    ///
+    /// # Panics
+    /// Panics if the rows are not all as long as there are rows.
+    pub fn from_rows(rows: Vec<Vec<Complex<T>>>) -> Matrix<T> {
+        let n = rows.len();
+        assert!(rows.iter().all(|row| row.len() == n), "matrix rows must have {n} elements");
+        Matrix { n, data: rows.into_iter().flatten().collect() }
+    }
+
+    /// Number of rows, and of columns
+    pub fn size(&self) -> usize {
+        self.n
+    }
+
+    /// The elements, row by row
+    pub fn as_slice(&self) -> &[Complex<T>] {
+        &self.data
+    }
+
+    /// The elements of row `i`
+    pub fn row(&self, i: usize) -> &[Complex<T>] {
+        &self.data[i * self.n..(i + 1) * self.n]
+    }
+}
+
+impl<T: Copy> Index<(usize, usize)> for Matrix<T> {
+    type Output = Complex<T>;
+    /// The element at `(row, column)`
+    fn index(&self, (i, j): (usize, usize)) -> &Complex<T> {
+        assert!(j < self.n, "column {j} out of range for a {} × {} matrix", self.n, self.n);
+        &self.data[i * self.n + j]
+    }
+}
+
+impl<T: Copy> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (i, j): (usize, usize)) -> &mut Complex<T> {
+        assert!(j < self.n, "column {j} out of range for a {} × {} matrix", self.n, self.n);
+        &mut self.data[i * self.n + j]
+    }
+}
+
+macro_rules! impl_lu {
+    ($t: ident) => {
+        impl Matrix<$t> {
+            /// The `n` × `n` identity matrix
+            pub fn identity(n: usize) -> Matrix<$t> {
+                let mut m = Matrix::new(n, vec![Complex::new(0.0, 0.0); n * n]);
+                for k in 0..n {
+                    m[(k, k)] = Complex::new(1.0, 0.0);
+                }
+                m
+            }
+
+            /// The product of the matrix and the column vector `x`
+            ///
+            /// # Panics
+            /// Panics if `x` is not as long as the matrix size.
+            pub fn mul_vec(&self, x: &[Complex<$t>]) -> Vec<Complex<$t>> {
+                assert_eq!(x.len(), self.n, "vector length differs from the matrix size");
+                (0..self.n).map(|i| {
+                    self.row(i).iter().zip(x).fold(Complex::new(0.0, 0.0), |sum, (&a, &x)| sum + a * x)
+                }).collect()
+            }
+
+            /// Maximum absolute column sum, the 1-norm
+            pub fn norm1(&self) -> $t {
+                (0..self.n).map(|j| (0..self.n).map(|i| self[(i, j)].abs()).sum::<$t>())
+                    .fold(0.0, $t::max)
+            }
+
+            /// LU decomposition with partial pivoting
+            ///
+            /// The largest remaining element of each column is used as the
+            /// pivot.  A singular matrix still factors, with a zero on the
+            /// diagonal of U, and [`Lu::solve`] then returns `None`.
+            pub fn lu(&self) -> Lu<$t> {
+                let n = self.n;
+                let mut lu = self.clone();
+                let mut perm: Vec<usize> = (0..n).collect();
+                let mut odd = false;
+                for k in 0..n {
+                    let p = (k..n).max_by(|&a, &b| lu[(a, k)].abs().total_cmp(&lu[(b, k)].abs())).unwrap();
+                    if p != k {
+                        for j in 0..n {
+                            lu.data.swap(k * n + j, p * n + j);
+                        }
+                        perm.swap(k, p);
+                        odd = !odd;
+                    }
+                    let pivot = lu[(k, k)];
+                    if pivot == Complex::new(0.0, 0.0) {
+                        continue;
+                    }
+                    for i in k + 1..n {
+                        let l = lu[(i, k)] / pivot;
+                        lu[(i, k)] = l;
+                        for j in k + 1..n {
+                            let u = lu[(k, j)];
+                            lu[(i, j)] -= l * u;
+                        }
+                    }
+                }
+                Lu { lu, perm, odd, norm: self.norm1() }
+            }
+        }
+
+        impl Lu<$t> {
+            /// Whether U has a zero on its diagonal, so that the matrix is
+            /// singular
+            pub fn is_singular(&self) -> bool {
+                (0..self.lu.n).any(|k| self.lu[(k, k)] == Complex::new(0.0, 0.0))
+            }
+
+            /// Solves A x = `b`, or returns `None` if A is singular
+            ///
+            /// # Panics
+            /// Panics if `b` is not as long as the matrix size.
+            pub fn solve(&self, b: &[Complex<$t>]) -> Option<Vec<Complex<$t>>> {
+                let n = self.lu.n;
+                assert_eq!(b.len(), n, "vector length differs from the matrix size");
+                if self.is_singular() {
+                    return None;
+                }
+                let mut x: Vec<Complex<$t>> = self.perm.iter().map(|&p| b[p]).collect();
+                for i in 0..n {
+                    for j in 0..i {
+                        let xj = x[j];
+                        x[i] -= self.lu[(i, j)] * xj;
+                    }
+                }
+                for i in (0..n).rev() {
+                    for j in i + 1..n {
+                        let xj = x[j];
+                        x[i] -= self.lu[(i, j)] * xj;
+                    }
+                    x[i] /= self.lu[(i, i)];
+                }
+                Some(x)
+            }
+
+            /// Solves Aᴴ x = `b`, for a nonsingular A
+            fn solve_adjoint(&self, b: &[Complex<$t>]) -> Vec<Complex<$t>> {
+                let n = self.lu.n;
+                let mut v = b.to_vec();
+                // Uᴴ w = b
+                for i in 0..n {
+                    for j in 0..i {
+                        let vj = v[j];
+                        v[i] -= self.lu[(j, i)].conj() * vj;
+                    }
+                    v[i] /= self.lu[(i, i)].conj();
+                }
+                // Lᴴ v = w
+                for i in (0..n).rev() {
+                    for j in i + 1..n {
+                        let vj = v[j];
+                        v[i] -= self.lu[(j, i)].conj() * vj;
+                    }
+                }
+                let mut x = vec![Complex::new(0.0, 0.0); n];
+                for (i, &p) in self.perm.iter().enumerate() {
+                    x[p] = v[i];
+                }
+                x
+            }
+
+            /// Determinant of A
+            pub fn determinant(&self) -> Complex<$t> {
+                let det = (0..self.lu.n).fold(Complex::new(1.0, 0.0), |det, k| det * self.lu[(k, k)]);
+                if self.odd { -det } else { det }
+            }
+
+            /// Estimate of the reciprocal of the 1-norm condition number,
+            /// 1 / (‖A‖₁ ‖A⁻¹‖₁)
+            ///
+            /// ‖A⁻¹‖₁ is estimated without forming the inverse, by Hager's
+            /// method as refined by Higham for complex matrices, from a few
+            /// solves with A and Aᴴ.  The estimate is a lower bound for
+            /// ‖A⁻¹‖₁ that is almost always within a factor of 3, so the
+            /// result is near zero for matrices that are close to singular,
+            /// and zero for singular ones.
+            pub fn rcond(&self) -> $t {
+                let n = self.lu.n;
+                if n == 0 {
+                    return 1.0;
+                }
+                if self.is_singular() || self.norm == 0.0 {
+                    return 0.0;
+                }
+                let norm1 = |x: &[Complex<$t>]| x.iter().map(|x| x.abs()).sum::<$t>();
+                let mut x = vec![Complex::new(1.0 / n as $t, 0.0); n];
+                let mut estimate: $t = 0.0;
+                for iteration in 0..5 {
+                    let y = self.solve(&x).unwrap();
+                    let y_norm = norm1(&y);
+                    if iteration > 0 && y_norm <= estimate {
+                        break;
+                    }
+                    estimate = y_norm;
+                    let sign: Vec<Complex<$t>> = y.iter()
+                        .map(|&y| if y.abs() == 0.0 { Complex::new(1.0, 0.0) } else { y / y.abs() })
+                        .collect();
+                    let z = self.solve_adjoint(&sign);
+                    let (j, z_max) = z.iter().enumerate()
+                        .map(|(j, z)| (j, z.abs()))
+                        .fold((0, -1.0), |best, (j, a)| if a > best.1 { (j, a) } else { best });
+                    let z_x = z.iter().zip(&x).fold(0.0, |sum, (z, x)| sum + (z.conj() * *x).r);
+                    if iteration > 0 && z_max <= z_x {
+                        break;
+                    }
+                    x = vec![Complex::new(0.0, 0.0); n];
+                    x[j] = Complex::new(1.0, 0.0);
+                }
+                // Higham's alternative estimate guards against the rare
+                // matrices where the iteration stalls
+                let alt: Vec<Complex<$t>> = (0..n).map(|i| {
+                    let s = if i % 2 == 0 { 1.0 } else { -1.0 };
+                    Complex::new(s * (1.0 + i as $t / (n as $t - 1.0).max(1.0)), 0.0)
+                }).collect();
+                let alt_estimate = 2.0 * norm1(&self.solve(&alt).unwrap()) / (3.0 * n as $t);
+                1.0 / (self.norm * estimate.max(alt_estimate))
+            }
+        }
+    }
+}
+impl_lu!(f32);
+impl_lu!(f64);
+
+macro_rules! linalg_mod {
+    ($m: ident for $t: ident) => {
+        pub(crate) mod $m {
+            use crate::Complex;
+
+            /// Σ conj(a) b
+            fn dot(a: &[Complex<$t>], b: &[Complex<$t>]) -> Complex<$t> {
+                a.iter().zip(b).fold(Complex::new(0.0, 0.0), |sum, (&a, &b)| sum + a.conj() * b)
+            }
+
+            fn norm_sq(a: &[Complex<$t>]) -> $t {
+                a.iter().fold(0.0, |sum, a| sum + a.r * a.r + a.i * a.i)
+            }
+
+            /// Householder QR factorization in place
+            ///
+            /// On return the upper triangle of `cols` holds R, and the
+            /// returned reflectors `v` apply Q<sup>H</sup> as
+            /// `x -= v (vᴴ x)` for each one in order, with the reflector for
+            /// column k acting on rows k and below.
+            pub(crate) fn householder_qr(cols: &mut [Vec<Complex<$t>>]) -> Vec<Vec<Complex<$t>>> {
+                let rows = cols.first().map_or(0, |c| c.len());
+                let mut reflectors = Vec::with_capacity(cols.len());
+                for k in 0..cols.len().min(rows) {
+                    let (col, rest) = cols[k..].split_first_mut().unwrap();
+                    let x = &col[k..];
+                    let norm = norm_sq(x).sqrt();
+                    let mut v = x.to_vec();
+                    if norm == 0.0 {
+                        reflectors.push(vec![Complex::new(0.0, 0.0); v.len()]);
+                        continue;
+                    }
+                    let phase = if x[0] == Complex::new(0.0, 0.0) {
+                        Complex::new(1.0, 0.0)
+                    } else {
+                        x[0] / x[0].abs()
+                    };
+                    let alpha = -phase * norm;
+                    v[0] -= alpha;
+                    // scale so that H = I - v vᴴ
+                    let scale = (2.0 / norm_sq(&v)).sqrt();
+                    for v in v.iter_mut() {
+                        *v *= scale;
+                    }
+                    col[k] = alpha;
+                    for c in col[k + 1..].iter_mut() {
+                        *c = Complex::new(0.0, 0.0);
+                    }
+                    for other in rest.iter_mut() {
+                        apply_reflector(&v, &mut other[k..]);
+                    }
+                    reflectors.push(v);
+                }
+                reflectors
+            }
+
+            /// `x -= v (vᴴ x)`
+            fn apply_reflector(v: &[Complex<$t>], x: &mut [Complex<$t>]) {
+                let d = dot(v, x);
+                for (x, &v) in x.iter_mut().zip(v) {
+                    *x -= v * d;
+                }
+            }
+
+            /// Least squares solution of `A x = b` for a matrix with at
+            /// least as many rows as columns
+            pub(crate) fn least_squares(mut cols: Vec<Vec<Complex<$t>>>, b: &[Complex<$t>])
+                    -> Vec<Complex<$t>> {
+                let n = cols.len();
+                let reflectors = householder_qr(&mut cols);
+                let mut y = b.to_vec();
+                for (k, v) in reflectors.iter().enumerate() {
+                    apply_reflector(v, &mut y[k..]);
+                }
+                let mut x = vec![Complex::new(0.0, 0.0); n];
+                for k in (0..n).rev() {
+                    let mut sum = y[k];
+                    for j in k + 1..n {
+                        sum -= cols[j][k] * x[j];
+                    }
+                    x[k] = if cols[k][k] == Complex::new(0.0, 0.0) {
+                        Complex::new(0.0, 0.0)
+                    } else {
+                        sum / cols[k][k]
+                    };
+                }
+                x
+            }
+
+            /// Unit right singular vector for the smallest singular value
+            ///
+            /// Tall matrices are first reduced to R by QR factorization, then
+            /// one-sided Jacobi rotations orthogonalize the columns.
+            pub(crate) fn min_singular_vector(mut cols: Vec<Vec<Complex<$t>>>) -> Vec<Complex<$t>> {
+                let n = cols.len();
+                let rows = cols.first().map_or(0, |c| c.len());
+                if rows > n {
+                    householder_qr(&mut cols);
+                    for col in cols.iter_mut() {
+                        col.truncate(n);
+                    }
+                }
+                let zero = Complex::new(0.0, 0.0);
+                let mut v: Vec<Vec<Complex<$t>>> = (0..n)
+                    .map(|j| (0..n).map(|i| if i == j { Complex::new(1.0, 0.0) } else { zero }).collect())
+                    .collect();
+                for _sweep in 0..64 {
+                    let mut rotated = false;
+                    for p in 0..n {
+                        for q in p + 1..n {
+                            let alpha = norm_sq(&cols[p]);
+                            let beta = norm_sq(&cols[q]);
+                            let gamma = dot(&cols[p], &cols[q]);
+                            let g = gamma.abs();
+                            if g == 0.0 || g <= $t::EPSILON * (alpha * beta).sqrt() {
+                                continue;
+                            }
+                            rotated = true;
+                            let phase = (gamma / g).conj();
+                            let zeta = (beta - alpha) / (2.0 * g);
+                            let t = zeta.signum() / (zeta.abs() + (1.0 + zeta * zeta).sqrt());
+                            let c = 1.0 / (1.0 + t * t).sqrt();
+                            let s = c * t;
+                            rotate(&mut cols, p, q, c, s, phase);
+                            rotate(&mut v, p, q, c, s, phase);
+                        }
+                    }
+                    if !rotated {
+                        break;
+                    }
+                }
+                let k = (0..n)
+                    .min_by(|&a, &b| norm_sq(&cols[a]).total_cmp(&norm_sq(&cols[b])))
+                    .unwrap_or(0);
+                v.swap_remove(k)
+            }
+
+            /// Rotates columns `p < q` by the unitary `[[c, s], [-s ē, c ē]]`
+            fn rotate(cols: &mut [Vec<Complex<$t>>], p: usize, q: usize,
+                      c: $t, s: $t, e_conj: Complex<$t>) {
+                let (left, right) = cols.split_at_mut(q);
+                for (x, y) in left[p].iter_mut().zip(right[0].iter_mut()) {
+                    let (a, b) = (*x, *y * e_conj);
+                    *x = c * a - s * b;
+                    *y = s * a + c * b;
+                }
+            }
+
+            #[cfg(test)]
+            mod test {
+                use super::*;
+
+                fn matrix() -> Vec<Vec<Complex<$t>>> {
+                    (0..4).map(|j| (0..6).map(|i| {
+                        Complex::new((i * 3 + j) as $t * 0.37 % 1.0, (i + 2 * j) as $t * 0.61 % 1.0)
+                    }).collect()).collect()
+                }
+
+                #[test]
+                fn check_least_squares() {
+                    let a = matrix();
+                    let x = [Complex::new(1.0, 2.0), Complex::new(-1.0, 0.0), Complex::new(0.0, 0.5), Complex::new(3.0, -1.0)];
+                    let b: Vec<Complex<$t>> = (0..6)
+                        .map(|i| (0..4).fold(Complex::new(0.0, 0.0), |sum, j| sum + a[j][i] * x[j]))
+                        .collect();
+                    let solved = least_squares(a, &b);
+                    for (&s, &x) in solved.iter().zip(&x) {
+                        assert!((s - x).abs() < 1e3 * $t::EPSILON, "{s} != {x}");
+                    }
+                }
+
+                #[test]
+                fn check_min_singular_vector() {
+                    // make the last column a combination of the others
+                    let mut a = matrix();
+                    let w = [Complex::new(0.5, 1.0), Complex::new(-2.0, 0.0), Complex::new(0.0, 1.0)];
+                    let last: Vec<Complex<$t>> = (0..6)
+                        .map(|i| (0..3).fold(Complex::new(0.0, 0.0), |sum, j| sum + a[j][i] * w[j]))
+                        .collect();
+                    a[3] = last;
+                    let v = min_singular_vector(a.clone());
+                    assert!((norm_sq(&v) - 1.0).abs() < 1e2 * $t::EPSILON);
+                    for i in 0..6 {
+                        let av = (0..4).fold(Complex::new(0.0, 0.0), |sum, j| sum + a[j][i] * v[j]);
+                        assert!(av.abs() < 1e2 * $t::EPSILON);
+                    }
+                }
+            }
+        }
+    }
+}
+linalg_mod!(c32 for f32);
+linalg_mod!(c64 for f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matrix(n: usize) -> Matrix<f64> {
+        // scrambled rather than smooth, so that the matrix is well conditioned
+        let data = (0..n * n).map(|k| {
+            Complex::new(((k * k * 31 + 7) % 101) as f64 / 101.0 - 0.5, ((k * k * 3 + k * 17) % 89) as f64 / 89.0 - 0.5)
+        }).collect();
+        Matrix::new(n, data)
+    }
+
+    #[test]
+    fn lu_solve() {
+        for n in [1, 2, 5, 12] {
+            let a = matrix(n);
+            let x: Vec<Complex<f64>> = (0..n).map(|k| Complex::new(k as f64, 1.0 - k as f64)).collect();
+            let b = a.mul_vec(&x);
+            let solved = a.lu().solve(&b).unwrap();
+            for (s, x) in solved.iter().zip(&x) {
+                assert!((*s - *x).abs() < 1e-10, "{n}: {s} != {x}");
+            }
+        }
+        // a zero leading element needs a row swap
+        let a = Matrix::<f64>::from_rows(vec![
+            vec![Complex::new(0.0, 0.0), Complex::new(2.0, 0.0)],
+            vec![Complex::new(0.0, 1.0), Complex::new(1.0, 0.0)],
+        ]);
+        let x = a.lu().solve(&[Complex::new(4.0, 0.0), Complex::new(0.0, 0.0)]).unwrap();
+        assert_eq!(x, [Complex::new(0.0, 2.0), Complex::new(2.0, 0.0)]);
+    }
+
+    #[test]
+    fn lu_determinant() {
+        let a = Matrix::<f64>::from_rows(vec![
+            vec![Complex::new(0.0, 0.0), Complex::new(2.0, 0.0)],
+            vec![Complex::new(0.0, 1.0), Complex::new(1.0, 0.0)],
+        ]);
+        assert_eq!(a.lu().determinant(), Complex::new(0.0, -2.0));
+        assert_eq!(Matrix::<f64>::identity(4).lu().determinant(), Complex::new(1.0, 0.0));
+        // cofactor expansion of a 3 × 3
+        let a = matrix(3);
+        let m = |i, j| a[(i, j)];
+        let cofactor = m(0, 0) * (m(1, 1) * m(2, 2) - m(1, 2) * m(2, 1))
+            - m(0, 1) * (m(1, 0) * m(2, 2) - m(1, 2) * m(2, 0))
+            + m(0, 2) * (m(1, 0) * m(2, 1) - m(1, 1) * m(2, 0));
+        assert!((a.lu().determinant() - cofactor).abs() < 1e-14);
+    }
+
+    #[test]
+    fn lu_singular() {
+        let mut a = matrix(4);
+        for j in 0..4 {
+            a[(3, j)] = a[(0, j)] * Complex::new(0.0, 2.0);
+        }
+        // rounding leaves a tiny pivot rather than an exact zero
+        let lu = a.lu();
+        assert!(lu.rcond() < 1e-14, "{}", lu.rcond());
+        let zero = Matrix::<f64>::new(2, vec![Complex::new(0.0, 0.0); 4]);
+        assert!(zero.lu().is_singular());
+        assert_eq!(zero.lu().solve(&[Complex::new(1.0, 0.0); 2]), None);
+        assert_eq!(zero.lu().rcond(), 0.0);
+    }
+
+    #[test]
+    fn lu_rcond() {
+        assert_eq!(Matrix::<f64>::identity(6).lu().rcond(), 1.0);
+        // diagonal, where the condition number is the ratio of extremes
+        let mut d = Matrix::<f64>::identity(5);
+        d[(2, 2)] = Complex::new(0.0, 1e-3);
+        d[(4, 4)] = Complex::new(-10.0, 0.0);
+        assert!((d.lu().rcond() - 1e-4).abs() < 1e-12);
+        // within a factor of 3 of the exact value from the inverse
+        for n in [3, 8] {
+            let a = matrix(n);
+            let lu = a.lu();
+            let inverse_norm = (0..n).map(|j| {
+                let mut e = vec![Complex::new(0.0, 0.0); n];
+                e[j] = Complex::new(1.0, 0.0);
+                lu.solve(&e).unwrap().iter().map(|x| x.abs()).sum::<f64>()
+            }).fold(0.0, f64::max);
+            let exact = 1.0 / (a.norm1() * inverse_norm);
+            let estimate = lu.rcond();
+            assert!(estimate >= exact * (1.0 - 1e-12) && estimate <= 3.0 * exact, "{estimate} {exact}");
+        }
+    }
+}
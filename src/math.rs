@@ -0,0 +1,88 @@
+//! Elementary functions of real numbers used by [`Complex`](crate::Complex)
+//! and the rest of the crate
+//!
+//! With the `deterministic` feature these come from the `libm` crate, the
+//! same code on every platform, so that results are bit-identical across
+//! operating systems and architectures.  The other modules call these
+//! rather than the methods of `f32` and `f64` for that reason.  Otherwise
+//! the platform's own, often faster, implementations are used.  Without
+//! the `std` feature there are no platform implementations, and all of
+//! them come from `libm`, including square roots and roundings, which are
+//! exact everywhere and so otherwise left to the platform even when
+//! `deterministic`.
+
+macro_rules! math_mod {
+    ($m: ident for $t: ident,
+     $($name: ident => $libm: ident($($arg: ident),*)),*;
+     exact $($exact: ident => $exact_libm: ident($x: ident)),*) => {
+        pub(crate) mod $m {
+            $(
+                #[inline]
+                pub(crate) fn $name($($arg: $t),*) -> $t {
+                    #[cfg(any(feature = "deterministic", not(feature = "std")))]
+                    {
+                        libm::$libm($($arg),*)
+                    }
+                    #[cfg(not(any(feature = "deterministic", not(feature = "std"))))]
+                    {
+                        <$t>::$name($($arg),*)
+                    }
+                }
+            )*
+            $(
+                #[inline]
+                pub(crate) fn $exact($x: $t) -> $t {
+                    #[cfg(not(feature = "std"))]
+                    {
+                        libm::$exact_libm($x)
+                    }
+                    #[cfg(feature = "std")]
+                    {
+                        <$t>::$exact($x)
+                    }
+                }
+            )*
+        }
+    }
+}
+math_mod!(c32 for f32,
+    hypot => hypotf(x, y), atan2 => atan2f(y, x), exp => expf(x), ln => logf(x), log2 => log2f(x), log10 => log10f(x),
+    powf => powf(x, n), cbrt => cbrtf(x),
+    sin => sinf(x), cos => cosf(x), tan => tanf(x), asin => asinf(x),
+    sinh => sinhf(x), cosh => coshf(x), tanh => tanhf(x);
+    exact sqrt => sqrtf(x), round => roundf(x), floor => floorf(x), ceil => ceilf(x), trunc => truncf(x));
+math_mod!(c64 for f64,
+    hypot => hypot(x, y), atan2 => atan2(y, x), exp => exp(x), ln => log(x), log2 => log2(x), log10 => log10(x),
+    powf => pow(x, n), cbrt => cbrt(x),
+    sin => sin(x), cos => cos(x), tan => tan(x), asin => asin(x),
+    sinh => sinh(x), cosh => cosh(x), tanh => tanh(x);
+    exact sqrt => sqrt(x), round => round(x), floor => floor(x), ceil => ceil(x), trunc => trunc(x));
+
+#[cfg(all(test, feature = "deterministic"))]
+mod tests {
+    use crate::Complex;
+
+    #[test]
+    fn uses_libm() {
+        let z = Complex::new(0.3f64, 1.1);
+        let w = z.exp();
+        assert_eq!(w, Complex::new(libm::exp(0.3) * libm::cos(1.1), libm::exp(0.3) * libm::sin(1.1)));
+        assert_eq!(w.ln().r, libm::log(libm::hypot(w.r, w.i)));
+        assert_eq!(Complex::new(1.5f32, -2.0).angle(), libm::atan2f(-2.0, 1.5));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn modules_use_libm() {
+        // w(x) = e^(-x²) + 2i Dawson(x) / √π on the real axis
+        let x = 0.7;
+        assert_eq!(crate::special::c64::faddeeva(Complex::new(x, 0.0)).r, libm::exp(-x * x));
+        assert_eq!(crate::signal::c64::to_bode(&[Complex::new(0.0, 3.0)]).0, [20.0 * libm::log10(3.0)]);
+        // the slice products are the scalar ones, without fused multiply-adds
+        let a: Vec<_> = (0..37).map(|k| Complex::new(libm::sin(k as f64), libm::cos(0.3 * k as f64))).collect();
+        let b: Vec<_> = a.iter().rev().map(|z| z.conj() * 0.7).collect();
+        let mut out = vec![Complex::new(0.0, 0.0); a.len()];
+        crate::slice::c64::mul(&a, &b, &mut out);
+        assert!(out.iter().zip(a.iter().zip(&b)).all(|(&z, (&a, &b))| z == a * b));
+    }
+}
@@ -0,0 +1,206 @@
+//! Circularly symmetric complex Gaussian noise
+//!
+//! Samples of CN(0, σ²) have independent real and imaginary parts, each
+//! normally distributed with variance σ²/2, so that E|z|² = σ².  They are
+//! generated with the basic, trigonometric, Box–Muller transform, which
+//! produces one complex sample per pair of uniform variates: a Rayleigh
+//! distributed magnitude and a uniform phase, taken through its sine and
+//! cosine.
+//!
+//! Normalizing a vector of independent samples gives a unit vector uniformly
+//! distributed on the sphere in ℂⁿ, the random pure states of quantum Monte
+//! Carlo and the starting vectors of randomized linear algebra, and uniform
+//! phases e<sup>iθ</sup> are generated on their own.
+//!
+//! Requires the `rand` feature.
+
+macro_rules! noise_mod {
+    ($m: ident for $t: ident) => {
+        /// Circularly symmetric complex Gaussian noise
+        pub mod $m {
+            use crate::{math, Complex};
+            use rand::Rng;
+
+            /// Draws one sample of CN(0, `variance`)
+            pub fn sample_cn<R: Rng + ?Sized>(rng: &mut R, variance: $t) -> Complex<$t> {
+                use core::$t::consts::TAU;
+                // 1 - [0, 1) keeps the logarithm finite
+                let u: $t = 1.0 - rng.random::<$t>();
+                let phase: $t = TAU * rng.random::<$t>();
+                let (s, c) = (math::$m::sin(phase), math::$m::cos(phase));
+                let r = math::$m::sqrt(-variance * math::$m::ln(u));
+                Complex::new(r * c, r * s)
+            }
+
+            /// Fills `buf` with independent samples of CN(0, `variance`)
+            ///
+            /// # Example
+            /// ```
+            /// use rand::{SeedableRng, rngs::SmallRng};
+            #[doc = concat!("use imaginary::{Complex, noise::", stringify!($m), "::fill_cn};")]
+            /// let mut rng = SmallRng::seed_from_u64(1);
+            /// let mut buf = vec![Complex::new(0.0, 0.0); 1000];
+            /// fill_cn(&mut rng, &mut buf, 2.0);
+            #[doc = concat!("let power = buf.iter().map(|z| z.abs().powi(2)).sum::<", stringify!($t), ">() / 1000.0;")]
+            /// assert!((power - 2.0).abs() < 0.3);
+            /// ```
+            pub fn fill_cn<R: Rng + ?Sized>(rng: &mut R, buf: &mut [Complex<$t>], variance: $t) {
+                for z in buf.iter_mut() {
+                    *z = sample_cn(rng, variance);
+                }
+            }
+
+            /// Adds independent samples of CN(0, `variance`) to `buf`, an
+            /// additive white Gaussian noise channel
+            pub fn add_cn<R: Rng + ?Sized>(rng: &mut R, buf: &mut [Complex<$t>], variance: $t) {
+                for z in buf.iter_mut() {
+                    *z += sample_cn(rng, variance);
+                }
+            }
+
+            /// Draws a uniformly distributed phase e<sup>iθ</sup>, θ uniform
+            /// in [0, 2π)
+            pub fn sample_phase<R: Rng + ?Sized>(rng: &mut R) -> Complex<$t> {
+                use core::$t::consts::TAU;
+                let phase = TAU * rng.random::<$t>();
+                let (s, c) = (math::$m::sin(phase), math::$m::cos(phase));
+                Complex::new(c, s)
+            }
+
+            /// Fills `buf` with independent uniformly distributed phases
+            /// e<sup>iθ</sup>
+            ///
+            /// # Example
+            /// ```
+            /// use rand::{SeedableRng, rngs::SmallRng};
+            #[doc = concat!("use imaginary::{Complex, noise::", stringify!($m), "::fill_phases};")]
+            /// let mut rng = SmallRng::seed_from_u64(1);
+            /// let mut phases = vec![Complex::new(0.0, 0.0); 8];
+            /// fill_phases(&mut rng, &mut phases);
+            /// assert!(phases.iter().all(|z| (z.abs() - 1.0).abs() < 1e-6));
+            /// ```
+            pub fn fill_phases<R: Rng + ?Sized>(rng: &mut R, buf: &mut [Complex<$t>]) {
+                for z in buf.iter_mut() {
+                    *z = sample_phase(rng);
+                }
+            }
+
+            /// Fills `buf` with a random unit vector, uniformly distributed
+            /// on the unit sphere of ℂⁿ for n the length of `buf`
+            ///
+            /// The vector is a normalized sample of independent CN(0, 1)
+            /// components, whose distribution is invariant under every
+            /// unitary transformation.
+            ///
+            /// # Example
+            /// ```
+            /// use rand::{SeedableRng, rngs::SmallRng};
+            #[doc = concat!("use imaginary::{Complex, noise::", stringify!($m), "::fill_unit_vector};")]
+            /// // a random pure state of three qubits
+            /// let mut rng = SmallRng::seed_from_u64(1);
+            /// let mut state = vec![Complex::new(0.0, 0.0); 8];
+            /// fill_unit_vector(&mut rng, &mut state);
+            #[doc = concat!("let norm = state.iter().map(|z| z.abs().powi(2)).sum::<", stringify!($t), ">();")]
+            /// assert!((norm - 1.0).abs() < 1e-6);
+            /// ```
+            pub fn fill_unit_vector<R: Rng + ?Sized>(rng: &mut R, buf: &mut [Complex<$t>]) {
+                if buf.is_empty() {
+                    return;
+                }
+                loop {
+                    fill_cn(rng, buf, 1.0);
+                    let norm = math::$m::sqrt(buf.iter().map(|z| z.r * z.r + z.i * z.i).sum::<$t>());
+                    // a zero vector has probability zero, but redraw it
+                    // rather than divide by zero
+                    if norm > 0.0 {
+                        for z in buf.iter_mut() {
+                            *z /= norm;
+                        }
+                        return;
+                    }
+                }
+            }
+
+            /// A random unit vector of length `n`, as by [`fill_unit_vector`]
+            #[cfg(feature = "std")]
+            pub fn unit_vector<R: Rng + ?Sized>(rng: &mut R, n: usize) -> Vec<Complex<$t>> {
+                let mut v = vec![Complex::new(0.0, 0.0); n];
+                fill_unit_vector(rng, &mut v);
+                v
+            }
+
+            #[cfg(test)]
+            mod test {
+                use super::*;
+                use rand::{SeedableRng, rngs::SmallRng};
+
+                #[test]
+                fn check_moments() {
+                    let mut rng = SmallRng::seed_from_u64(7);
+                    let n = 200_000;
+                    let mut buf = vec![Complex::new(0.0, 0.0); n];
+                    fill_cn(&mut rng, &mut buf, 3.0);
+                    let mean = buf.iter().fold(Complex::new(0.0, 0.0), |sum, &z| sum + z) / n as $t;
+                    let (mut rr, mut ii, mut ri) = (0.0, 0.0, 0.0);
+                    for z in &buf {
+                        rr += (z.r * z.r) as f64;
+                        ii += (z.i * z.i) as f64;
+                        ri += (z.r * z.i) as f64;
+                    }
+                    let (rr, ii, ri) = (rr / n as f64, ii / n as f64, ri / n as f64);
+                    assert!(mean.abs() < 0.02, "{mean}");
+                    assert!((rr - 1.5).abs() < 0.02 && (ii - 1.5).abs() < 0.02, "{rr} {ii}");
+                    assert!(ri.abs() < 0.02, "{ri}");
+                    // fourth moment of a Gaussian component is 3σ⁴
+                    let kurt = buf.iter().map(|z| (z.r as f64).powi(4)).sum::<f64>() / n as f64;
+                    assert!((kurt / (rr * rr) - 3.0).abs() < 0.1, "{kurt}");
+
+                    let mut shifted = vec![Complex::new(1.0, -1.0); 4];
+                    add_cn(&mut rng, &mut shifted, 0.0);
+                    assert_eq!(shifted, vec![Complex::new(1.0, -1.0); 4]);
+                }
+
+                #[test]
+                fn check_unit_vectors() {
+                    let mut rng = SmallRng::seed_from_u64(13);
+                    let n = 4;
+                    let trials = 20_000;
+                    // each |v_k|² of a uniform unit vector in ℂ⁴ has mean 1/4
+                    // and variance (n − 1)/(n²(n + 1)) = 3/80
+                    let (mut mean, mut square) = ([0.0f64; 4], [0.0f64; 4]);
+                    let mut phase = Complex::new(0.0f64, 0.0);
+                    for _ in 0..trials {
+                        let v = unit_vector(&mut rng, n);
+                        let norm: f64 = v.iter().map(|z| (z.abs() as f64).powi(2)).sum();
+                        assert!((norm - 1.0).abs() < 1e2 * $t::EPSILON as f64, "{norm}");
+                        for k in 0..n {
+                            let p = (v[k].abs() as f64).powi(2);
+                            mean[k] += p / trials as f64;
+                            square[k] += p * p / trials as f64;
+                        }
+                        phase += Complex::new(v[0].r as f64, v[0].i as f64) / trials as f64;
+                    }
+                    for k in 0..n {
+                        assert!((mean[k] - 0.25).abs() < 0.01, "{mean:?}");
+                        assert!((square[k] - mean[k] * mean[k] - 3.0 / 80.0).abs() < 0.005, "{square:?}");
+                    }
+                    assert!(phase.abs() < 0.02, "{phase}");
+                    assert_eq!(unit_vector(&mut rng, 0), []);
+                }
+
+                #[test]
+                fn check_phases() {
+                    let mut rng = SmallRng::seed_from_u64(17);
+                    let mut phases = vec![Complex::new(0.0, 0.0); 50_000];
+                    fill_phases(&mut rng, &mut phases);
+                    let mean = phases.iter().fold(Complex::new(0.0, 0.0), |sum, &z| sum + z) / phases.len() as $t;
+                    let second = phases.iter().fold(Complex::new(0.0, 0.0), |sum, &z| sum + z * z) / phases.len() as $t;
+                    assert!(phases.iter().all(|z| (z.abs() - 1.0).abs() < 10.0 * $t::EPSILON));
+                    assert!(mean.abs() < 0.02 && second.abs() < 0.02, "{mean} {second}");
+                }
+            }
+        }
+    }
+}
+noise_mod!(c32 for f32);
+noise_mod!(c64 for f64);
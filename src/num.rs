@@ -0,0 +1,210 @@
+//! Implementations of the `num-traits` numeric traits, with the
+//! `num-traits` feature
+//!
+//! [`Complex`] is `Zero`, `One` and `Num`, and so `NumAssign` too, when
+//! its components are, so it can be used in generic numeric code.  `Num`
+//! needs the remainder operator `%`, which is implemented here as well.
+
+use crate::{Complex, ParseComplexError};
+use core::ops::{Add, Div, Mul, Rem, RemAssign, Sub};
+use num_traits::{Num, One, Zero};
+
+/// # Example
+/// ```
+/// use imaginary::Complex;
+/// use num_traits::Zero;
+/// assert!(Complex::<f64>::zero().is_zero());
+/// ```
+impl<T: Zero + Clone> Zero for Complex<T> {
+    fn zero() -> Complex<T> {
+        Complex::new(T::zero(), T::zero())
+    }
+
+    fn is_zero(&self) -> bool {
+        self.r.is_zero() && self.i.is_zero()
+    }
+}
+
+impl<T: Zero + One + Sub<Output=T> + Clone> One for Complex<T> {
+    fn one() -> Complex<T> {
+        Complex::new(T::one(), T::zero())
+    }
+}
+
+/// The remainder of the division by `rhs`, `self - rhs * q` for the
+/// quotient `q` with its parts truncated toward zero
+///
+/// For integer components this is a remainder of the Gaussian integers,
+/// though not always the smallest, which
+/// [`div_rem`](Complex::div_rem) gives.  For float components the quotient
+/// is truncated in the same way, after the rounding of the division, so
+/// the remainder is not that of each part on its own.
+///
+/// # Example
+/// ```
+/// use imaginary::Complex;
+/// assert_eq!(Complex::new(7, 3) % Complex::new(2, 0), Complex::new(1, 1));
+/// assert_eq!(Complex::new(5.5, -1.0) % Complex::new(0.0, 2.0), Complex::new(1.5, -1.0));
+/// ```
+impl<T> Rem for Complex<T>
+where T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> +
+         Div<Output=T> + Rem<Output=T> + One + Clone {
+    type Output = Complex<T>;
+    fn rem(self, rhs: Complex<T>) -> Complex<T> {
+        let q = &self / &rhs;
+        let q = Complex { r: q.r.clone() - q.r % T::one(), i: q.i.clone() - q.i % T::one() };
+        self - rhs * q
+    }
+}
+
+/// # Example
+/// ```
+/// use imaginary::Complex;
+/// let mut z = Complex::new(7, 3);
+/// z %= Complex::new(2, 0);
+/// assert_eq!(z, Complex::new(1, 1));
+/// ```
+impl<T> RemAssign for Complex<T>
+where T: Add<Output=T> + Sub<Output=T> + Mul<Output=T> +
+         Div<Output=T> + Rem<Output=T> + One + Clone {
+    fn rem_assign(&mut self, rhs: Self) {
+        *self = self.clone() % rhs;
+    }
+}
+
+/// `from_str_radix` reads `a`, `bi`, `a+bi` or `a-bi` with each part in
+/// the radix, and `i` or `j`, optionally after `*`, for the unit
+///
+/// Radices above 18, in which `i` is a digit, are an error, as is `-i` for
+/// unsigned components.
+///
+/// # Example
+/// ```
+/// use imaginary::Complex;
+/// use num_traits::Num;
+/// assert_eq!(Complex::from_str_radix("ff-10i", 16), Ok(Complex::new(255, -16)));
+/// assert_eq!(Complex::from_str_radix("1.5e3+i", 10), Ok(Complex::new(1500.0, 1.0)));
+/// ```
+impl<T: Num + Clone> Num for Complex<T> {
+    type FromStrRadixErr = ParseComplexError;
+
+    fn from_str_radix(s: &str, radix: u32) -> Result<Complex<T>, ParseComplexError> {
+        if radix > 18 {
+            return Err(ParseComplexError { pos: 0 });
+        }
+        let part = |start: usize, text: &str| T::from_str_radix(text, radix).map_err(|_| ParseComplexError { pos: start });
+        // the text of the real part, and of the imaginary part with its
+        // position when the unit is present
+        let (real, imag) = match s.strip_suffix(['i', 'j']) {
+            None => (s, None),
+            Some(rest) => {
+                let rest = rest.strip_suffix('*').unwrap_or(rest);
+                // the sign before the imaginary part, skipping the signs of
+                // exponents in radices where e is not a digit
+                let split = rest.char_indices().rev().find(|&(k, c)| {
+                    (c == '+' || c == '-') && (k == 0 || radix > 14 || !rest[..k].ends_with(['e', 'E']))
+                }).map_or(0, |(k, _)| k);
+                (&s[..split], Some((split, &rest[split..])))
+            }
+        };
+        let r = if real.is_empty() && imag.is_some() { T::zero() } else { part(0, real)? };
+        let i = match imag {
+            None => T::zero(),
+            Some((start, text)) => match text {
+                "" | "+" => T::one(),
+                // parsed, as unsigned types have no -1
+                "-" => part(start, "-1")?,
+                _ => part(start, text.strip_prefix('+').unwrap_or(text))?
+            }
+        };
+        Ok(Complex::new(r, i))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use num_traits::NumAssign;
+
+    fn sum_of_squares<T: NumAssign + Copy>(values: &[T]) -> T {
+        let mut sum = T::zero();
+        for &x in values {
+            sum += x * x;
+        }
+        sum
+    }
+
+    #[test]
+    fn generic() {
+        let z = [Complex::new(1.0, 2.0), Complex::new(0.0, -1.0), Complex::one()];
+        assert_eq!(sum_of_squares(&z), Complex::new(-3.0, 4.0));
+        assert_eq!(sum_of_squares(&[Complex::new(2i64, 1)]), Complex::new(3, 4));
+        assert!(!Complex::new(0.0, 1e-300).is_zero());
+        assert!(Complex::<i8>::one().is_one());
+    }
+
+    #[test]
+    fn big_components() {
+        use num_bigint::BigInt;
+        fn square<T: Num + Clone>(x: T) -> T {
+            x.clone() * x
+        }
+        let big = |r: i64, i: i64| Complex::new(BigInt::from(r), BigInt::from(i));
+        assert_eq!(square(big(i64::MAX, 1)), Complex::new(BigInt::from(i64::MAX) * i64::MAX - 1, BigInt::from(i64::MAX) * 2));
+        assert_eq!(Complex::<BigInt>::from_str_radix("ff-10i", 16), Ok(big(255, -16)));
+        assert_eq!(big(7, 3) % big(2, 0), big(1, 1));
+        assert!(Complex::<BigInt>::one().is_one() && Complex::<BigInt>::zero().is_zero());
+    }
+
+    #[cfg(feature = "num-rational")]
+    #[test]
+    fn rational_components() {
+        use num_rational::Ratio;
+        let half = Complex::new(Ratio::new(1, 2), Ratio::new(-1, 2));
+        assert_eq!(Complex::<Ratio<i64>>::from_str_radix("1/2-1/2i", 10), Ok(half));
+        assert_eq!(half * half, Complex::new(Ratio::from(0), Ratio::new(-1, 2)));
+        assert!((half / half).is_one());
+    }
+
+    #[test]
+    fn remainder() {
+        let b = Complex::new(3i64, 4);
+        for a in [Complex::new(17, -9), Complex::new(-2, 0), Complex::new(100, 41), Complex::new(3, 4)] {
+            let r = a % b;
+            let q = (a - r) / b;
+            assert_eq!(q * b + r, a);
+            assert!(r.dot(r) < 2 * b.dot(b), "{a}");
+            let mut c = a;
+            c %= b;
+            assert_eq!(c, r);
+        }
+        let (a, b) = (Complex::new(10.25f64, -3.5), Complex::new(-1.5, 2.0));
+        let q = (a - a % b) / b;
+        assert!((q.r - q.r.round()).abs() < 1e-12 && (q.i - q.i.round()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn from_str_radix() {
+        let parse = |s: &str, radix| Complex::<i32>::from_str_radix(s, radix);
+        assert_eq!(parse("101", 2), Ok(Complex::new(5, 0)));
+        assert_eq!(parse("-11i", 2), Ok(Complex::new(0, -3)));
+        assert_eq!(parse("-i", 10), Ok(Complex::new(0, -1)));
+        assert_eq!(parse("i", 10), Ok(Complex::new(0, 1)));
+        assert_eq!(parse("7+j", 8), Ok(Complex::new(7, 1)));
+        assert_eq!(parse("-h-h*i", 18), Ok(Complex::new(-17, -17)));
+        assert_eq!(parse("e+e", 16).map_err(|e| e.to_string()), Err("invalid complex number syntax at byte 0".into()));
+        assert_eq!(parse("1+2", 10), Err(ParseComplexError { pos: 0 }));
+        assert_eq!(parse("1+xi", 10), Err(ParseComplexError { pos: 1 }));
+        let parse = |s: &str| Complex::<f64>::from_str_radix(s, 10);
+        assert_eq!(parse("-2.5e-3-1e2i"), Ok(Complex::new(-2.5e-3, -100.0)));
+        assert_eq!(parse("1e+3"), Ok(Complex::new(1e3, 0.0)));
+        assert_eq!(parse("4E+1i"), Ok(Complex::new(0.0, 40.0)));
+        assert_eq!(parse("-i"), Ok(Complex::new(0.0, -1.0)));
+        // i is a digit above radix 18, and unsigned parts have no -i
+        assert_eq!(Complex::<i32>::from_str_radix("10", 19), Err(ParseComplexError { pos: 0 }));
+        assert_eq!(Complex::<u32>::from_str_radix("z", 36), Err(ParseComplexError { pos: 0 }));
+        assert_eq!(Complex::<u32>::from_str_radix("-i", 10), Err(ParseComplexError { pos: 0 }));
+        assert_eq!(Complex::<u32>::from_str_radix("5-i", 10), Err(ParseComplexError { pos: 1 }));
+        assert_eq!(Complex::<u32>::from_str_radix("5+i", 10), Ok(Complex::new(5, 1)));
+    }
+}
@@ -0,0 +1,316 @@
+//! Integrators for ordinary differential equations with complex state
+//!
+//! Schrödinger-type and envelope equations are naturally complex, and these
+//! integrate y′ = f(t, y) for a vector y of complex numbers directly instead
+//! of through stacked real and imaginary parts.  The right hand side is a
+//! closure writing f(t, y) into a buffer, so that nothing is allocated per
+//! evaluation.  [`rk4`](c64::rk4) takes a fixed number of classical
+//! Runge–Kutta steps, and [`rk45`](c64::rk45) adapts its step size with the
+//! Dormand–Prince 5(4) pair to keep the local error within a tolerance.
+//!
+//! Both return an [`OdeSolution`] holding the state and its derivative at
+//! every step, from which [`at`](OdeSolution::at) gives dense output anywhere
+//! in between by cubic Hermite interpolation.
+
+use crate::Complex;
+
+/// The steps taken by an ODE integrator
+#[derive(Clone, Debug, PartialEq)]
+pub struct OdeSolution<T: Copy> {
+    /// the times of the steps, from the start to the end of the integration
+    pub t: Vec<T>,
+    /// the state at each time
+    pub y: Vec<Vec<Complex<T>>>,
+    /// the derivative of the state at each time
+    pub dy: Vec<Vec<Complex<T>>>,
+    /// whether the integration reached the end time
+    pub complete: bool
+}
+
+impl<T: Copy> OdeSolution<T> {
+    /// The state at the last step taken
+    pub fn last(&self) -> &[Complex<T>] {
+        self.y.last().expect("a solution holds at least its initial state")
+    }
+}
+
+macro_rules! impl_ode_solution {
+    ($t: ident) => {
+        impl OdeSolution<$t> {
+            /// The state at time `t`, interpolated between the steps taken
+            ///
+            /// The cubic Hermite interpolant through the states and
+            /// derivatives at the two neighbouring steps is third order
+            /// accurate, and continuous with a continuous derivative.
+            ///
+            /// # Panics
+            /// Panics if `t` lies outside the range integrated over.
+            pub fn at(&self, t: $t) -> Vec<Complex<$t>> {
+                let (first, last) = (self.t[0], *self.t.last().unwrap());
+                let dir: $t = if last < first { -1.0 } else { 1.0 };
+                assert!((t - first) * dir >= 0.0 && (last - t) * dir >= 0.0,
+                    "time {t} is outside the solution range {first} to {last}");
+                let k = self.t.partition_point(|&s| (t - s) * dir > 0.0).max(1).min(self.t.len() - 1);
+                if self.t.len() == 1 {
+                    return self.y[0].clone();
+                }
+                let (t0, t1) = (self.t[k - 1], self.t[k]);
+                let h = t1 - t0;
+                let s = (t - t0) / h;
+                let (s2, s3) = (s * s, s * s * s);
+                let h00 = 2.0 * s3 - 3.0 * s2 + 1.0;
+                let h10 = (s3 - 2.0 * s2 + s) * h;
+                let h01 = 3.0 * s2 - 2.0 * s3;
+                let h11 = (s3 - s2) * h;
+                self.y[k - 1].iter().zip(&self.dy[k - 1]).zip(self.y[k].iter().zip(&self.dy[k]))
+                    .map(|((&y0, &f0), (&y1, &f1))| y0 * h00 + f0 * h10 + y1 * h01 + f1 * h11)
+                    .collect()
+            }
+        }
+    }
+}
+impl_ode_solution!(f32);
+impl_ode_solution!(f64);
+
+macro_rules! ode_mod {
+    ($m: ident for $t: ident) => {
+        /// Integrators for ordinary differential equations with complex state
+        pub mod $m {
+            use crate::Complex;
+            use super::OdeSolution;
+
+            /// `y + h Σ a_j k_j`
+            fn stage(y: &[Complex<$t>], h: $t, terms: &[($t, &[Complex<$t>])]) -> Vec<Complex<$t>> {
+                let mut out = y.to_vec();
+                for &(a, k) in terms {
+                    for (out, &k) in out.iter_mut().zip(k) {
+                        *out += k * (a * h);
+                    }
+                }
+                out
+            }
+
+            /// Integrates y′ = f(t, y) from `t0` to `t1` in `steps` equal
+            /// steps of the classical fourth order Runge–Kutta method
+            ///
+            /// `f(t, y, dy)` writes the derivative at `(t, y)` into `dy`.
+            /// `t1` may be before `t0` to integrate backwards.
+            ///
+            /// # Panics
+            /// Panics if `steps` is zero.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, ode::", stringify!($m), "::rk4};")]
+            /// // iy′ = y, so y = e^(−it)
+            /// let f = |_t, y: &[Complex<_>], dy: &mut [Complex<_>]| dy[0] = -Complex::new(0.0, 1.0) * y[0];
+            /// let solution = rk4(f, 0.0, &[Complex::new(1.0, 0.0)], 1.0, 100);
+            #[doc = concat!("let exact = Complex::<", stringify!($t), ">::cis(-1.0);")]
+            /// assert!((solution.last()[0] - exact).abs() < 1e-6);
+            /// ```
+            pub fn rk4(
+                mut f: impl FnMut($t, &[Complex<$t>], &mut [Complex<$t>]),
+                t0: $t, y0: &[Complex<$t>], t1: $t, steps: usize
+            ) -> OdeSolution<$t> {
+                assert!(steps > 0, "rk4 needs at least one step");
+                let n = y0.len();
+                let h = (t1 - t0) / steps as $t;
+                let zero = Complex::new(0.0, 0.0);
+                let mut k1 = vec![zero; n];
+                f(t0, y0, &mut k1);
+                let mut solution = OdeSolution {
+                    t: vec![t0], y: vec![y0.to_vec()], dy: vec![k1.clone()], complete: true
+                };
+                let (mut k2, mut k3, mut k4) = (vec![zero; n], vec![zero; n], vec![zero; n]);
+                let mut y = y0.to_vec();
+                for step in 1..=steps {
+                    let t = t0 + h * (step - 1) as $t;
+                    f(t + 0.5 * h, &stage(&y, h, &[(0.5, &k1)]), &mut k2);
+                    f(t + 0.5 * h, &stage(&y, h, &[(0.5, &k2)]), &mut k3);
+                    f(t + h, &stage(&y, h, &[(1.0, &k3)]), &mut k4);
+                    y = stage(&y, h, &[(1.0 / 6.0, &k1), (1.0 / 3.0, &k2), (1.0 / 3.0, &k3), (1.0 / 6.0, &k4)]);
+                    let t = if step == steps { t1 } else { t0 + h * step as $t };
+                    f(t, &y, &mut k1);
+                    solution.t.push(t);
+                    solution.y.push(y.clone());
+                    solution.dy.push(k1.clone());
+                }
+                solution
+            }
+
+            /// Integrates y′ = f(t, y) from `t0` to `t1` with the adaptive
+            /// Dormand–Prince 5(4) method
+            ///
+            /// `f(t, y, dy)` writes the derivative at `(t, y)` into `dy`.
+            /// Steps are sized so that the estimated local error in each
+            /// component is within `tol` relative to its magnitude, or
+            /// absolute below 1, and the fifth order solution is kept.  `t1`
+            /// may be before `t0` to integrate backwards.  The integration
+            /// stops early, with `complete` false, if the step size falls to
+            /// the rounding error in t, as at a singularity.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, ode::", stringify!($m), "::rk45};")]
+            /// // a two level system driven on resonance: Rabi oscillation
+            /// let f = |_t, y: &[Complex<_>], dy: &mut [Complex<_>]| {
+            ///     let i = Complex::new(0.0, 1.0);
+            ///     dy[0] = -i * y[1];
+            ///     dy[1] = -i * y[0];
+            /// };
+            /// let solution = rk45(f, 0.0, &[Complex::new(1.0, 0.0), Complex::new(0.0, 0.0)], 10.0, 1e-6);
+            /// assert!(solution.complete);
+            #[doc = concat!("let half_period = solution.at(std::", stringify!($t), "::consts::FRAC_PI_2);")]
+            /// // all the population is in the upper level after half a period
+            /// assert!(half_period[0].abs() < 1e-4);
+            /// assert!((half_period[1].abs() - 1.0).abs() < 1e-4);
+            /// ```
+            pub fn rk45(
+                mut f: impl FnMut($t, &[Complex<$t>], &mut [Complex<$t>]),
+                t0: $t, y0: &[Complex<$t>], t1: $t, tol: $t
+            ) -> OdeSolution<$t> {
+                const C: [$t; 6] = [1.0 / 5.0, 3.0 / 10.0, 4.0 / 5.0, 8.0 / 9.0, 1.0, 1.0];
+                const A2: [$t; 1] = [1.0 / 5.0];
+                const A3: [$t; 2] = [3.0 / 40.0, 9.0 / 40.0];
+                const A4: [$t; 3] = [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0];
+                const A5: [$t; 4] = [19372.0 / 6561.0, -25360.0 / 2187.0, 64448.0 / 6561.0, -212.0 / 729.0];
+                const A6: [$t; 5] = [9017.0 / 3168.0, -355.0 / 33.0, 46732.0 / 5247.0, 49.0 / 176.0, -5103.0 / 18656.0];
+                const B: [$t; 6] = [35.0 / 384.0, 0.0, 500.0 / 1113.0, 125.0 / 192.0, -2187.0 / 6784.0, 11.0 / 84.0];
+                // difference between the fifth and fourth order weights
+                const E: [$t; 7] = [
+                    71.0 / 57600.0, 0.0, -71.0 / 16695.0, 71.0 / 1920.0, -17253.0 / 339200.0, 22.0 / 525.0, -1.0 / 40.0
+                ];
+
+                let n = y0.len();
+                let zero = Complex::new(0.0, 0.0);
+                let mut k = vec![vec![zero; n]; 7];
+                f(t0, y0, &mut k[0]);
+                let mut solution = OdeSolution {
+                    t: vec![t0], y: vec![y0.to_vec()], dy: vec![k[0].clone()], complete: true
+                };
+                let span = t1 - t0;
+                if span == 0.0 || n == 0 {
+                    return solution;
+                }
+                let dir = span.signum();
+                let mut h = span / 100.0;
+                let (mut t, mut y) = (t0, y0.to_vec());
+                while (t1 - t) * dir > 0.0 {
+                    if (t + h - t1) * dir > 0.0 {
+                        h = t1 - t;
+                    }
+                    if h.abs() <= 4.0 * $t::EPSILON * t.abs().max(h.abs().min(1.0)) {
+                        solution.complete = false;
+                        break;
+                    }
+                    let (k1, rest) = k.split_at_mut(1);
+                    let (k2, rest) = rest.split_at_mut(1);
+                    let (k3, rest) = rest.split_at_mut(1);
+                    let (k4, rest) = rest.split_at_mut(1);
+                    let (k5, rest) = rest.split_at_mut(1);
+                    let (k6, k7) = rest.split_at_mut(1);
+                    let (k1, k2, k3, k4, k5, k6, k7) =
+                        (&k1[0], &mut k2[0], &mut k3[0], &mut k4[0], &mut k5[0], &mut k6[0], &mut k7[0]);
+                    f(t + C[0] * h, &stage(&y, h, &[(A2[0], k1)]), k2);
+                    f(t + C[1] * h, &stage(&y, h, &[(A3[0], k1), (A3[1], k2)]), k3);
+                    f(t + C[2] * h, &stage(&y, h, &[(A4[0], k1), (A4[1], k2), (A4[2], k3)]), k4);
+                    f(t + C[3] * h, &stage(&y, h, &[(A5[0], k1), (A5[1], k2), (A5[2], k3), (A5[3], k4)]), k5);
+                    f(t + C[4] * h, &stage(&y, h, &[(A6[0], k1), (A6[1], k2), (A6[2], k3), (A6[3], k4), (A6[4], k5)]), k6);
+                    let next = stage(&y, h, &[(B[0], k1), (B[2], k3), (B[3], k4), (B[4], k5), (B[5], k6)]);
+                    let t_next = if h == t1 - t { t1 } else { t + h };
+                    f(t_next, &next, k7);
+
+                    let mut error: $t = 0.0;
+                    for j in 0..n {
+                        let e = (k1[j] * E[0] + k3[j] * E[2] + k4[j] * E[3] + k5[j] * E[4] + k6[j] * E[5] + k7[j] * E[6]) * h;
+                        let scale = tol * y[j].abs().max(next[j].abs()).max(1.0);
+                        error = error.max(e.abs() / scale);
+                    }
+                    if error.is_nan() {
+                        h *= 0.2;
+                        continue;
+                    }
+                    if error <= 1.0 {
+                        t = t_next;
+                        y = next;
+                        k.swap(0, 6);
+                        solution.t.push(t);
+                        solution.y.push(y.clone());
+                        solution.dy.push(k[0].clone());
+                    }
+                    let factor = if error == 0.0 { 5.0 } else { 0.9 * crate::math::$m::powf(error, -0.2) };
+                    h *= factor.clamp(0.2, 5.0);
+                }
+                solution
+            }
+
+            #[cfg(test)]
+            mod test {
+                use super::*;
+
+                fn rotate(_t: $t, y: &[Complex<$t>], dy: &mut [Complex<$t>]) {
+                    dy[0] = Complex::new(0.0, -1.0) * y[0];
+                }
+
+                #[test]
+                fn check_rk4() {
+                    let y0 = [Complex::new(1.0, 0.0)];
+                    let coarse = (rk4(rotate, 0.0, &y0, 2.0, 5).last()[0] - Complex::<$t>::cis(-2.0)).abs();
+                    let fine = (rk4(rotate, 0.0, &y0, 2.0, 10).last()[0] - Complex::<$t>::cis(-2.0)).abs();
+                    // fourth order convergence
+                    assert!(coarse / fine > 12.0 && coarse / fine < 20.0, "{coarse} {fine}");
+                    let solution = rk4(rotate, 2.0, &[Complex::<$t>::cis(-2.0)], 0.0, 40);
+                    assert_eq!(solution.t.len(), 41);
+                    assert_eq!(*solution.t.last().unwrap(), 0.0);
+                    assert!((solution.last()[0] - y0[0]).abs() < 1e-4);
+                }
+
+                #[test]
+                fn check_rk45() {
+                    let tol = if $t::EPSILON < 1e-10 { 1e-10 } else { 1e-5 };
+                    let y0 = [Complex::new(1.0, 0.0)];
+                    let solution = rk45(rotate, 0.0, &y0, 10.0, tol);
+                    assert!(solution.complete);
+                    assert_eq!(*solution.t.last().unwrap(), 10.0);
+                    assert!((solution.last()[0] - Complex::<$t>::cis(-10.0)).abs() < 100.0 * tol);
+                    let coarse = rk45(rotate, 0.0, &y0, 10.0, 1e3 * tol);
+                    assert!(coarse.t.len() < solution.t.len());
+                    // backwards
+                    let solution = rk45(rotate, 10.0, &[Complex::<$t>::cis(-10.0)], 0.0, tol);
+                    assert!((solution.last()[0] - y0[0]).abs() < 100.0 * tol);
+                    // nothing to do
+                    assert_eq!(rk45(rotate, 1.0, &y0, 1.0, tol).t, [1.0]);
+                }
+
+                #[test]
+                fn check_dense_output() {
+                    let solution = rk45(rotate, 0.0, &[Complex::new(1.0, 0.0)], 5.0, 1e-6);
+                    for k in 0..=50 {
+                        let t = 0.1 * k as $t;
+                        assert!((solution.at(t)[0] - Complex::<$t>::cis(-t)).abs() < 1e-4, "{t}");
+                    }
+                    assert_eq!(solution.at(0.0), [Complex::new(1.0, 0.0)]);
+                    let backwards = rk4(rotate, 1.0, &[Complex::new(1.0, 0.0)], 0.0, 10);
+                    assert!((backwards.at(0.55)[0] - Complex::<$t>::cis(0.45)).abs() < 1e-4);
+                }
+
+                #[test]
+                fn check_blow_up() {
+                    // y′ = y², y = 1/(1 − t) blows up at t = 1
+                    let solution = rk45(|_t, y, dy| dy[0] = y[0] * y[0], 0.0, &[Complex::new(1.0, 0.0)], 2.0, 1e-6);
+                    assert!(!solution.complete);
+                    let end = *solution.t.last().unwrap();
+                    assert!((end - 1.0).abs() < 1e-3, "{end}");
+                }
+
+                #[test]
+                #[should_panic]
+                fn check_at_outside() {
+                    rk4(rotate, 0.0, &[Complex::new(1.0, 0.0)], 1.0, 4).at(1.5);
+                }
+            }
+        }
+    }
+}
+ode_mod!(c32 for f32);
+ode_mod!(c64 for f64);
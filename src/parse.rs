@@ -0,0 +1,124 @@
+//! Scanning of the text of complex numbers, shared by the `FromStr`
+//! implementations of [`Complex`] and [`Polynomial`](crate::poly::Polynomial)
+
+use crate::Complex;
+use core::ops::Neg;
+use core::str::FromStr;
+
+/// Scans text for numbers, signs and the imaginary unit
+pub(crate) struct Scanner<'a> {
+    pub(crate) s: &'a str,
+    pub(crate) pos: usize
+}
+
+impl<'a> Scanner<'a> {
+    pub(crate) fn peek(&self) -> Option<char> {
+        self.s[self.pos..].chars().next()
+    }
+
+    pub(crate) fn eat(&mut self, chars: &[char]) -> bool {
+        match self.peek() {
+            Some(c) if chars.contains(&c) => {
+                self.pos += c.len_utf8();
+                true
+            }
+            _ => false
+        }
+    }
+
+    pub(crate) fn skip_spaces(&mut self) {
+        while let Some(c) = self.peek().filter(|c| c.is_whitespace()) {
+            self.pos += c.len_utf8();
+        }
+    }
+
+    /// `Some(true)` for a minus sign, `Some(false)` for a plus sign
+    pub(crate) fn sign(&mut self) -> Option<bool> {
+        if self.eat(&['-', '−']) {
+            Some(true)
+        } else if self.eat(&['+']) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn digits(&mut self) -> usize {
+        let start = self.pos;
+        while self.eat(&['0', '1', '2', '3', '4', '5', '6', '7', '8', '9']) {}
+        self.pos - start
+    }
+
+    /// An unsigned decimal number with an optional exponent
+    pub(crate) fn number(&mut self) -> Option<&'a str> {
+        let start = self.pos;
+        // as written by `Display` for floats
+        for special in ["inf", "NaN"] {
+            if self.s[start..].starts_with(special) {
+                self.pos += special.len();
+                return Some(special);
+            }
+        }
+        let mut count = self.digits();
+        if self.eat(&['.']) {
+            count += self.digits();
+        }
+        if count == 0 {
+            self.pos = start;
+            return None;
+        }
+        let mantissa = self.pos;
+        if self.eat(&['e', 'E']) {
+            self.sign();
+            if self.digits() == 0 {
+                self.pos = mantissa;
+            }
+        }
+        Some(&self.s[start..self.pos])
+    }
+
+    /// A number or `i`, returns the value and whether it is imaginary
+    ///
+    /// The imaginary unit may also be written `j`, as by engineers.
+    pub(crate) fn component<T>(&mut self) -> Option<(T, bool)>
+    where T: FromStr {
+        let Some(number) = self.number() else {
+            if !self.eat(&['i', 'j']) {
+                return None;
+            }
+            return "1".parse().ok().map(|one| (one, true));
+        };
+        let value = number.parse().ok()?;
+        let end = self.pos;
+        if self.eat(&['i', 'j']) {
+            return Some((value, true));
+        }
+        if self.eat(&['*', '·']) && self.eat(&['i', 'j']) {
+            return Some((value, true));
+        }
+        self.pos = end;
+        Some((value, false))
+    }
+
+    /// A complex number written as `a`, `bi` or `a+bi`
+    pub(crate) fn complex<T>(&mut self) -> Option<Complex<T>>
+    where T: Clone + Default + FromStr + Neg<Output=T> {
+        let signed = |neg: bool, x: T| if neg { -x } else { x };
+        let neg = self.sign().unwrap_or(false);
+        self.skip_spaces();
+        let (a, imag) = self.component::<T>()?;
+        let a = signed(neg, a);
+        if imag {
+            return Some(Complex::new(T::default(), a));
+        }
+        self.skip_spaces();
+        let Some(neg) = self.sign() else {
+            return Some(Complex::new(a, T::default()));
+        };
+        self.skip_spaces();
+        match self.component::<T>()? {
+            (b, true) => Some(Complex::new(a, signed(neg, b))),
+            (_, false) => None
+        }
+    }
+}
@@ -0,0 +1,164 @@
+//! Complex numbers in polar form
+//!
+//! A [`Polar`] holds a magnitude and a phase angle, in which form products
+//! and quotients are a multiplication and an addition, so chains of gains
+//! and phase shifts can be applied without converting back and forth with
+//! [`abs`](Complex::abs) and [`angle`](Complex::angle).
+//!
+//! # Example
+//! ```
+//! use imaginary::Complex;
+//! use imaginary::polar::Polar;
+//! use std::f64::consts::FRAC_PI_2;
+//! let gain = Polar::new(2.0, FRAC_PI_2);
+//! let z = Complex::<f64>::new(1.0, 1.0).to_polar() * gain * gain;
+//! assert!((Complex::from(z) - Complex::new(-4.0, -4.0)).abs() < 1e-12);
+//! ```
+
+use crate::{math, Complex};
+use core::ops::{Div, Mul};
+
+/// A complex number as a magnitude and a phase angle in radians
+///
+/// The angle is not wrapped, so that products and quotients keep the
+/// total turn; [`principal`](Polar::principal) brings it into (−π, π].
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Polar<T: Copy> {
+    /// magnitude
+    pub mag: T,
+    /// phase angle in radians
+    pub arg: T
+}
+
+impl<T: Copy> Polar<T> {
+    /// Polar form with magnitude `mag` and phase angle `arg`
+    pub const fn new(mag: T, arg: T) -> Polar<T> {
+        Polar { mag, arg }
+    }
+}
+
+macro_rules! impl_polar {
+    ($m: ident for $t: ident) => {
+        impl Polar<$t> {
+            /// The complex number mag e<sup>i arg</sup>
+            pub fn to_complex(self) -> Complex<$t> {
+                Complex::<$t>::from_polar(self.mag, self.arg)
+            }
+
+            /// The same number with its angle in (−π, π]
+            pub fn principal(self) -> Polar<$t> {
+                Polar::new(self.mag, crate::angle::Angle::<$t>::new(self.arg).radians())
+            }
+
+            /// The reciprocal, 1 / z
+            pub fn recip(self) -> Polar<$t> {
+                Polar::new(1.0 / self.mag, -self.arg)
+            }
+
+            /// Power, z<sup>n</sup>, with the angle multiplied by `n`
+            /// as it stands, unwrapped
+            pub fn powf(self, n: $t) -> Polar<$t> {
+                Polar::new(math::$m::powf(self.mag, n), self.arg * n)
+            }
+        }
+
+        impl Complex<$t> {
+            /// The magnitude and principal phase angle
+            pub fn to_polar(self) -> Polar<$t> {
+                Polar::new(self.abs(), self.angle())
+            }
+
+            /// The complex number `mag` e<sup>i `arg`</sup>
+            ///
+            /// # Example
+            /// ```
+            /// use imaginary::Complex;
+            #[doc = concat!("let z = Complex::<", stringify!($t), ">::from_polar(2.0, std::", stringify!($t), "::consts::PI / 3.0);")]
+            /// assert!((z - Complex::new(1.0, 3.0f64.sqrt() as _)).abs() < 1e-6);
+            /// ```
+            pub fn from_polar(mag: $t, arg: $t) -> Complex<$t> {
+                mag * Complex::<$t>::cis(arg)
+            }
+        }
+
+        impl From<Polar<$t>> for Complex<$t> {
+            fn from(z: Polar<$t>) -> Complex<$t> {
+                z.to_complex()
+            }
+        }
+
+        impl From<Complex<$t>> for Polar<$t> {
+            fn from(z: Complex<$t>) -> Polar<$t> {
+                z.to_polar()
+            }
+        }
+
+        impl Mul for Polar<$t> {
+            type Output = Polar<$t>;
+            fn mul(self, rhs: Polar<$t>) -> Polar<$t> {
+                Polar::new(self.mag * rhs.mag, self.arg + rhs.arg)
+            }
+        }
+
+        impl Div for Polar<$t> {
+            type Output = Polar<$t>;
+            fn div(self, rhs: Polar<$t>) -> Polar<$t> {
+                Polar::new(self.mag / rhs.mag, self.arg - rhs.arg)
+            }
+        }
+
+        /// Scales the magnitude
+        impl Mul<$t> for Polar<$t> {
+            type Output = Polar<$t>;
+            fn mul(self, rhs: $t) -> Polar<$t> {
+                Polar::new(self.mag * rhs, self.arg)
+            }
+        }
+
+        /// Scales the magnitude
+        impl Div<$t> for Polar<$t> {
+            type Output = Polar<$t>;
+            fn div(self, rhs: $t) -> Polar<$t> {
+                Polar::new(self.mag / rhs, self.arg)
+            }
+        }
+    }
+}
+impl_polar!(c32 for f32);
+impl_polar!(c64 for f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn conversions() {
+        let z = Complex::<f64>::new(-3.0, 4.0);
+        let p = z.to_polar();
+        assert_eq!((p.mag, p.arg), (5.0, z.angle()));
+        assert!((Complex::from(p) - z).abs() < 1e-14);
+        assert_eq!(Polar::from(z), p);
+        assert_eq!(Complex::<f64>::from_polar(1.0, PI / 2.0), Complex::<f64>::cis(PI / 2.0));
+        assert_eq!(Complex::<f32>::new(0.0, 0.0).to_polar(), Polar::new(0.0, 0.0));
+        assert_eq!(Polar::new(2.0, 3.0 * PI).principal(), Polar::new(2.0, PI));
+        assert_eq!(Polar::<f64>::default().to_complex(), Complex::new(0.0, 0.0));
+    }
+
+    #[test]
+    fn arithmetic() {
+        let (a, b) = (Complex::<f64>::new(1.5, -0.5), Complex::<f64>::new(-2.0, 0.25));
+        let (p, q) = (a.to_polar(), b.to_polar());
+        assert!(((p * q).to_complex() - a * b).abs() < 1e-14);
+        assert!(((p / q).to_complex() - a / b).abs() < 1e-14);
+        assert!(((p * 2.0).to_complex() - a * 2.0).abs() < 1e-14);
+        assert!(((p / 4.0).to_complex() - a / 4.0).abs() < 1e-14);
+        assert!((p.recip().to_complex() - a.recip()).abs() < 1e-14);
+        assert!((q.powf(3.0).to_complex() - b * b * b).abs() < 1e-12);
+        // angles accumulate without wrapping
+        let turn = Polar::new(1.0, PI / 2.0);
+        let four = turn * turn * turn * turn;
+        assert_eq!(four.arg, 2.0 * PI);
+        assert!(four.principal().arg.abs() < 1e-15);
+    }
+}
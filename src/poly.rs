@@ -0,0 +1,1593 @@
+//! Polynomials with complex coefficients
+
+use crate::Complex;
+use crate::parse::Scanner;
+use core::ops::{Mul, Neg};
+use core::str::FromStr;
+
+/// A polynomial with complex coefficients
+///
+/// The coefficients are stored from the highest degree term down to the
+/// constant term, the same order used by the arguments of
+/// [`c64::quad`](crate::c64::quad) and [`c64::cubic`](crate::c64::cubic).
+///
+/// # Example
+/// ```
+/// use imaginary::{Complex, poly::Polynomial};
+/// // z² + 1
+/// let p = Polynomial::<f64>::new(vec![1.0.into(), 0.0.into(), 1.0.into()]);
+/// assert_eq!(p.degree(), 2);
+/// assert_eq!(p.eval(Complex::new(0.0, 1.0)), Complex::new(0.0, 0.0));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Polynomial<T: Copy> {
+    coeffs: Vec<Complex<T>>
+}
+
+impl<T> Polynomial<T>
+where T: Copy + Default + PartialEq {
+    /// Creates a polynomial from its coefficients, highest degree first
+    ///
+    /// Leading zero coefficients are removed.  An empty list of
+    /// coefficients is the zero polynomial.
+    pub fn new(coeffs: Vec<Complex<T>>) -> Polynomial<T> {
+        let zero = Complex::<T>::default();
+        let lead = coeffs.iter().position(|&c| c != zero);
+        match lead {
+            Some(lead) => Polynomial { coeffs: coeffs[lead..].to_vec() },
+            None => Polynomial { coeffs: vec![zero] }
+        }
+    }
+}
+
+impl<T: Copy> Polynomial<T> {
+    /// The coefficients, highest degree first
+    pub fn coeffs(&self) -> &[Complex<T>] {
+        &self.coeffs
+    }
+
+    /// The degree of the polynomial
+    ///
+    /// Constants, including the zero polynomial, have a degree of 0
+    pub fn degree(&self) -> usize {
+        self.coeffs.len() - 1
+    }
+}
+
+/// The result of deflating a polynomial by one of its roots
+///
+/// See [`Polynomial::deflate`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Deflation<T: Copy> {
+    /// The quotient polynomial, one degree lower than the original
+    pub quotient: Polynomial<T>,
+    /// The remainder left over by the division, zero for an exact root
+    pub remainder: Complex<T>,
+    /// Bound on the absolute rounding error in the quotient coefficients
+    pub error: T
+}
+
+/// A term `residue / (z - pole)ᵒʳᵈᵉʳ` of a partial fraction expansion
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PartialFraction<T: Copy> {
+    /// pole
+    pub pole: Complex<T>,
+    /// power of the denominator
+    pub order: usize,
+    /// numerator
+    pub residue: Complex<T>
+}
+
+/// Partial fraction expansion of a rational function
+///
+/// The rational function is the sum of the polynomial `direct` and the
+/// `terms`.  For a pole of order `m` there are `m` terms, one for each order
+/// from 1 to `m`.
+///
+/// See [`Polynomial::partial_fractions`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct PartialFractions<T: Copy> {
+    /// terms of the form `residue / (z - pole)ᵒʳᵈᵉʳ`
+    pub terms: Vec<PartialFraction<T>>,
+    /// polynomial part, zero for a proper rational function
+    pub direct: Polynomial<T>
+}
+
+macro_rules! impl_partial_fractions {
+    ($t: ident) => {
+        impl PartialFractions<$t> {
+            /// Partial fraction expansion of the rational function with the
+            /// given zeros, poles and gain
+            ///
+            /// The rational function is `gain ∏ (z - zero) / ∏ (z - pole)`.
+            /// Poles within `tol` of each other, relative to their modulus
+            /// when greater than 1, are grouped into a repeated pole at their
+            /// mean.
+            pub fn from_zeros_poles(zeros: &[Complex<$t>], poles: &[Complex<$t>],
+                    gain: Complex<$t>, tol: $t) -> PartialFractions<$t> {
+                let num = Polynomial::<$t>::from_roots(zeros);
+                let num = Polynomial::new(num.coeffs.iter().map(|&c| c * gain).collect());
+                let (direct, num) = num.div_rem(&Polynomial::<$t>::from_roots(poles));
+                let mut groups: Vec<(Complex<$t>, usize)> = Vec::new();
+                for &pole in poles {
+                    let group = groups.iter_mut().find(|(center, _)| {
+                        (pole - *center).abs() <= tol * center.abs().max(1.0)
+                    });
+                    match group {
+                        Some((center, order)) => {
+                            let n = *order as $t;
+                            *center = (*center * n + pole) / (n + 1.0);
+                            *order += 1;
+                        }
+                        None => groups.push((pole, 1))
+                    }
+                }
+                let terms = num.residues(Complex::new(1.0, 0.0), &groups);
+                PartialFractions { terms, direct }
+            }
+
+            /// Evaluates the expansion at `z`
+            pub fn eval(&self, z: Complex<$t>) -> Complex<$t> {
+                let mut sum = self.direct.eval(z);
+                for term in &self.terms {
+                    let mut den = z - term.pole;
+                    for _ in 1..term.order {
+                        den *= z - term.pole;
+                    }
+                    sum += term.residue / den;
+                }
+                sum
+            }
+        }
+    }
+}
+impl_partial_fractions!(f32);
+impl_partial_fractions!(f64);
+
+/// Working state of the Jenkins–Traub CPOLY algorithm
+struct Cpoly<T: Copy> {
+    /// polynomial being solved, deflated as roots are found
+    p: Vec<Complex<T>>,
+    /// H polynomial, scaled so its leading coefficient matches `p`
+    h: Vec<Complex<T>>,
+    /// partial sums from evaluating `p` at `s`
+    qp: Vec<Complex<T>>,
+    /// partial sums from evaluating `h` at `s`
+    qh: Vec<Complex<T>>,
+    /// current shift
+    s: Complex<T>,
+    /// p(s)
+    pv: Complex<T>,
+    /// -p(s) / h(s)
+    t: Complex<T>
+}
+
+macro_rules! impl_polynomial {
+    ($m: ident for $t: ident) => {
+        impl Polynomial<$t> {
+            /// Creates the monic polynomial with the given roots
+            ///
+            /// # Example
+            /// ```
+            /// use imaginary::{Complex, poly::Polynomial};
+            /// let p = Polynomial::<f64>::from_roots(&[1.0.into(), (-1.0).into()]);
+            /// assert_eq!(p.coeffs(), &[1.0.into(), 0.0.into(), (-1.0).into()]);
+            /// ```
+            pub fn from_roots(roots: &[Complex<$t>]) -> Polynomial<$t> {
+                let mut coeffs = vec![Complex::new(1.0, 0.0)];
+                for &root in roots {
+                    coeffs.push(Complex::new(0.0, 0.0));
+                    for k in (1..coeffs.len()).rev() {
+                        let prev = coeffs[k - 1];
+                        coeffs[k] -= root * prev;
+                    }
+                }
+                Polynomial { coeffs }
+            }
+
+            /// Evaluates the polynomial at `z` using Horner's method
+            pub fn eval(&self, z: Complex<$t>) -> Complex<$t> {
+                let mut sum = self.coeffs[0];
+                for &c in &self.coeffs[1..] {
+                    sum = sum * z + c;
+                }
+                sum
+            }
+
+            /// Evaluates the polynomial and its first `k` derivatives at `z`
+            ///
+            /// Returns `[p(z), p′(z), …, p⁽ᵏ⁾(z)]`, computed together in a
+            /// single pass of synthetic division over the coefficients.
+            ///
+            /// # Example
+            /// ```
+            /// use imaginary::{Complex, poly::Polynomial};
+            /// // z³ + 2 z
+            /// let p = Polynomial::<f64>::new(vec![1.0.into(), 0.0.into(), 2.0.into(), 0.0.into()]);
+            /// let z = Complex::new(1.0, 1.0);
+            /// assert_eq!(p.eval_derivatives(z, 4), [
+            ///     z * z * z + 2.0 * z,
+            ///     3.0 * z * z + 2.0,
+            ///     6.0 * z,
+            ///     6.0.into(),
+            ///     0.0.into()
+            /// ]);
+            /// ```
+            pub fn eval_derivatives(&self, z: Complex<$t>, k: usize) -> Vec<Complex<$t>> {
+                let mut derivatives = self.taylor(z, k + 1);
+                let mut factorial = 1.0;
+                for (i, d) in derivatives.iter_mut().enumerate().skip(2) {
+                    factorial *= i as $t;
+                    *d *= factorial;
+                }
+                derivatives
+            }
+
+            /// The derivative of the polynomial
+            pub fn derivative(&self) -> Polynomial<$t> {
+                let n = self.degree();
+                if n == 0 {
+                    return Polynomial { coeffs: vec![Complex::new(0.0, 0.0)] };
+                }
+                let coeffs = self.coeffs[..n].iter().enumerate()
+                    .map(|(k, &c)| c * (n - k) as $t)
+                    .collect();
+                Polynomial { coeffs }
+            }
+
+            /// The polynomial scaled to have a leading coefficient of one
+            ///
+            /// The zero polynomial is returned unchanged
+            pub fn monic(&self) -> Polynomial<$t> {
+                let lead = self.coeffs[0];
+                if lead == Complex::new(0.0, 0.0) {
+                    return self.clone();
+                }
+                let coeffs = self.coeffs.iter().map(|&c| c / lead).collect();
+                Polynomial { coeffs }
+            }
+
+            /// Product of two polynomials by direct convolution of the
+            /// coefficients
+            pub fn mul_schoolbook(&self, other: &Polynomial<$t>) -> Polynomial<$t> {
+                let mut coeffs = vec![Complex::new(0.0, 0.0); self.coeffs.len() + other.coeffs.len() - 1];
+                for (i, &a) in self.coeffs.iter().enumerate() {
+                    for (c, &b) in coeffs[i..].iter_mut().zip(&other.coeffs) {
+                        *c += a * b;
+                    }
+                }
+                Polynomial::new(coeffs)
+            }
+
+            /// Product of two polynomials by convolution of the coefficients
+            /// with the FFT
+            ///
+            /// The error in each coefficient is relative to the largest
+            /// coefficients of the two polynomials, so coefficients much
+            /// smaller than that lose accuracy compared to
+            /// [`mul_schoolbook`](Self::mul_schoolbook).
+            pub fn mul_fft(&self, other: &Polynomial<$t>) -> Polynomial<$t> {
+                use crate::fft::$m::{fft, ifft};
+                let len = self.coeffs.len() + other.coeffs.len() - 1;
+                let n = len.next_power_of_two();
+                let mut a = self.coeffs.clone();
+                let mut b = other.coeffs.clone();
+                a.resize(n, Complex::new(0.0, 0.0));
+                b.resize(n, Complex::new(0.0, 0.0));
+                fft(&mut a);
+                fft(&mut b);
+                for (a, &b) in a.iter_mut().zip(&b) {
+                    *a *= b;
+                }
+                ifft(&mut a);
+                a.truncate(len);
+                Polynomial::new(a)
+            }
+
+            /// Polynomial long division, returns the quotient and remainder
+            ///
+            /// # Panics
+            ///
+            /// Panics if `divisor` is the zero polynomial
+            ///
+            /// # Example
+            /// ```
+            /// use imaginary::{Complex, poly::Polynomial};
+            /// // z³ + 2 = (z² - z + 1)(z + 1) + 1
+            /// let p = Polynomial::<f64>::new(vec![1.0.into(), 0.0.into(), 0.0.into(), 2.0.into()]);
+            /// let d = Polynomial::<f64>::new(vec![1.0.into(), 1.0.into()]);
+            /// let (q, r) = p.div_rem(&d);
+            /// assert_eq!(q.coeffs(), &[1.0.into(), (-1.0).into(), 1.0.into()]);
+            /// assert_eq!(r.coeffs(), &[1.0.into()]);
+            /// ```
+            pub fn div_rem(&self, divisor: &Polynomial<$t>) -> (Polynomial<$t>, Polynomial<$t>) {
+                let zero = Complex::new(0.0, 0.0);
+                let lead = divisor.coeffs[0];
+                assert!(lead != zero, "Division by the zero polynomial");
+                let (n, m) = (self.degree(), divisor.degree());
+                if n < m {
+                    return (Polynomial { coeffs: vec![zero] }, self.clone());
+                }
+                let mut rem = self.coeffs.clone();
+                let mut quot = Vec::with_capacity(n - m + 1);
+                for k in 0..=(n - m) {
+                    let c = rem[k] / lead;
+                    for (r, &d) in rem[k..].iter_mut().zip(&divisor.coeffs) {
+                        *r -= c * d;
+                    }
+                    quot.push(c);
+                }
+                (Polynomial { coeffs: quot }, Polynomial::new(rem.split_off(n - m + 1)))
+            }
+
+            /// The roots of the polynomial
+            ///
+            /// Roots are found one at a time with the Jenkins–Traub CPOLY
+            /// algorithm, deflating the polynomial after each one.  They are
+            /// returned roughly in order of increasing modulus.  Returns
+            /// `None` if any coefficient is not finite, or in the rare case
+            /// the iteration fails to converge.
+            ///
+            /// # Example
+            /// ```
+            /// use imaginary::{Complex, poly::Polynomial};
+            /// // z² - 2 z + 2 = 0
+            /// let p = Polynomial::<f64>::new(vec![1.0.into(), (-2.0).into(), 2.0.into()]);
+            /// for root in p.roots().unwrap() {
+            ///     assert!(p.eval(root).abs() < 1e-15);
+            /// }
+            /// ```
+            pub fn roots(&self) -> Option<Vec<Complex<$t>>> {
+                if !self.coeffs.iter().all(|c| c.r.is_finite() && c.i.is_finite()) {
+                    return None;
+                }
+                let zero = Complex::new(0.0, 0.0);
+                let mut roots = Vec::with_capacity(self.degree());
+                let mut p = self.coeffs.clone();
+                // remove the roots at the origin
+                while p.len() > 1 && p[p.len() - 1] == zero {
+                    roots.push(zero);
+                    p.pop();
+                }
+                let moduli: Vec<$t> = p.iter().map(|c| c.abs()).collect();
+                let factor = Cpoly::<$t>::scale(&moduli);
+                if factor != 1.0 {
+                    for c in &mut p {
+                        *c *= factor;
+                    }
+                }
+                let mut cpoly = Cpoly::<$t>::new(p);
+                while cpoly.p.len() > 2 {
+                    roots.push(cpoly.next_root()?);
+                    let n = cpoly.p.len() - 1;
+                    cpoly.p.clear();
+                    cpoly.p.extend_from_slice(&cpoly.qp[..n]);
+                }
+                if cpoly.p.len() == 2 {
+                    roots.push(-Cpoly::<$t>::cdivid(cpoly.p[1], cpoly.p[0]));
+                }
+                Some(roots)
+            }
+
+            /// Forward deflation, divides out `(z - root)` starting from the
+            /// leading coefficient
+            ///
+            /// Forward deflation is stable when `root` is among the smallest
+            /// roots in modulus.  The remainder is `p(root)`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the polynomial is a constant
+            pub fn deflate_forward(&self, root: Complex<$t>) -> Deflation<$t> {
+                let (quotient, remainder, errors) = self.forward_quotient(root);
+                Deflation {
+                    quotient: Polynomial { coeffs: quotient },
+                    remainder,
+                    error: errors.into_iter().fold(0.0, $t::max)
+                }
+            }
+
+            /// Backward deflation, divides out `(z - root)` starting from the
+            /// constant term
+            ///
+            /// Backward deflation is stable when `root` is among the largest
+            /// roots in modulus.  The remainder is the mismatch in the
+            /// leading coefficient.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the polynomial is a constant or `root` is zero
+            pub fn deflate_backward(&self, root: Complex<$t>) -> Deflation<$t> {
+                let (quotient, remainder, errors) = self.backward_quotient(root);
+                Deflation {
+                    quotient: Polynomial { coeffs: quotient },
+                    remainder,
+                    error: errors.into_iter().fold(0.0, $t::max)
+                }
+            }
+
+            /// Composite deflation, divides out `(z - root)` using forward
+            /// deflation for the leading coefficients and backward deflation
+            /// for the rest
+            ///
+            /// The two are joined at the term `c zᵏ` with the largest
+            /// `|c rootᵏ|`, the term that dominates `p(root)`, as suggested by
+            /// Peters and Wilkinson.  This keeps the deflation stable for a
+            /// root of any modulus, not just the smallest or largest.  The
+            /// remainder is `p(root)`.
+            ///
+            /// # Panics
+            ///
+            /// Panics if the polynomial is a constant
+            ///
+            /// # Example
+            /// ```
+            /// use imaginary::{Complex, poly::Polynomial};
+            /// let roots = [Complex::new(1.0, 2.0), Complex::new(-3.0, 0.0)];
+            /// let p = Polynomial::<f64>::from_roots(&roots);
+            /// let deflated = p.deflate(roots[0]);
+            /// assert_eq!(deflated.quotient, Polynomial::<f64>::from_roots(&roots[1..]));
+            /// assert_eq!(deflated.remainder, Complex::new(0.0, 0.0));
+            /// ```
+            pub fn deflate(&self, root: Complex<$t>) -> Deflation<$t> {
+                let n = self.degree();
+                let (mut quotient, remainder, mut errors) = self.forward_quotient(root);
+                let r = root.abs();
+                let mut split = 0;
+                let mut largest = 0.0;
+                let mut power = 1.0;
+                for (k, c) in self.coeffs.iter().enumerate().rev() {
+                    let term = c.abs() * power;
+                    if term >= largest {
+                        largest = term;
+                        split = k;
+                    }
+                    power *= r;
+                }
+                if split < n && root != Complex::new(0.0, 0.0) {
+                    let (backward, _, backward_errors) = self.backward_quotient(root);
+                    quotient[split..].copy_from_slice(&backward[split..]);
+                    errors[split..].copy_from_slice(&backward_errors[split..]);
+                }
+                Deflation {
+                    quotient: Polynomial { coeffs: quotient },
+                    remainder,
+                    error: errors.into_iter().fold(0.0, $t::max)
+                }
+            }
+
+            /// Greatest common divisor of two polynomials
+            ///
+            /// The GCD is found with the Euclidean algorithm, keeping each
+            /// remainder monic.  A remainder is treated as zero, ending the
+            /// algorithm, once its coefficients are all within `tol` of zero
+            /// relative to the largest coefficient of the dividend.  Leading
+            /// coefficients of a remainder within the same tolerance are
+            /// dropped, lowering its degree.  The returned GCD is monic, or
+            /// zero if both polynomials are zero.
+            ///
+            /// # Example
+            /// ```
+            /// use imaginary::{Complex, poly::Polynomial};
+            /// let (a, b, c) = (Complex::new(1.0, 1.0), Complex::new(2.0, 0.0), Complex::new(0.0, -3.0));
+            /// let p = Polynomial::<f64>::from_roots(&[a, b, b]);
+            /// let q = Polynomial::<f64>::from_roots(&[b, c]);
+            /// let gcd = p.gcd(&q, 1e-12);
+            /// assert_eq!(gcd.degree(), 1);
+            /// assert!((gcd.coeffs()[1] + b).abs() < 1e-12);
+            /// ```
+            pub fn gcd(&self, other: &Polynomial<$t>, tol: $t) -> Polynomial<$t> {
+                let (mut a, mut b) = if self.degree() >= other.degree() {
+                    (self.monic(), other.monic())
+                } else {
+                    (other.monic(), self.monic())
+                };
+                loop {
+                    let zero_tol = tol * a.max_coeff();
+                    b = b.trim_leading(zero_tol).monic();
+                    if b.max_coeff() <= zero_tol {
+                        return a;
+                    }
+                    let (_, r) = a.div_rem(&b);
+                    a = b;
+                    b = r;
+                }
+            }
+
+            /// Square-free factorization, returns factors paired with their
+            /// multiplicity
+            ///
+            /// The polynomial is written as `c f₁ f₂² f₃³ ⋯` where each `fₖ`
+            /// is monic with simple roots and no root shared with another
+            /// factor.  Factors that are constants are left out, so the
+            /// returned multiplicities increase but may skip values.  The
+            /// tolerance `tol` is passed on to [`gcd`](Self::gcd), which
+            /// decides when nearby roots are treated as a repeated root.
+            /// Constants, including the zero polynomial, have no factors.
+            ///
+            /// # Example
+            /// ```
+            /// use imaginary::{Complex, poly::Polynomial};
+            /// let (a, b) = (Complex::new(1.0, 1.0), Complex::new(-2.0, 0.0));
+            /// let p = Polynomial::<f64>::from_roots(&[a, b, a, a]);
+            /// let factors = p.square_free_factorization(1e-10);
+            /// assert_eq!(factors.len(), 2);
+            /// assert_eq!((factors[0].0.degree(), factors[0].1), (1, 1));
+            /// assert_eq!((factors[1].0.degree(), factors[1].1), (1, 3));
+            /// assert!((factors[1].0.roots().unwrap()[0] - a).abs() < 1e-8);
+            /// ```
+            pub fn square_free_factorization(&self, tol: $t) -> Vec<(Polynomial<$t>, usize)> {
+                let mut factors = Vec::new();
+                if self.degree() == 0 {
+                    // the gcd of zero and its derivative is zero, which
+                    // cannot divide
+                    return factors;
+                }
+                let mut g = self.gcd(&self.derivative(), tol);
+                let mut w = self.div_rem(&g).0.monic();
+                let mut multiplicity = 1;
+                while w.degree() > 0 {
+                    let y = w.gcd(&g, tol);
+                    let z = w.div_rem(&y).0.monic();
+                    if z.degree() > 0 {
+                        factors.push((z, multiplicity));
+                    }
+                    multiplicity += 1;
+                    g = g.div_rem(&y).0;
+                    w = y;
+                }
+                factors
+            }
+
+            /// Partial fraction expansion of `self / den`
+            ///
+            /// The poles are the roots of `den`, grouped into repeated poles
+            /// by [`square_free_factorization`](Self::square_free_factorization)
+            /// with the tolerance `tol`.  Returns `None` if the roots of `den`
+            /// cannot be found.
+            ///
+            /// # Panics
+            ///
+            /// Panics if `den` is the zero polynomial
+            ///
+            /// # Example
+            /// ```
+            /// use imaginary::{Complex, poly::Polynomial};
+            /// // 1 / (z² - 1) = 0.5 / (z - 1) - 0.5 / (z + 1)
+            /// let num = Polynomial::<f64>::new(vec![1.0.into()]);
+            /// let den = Polynomial::<f64>::new(vec![1.0.into(), 0.0.into(), (-1.0).into()]);
+            /// let pf = num.partial_fractions(&den, 1e-10).unwrap();
+            /// for term in pf.terms {
+            ///     assert_eq!(term.order, 1);
+            ///     assert!((term.residue - 0.5 * term.pole).abs() < 1e-15);
+            /// }
+            /// ```
+            pub fn partial_fractions(&self, den: &Polynomial<$t>, tol: $t)
+                    -> Option<PartialFractions<$t>> {
+                let (direct, num) = self.div_rem(den);
+                let mut poles = Vec::with_capacity(den.degree());
+                for (factor, order) in den.square_free_factorization(tol) {
+                    for pole in factor.roots()? {
+                        poles.push((pole, order));
+                    }
+                }
+                let terms = num.residues(den.coeffs[0], &poles);
+                Some(PartialFractions { terms, direct })
+            }
+
+            /// The first `count` Taylor coefficients about `z`, found in a
+            /// single pass of synthetic division
+            fn taylor(&self, z: Complex<$t>, count: usize) -> Vec<Complex<$t>> {
+                let zero = Complex::new(0.0, 0.0);
+                let mut taylor = vec![zero; count];
+                if count == 0 {
+                    return taylor;
+                }
+                for (j, &c) in self.coeffs.iter().enumerate() {
+                    for i in (1..count.min(j + 1)).rev() {
+                        taylor[i] = taylor[i] * z + taylor[i - 1];
+                    }
+                    taylor[0] = taylor[0] * z + c;
+                }
+                taylor
+            }
+
+            /// Partial fraction terms of `self / (lead ∏ (z - pole)ᵒʳᵈᵉʳ)`
+            fn residues(&self, lead: Complex<$t>, poles: &[(Complex<$t>, usize)])
+                    -> Vec<PartialFraction<$t>> {
+                let mut terms = Vec::new();
+                for (i, &(pole, order)) in poles.iter().enumerate() {
+                    // Taylor series about the pole of the numerator and of the
+                    // denominator with this pole removed
+                    let num = self.taylor(pole, order);
+                    let mut den = vec![Complex::new(0.0, 0.0); order];
+                    den[0] = lead;
+                    for (j, &(other, other_order)) in poles.iter().enumerate() {
+                        if j == i {
+                            continue;
+                        }
+                        let a = pole - other;
+                        for _ in 0..other_order {
+                            for k in (1..order).rev() {
+                                den[k] = a * den[k] + den[k - 1];
+                            }
+                            den[0] *= a;
+                        }
+                    }
+                    // series division, g[k] is the residue of the term of
+                    // order `order - k`
+                    let mut g: Vec<Complex<$t>> = Vec::with_capacity(order);
+                    for k in 0..order {
+                        let mut sum = num[k];
+                        for j in 1..=k {
+                            sum -= den[j] * g[k - j];
+                        }
+                        g.push(sum / den[0]);
+                    }
+                    for k in 1..=order {
+                        terms.push(PartialFraction { pole, order: k, residue: g[order - k] });
+                    }
+                }
+                terms
+            }
+
+            /// Largest coefficient modulus
+            fn max_coeff(&self) -> $t {
+                self.coeffs.iter().fold(0.0, |max, c| max.max(c.abs()))
+            }
+
+            /// Drops leading coefficients with a modulus of at most `tol`
+            fn trim_leading(&self, tol: $t) -> Polynomial<$t> {
+                let lead = self.coeffs.iter().position(|c| c.abs() > tol);
+                match lead {
+                    Some(lead) => Polynomial { coeffs: self.coeffs[lead..].to_vec() },
+                    None => Polynomial { coeffs: vec![Complex::new(0.0, 0.0)] }
+                }
+            }
+
+            /// Quotient coefficients of forward deflation, the remainder and
+            /// the running error bound of each coefficient
+            fn forward_quotient(&self, root: Complex<$t>)
+                    -> (Vec<Complex<$t>>, Complex<$t>, Vec<$t>) {
+                use std::$t::consts::SQRT_2;
+                assert!(self.degree() > 0, "Cannot deflate a constant polynomial");
+                let u = $t::EPSILON;
+                let r = root.abs();
+                let n = self.degree();
+                let mut quotient = Vec::with_capacity(n);
+                let mut errors = Vec::with_capacity(n);
+                let mut q = self.coeffs[0];
+                let mut e = 0.0;
+                quotient.push(q);
+                errors.push(e);
+                for &c in &self.coeffs[1..] {
+                    let rq = root * q;
+                    q = c + rq;
+                    e = r * e + u * (q.abs() + 2.0 * SQRT_2 * rq.abs());
+                    quotient.push(q);
+                    errors.push(e);
+                }
+                let remainder = quotient.pop().unwrap();
+                errors.pop();
+                (quotient, remainder, errors)
+            }
+
+            /// Quotient coefficients of backward deflation, the remainder and
+            /// the running error bound of each coefficient
+            fn backward_quotient(&self, root: Complex<$t>)
+                    -> (Vec<Complex<$t>>, Complex<$t>, Vec<$t>) {
+                use std::$t::consts::SQRT_2;
+                assert!(self.degree() > 0, "Cannot deflate a constant polynomial");
+                assert!(root != Complex::new(0.0, 0.0),
+                    "Cannot backward deflate a root at the origin"
+                );
+                let u = $t::EPSILON;
+                let r = root.abs();
+                let n = self.degree();
+                let mut quotient = vec![Complex::new(0.0, 0.0); n];
+                let mut errors = vec![0.0; n];
+                let mut q = Complex::new(0.0, 0.0);
+                let mut e = 0.0;
+                for k in (0..n).rev() {
+                    let diff = q - self.coeffs[k + 1];
+                    q = diff / root;
+                    e = e / r + u * (diff.abs() / r + 2.0 * SQRT_2 * q.abs());
+                    quotient[k] = q;
+                    errors[k] = e;
+                }
+                let remainder = self.coeffs[0] - quotient[0];
+                (quotient, remainder, errors)
+            }
+        }
+
+        impl Cpoly<$t> {
+            const ETA: $t = $t::EPSILON;
+            /// error bound on complex addition
+            const ARE: $t = Self::ETA;
+            /// error bound on complex multiplication
+            const MRE: $t = 2.0 * std::$t::consts::SQRT_2 * Self::ETA;
+
+            fn new(p: Vec<Complex<$t>>) -> Cpoly<$t> {
+                let zero = Complex::new(0.0, 0.0);
+                Cpoly {
+                    h: Vec::with_capacity(p.len()),
+                    qp: Vec::with_capacity(p.len()),
+                    qh: Vec::with_capacity(p.len()),
+                    p, s: zero, pv: zero, t: zero
+                }
+            }
+
+            /// Power of 2 that keeps the coefficients away from overflow and
+            /// underflow, given their moduli
+            ///
+            /// Coefficients of widely differing moduli are scaled by the
+            /// geometric mean of the extreme moduli, when it keeps them in
+            /// range.
+            fn scale(moduli: &[$t]) -> $t {
+                let hi = $t::MAX.sqrt();
+                let lo = $t::MIN_POSITIVE / Self::ETA;
+                let mut max: $t = 0.0;
+                let mut min = $t::MAX;
+                for &x in moduli {
+                    max = max.max(x);
+                    if x != 0.0 {
+                        min = min.min(x);
+                    }
+                }
+                if max == 0.0 {
+                    return 1.0;
+                }
+                let wide = max / min > 1.0 / Self::ETA;
+                if min >= lo && max <= hi && !wide {
+                    return 1.0;
+                }
+                let mean = 1.0 / (max.sqrt() * min.sqrt());
+                let x = lo / min;
+                let sc = if max * mean <= hi && min * mean >= lo {
+                    mean
+                } else if x <= 1.0 {
+                    1.0
+                } else if $t::MAX / x > max {
+                    1.0
+                } else {
+                    x
+                };
+                (2.0 as $t).powi(crate::math::$m::floor(crate::math::$m::log2(sc) + 0.5) as i32)
+            }
+
+            /// Complex quotient `a / b` by Smith's method, which divides by
+            /// the larger component of `b` rather than forming `|b|²`, so
+            /// that it neither overflows nor underflows needlessly
+            fn cdivid(a: Complex<$t>, b: Complex<$t>) -> Complex<$t> {
+                if b.r == 0.0 && b.i == 0.0 {
+                    return Complex::new($t::MAX, $t::MAX);
+                }
+                if b.r.abs() < b.i.abs() {
+                    let r = b.r / b.i;
+                    let d = b.i + r * b.r;
+                    Complex::new((a.r * r + a.i) / d, (a.i * r - a.r) / d)
+                } else {
+                    let r = b.i / b.r;
+                    let d = b.r + r * b.i;
+                    Complex::new((a.r + a.i * r) / d, (a.i - a.r * r) / d)
+                }
+            }
+
+            /// Lower bound on the moduli of the roots, from the Cauchy
+            /// polynomial of the coefficient moduli
+            fn cauchy_bound(moduli: &[$t]) -> $t {
+                let n = moduli.len() - 1;
+                let mut pt = moduli.to_vec();
+                pt[n] = -pt[n];
+                // upper estimate of the bound
+                let mut x = crate::math::$m::exp((crate::math::$m::ln(-pt[n]) - crate::math::$m::ln(pt[0])) / n as $t);
+                if pt[n - 1] != 0.0 {
+                    // Newton step at the origin is better, use it
+                    x = x.min(-pt[n] / pt[n - 1]);
+                }
+                // chop the interval (0, x) until f <= 0, which happens
+                // once x underflows if not before
+                for _ in 0..1000 {
+                    let xm = x * 0.1;
+                    let f = pt.iter().fold(0.0, |f, &c| f * xm + c);
+                    if f <= 0.0 {
+                        break;
+                    }
+                    x = xm;
+                }
+                // Newton iteration until x converges to two decimal places
+                let mut dx = x;
+                let mut q = vec![0.0; n + 1];
+                for _ in 0..100 {
+                    if (dx / x).abs() <= 0.005 {
+                        break;
+                    }
+                    q[0] = pt[0];
+                    for i in 1..=n {
+                        q[i] = q[i - 1] * x + pt[i];
+                    }
+                    let f = q[n];
+                    let df = q[..n].iter().fold(0.0, |df, &c| df * x + c);
+                    dx = f / df;
+                    x -= dx;
+                }
+                x
+            }
+
+            /// Evaluates `coeffs` at `s`, storing the partial sums in `q`
+            fn eval(coeffs: &[Complex<$t>], s: Complex<$t>,
+                    q: &mut Vec<Complex<$t>>) -> Complex<$t> {
+                q.clear();
+                let mut sum = coeffs[0];
+                q.push(sum);
+                for &c in &coeffs[1..] {
+                    sum = sum * s + c;
+                    q.push(sum);
+                }
+                sum
+            }
+
+            /// Bound on the rounding error in evaluating `p` at `s`, where
+            /// `ms = |s|` and `mp = |p(s)|`
+            fn error_bound(&self, ms: $t, mp: $t) -> $t {
+                let mut e = self.qp[0].abs() * Self::MRE / (Self::ARE + Self::MRE);
+                for q in &self.qp {
+                    e = e * ms + q.abs();
+                }
+                e * (Self::ARE + Self::MRE) - mp * Self::MRE
+            }
+
+            /// Finds one root of `p` with the three stage iteration,
+            /// leaving the deflated polynomial in `qp`
+            fn next_root(&mut self) -> Option<Complex<$t>> {
+                use std::$t::consts::FRAC_1_SQRT_2;
+                // shifts are rotated by 94° from the previous shift
+                let rotation = Complex::<$t>::cis((94.0 as $t).to_radians());
+                let moduli: Vec<$t> = self.p.iter().map(|c| c.abs()).collect();
+                let bound = Self::cauchy_bound(&moduli);
+                let mut dir = Complex::new(FRAC_1_SQRT_2, -FRAC_1_SQRT_2);
+                // two major passes with different sequences of shifts
+                for _ in 0..2 {
+                    self.no_shift(5);
+                    for cnt in 1..=9 {
+                        dir *= rotation;
+                        self.s = bound * dir;
+                        if let Some(root) = self.fixed_shift(10 * cnt) {
+                            return Some(root);
+                        }
+                    }
+                }
+                None
+            }
+
+            /// Stage one, computes the H polynomials without a shift
+            fn no_shift(&mut self, steps: usize) {
+                let n = self.p.len() - 1;
+                let scale = 1.0 / n as $t;
+                self.h.clear();
+                for (i, &c) in self.p[..n].iter().enumerate() {
+                    self.h.push(c * ((n - i) as $t * scale));
+                }
+                for _ in 0..steps {
+                    let h_0 = self.h[n - 1];
+                    let p_0 = self.p[n];
+                    if h_0.abs() > Self::ETA * 10.0 * p_0.abs() {
+                        let t = -Self::cdivid(p_0, h_0);
+                        for j in (1..n).rev() {
+                            self.h[j] = t * self.h[j - 1] + self.p[j];
+                        }
+                        self.h[0] = self.p[0];
+                    } else {
+                        // constant term is essentially zero, shift h
+                        for j in (1..n).rev() {
+                            self.h[j] = self.h[j - 1];
+                        }
+                        self.h[0] = Complex::new(0.0, 0.0);
+                    }
+                }
+            }
+
+            /// Evaluates `h` at `s` and computes `t = -p(s)/h(s)`, returns
+            /// `true` if `h(s)` is essentially zero
+            fn calc_t(&mut self) -> bool {
+                let n = self.h.len();
+                let hv = Self::eval(&self.h, self.s, &mut self.qh);
+                let h_zero = hv.abs() <= Self::ARE * 10.0 * self.h[n - 1].abs();
+                self.t = if h_zero { Complex::new(0.0, 0.0) } else { -Self::cdivid(self.pv, hv) };
+                h_zero
+            }
+
+            /// Computes the next shifted H polynomial
+            fn next_h(&mut self, h_zero: bool) {
+                let n = self.h.len();
+                if h_zero {
+                    for j in 1..n {
+                        self.h[j] = self.qh[j - 1];
+                    }
+                    self.h[0] = Complex::new(0.0, 0.0);
+                } else {
+                    for j in 1..n {
+                        self.h[j] = self.t * self.qh[j - 1] + self.qp[j];
+                    }
+                    self.h[0] = self.qp[0];
+                }
+            }
+
+            /// Stage two, computes up to `steps` H polynomials with the
+            /// current fixed shift and starts stage three once the iterates
+            /// appear to converge
+            fn fixed_shift(&mut self, steps: usize) -> Option<Complex<$t>> {
+                self.pv = Self::eval(&self.p, self.s, &mut self.qp);
+                let mut test = true;
+                let mut passed = false;
+                let mut h_zero = self.calc_t();
+                let mut z = self.s;
+                for j in 1..=steps {
+                    let t_old = self.t;
+                    self.next_h(h_zero);
+                    h_zero = self.calc_t();
+                    z = self.s + self.t;
+                    // test for convergence unless stage three has failed once
+                    // or this is the last H polynomial
+                    if h_zero || !test || j == steps {
+                        continue;
+                    }
+                    if (self.t - t_old).abs() >= 0.5 * z.abs() {
+                        passed = false;
+                        continue;
+                    }
+                    if !passed {
+                        passed = true;
+                        continue;
+                    }
+                    // the weak convergence test has passed twice, start stage
+                    // three after saving the current H polynomial and shift
+                    let h_saved = self.h.clone();
+                    let s_saved = self.s;
+                    if let Some(root) = self.variable_shift(10, z) {
+                        return Some(root);
+                    }
+                    // stage three failed, turn off testing and restore
+                    test = false;
+                    self.h = h_saved;
+                    self.s = s_saved;
+                    self.pv = Self::eval(&self.p, self.s, &mut self.qp);
+                    h_zero = self.calc_t();
+                }
+                self.variable_shift(10, z)
+            }
+
+            /// Stage three, variable shift iteration starting from `z`
+            fn variable_shift(&mut self, steps: usize, z: Complex<$t>) -> Option<Complex<$t>> {
+                let mut stalled = false;
+                let mut rel_step: $t = 1.0;
+                let mut mp_old: $t = 0.0;
+                self.s = z;
+                for i in 1..=steps {
+                    self.pv = Self::eval(&self.p, self.s, &mut self.qp);
+                    let mp = self.pv.abs();
+                    let ms = self.s.abs();
+                    // p(s) is smaller than the bound on its rounding error
+                    if mp <= 20.0 * self.error_bound(ms, mp) {
+                        return Some(self.s);
+                    }
+                    if i == 1 {
+                        mp_old = mp;
+                    } else if !(stalled || mp < mp_old || rel_step >= 0.05) {
+                        // iteration has stalled, probably a cluster of roots,
+                        // take 5 fixed shift steps into the cluster to force
+                        // one root to dominate
+                        stalled = true;
+                        let r = rel_step.max(Self::ETA).sqrt();
+                        self.s *= Complex::new(1.0 + r, r);
+                        self.pv = Self::eval(&self.p, self.s, &mut self.qp);
+                        for _ in 0..5 {
+                            let h_zero = self.calc_t();
+                            self.next_h(h_zero);
+                        }
+                        mp_old = $t::INFINITY;
+                    } else if mp * 0.1 > mp_old {
+                        // p(s) increased significantly
+                        return None;
+                    } else {
+                        mp_old = mp;
+                    }
+                    let h_zero = self.calc_t();
+                    self.next_h(h_zero);
+                    if !self.calc_t() {
+                        rel_step = self.t.abs() / self.s.abs();
+                        self.s += self.t;
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+impl_polynomial!(c32 for f32);
+impl_polynomial!(c64 for f64);
+
+macro_rules! impl_polynomial_ops {
+    ($t: ident) => {
+        /// Product of two polynomials
+        ///
+        /// Low degree products use [`mul_schoolbook`](Polynomial::mul_schoolbook)
+        /// and high degree products use [`mul_fft`](Polynomial::mul_fft),
+        /// whichever is expected to be faster.
+        ///
+        /// # Example
+        /// ```
+        /// use imaginary::{Complex, poly::Polynomial};
+        /// let p = Polynomial::<f64>::new(vec![1.0.into(), 1.0.into()]);
+        /// let q = Polynomial::<f64>::new(vec![1.0.into(), (-1.0).into()]);
+        /// assert_eq!((&p * &q).coeffs(), &[1.0.into(), 0.0.into(), (-1.0).into()]);
+        /// ```
+        impl Mul for &Polynomial<$t> {
+            type Output = Polynomial<$t>;
+            fn mul(self, rhs: &Polynomial<$t>) -> Polynomial<$t> {
+                if prefer_fft(self.coeffs.len(), rhs.coeffs.len()) {
+                    self.mul_fft(rhs)
+                } else {
+                    self.mul_schoolbook(rhs)
+                }
+            }
+        }
+
+        impl Mul for Polynomial<$t> {
+            type Output = Polynomial<$t>;
+            fn mul(self, rhs: Polynomial<$t>) -> Polynomial<$t> {
+                &self * &rhs
+            }
+        }
+    }
+}
+impl_polynomial_ops!(f32);
+impl_polynomial_ops!(f64);
+
+/// An error which can be returned when parsing a [`Polynomial`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParsePolynomialError {
+    pos: usize
+}
+
+impl std::fmt::Display for ParsePolynomialError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "invalid polynomial syntax at byte {}", self.pos)
+    }
+}
+
+impl std::error::Error for ParsePolynomialError {}
+
+/// The highest power accepted by [`Polynomial::from_str`], so that a short
+/// string cannot ask for the coefficients of an enormous degree
+const MAX_PARSED_DEGREE: usize = 1 << 20;
+
+impl Scanner<'_> {
+    fn error(&self) -> ParsePolynomialError {
+        ParsePolynomialError { pos: self.pos }
+    }
+
+    /// One term of a polynomial, returns the power and coefficient
+    fn term<T>(&mut self, first: bool) -> Result<(usize, Complex<T>), ParsePolynomialError>
+    where T: Copy + Default + FromStr + Neg<Output=T> {
+        self.skip_spaces();
+        let neg = match self.sign() {
+            Some(neg) => neg,
+            None if first => false,
+            None => return Err(self.error())
+        };
+        self.skip_spaces();
+        let coeff = if self.eat(&['(']) {
+            let coeff = self.complex::<T>().ok_or(self.error())?;
+            self.skip_spaces();
+            if !self.eat(&[')']) {
+                return Err(self.error());
+            }
+            Some(coeff)
+        } else {
+            let start = self.pos;
+            match self.component::<T>() {
+                Some((x, false)) => Some(Complex::new(x, T::default())),
+                Some((y, true)) => Some(Complex::new(T::default(), y)),
+                None => {
+                    self.pos = start;
+                    None
+                }
+            }
+        };
+        self.skip_spaces();
+        let times = coeff.is_some() && self.eat(&['·', '*']);
+        self.skip_spaces();
+        let power = if self.eat(&['x']) {
+            self.skip_spaces();
+            if self.eat(&['^']) {
+                self.skip_spaces();
+                let start = self.pos;
+                self.digits();
+                match self.s[start..self.pos].parse() {
+                    Ok(power) if power <= MAX_PARSED_DEGREE => power,
+                    _ => return Err(ParsePolynomialError { pos: start })
+                }
+            } else {
+                1
+            }
+        } else if times || coeff.is_none() {
+            return Err(self.error());
+        } else {
+            0
+        };
+        let one = "1".parse().map_err(|_| self.error())?;
+        let coeff = coeff.unwrap_or(Complex::new(one, T::default()));
+        Ok((power, if neg { -coeff } else { coeff }))
+    }
+}
+
+/// Whether multiplying polynomials with `n` and `m` coefficients is expected
+/// to be faster with the FFT than with direct convolution
+///
+/// Direct convolution takes `n m` complex multiplications, while the three
+/// transforms take time proportional to `L log₂ L` for the padded length `L`.
+/// The ratio between the two was measured to be about 5.
+fn prefer_fft(n: usize, m: usize) -> bool {
+    let len = (n + m - 1).next_power_of_two();
+    n * m > 5 * len * len.trailing_zeros() as usize
+}
+
+macro_rules! impl_polynomial_fmt {
+    ($t: ident) => {
+        impl std::fmt::Display for Polynomial<$t> {
+            /// Writes the polynomial in terms of `x`, skipping zero terms
+            ///
+            /// # Examples
+            /// ```
+            /// use imaginary::{Complex, poly::Polynomial};
+            /// let p = Polynomial::<f64>::new(vec![
+            ///     Complex::new(1.0, 2.0), 0.0.into(), (-4.0).into(), Complex::new(0.0, 2.0)
+            /// ]);
+            /// assert_eq!(p.to_string(), "(1+2i)·x^3 − 4·x + 2i");
+            /// ```
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let n = self.degree();
+                let mut first = true;
+                for (k, &c) in self.coeffs.iter().enumerate() {
+                    let power = n - k;
+                    if c == Complex::new(0.0, 0.0) && !(first && power == 0) {
+                        continue;
+                    }
+                    let (neg, value) = if c.i == 0.0 {
+                        (c.r.is_sign_negative(), c.r.abs())
+                    } else if c.r == 0.0 {
+                        (c.i.is_sign_negative(), c.i.abs())
+                    } else {
+                        (false, 0.0)
+                    };
+                    match (first, neg) {
+                        (true, true) => write!(f, "−")?,
+                        (true, false) => (),
+                        (false, true) => write!(f, " − ")?,
+                        (false, false) => write!(f, " + ")?
+                    }
+                    first = false;
+                    let mut coeff = true;
+                    if c.i == 0.0 {
+                        if value != 1.0 || power == 0 {
+                            write!(f, "{value}")?;
+                        } else {
+                            coeff = false;
+                        }
+                    } else if c.r == 0.0 {
+                        if value != 1.0 {
+                            write!(f, "{value}")?;
+                        }
+                        write!(f, "i")?;
+                    } else {
+                        let sign = if c.i.is_sign_negative() { '−' } else { '+' };
+                        let real = c.r.to_string().replace('-', "−");
+                        write!(f, "({real}{sign}{}i)", c.i.abs())?;
+                    }
+                    if power > 0 {
+                        if coeff {
+                            write!(f, "·")?;
+                        }
+                        write!(f, "x")?;
+                        if power > 1 {
+                            write!(f, "^{power}")?;
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        impl FromStr for Polynomial<$t> {
+            type Err = ParsePolynomialError;
+            /// Parses a polynomial in the format written by `Display`
+            ///
+            /// Terms may be in any order and powers may repeat.  A complex
+            /// coefficient is written in parentheses, `(a+bi)`.  ASCII `-`
+            /// and `*` are accepted in place of `−` and `·`.  Powers above
+            /// 2²⁰ are rejected.
+            ///
+            /// # Examples
+            /// ```
+            /// use imaginary::{Complex, poly::Polynomial};
+            /// let p: Polynomial<f64> = "(1+2i)·x^3 − 4·x + 2i".parse().unwrap();
+            /// assert_eq!(p.coeffs(), &[
+            ///     Complex::new(1.0, 2.0), 0.0.into(), (-4.0).into(), Complex::new(0.0, 2.0)
+            /// ]);
+            /// assert_eq!(p, "2i - 4*x + (1+2i)*x^3".parse().unwrap());
+            /// ```
+            fn from_str(s: &str) -> Result<Polynomial<$t>, ParsePolynomialError> {
+                let mut scanner = Scanner { s, pos: 0 };
+                let mut terms = vec![scanner.term::<$t>(true)?];
+                scanner.skip_spaces();
+                while scanner.pos < s.len() {
+                    terms.push(scanner.term::<$t>(false)?);
+                    scanner.skip_spaces();
+                }
+                let degree = terms.iter().map(|&(power, _)| power).max().unwrap_or(0);
+                let mut coeffs = vec![Complex::new(0.0, 0.0); degree + 1];
+                for (power, c) in terms {
+                    coeffs[degree - power] += c;
+                }
+                Ok(Polynomial::new(coeffs))
+            }
+        }
+    }
+}
+impl_polynomial_fmt!(f32);
+impl_polynomial_fmt!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_roots(p: &Polynomial<f64>, expected: &[Complex<f64>]) {
+        let mut roots = p.roots().unwrap();
+        assert_eq!(roots.len(), expected.len());
+        for &root in expected {
+            let (k, nearest) = roots.iter().enumerate()
+                .map(|(k, &z)| (k, (z - root).abs()))
+                .fold((0, f64::INFINITY), |a, b| if b.1 < a.1 { b } else { a });
+            println!("root = {root}, error = {nearest}");
+            assert!(nearest <= 1e-10 * root.abs().max(1.0));
+            roots.swap_remove(k);
+        }
+    }
+
+    #[test]
+    fn new_strips_leading_zeros() {
+        let zero = Complex::new(0.0, 0.0);
+        let one = Complex::new(1.0, 0.0);
+        let p = Polynomial::new(vec![zero, zero, one, zero]);
+        assert_eq!(p.coeffs(), &[one, zero]);
+        assert_eq!(p.degree(), 1);
+        assert_eq!(Polynomial::<f64>::new(vec![]).coeffs(), &[zero]);
+    }
+
+    #[test]
+    fn roots_real() {
+        let expected: Vec<Complex<f64>> = (1..=6).map(|k| (k as f64).into()).collect();
+        check_roots(&Polynomial::<f64>::from_roots(&expected), &expected);
+    }
+
+    #[test]
+    fn roots_complex() {
+        let expected = [
+            Complex::new(1.0, 2.0),
+            Complex::new(-3.0, 0.5),
+            Complex::new(0.0, -1.0),
+            Complex::new(0.0, 0.0),
+            Complex::new(10.0, -10.0),
+            Complex::new(1e-3, 1e-3)
+        ];
+        check_roots(&Polynomial::<f64>::from_roots(&expected), &expected);
+    }
+
+    #[test]
+    fn roots_unit_circle() {
+        // z^12 - 1
+        let mut coeffs = vec![Complex::new(0.0, 0.0); 13];
+        coeffs[0] = 1.0.into();
+        coeffs[12] = (-1.0).into();
+        let expected: Vec<_> = (0..12)
+            .map(|k| Complex::<f64>::cis(k as f64 * std::f64::consts::PI / 6.0))
+            .collect();
+        check_roots(&Polynomial::new(coeffs), &expected);
+    }
+
+    #[test]
+    fn roots_f32() {
+        let expected = [Complex::new(1.0_f32, 1.0), Complex::new(-2.0, 0.0)];
+        let p = Polynomial::<f32>::from_roots(&expected);
+        for root in p.roots().unwrap() {
+            assert!(p.eval(root).abs() <= 1e-5);
+        }
+    }
+
+    #[test]
+    fn deflate_exact() {
+        let roots = [Complex::new(2.0, 1.0), Complex::new(-0.5, 0.0), Complex::new(0.0, 3.0)];
+        let p = Polynomial::<f64>::from_roots(&roots);
+        let expected = Polynomial::<f64>::from_roots(&roots[1..]);
+        for deflated in [p.deflate_forward(roots[0]), p.deflate_backward(roots[0]), p.deflate(roots[0])] {
+            assert_eq!(deflated.quotient, expected);
+            assert_eq!(deflated.remainder, Complex::new(0.0, 0.0));
+            assert!(deflated.error < 1e-14);
+        }
+    }
+
+    #[test]
+    fn deflate_remainder() {
+        // z^2 + 1 = (z - 1)(z + 1) + 2
+        let p = Polynomial::<f64>::new(vec![1.0.into(), 0.0.into(), 1.0.into()]);
+        let forward = p.deflate_forward(1.0.into());
+        assert_eq!(forward.quotient.coeffs(), &[1.0.into(), 1.0.into()]);
+        assert_eq!(forward.remainder, 2.0.into());
+        // z^2 + 1 = (z - 1)(-z - 1) + 2 z^2
+        let backward = p.deflate_backward(1.0.into());
+        assert_eq!(backward.quotient.coeffs(), &[(-1.0).into(), (-1.0).into()]);
+        assert_eq!(backward.remainder, 2.0.into());
+    }
+
+    #[test]
+    fn deflate_composite_stable() {
+        // roots spread over many orders of magnitude
+        let roots: Vec<Complex<f64>> = (0..12)
+            .map(|k| Complex::<f64>::cis(k as f64) * 4.0_f64.powi(k - 6))
+            .collect();
+        let p = Polynomial::<f64>::from_roots(&roots);
+        for (k, &root) in roots.iter().enumerate() {
+            let mut rest = roots.clone();
+            rest.remove(k);
+            let expected = Polynomial::<f64>::from_roots(&rest);
+            let deflated = p.deflate(root);
+            for (&c, &e) in deflated.quotient.coeffs().iter().zip(expected.coeffs()) {
+                assert!((c - e).abs() <= 1e-12 * e.abs().max(1.0));
+            }
+        }
+    }
+
+    fn assert_close(p: &Polynomial<f64>, q: &Polynomial<f64>, tol: f64) {
+        assert_eq!(p.degree(), q.degree(), "{p:?} != {q:?}");
+        for (&a, &b) in p.coeffs().iter().zip(q.coeffs()) {
+            assert!((a - b).abs() <= tol, "{p:?} != {q:?}");
+        }
+    }
+
+    #[test]
+    fn div_rem_identity() {
+        let p = Polynomial::<f64>::new(vec![
+            Complex::new(1.0, 2.0), Complex::new(-3.0, 0.5), Complex::new(0.0, 1.0),
+            Complex::new(4.0, 0.0), Complex::new(-1.0, -1.0)
+        ]);
+        let d = Polynomial::<f64>::new(vec![Complex::new(2.0, -1.0), Complex::new(0.5, 0.0), 1.0.into()]);
+        let (q, r) = p.div_rem(&d);
+        assert_eq!(q.degree(), 2);
+        assert!(r.degree() < 2);
+        // p = q d + r at a few points
+        for z in [Complex::new(0.3, -1.2), Complex::new(2.0, 2.0), Complex::new(-1.0, 0.0)] {
+            assert!((q.eval(z) * d.eval(z) + r.eval(z) - p.eval(z)).abs() < 1e-12);
+        }
+        let (q, r) = d.div_rem(&p);
+        assert_eq!(q.coeffs(), &[Complex::new(0.0, 0.0)]);
+        assert_eq!(r, d);
+    }
+
+    #[test]
+    fn derivative() {
+        let p = Polynomial::<f64>::new(vec![Complex::new(1.0, 1.0), 2.0.into(), 3.0.into(), 4.0.into()]);
+        assert_eq!(p.derivative().coeffs(), &[Complex::new(3.0, 3.0), 4.0.into(), 3.0.into()]);
+        assert_eq!(Polynomial::<f64>::new(vec![5.0.into()]).derivative().coeffs(), &[0.0.into()]);
+    }
+
+    #[test]
+    fn gcd() {
+        let (a, b, c, d) = (
+            Complex::new(1.0, 0.0), Complex::new(0.0, 1.0),
+            Complex::new(-3.0, 0.0), Complex::new(2.0, -2.0)
+        );
+        let p = Polynomial::<f64>::from_roots(&[a, a, b, d]);
+        let q = Polynomial::<f64>::from_roots(&[a, b, c]);
+        assert_close(&p.gcd(&q, 1e-12), &Polynomial::<f64>::from_roots(&[a, b]), 1e-12);
+        assert_close(&q.gcd(&p, 1e-12), &Polynomial::<f64>::from_roots(&[a, b]), 1e-12);
+        // coprime
+        let r = Polynomial::<f64>::from_roots(&[c, Complex::new(5.0, 5.0)]);
+        assert_eq!(p.gcd(&r, 1e-12).degree(), 0);
+        // with the zero polynomial
+        let zero = Polynomial::<f64>::new(vec![]);
+        assert_close(&p.gcd(&zero, 1e-12), &p, 0.0);
+    }
+
+    #[test]
+    fn gcd_tolerance() {
+        let a = Complex::new(1.0, 0.0);
+        let p = Polynomial::<f64>::from_roots(&[a, Complex::new(2.0, 0.0)]);
+        let q = Polynomial::<f64>::from_roots(&[a + 1e-9, Complex::new(-2.0, 0.0)]);
+        assert_eq!(p.gcd(&q, 1e-12).degree(), 0);
+        assert_eq!(p.gcd(&q, 1e-6).degree(), 1);
+    }
+
+    #[test]
+    fn square_free_factorization() {
+        let (a, b, c) = (Complex::new(1.0, -1.0), Complex::new(0.0, 1.0), Complex::new(2.0, 0.0));
+        let p = Polynomial::<f64>::from_roots(&[a, b, a, c, c, a]);
+        let p = Polynomial::new(p.coeffs().iter().map(|&c| c * 3.0).collect());
+        let factors = p.square_free_factorization(1e-10);
+        let multiplicities: Vec<usize> = factors.iter().map(|f| f.1).collect();
+        assert_eq!(multiplicities, [1, 2, 3]);
+        assert_close(&factors[0].0, &Polynomial::<f64>::from_roots(&[b]), 1e-8);
+        assert_close(&factors[1].0, &Polynomial::<f64>::from_roots(&[c]), 1e-8);
+        assert_close(&factors[2].0, &Polynomial::<f64>::from_roots(&[a]), 1e-8);
+        // constants, and zero, have no factors
+        assert_eq!(Polynomial::<f64>::new(vec![]).square_free_factorization(1e-10), []);
+        assert_eq!(Polynomial::<f32>::new(vec![Complex::new(0.0, 2.0)]).square_free_factorization(1e-5), []);
+    }
+
+    #[test]
+    fn partial_fractions_simple() {
+        // (z + 3) / ((z - 1)(z + 2)) = (4/3) / (z - 1) - (1/3) / (z + 2)
+        let num = Polynomial::<f64>::new(vec![1.0.into(), 3.0.into()]);
+        let den = Polynomial::<f64>::from_roots(&[1.0.into(), (-2.0).into()]);
+        let pf = num.partial_fractions(&den, 1e-10).unwrap();
+        assert_eq!(pf.direct.coeffs(), &[0.0.into()]);
+        assert_eq!(pf.terms.len(), 2);
+        for term in &pf.terms {
+            let expected = if term.pole.r > 0.0 { 4.0 / 3.0 } else { -1.0 / 3.0 };
+            assert_eq!(term.order, 1);
+            assert!((term.residue - expected).abs() < 1e-14);
+        }
+    }
+
+    #[test]
+    fn partial_fractions_repeated() {
+        let (a, b) = (Complex::new(1.0, 0.0), Complex::new(0.0, -1.0));
+        let num = Polynomial::<f64>::new(vec![
+            Complex::new(2.0, 0.0), Complex::new(1.0, 1.0), 0.0.into(), 3.0.into(),
+            Complex::new(0.0, 5.0), 1.0.into()
+        ]);
+        let den = Polynomial::<f64>::from_roots(&[a, a, a, b, b]);
+        let den = Polynomial::new(den.coeffs().iter().map(|&c| c * 2.0).collect());
+        let pf = num.partial_fractions(&den, 1e-8).unwrap();
+        assert_eq!(pf.direct.degree(), 0);
+        assert_eq!(pf.terms.len(), 5);
+        for z in [Complex::new(0.5, 0.5), Complex::new(-2.0, 1.0), Complex::new(3.0, -4.0)] {
+            let expected = num.eval(z) / den.eval(z);
+            assert!((pf.eval(z) - expected).abs() < 1e-6 * expected.abs());
+        }
+    }
+
+    #[test]
+    fn partial_fractions_zeros_poles() {
+        let zeros = [Complex::new(1.0, 1.0), Complex::new(-1.0, 0.0), Complex::new(3.0, 0.0)];
+        let poles = [Complex::new(2.0, 0.0), Complex::new(0.0, 1.0), Complex::new(2.0, 0.0)];
+        let gain = Complex::new(0.5, -2.0);
+        let pf = PartialFractions::<f64>::from_zeros_poles(&zeros, &poles, gain, 1e-12);
+        assert_eq!(pf.terms.len(), 3);
+        assert_eq!(pf.terms.iter().map(|t| t.order).max(), Some(2));
+        let num = Polynomial::<f64>::from_roots(&zeros);
+        let den = Polynomial::<f64>::from_roots(&poles);
+        for z in [Complex::new(0.5, 0.5), Complex::new(-2.0, 1.0), Complex::new(30.0, -4.0)] {
+            let expected = gain * num.eval(z) / den.eval(z);
+            assert!((pf.eval(z) - expected).abs() < 1e-12 * expected.abs());
+        }
+    }
+
+    #[test]
+    fn display() {
+        let p = |coeffs: &[Complex<f64>]| Polynomial::new(coeffs.to_vec()).to_string();
+        let (one, i) = (Complex::new(1.0, 0.0), Complex::new(0.0, 1.0));
+        assert_eq!(p(&[]), "0");
+        assert_eq!(p(&[-one]), "−1");
+        assert_eq!(p(&[one, -one]), "x − 1");
+        assert_eq!(p(&[-one, 0.0.into(), 0.0.into()]), "−x^2");
+        assert_eq!(p(&[i, -i, 2.5 * i]), "i·x^2 − i·x + 2.5i");
+        assert_eq!(p(&[Complex::new(-1.5, -2.0), Complex::new(3.0, 0.5)]), "(−1.5−2i)·x + (3+0.5i)");
+    }
+
+    #[test]
+    fn parse_round_trip() {
+        let polys = [
+            vec![Complex::new(1.0, 2.0), 0.0.into(), (-4.0).into(), Complex::new(0.0, 2.0)],
+            vec![Complex::new(-1.5, -2.0), Complex::new(0.0, -1.0), 1.0.into()],
+            vec![(-1.0).into(), Complex::new(0.0, 1.0), Complex::new(1e-20, 3e10), 0.0.into()],
+            vec![Complex::new(0.0, 0.0)],
+        ];
+        for coeffs in polys {
+            let p = Polynomial::<f64>::new(coeffs);
+            assert_eq!(p.to_string().parse::<Polynomial<f64>>(), Ok(p));
+        }
+    }
+
+    #[test]
+    fn parse_variants() {
+        let p: Polynomial<f64> = " x^2 -3*x + (2 - 4*i)".parse().unwrap();
+        assert_eq!(p.coeffs(), &[1.0.into(), (-3.0).into(), Complex::new(2.0, -4.0)]);
+        let p: Polynomial<f64> = "1 + x + x - i·x^2".parse().unwrap();
+        assert_eq!(p.coeffs(), &[Complex::new(0.0, -1.0), 2.0.into(), 1.0.into()]);
+        let p: Polynomial<f32> = "1.5e2x".parse().unwrap();
+        assert_eq!(p.coeffs(), &[150.0.into(), 0.0.into()]);
+        for bad in ["", "x +", "2 3", "(1+2)", "(1+2i", "x^", "·x", "y"] {
+            assert!(bad.parse::<Polynomial<f64>>().is_err(), "{bad}");
+        }
+        // powers that overflow, or would allocate terabytes
+        assert_eq!("x^18446744073709551615".parse::<Polynomial<f64>>(), Err(ParsePolynomialError { pos: 2 }));
+        assert_eq!("1 + x^4000000000000".parse::<Polynomial<f32>>(), Err(ParsePolynomialError { pos: 6 }));
+        assert_eq!("x^1048576".parse::<Polynomial<f64>>().map(|p| p.degree()), Ok(1 << 20));
+    }
+
+    #[test]
+    fn mul() {
+        let p = Polynomial::<f64>::new(vec![Complex::new(1.0, 2.0), 3.0.into(), Complex::new(0.0, -1.0)]);
+        let q = Polynomial::<f64>::new(vec![2.0.into(), Complex::new(-1.0, 1.0)]);
+        let expected = [Complex::new(2.0, 4.0), Complex::new(3.0, -1.0), Complex::new(-3.0, 1.0), Complex::new(1.0, 1.0)];
+        assert_eq!((&p * &q).coeffs(), &expected);
+        assert_eq!((q.clone() * p.clone()).coeffs(), &expected);
+        let zero = Polynomial::<f64>::new(vec![]);
+        assert_eq!(&p * &zero, zero);
+    }
+
+    #[test]
+    fn mul_fft_matches_schoolbook() {
+        assert!(!prefer_fft(3, 2));
+        assert!(prefer_fft(1000, 1000));
+        let p = Polynomial::<f64>::new((0..300).map(|k| Complex::new((k as f64).sin(), 1.0)).collect());
+        let q = Polynomial::<f64>::new((0..200).map(|k| Complex::new(1.0, (k as f64).cos())).collect());
+        let (fft, schoolbook) = (p.mul_fft(&q), p.mul_schoolbook(&q));
+        assert_eq!(fft.degree(), 498);
+        for (&a, &b) in fft.coeffs().iter().zip(schoolbook.coeffs()) {
+            assert!((a - b).abs() < 1e-11);
+        }
+    }
+
+    #[test]
+    fn eval_derivatives() {
+        let p = Polynomial::<f64>::new(vec![
+            Complex::new(1.0, -1.0), 2.0.into(), Complex::new(0.0, 3.0), (-4.0).into(), 5.0.into()
+        ]);
+        let z = Complex::new(0.5, -1.5);
+        let derivatives = p.eval_derivatives(z, 6);
+        let mut d = p.clone();
+        for (k, &value) in derivatives.iter().enumerate() {
+            assert!((value - d.eval(z)).abs() <= 1e-13 * value.abs().max(1.0), "k = {k}");
+            d = d.derivative();
+        }
+        assert_eq!(p.eval_derivatives(z, 0), [p.eval(z)]);
+    }
+
+    #[test]
+    fn roots_non_finite() {
+        let p = Polynomial::new(vec![1.0.into(), f64::INFINITY.into(), 1.0.into()]);
+        assert_eq!(p.roots(), None);
+        let p = Polynomial::new(vec![1.0.into(), Complex::new(0.0, f64::NAN), 1.0.into()]);
+        assert_eq!(p.roots(), None);
+    }
+
+    #[test]
+    fn roots_tiny_coeffs() {
+        // 1e-160 (z - 1)(z - 2)
+        let expected = [Complex::new(1.0, 0.0), Complex::new(2.0, 0.0)];
+        let p = Polynomial::<f64>::from_roots(&expected);
+        let coeffs = p.coeffs().iter().map(|&c| c * 1e-160).collect();
+        check_roots(&Polynomial::new(coeffs), &expected);
+    }
+
+    #[test]
+    fn roots_wide_coeffs() {
+        // 1e160 z² + z + 1e-160, whose roots are 1e-160 times the primitive
+        // cube roots of unity
+        let p = Polynomial::<f64>::new(vec![1e160.into(), 1.0.into(), 1e-160.into()]);
+        let roots = p.roots().unwrap();
+        assert_eq!(roots.len(), 2);
+        for sign in [1.0, -1.0] {
+            let root = 1e-160 * Complex::<f64>::cis(sign * 2.0 * std::f64::consts::FRAC_PI_3);
+            assert!(roots.iter().any(|&z| (z - root).abs() <= 1e-10 * root.abs()), "{roots:?}");
+        }
+    }
+
+    #[test]
+    fn roots_constant() {
+        let p = Polynomial::new(vec![Complex::new(2.0_f64, 0.0)]);
+        assert_eq!(p.roots(), Some(vec![]));
+    }
+}
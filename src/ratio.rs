@@ -0,0 +1,119 @@
+//! Exact complex rationals, with the `num-rational` feature
+//!
+//! `Complex<Ratio<i64>>` has rational parts, so that its sums, differences,
+//! products and quotients, by the generic operators of [`Complex`], are
+//! exact, without the rounding of floats, as are the
+//! [`conj`](Complex::conj) and the [`recip`](Complex::recip) and
+//! [`norm`](Complex::norm) of this module.  The parts are written as
+//! fractions, and [`From`] converts to the nearest `Complex<f64>`, and from
+//! the Gaussian integers of `Complex<i64>`.  Like `Ratio<i64>` itself, the
+//! arithmetic panics when a numerator or denominator overflows.
+//!
+//! # Example
+//! ```
+//! use imaginary::Complex;
+//! use num_rational::Ratio;
+//! let z = Complex::new(Ratio::new(1, 2), Ratio::new(-2, 3));
+//! let w = Complex::from(Complex::new(3i64, 1));
+//! assert_eq!((z * w).to_string(), "13/6 - 3/2*i");
+//! assert_eq!(z / w * w, z);
+//! assert_eq!(w.recip().to_string(), "3/10 - 1/10*i");
+//! assert_eq!(Complex::<f64>::from(z.conj()), Complex::new(0.5, 2.0 / 3.0));
+//! ```
+
+use crate::Complex;
+use num_rational::Ratio;
+use num_traits::ToPrimitive;
+
+impl Complex<Ratio<i64>> {
+    /// The norm, r² + i², the square of the absolute value, exactly
+    ///
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// use num_rational::Ratio;
+    /// let z = Complex::new(Ratio::new(1, 2), Ratio::new(-2, 3));
+    /// assert_eq!(z.norm(), Ratio::new(25, 36));
+    /// ```
+    pub fn norm(self) -> Ratio<i64> {
+        self.r * self.r + self.i * self.i
+    }
+
+    /// The reciprocal, 1 / `self`, exactly, as the conjugate over the norm
+    ///
+    /// # Panics
+    /// `recip` panics when `self` is zero.
+    ///
+    /// # Example
+    /// ```
+    /// use imaginary::Complex;
+    /// use num_rational::Ratio;
+    /// let z = Complex::new(Ratio::new(1, 2), Ratio::new(-2, 3));
+    /// assert_eq!(z.recip(), Complex::new(Ratio::new(18, 25), Ratio::new(24, 25)));
+    /// ```
+    pub fn recip(self) -> Complex<Ratio<i64>> {
+        let norm = self.norm();
+        Complex::new(self.r / norm, -self.i / norm)
+    }
+}
+
+/// The nearest `Complex<f64>` to a complex rational
+///
+/// # Example
+/// ```
+/// use imaginary::Complex;
+/// use num_rational::Ratio;
+/// let z = Complex::new(Ratio::new(1, 3), Ratio::new(i64::MAX, 2));
+/// assert_eq!(Complex::<f64>::from(z), Complex::new(1.0 / 3.0, i64::MAX as f64 / 2.0));
+/// ```
+impl From<Complex<Ratio<i64>>> for Complex<f64> {
+    fn from(z: Complex<Ratio<i64>>) -> Complex<f64> {
+        // a Ratio<i64> is always finite, and so always converts
+        let part = |x: Ratio<i64>| x.to_f64().unwrap_or(f64::NAN);
+        Complex::new(part(z.r), part(z.i))
+    }
+}
+
+/// A Gaussian integer as a complex rational
+impl From<Complex<i64>> for Complex<Ratio<i64>> {
+    fn from(z: Complex<i64>) -> Complex<Ratio<i64>> {
+        Complex::new(Ratio::from_integer(z.r), Ratio::from_integer(z.i))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn q(numer: i64, denom: i64) -> Ratio<i64> {
+        Ratio::new(numer, denom)
+    }
+
+    #[test]
+    fn exact() {
+        let z = Complex::new(q(1, 3), q(1, 7));
+        let w = Complex::new(q(-2, 5), q(3, 4));
+        assert_eq!(z + w, Complex::new(q(-1, 15), q(25, 28)));
+        assert_eq!(z - w, Complex::new(q(11, 15), q(-17, 28)));
+        assert_eq!(z * w, Complex::new(q(-2, 15) - q(3, 28), q(1, 4) - q(2, 35)));
+        assert_eq!(z / w * w, z);
+        assert_eq!(z * z.recip(), Complex::new(q(1, 1), q(0, 1)));
+        assert_eq!(w.recip(), Complex::new(q(1, 1), q(0, 1)) / w);
+        // thirds are not exact in floats
+        let third = Complex::new(q(1, 3), q(0, 1));
+        assert_eq!(third + third + third, Complex::new(q(1, 1), q(0, 1)));
+        assert_eq!((z.conj() * z).i, q(0, 1));
+        assert_eq!((z * z.conj()).r, z.norm());
+    }
+
+    #[test]
+    fn display_and_conversion() {
+        assert_eq!(Complex::new(q(-1, 2), q(0, 1)).to_string(), "-1/2");
+        assert_eq!(Complex::new(q(0, 1), q(-1, 1)).to_string(), "-i");
+        assert_eq!(Complex::new(q(4, 2), q(-6, 4)).to_string(), "2 - 3/2*i");
+        assert_eq!(Complex::<f64>::from(Complex::new(q(1, 10), q(-3, 8))), Complex::new(0.1, -0.375));
+        let big = Complex::new(q(i64::MAX, 3), q(-1, i64::MAX));
+        assert_eq!(Complex::<f64>::from(big), Complex::new(i64::MAX as f64 / 3.0, -1.0 / i64::MAX as f64));
+        assert_eq!(Complex::<Ratio<i64>>::from(Complex::new(2i64, -5)), Complex::new(q(2, 1), q(-5, 1)));
+    }
+}
@@ -0,0 +1,272 @@
+//! Rational functions in barycentric form
+//!
+//! [`Barycentric::aaa`] fits a rational approximant to samples of a function
+//! with the AAA algorithm of Nakatsukasa, Sète and Trefethen, greedily adding
+//! support points and choosing weights from the smallest singular vector of a
+//! Loewner matrix.
+
+use crate::Complex;
+use crate::poly::Polynomial;
+
+/// A rational function in barycentric form
+///
+/// r(z) = Σ w<sub>k</sub> f<sub>k</sub> / (z − z<sub>k</sub>) /
+/// Σ w<sub>k</sub> / (z − z<sub>k</sub>)
+///
+/// with support points z<sub>k</sub>, values f<sub>k</sub> = r(z<sub>k</sub>)
+/// and weights w<sub>k</sub>.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Barycentric<T: Copy> {
+    support: Vec<Complex<T>>,
+    values: Vec<Complex<T>>,
+    weights: Vec<Complex<T>>
+}
+
+impl<T: Copy> Barycentric<T> {
+    /// Support points z<sub>k</sub>
+    pub fn support(&self) -> &[Complex<T>] {
+        &self.support
+    }
+
+    /// Values f<sub>k</sub> at the support points
+    pub fn values(&self) -> &[Complex<T>] {
+        &self.values
+    }
+
+    /// Barycentric weights w<sub>k</sub>
+    pub fn weights(&self) -> &[Complex<T>] {
+        &self.weights
+    }
+}
+
+macro_rules! impl_barycentric {
+    ($m: ident for $t: ident) => {
+        impl Barycentric<$t> {
+            /// Fits a rational approximant to `values` sampled at `points`
+            /// with the AAA algorithm
+            ///
+            /// Support points are added one at a time, where the current
+            /// approximation is worst, until the error on the samples is at
+            /// most `tol` relative to the largest sample or `max_terms` support
+            /// points are in use.  The result has type (m − 1, m − 1) for m
+            /// support points.
+            ///
+            /// # Panics
+            /// Panics if `points` and `values` differ in length or are empty.
+            ///
+            /// # Example
+            /// ```
+            /// use imaginary::{Complex, rational::Barycentric};
+            /// let points: Vec<Complex<f64>> = (0..100)
+            ///     .map(|k| Complex::<f64>::cis(k as f64 * 0.0628))
+            ///     .collect();
+            /// let values: Vec<Complex<f64>> = points.iter()
+            ///     .map(|&z| 1.0 / (z - 2.0))
+            ///     .collect();
+            /// let r = Barycentric::<f64>::aaa(&points, &values, 1e-13, 20);
+            /// let poles = r.poles().unwrap();
+            /// assert_eq!(poles.len(), 1);
+            /// assert!((poles[0] - 2.0).abs() < 1e-10);
+            /// ```
+            pub fn aaa(points: &[Complex<$t>], values: &[Complex<$t>],
+                    tol: $t, max_terms: usize) -> Barycentric<$t> {
+                assert_eq!(points.len(), values.len(), "points and values must have the same length");
+                assert!(!points.is_empty(), "no samples to fit");
+                let zero = Complex::new(0.0, 0.0);
+                let scale = values.iter().fold(0.0, |max: $t, f| max.max(f.abs()));
+                let mean = values.iter().fold(zero, |sum, &f| sum + f) / values.len() as $t;
+                let mut approx = vec![mean; values.len()];
+                let mut remaining: Vec<usize> = (0..points.len()).collect();
+                let mut r = Barycentric { support: Vec::new(), values: Vec::new(), weights: Vec::new() };
+                for _ in 0..max_terms.clamp(1, points.len().saturating_sub(1).max(1)) {
+                    let worst = (0..remaining.len())
+                        .max_by(|&a, &b| {
+                            let (a, b) = (remaining[a], remaining[b]);
+                            (values[a] - approx[a]).abs().total_cmp(&(values[b] - approx[b]).abs())
+                        })
+                        .unwrap();
+                    let j = remaining.swap_remove(worst);
+                    r.support.push(points[j]);
+                    r.values.push(values[j]);
+                    approx[j] = values[j];
+                    if remaining.is_empty() {
+                        r.weights = vec![Complex::new(1.0, 0.0)];
+                        break;
+                    }
+                    // Loewner matrix (F_i - f_k) / (Z_i - z_k) over the
+                    // points that are not yet support points
+                    let loewner = r.support.iter().zip(&r.values).map(|(&z, &f)| {
+                        remaining.iter().map(|&i| (values[i] - f) / (points[i] - z)).collect()
+                    }).collect();
+                    r.weights = crate::linalg::$m::min_singular_vector(loewner);
+                    let mut error: $t = 0.0;
+                    for &i in &remaining {
+                        approx[i] = r.eval(points[i]);
+                        error = error.max((values[i] - approx[i]).abs());
+                    }
+                    if error <= tol * scale {
+                        break;
+                    }
+                }
+                r
+            }
+
+            /// Evaluates the rational function at `z`
+            pub fn eval(&self, z: Complex<$t>) -> Complex<$t> {
+                let zero = Complex::new(0.0, 0.0);
+                let mut num = zero;
+                let mut den = zero;
+                for ((&zk, &fk), &wk) in self.support.iter().zip(&self.values).zip(&self.weights) {
+                    if z == zk {
+                        return fk;
+                    }
+                    let c = wk / (z - zk);
+                    num += c * fk;
+                    den += c;
+                }
+                num / den
+            }
+
+            /// Poles of the rational function
+            ///
+            /// The roots of the denominator are found as polynomial roots in
+            /// a coordinate centered on the support points, then refined by
+            /// Newton's method on the barycentric form.  Returns `None` if the
+            /// polynomial root finder fails.
+            pub fn poles(&self) -> Option<Vec<Complex<$t>>> {
+                self.roots(&self.weights)
+            }
+
+            /// Zeros of the rational function
+            ///
+            /// Found the same way as [`poles`](Self::poles).
+            pub fn zeros(&self) -> Option<Vec<Complex<$t>>> {
+                let weights: Vec<Complex<$t>> = self.weights.iter().zip(&self.values)
+                    .map(|(&w, &f)| w * f)
+                    .collect();
+                self.roots(&weights)
+            }
+
+            /// Roots of Σ c<sub>k</sub> / (z − z<sub>k</sub>)
+            fn roots(&self, coeffs: &[Complex<$t>]) -> Option<Vec<Complex<$t>>> {
+                let n = self.support.len() as $t;
+                let center = self.support.iter().fold(Complex::new(0.0, 0.0), |sum, &z| sum + z) / n;
+                let radius = self.support.iter()
+                    .fold(0.0, |max: $t, &z| max.max((z - center).abs()))
+                    .max($t::MIN_POSITIVE);
+                let nodes: Vec<Complex<$t>> = self.support.iter().map(|&z| (z - center) / radius).collect();
+                // Σ c_k ∏_{j≠k} (u - u_j)
+                let mut sum = vec![Complex::new(0.0, 0.0); nodes.len().max(1)];
+                for (k, &c) in coeffs.iter().enumerate() {
+                    let others: Vec<Complex<$t>> = nodes.iter().enumerate()
+                        .filter(|&(j, _)| j != k)
+                        .map(|(_, &u)| u)
+                        .collect();
+                    let term = Polynomial::<$t>::from_roots(&others);
+                    let offset = sum.len() - term.coeffs().len();
+                    for (s, &t) in sum[offset..].iter_mut().zip(term.coeffs()) {
+                        *s += c * t;
+                    }
+                }
+                let roots = Polynomial::new(sum).roots()?;
+                Some(roots.into_iter().map(|u| self.polish(center + u * radius, coeffs)).collect())
+            }
+
+            /// Refines a root of Σ c<sub>k</sub> / (z − z<sub>k</sub>) with
+            /// Newton's method
+            fn polish(&self, mut z: Complex<$t>, coeffs: &[Complex<$t>]) -> Complex<$t> {
+                let mut last_step = $t::INFINITY;
+                for _ in 0..8 {
+                    let zero = Complex::new(0.0, 0.0);
+                    let (mut f, mut df) = (zero, zero);
+                    for (&zk, &c) in self.support.iter().zip(coeffs) {
+                        if z == zk {
+                            return z;
+                        }
+                        let inv = 1.0 / (z - zk);
+                        f += c * inv;
+                        df -= c * inv * inv;
+                    }
+                    let step = f / df;
+                    let size = step.abs();
+                    if !size.is_finite() || size >= last_step {
+                        break;
+                    }
+                    z -= step;
+                    last_step = size;
+                    if size <= $t::EPSILON * z.abs() {
+                        break;
+                    }
+                }
+                z
+            }
+        }
+    }
+}
+impl_barycentric!(c32 for f32);
+impl_barycentric!(c64 for f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn circle(n: usize) -> Vec<Complex<f64>> {
+        (0..n).map(|k| Complex::<f64>::cis(std::f64::consts::TAU * k as f64 / n as f64)).collect()
+    }
+
+    #[test]
+    fn aaa_rational() {
+        let points = circle(200);
+        let f = |z: Complex<f64>| (z - 0.5) * (z + 0.25) / ((z - 2.0) * (z + Complex::new(0.0, 1.5)));
+        let values: Vec<Complex<f64>> = points.iter().map(|&z| f(z)).collect();
+        let r = Barycentric::<f64>::aaa(&points, &values, 1e-13, 50);
+        assert_eq!(r.support().len(), 3);
+        let mut poles = r.poles().unwrap();
+        poles.sort_by(|a, b| a.r.total_cmp(&b.r));
+        assert!((poles[0] - Complex::new(0.0, -1.5)).abs() < 1e-12, "{:?}", poles);
+        assert!((poles[1] - 2.0).abs() < 1e-12, "{:?}", poles);
+        let mut zeros = r.zeros().unwrap();
+        zeros.sort_by(|a, b| a.r.total_cmp(&b.r));
+        assert!((zeros[0] + 0.25).abs() < 1e-12, "{:?}", zeros);
+        assert!((zeros[1] - 0.5).abs() < 1e-12, "{:?}", zeros);
+    }
+
+    #[test]
+    fn aaa_exp() {
+        let points = circle(300);
+        let values: Vec<Complex<f64>> = points.iter().map(|&z| z.exp()).collect();
+        let r = Barycentric::<f64>::aaa(&points, &values, 1e-13, 50);
+        for k in 0..50 {
+            let z = 0.9 * Complex::<f64>::cis(k as f64 * 0.3 + 0.01);
+            assert!((r.eval(z) - z.exp()).abs() < 1e-12, "{}", z);
+        }
+        for (&z, &f) in r.support().iter().zip(r.values()) {
+            assert_eq!(r.eval(z), f);
+        }
+    }
+
+    #[test]
+    fn aaa_tan() {
+        // tan has poles at ±π/2 just outside the sampled interval
+        let points: Vec<Complex<f64>> = (0..400)
+            .map(|k| Complex::new(-1.5 + 3.0 * k as f64 / 399.0, 0.0))
+            .collect();
+        let values: Vec<Complex<f64>> = points.iter().map(|&z| z.tan()).collect();
+        let r = Barycentric::<f64>::aaa(&points, &values, 1e-13, 50);
+        let poles = r.poles().unwrap();
+        let half_pi = std::f64::consts::FRAC_PI_2;
+        for target in [half_pi, -half_pi] {
+            assert!(poles.iter().any(|&p| (p - target).abs() < 1e-8), "{:?}", poles);
+        }
+    }
+
+    #[test]
+    fn aaa_constant() {
+        let points = circle(10);
+        let values = vec![Complex::new(2.0, 1.0); 10];
+        let r = Barycentric::<f64>::aaa(&points, &values, 1e-13, 10);
+        assert_eq!(r.support().len(), 1);
+        assert!((r.eval(Complex::new(0.3, 0.2)) - Complex::new(2.0, 1.0)).abs() < 1e-15);
+        assert_eq!(r.poles().unwrap(), vec![]);
+    }
+}
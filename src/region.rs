@@ -0,0 +1,329 @@
+//! Uniformly distributed random points in regions of the complex plane
+//!
+//! Points are drawn uniformly by area from rectangles, annuli, sectors of a
+//! disk and half-planes cut down to a half-disk, for randomized tests that
+//! need inputs on one side of a branch cut or away from a singularity.
+//! Other regions are sampled by rejection: [`sample_where`](c64::sample_where)
+//! draws from a bounding rectangle until a point satisfies a predicate, and
+//! [`reject`](c64::reject) does the same for points from any sampler.
+//!
+//! The unit circle, the unit disk and rectangles are also
+//! [`Distribution`]s, [`UnitCircle`], [`UnitDisk`] and [`Rect`], to draw
+//! with [`Rng::sample`] or as iterators with [`Rng::sample_iter`].
+//!
+//! Requires the `rand` feature.
+//!
+//! # Example
+//! ```
+//! use imaginary::Complex;
+//! use imaginary::region::{UnitCircle, UnitDisk};
+//! use rand::{Rng, SeedableRng, rngs::SmallRng};
+//! let mut rng = SmallRng::seed_from_u64(1);
+//! let z: Complex<f64> = rng.sample(UnitCircle);
+//! assert!((z.abs() - 1.0).abs() < 1e-12);
+//! let inside = rng.sample_iter::<Complex<f32>, _>(UnitDisk).take(100).all(|z| z.abs() <= 1.0);
+//! assert!(inside);
+//! ```
+
+use crate::Complex;
+use rand::Rng;
+use rand::distr::Distribution;
+
+/// Points uniform on the unit circle, e<sup>iθ</sup> for θ uniform
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct UnitCircle;
+
+/// Points uniform by area in the unit disk, |z| ≤ 1
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct UnitDisk;
+
+/// Points uniform in a rectangle with sides parallel to the axes
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rect<T: Copy> {
+    /// a corner
+    pub corner0: Complex<T>,
+    /// the opposite corner
+    pub corner1: Complex<T>
+}
+
+impl<T: Copy> Rect<T> {
+    /// The rectangle with opposite corners `corner0` and `corner1`
+    pub fn new(corner0: Complex<T>, corner1: Complex<T>) -> Rect<T> {
+        Rect { corner0, corner1 }
+    }
+}
+
+macro_rules! impl_distributions {
+    ($m: ident for $t: ident) => {
+        impl Distribution<Complex<$t>> for UnitCircle {
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Complex<$t> {
+                use core::$t::consts::TAU;
+                Complex::<$t>::cis(TAU * rng.random::<$t>())
+            }
+        }
+
+        impl Distribution<Complex<$t>> for UnitDisk {
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Complex<$t> {
+                $m::sample_annulus(rng, Complex::new(0.0, 0.0), 0.0, 1.0)
+            }
+        }
+
+        impl Distribution<Complex<$t>> for Rect<$t> {
+            fn sample<R: Rng + ?Sized>(&self, rng: &mut R) -> Complex<$t> {
+                $m::sample_rect(rng, self.corner0, self.corner1)
+            }
+        }
+    }
+}
+impl_distributions!(c32 for f32);
+impl_distributions!(c64 for f64);
+
+macro_rules! region_mod {
+    ($m: ident for $t: ident) => {
+        /// Uniformly distributed random points in regions of the complex plane
+        pub mod $m {
+            use crate::{math, Complex};
+            use rand::Rng;
+
+            /// A point uniform in the rectangle with opposite corners
+            /// `corner0` and `corner1`
+            pub fn sample_rect<R: Rng + ?Sized>(rng: &mut R, corner0: Complex<$t>, corner1: Complex<$t>) -> Complex<$t> {
+                let (u, v): ($t, $t) = (rng.random(), rng.random());
+                Complex::new(corner0.r + u * (corner1.r - corner0.r), corner0.i + v * (corner1.i - corner0.i))
+            }
+
+            /// A point uniform in the annulus `inner` ≤ |z − `centre`| ≤
+            /// `outer`
+            ///
+            /// An `inner` radius of zero gives the whole disk.
+            ///
+            /// # Panics
+            /// Panics unless 0 ≤ `inner` ≤ `outer`.
+            ///
+            /// # Example
+            /// ```
+            /// use rand::{SeedableRng, rngs::SmallRng};
+            #[doc = concat!("use imaginary::{Complex, region::", stringify!($m), "::sample_annulus};")]
+            /// let mut rng = SmallRng::seed_from_u64(1);
+            /// let z = sample_annulus(&mut rng, Complex::new(1.0, 0.0), 0.5, 2.0);
+            /// let r = (z - 1.0).abs();
+            /// assert!(r >= 0.5 && r <= 2.0);
+            /// ```
+            pub fn sample_annulus<R: Rng + ?Sized>(rng: &mut R, centre: Complex<$t>, inner: $t, outer: $t) -> Complex<$t> {
+                use core::$t::consts::PI;
+                sample_sector(rng, centre, inner, outer, -PI, PI)
+            }
+
+            /// A point uniform in the part of the annulus `inner` ≤
+            /// |z − `centre`| ≤ `outer` with argument about `centre` from
+            /// `start` to `end`
+            ///
+            /// The sector turns counterclockwise from `start` to `end`, so
+            /// angles beyond ±π wrap round, and `start` = −π, `end` = π is
+            /// the whole annulus.  An `inner` radius of zero gives a sector
+            /// of a disk.
+            ///
+            /// # Panics
+            /// Panics unless 0 ≤ `inner` ≤ `outer`.
+            ///
+            /// # Example
+            /// ```
+            /// use rand::{SeedableRng, rngs::SmallRng};
+            #[doc = concat!("use imaginary::{Complex, region::", stringify!($m), "::sample_sector};")]
+            #[doc = concat!("use std::", stringify!($t), "::consts::PI;")]
+            /// // near the negative real axis, just above the branch cut of ln
+            /// let mut rng = SmallRng::seed_from_u64(1);
+            /// let z = sample_sector(&mut rng, Complex::new(0.0, 0.0), 0.0, 10.0, 0.9 * PI, PI);
+            /// assert!(z.r < 0.0 && z.i >= 0.0);
+            /// ```
+            pub fn sample_sector<R: Rng + ?Sized>(
+                rng: &mut R, centre: Complex<$t>, inner: $t, outer: $t, start: $t, end: $t
+            ) -> Complex<$t> {
+                assert!(0.0 <= inner && inner <= outer, "radii must satisfy 0 ≤ inner ≤ outer, inner = {inner}, outer = {outer}");
+                let (u, v): ($t, $t) = (rng.random(), rng.random());
+                // uniform in r² for uniform area
+                let r = math::$m::sqrt(inner * inner + u * (outer * outer - inner * inner));
+                let phase = start + v * (end - start);
+                let (s, c) = (math::$m::sin(phase), math::$m::cos(phase));
+                centre + Complex::new(r * c, r * s)
+            }
+
+            /// A point uniform in the half-plane through `point` on the side
+            /// `direction` points to, within `extent` of `point`
+            ///
+            /// A half-plane has infinite area, so it is cut down to the
+            /// half-disk of radius `extent` about `point`, the points z with
+            /// |z − `point`| ≤ `extent` and Re((z − `point`) conj(`direction`))
+            /// ≥ 0.
+            ///
+            /// # Panics
+            /// Panics if `direction` is zero or `extent` negative.
+            ///
+            /// # Example
+            /// ```
+            /// use rand::{SeedableRng, rngs::SmallRng};
+            #[doc = concat!("use imaginary::{Complex, region::", stringify!($m), "::sample_half_plane};")]
+            /// // the left half-plane
+            /// let mut rng = SmallRng::seed_from_u64(1);
+            /// let z = sample_half_plane(&mut rng, Complex::new(0.0, 0.0), Complex::new(-1.0, 0.0), 100.0);
+            /// assert!(z.r <= 0.0 && z.abs() <= 100.0);
+            /// ```
+            pub fn sample_half_plane<R: Rng + ?Sized>(
+                rng: &mut R, point: Complex<$t>, direction: Complex<$t>, extent: $t
+            ) -> Complex<$t> {
+                use core::$t::consts::FRAC_PI_2;
+                assert!(direction != Complex::new(0.0, 0.0), "the direction into the half-plane must be nonzero");
+                let angle = direction.angle();
+                sample_sector(rng, point, 0.0, extent, angle - FRAC_PI_2, angle + FRAC_PI_2)
+            }
+
+            /// Draws points from `sample` until one satisfies `accept`,
+            /// giving up with `None` after `max_tries` draws
+            ///
+            /// The points accepted are distributed as those of `sample`
+            /// restricted to the region, so a uniform sampler gives points
+            /// uniform in the intersection.
+            ///
+            /// # Example
+            /// ```
+            /// use rand::{SeedableRng, rngs::SmallRng};
+            #[doc = concat!("use imaginary::{Complex, region::", stringify!($m), "::{reject, sample_annulus}};")]
+            /// // the right half of the unit annulus
+            /// let mut rng = SmallRng::seed_from_u64(1);
+            /// let annulus = |rng: &mut SmallRng| sample_annulus(rng, Complex::new(0.0, 0.0), 0.5, 1.0);
+            /// let z = reject(&mut rng, annulus, |z| z.r > 0.0, 100).unwrap();
+            /// assert!(z.r > 0.0);
+            /// ```
+            pub fn reject<R: Rng + ?Sized>(
+                rng: &mut R, mut sample: impl FnMut(&mut R) -> Complex<$t>,
+                accept: impl Fn(Complex<$t>) -> bool, max_tries: usize
+            ) -> Option<Complex<$t>> {
+                (0..max_tries).map(|_| sample(rng)).find(|&z| accept(z))
+            }
+
+            /// A point uniform in the region where `accept` holds, by
+            /// rejection from the bounding rectangle with opposite corners
+            /// `corner0` and `corner1`
+            ///
+            /// Gives up with `None` after `max_tries` draws.  The expected
+            /// number of draws is the ratio of the area of the rectangle to
+            /// that of the region.
+            ///
+            /// # Example
+            /// ```
+            /// use rand::{SeedableRng, rngs::SmallRng};
+            #[doc = concat!("use imaginary::{Complex, region::", stringify!($m), "::sample_where};")]
+            /// // inside the cardioid |z| < 1 + Re z / |z|
+            /// let mut rng = SmallRng::seed_from_u64(1);
+            #[doc = concat!("let cardioid = |z: Complex<", stringify!($t), ">| z.abs() * z.abs() < z.abs() + z.r;")]
+            /// let z = sample_where(&mut rng, Complex::new(-0.5, -1.5), Complex::new(2.0, 1.5), cardioid, 1000).unwrap();
+            /// assert!(cardioid(z));
+            /// ```
+            pub fn sample_where<R: Rng + ?Sized>(
+                rng: &mut R, corner0: Complex<$t>, corner1: Complex<$t>,
+                accept: impl Fn(Complex<$t>) -> bool, max_tries: usize
+            ) -> Option<Complex<$t>> {
+                reject(rng, |rng| sample_rect(rng, corner0, corner1), accept, max_tries)
+            }
+
+            #[cfg(test)]
+            mod test {
+                use super::*;
+                use rand::{SeedableRng, rngs::SmallRng};
+
+                #[test]
+                fn check_rect() {
+                    let mut rng = SmallRng::seed_from_u64(3);
+                    let (a, b) = (Complex::new(1.0, 2.0), Complex::new(-1.0, 3.0));
+                    let mut left = 0;
+                    for _ in 0..10_000 {
+                        let z = sample_rect(&mut rng, a, b);
+                        assert!((-1.0..=1.0).contains(&z.r) && (2.0..=3.0).contains(&z.i), "{z}");
+                        left += (z.r < 0.0) as usize;
+                    }
+                    assert!((left as f64 / 10_000.0 - 0.5).abs() < 0.02);
+                }
+
+                #[test]
+                fn check_annulus_sector() {
+                    use core::$t::consts::PI;
+                    let mut rng = SmallRng::seed_from_u64(5);
+                    let centre = Complex::new(-1.0, 1.0);
+                    // the annulus from 1 to 3 has area 8π, of which 3π is
+                    // within radius 2
+                    let mut near = 0;
+                    for _ in 0..20_000 {
+                        let r = (sample_annulus(&mut rng, centre, 1.0, 3.0) - centre).abs();
+                        assert!((1.0 - 1e-5..=3.0 + 1e-5).contains(&r), "{r}");
+                        near += (r < 2.0) as usize;
+                    }
+                    assert!((near as f64 / 20_000.0 - 3.0 / 8.0).abs() < 0.02, "{near}");
+                    // a sector across the negative real axis, by wrapping
+                    for _ in 0..1000 {
+                        let z = sample_sector(&mut rng, centre, 0.0, 1.0, 0.75 * PI, 1.25 * PI) - centre;
+                        assert!(z.r <= 0.0 && z.i.abs() <= -z.r + 1e-5, "{z}");
+                    }
+                    let z = sample_annulus(&mut rng, centre, 2.0, 2.0);
+                    assert!(((z - centre).abs() - 2.0).abs() < 1e-5);
+                }
+
+                #[test]
+                fn check_half_plane() {
+                    let mut rng = SmallRng::seed_from_u64(9);
+                    let (point, direction) = (Complex::new(1.0, 1.0), Complex::new(1.0, 1.0));
+                    for _ in 0..1000 {
+                        let z = sample_half_plane(&mut rng, point, direction, 5.0) - point;
+                        assert!((z * direction.conj()).r >= -1e-5 && z.abs() <= 5.0 + 1e-5, "{z}");
+                    }
+                }
+
+                #[test]
+                fn check_rejection() {
+                    let mut rng = SmallRng::seed_from_u64(11);
+                    let disk = |z: Complex<$t>| z.abs() < 1.0;
+                    let corner = Complex::new(1.0, 1.0);
+                    // a quarter disk by rejection from a quadrant of the square
+                    let mut count = 0;
+                    for _ in 0..10_000 {
+                        let z = sample_where(&mut rng, -corner, corner, |z| disk(z) && z.r > 0.0 && z.i > 0.0, 1000).unwrap();
+                        assert!(disk(z) && z.r > 0.0 && z.i > 0.0);
+                        count += (z.abs() < 0.5) as usize;
+                    }
+                    assert!((count as f64 / 10_000.0 - 0.25).abs() < 0.02, "{count}");
+                    // an empty region
+                    assert_eq!(sample_where(&mut rng, -corner, corner, |z| z.abs() > 2.0, 100), None);
+                    assert_eq!(reject(&mut rng, |rng| sample_rect(rng, -corner, corner), disk, 0), None);
+                }
+            }
+        }
+    }
+}
+region_mod!(c32 for f32);
+region_mod!(c64 for f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{SeedableRng, rngs::SmallRng};
+
+    #[test]
+    fn distributions() {
+        let mut rng = SmallRng::seed_from_u64(13);
+        let n = 20_000;
+        // a uniform angle puts a quarter of the circle in each quadrant
+        let circle: Vec<Complex<f64>> = (&mut rng).sample_iter(UnitCircle).take(n).collect();
+        assert!(circle.iter().all(|z| (z.abs() - 1.0).abs() < 1e-12));
+        let first = circle.iter().filter(|z| z.r > 0.0 && z.i > 0.0).count();
+        assert!((first as f64 / n as f64 - 0.25).abs() < 0.02, "{first}");
+        // a quarter of the area of the disk is within radius ½
+        let disk: Vec<Complex<f32>> = (&mut rng).sample_iter(UnitDisk).take(n).collect();
+        assert!(disk.iter().all(|z| z.abs() <= 1.0 + 1e-6));
+        let near = disk.iter().filter(|z| z.abs() < 0.5).count();
+        assert!((near as f64 / n as f64 - 0.25).abs() < 0.02, "{near}");
+        let rect = Rect::new(Complex::new(2.0, -1.0), Complex::new(3.0, 1.0));
+        for _ in 0..1000 {
+            let z: Complex<f64> = rng.sample(rect);
+            assert!((2.0..=3.0).contains(&z.r) && (-1.0..=1.0).contains(&z.i), "{z}");
+        }
+    }
+}
@@ -0,0 +1,132 @@
+//! Smith chart and transmission line transforms
+//!
+//! Impedances are normalized to a real characteristic impedance Z<sub>0</sub>,
+//! giving the reflection coefficient Γ = (z − 1) / (z + 1) for the
+//! normalized impedance z = Z / Z<sub>0</sub>.
+
+macro_rules! rf_mod {
+    ($m: ident for $t: ident) => {
+        /// Smith chart and transmission line transforms
+        pub mod $m {
+            use crate::Complex;
+
+            /// Reflection coefficient of the impedance `z` on a line of
+            /// characteristic impedance `z0`
+            ///
+            /// Γ = (Z − Z<sub>0</sub>) / (Z + Z<sub>0</sub>)
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, rf::", stringify!($m), "::{impedance_to_gamma, vswr}};")]
+            /// let gamma = impedance_to_gamma(Complex::new(100.0, 0.0), 50.0);
+            /// assert!((gamma - 1.0 / 3.0).abs() < 1e-6);
+            /// assert!((vswr(gamma) - 2.0).abs() < 1e-6);
+            /// ```
+            pub fn impedance_to_gamma(z: Complex<$t>, z0: $t) -> Complex<$t> {
+                let z = z / z0;
+                (z - 1.0) / (z + 1.0)
+            }
+
+            /// Impedance with the reflection coefficient `gamma` on a line of
+            /// characteristic impedance `z0`
+            ///
+            /// Z = Z<sub>0</sub> (1 + Γ) / (1 − Γ)
+            pub fn gamma_to_impedance(gamma: Complex<$t>, z0: $t) -> Complex<$t> {
+                z0 * (1.0 + gamma) / (1.0 - gamma)
+            }
+
+            /// Reflection coefficient of the admittance `y` on a line of
+            /// characteristic admittance `y0`
+            ///
+            /// Γ = (Y<sub>0</sub> − Y) / (Y<sub>0</sub> + Y)
+            pub fn admittance_to_gamma(y: Complex<$t>, y0: $t) -> Complex<$t> {
+                let y = y / y0;
+                (1.0 - y) / (1.0 + y)
+            }
+
+            /// Admittance with the reflection coefficient `gamma` on a line
+            /// of characteristic admittance `y0`
+            ///
+            /// Y = Y<sub>0</sub> (1 − Γ) / (1 + Γ)
+            pub fn gamma_to_admittance(gamma: Complex<$t>, y0: $t) -> Complex<$t> {
+                y0 * (1.0 - gamma) / (1.0 + gamma)
+            }
+
+            /// Voltage standing wave ratio (1 + |Γ|) / (1 − |Γ|)
+            ///
+            /// Returns infinity for total reflection, |Γ| ≥ 1.
+            pub fn vswr(gamma: Complex<$t>) -> $t {
+                let mag = gamma.abs();
+                if mag >= 1.0 {
+                    $t::INFINITY
+                } else {
+                    (1.0 + mag) / (1.0 - mag)
+                }
+            }
+
+            /// Reflection coefficient seen through a lossless line of
+            /// electrical length `beta_l` radians
+            ///
+            /// Γ<sub>in</sub> = Γ<sub>L</sub> e<sup>−2jβl</sup>, a clockwise
+            /// rotation on the Smith chart towards the generator.
+            pub fn rotate_gamma(gamma: Complex<$t>, beta_l: $t) -> Complex<$t> {
+                gamma * Complex::<$t>::cis(-2.0 * beta_l)
+            }
+
+            /// Input impedance of a lossless line of characteristic impedance
+            /// `z0` and electrical length `beta_l` radians terminated by
+            /// `z_load`
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, rf::", stringify!($m), "::input_impedance};")]
+            #[doc = concat!("use std::", stringify!($t), "::consts::FRAC_PI_2;")]
+            /// // a quarter wave line transforms 100 Ω to 50² / 100 = 25 Ω
+            /// let z = input_impedance(Complex::new(100.0, 0.0), 50.0, FRAC_PI_2);
+            /// assert!((z - 25.0).abs() < 1e-4);
+            /// ```
+            pub fn input_impedance(z_load: Complex<$t>, z0: $t, beta_l: $t) -> Complex<$t> {
+                gamma_to_impedance(rotate_gamma(impedance_to_gamma(z_load, z0), beta_l), z0)
+            }
+
+            #[cfg(test)]
+            mod test {
+                use super::*;
+
+                #[test]
+                fn check_round_trip() {
+                    let ep = 1e2 * $t::EPSILON;
+                    for z in [Complex::new(25.0, 10.0), Complex::new(50.0, 0.0), Complex::new(3.0, -80.0)] {
+                        let gamma = impedance_to_gamma(z, 50.0);
+                        assert!(gamma.abs() < 1.0);
+                        assert!((gamma_to_impedance(gamma, 50.0) - z).abs() < ep * z.abs());
+                        // the admittance gives the same reflection coefficient
+                        let y = 1.0 / z;
+                        assert!((admittance_to_gamma(y, 0.02) - gamma).abs() < ep);
+                        assert!((gamma_to_admittance(gamma, 0.02) - y).abs() < ep * y.abs());
+                    }
+                    assert_eq!(impedance_to_gamma(Complex::new(50.0, 0.0), 50.0), Complex::new(0.0, 0.0));
+                    assert_eq!(vswr(Complex::new(0.0, 0.0)), 1.0);
+                    assert_eq!(vswr(Complex::new(0.0, -1.0)), $t::INFINITY);
+                }
+
+                #[test]
+                fn check_input_impedance() {
+                    use core::$t::consts::PI;
+                    let ep = 1e3 * $t::EPSILON;
+                    let load = Complex::new(30.0, 40.0);
+                    // half wave lines repeat the load
+                    assert!((input_impedance(load, 50.0, PI) - load).abs() < ep * 50.0);
+                    // short circuit stubs are reactive, j Z0 tan βl
+                    let stub = input_impedance(Complex::new(0.0, 0.0), 50.0, 0.3);
+                    assert!((stub - Complex::new(0.0, 50.0 * (0.3 as $t).tan())).abs() < ep * 50.0);
+                    // rotation keeps the VSWR
+                    let gamma = impedance_to_gamma(load, 50.0);
+                    assert!((vswr(rotate_gamma(gamma, 1.1)) - vswr(gamma)).abs() < ep);
+                }
+            }
+        }
+    }
+}
+rf_mod!(c32 for f32);
+rf_mod!(c64 for f64);
@@ -0,0 +1,760 @@
+//! Roots of analytic functions given as closures
+//!
+//! The iterative solvers start from one or more guesses and stop once a step
+//! is smaller than `tol` relative to the iterate (absolute below 1), once the
+//! function vanishes exactly, or after `max_iter` iterations.  Newton's method
+//! needs the derivative, the secant method two starting points and Muller's
+//! method three; Muller's parabolic interpolation finds complex roots even
+//! from real starting points.  Householder's methods, with Halley's as the
+//! second order one, use higher derivatives for faster convergence.
+//! [`roots_in_rectangle`](c64::roots_in_rectangle) finds every zero in a
+//! rectangle without starting guesses, and
+//! [`delves_lyness`](c64::delves_lyness) the few zeros inside a circle from
+//! contour integrals.
+//!
+//! Small systems of equations, such as coupled dispersion relations, are
+//! solved together by the damped Newton's method of
+//! [`newton_system`](c64::newton_system), with the Jacobian given or
+//! estimated by [`newton_system_estimated`](c64::newton_system_estimated).
+
+use crate::Complex;
+
+/// The outcome of an iterative root search
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Root<T: Copy> {
+    /// the last iterate
+    pub root: Complex<T>,
+    /// the function at `root`
+    pub value: Complex<T>,
+    /// number of iterations taken
+    pub iterations: usize,
+    /// whether the tolerance was met within the iteration limit
+    pub converged: bool
+}
+
+/// The outcome of an iterative search for a root of a system of `N`
+/// equations
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SystemRoot<T: Copy, const N: usize> {
+    /// the last iterate
+    pub root: [Complex<T>; N],
+    /// the functions at `root`
+    pub value: [Complex<T>; N],
+    /// number of iterations taken
+    pub iterations: usize,
+    /// whether the tolerance was met within the iteration limit
+    pub converged: bool
+}
+
+macro_rules! roots_mod {
+    ($m: ident for $t: ident) => {
+        /// Roots of analytic functions given as closures
+        pub mod $m {
+            use crate::Complex;
+            use crate::poly::Polynomial;
+            use crate::linalg::Matrix;
+            use super::{Root, SystemRoot};
+
+            /// Whether the step `dz` from `z` is within the tolerance
+            fn small_step(dz: Complex<$t>, z: Complex<$t>, tol: $t) -> bool {
+                dz.abs() <= tol * z.abs().max(1.0)
+            }
+
+            /// Newton's method, z ← z − f(z) / f′(z)
+            ///
+            /// Stops without converging if the derivative vanishes.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, roots::", stringify!($m), "::newton};")]
+            /// // a root of z³ − 1 near e^(2πi/3)
+            /// let root = newton(|z| z * z * z - 1.0, |z| 3.0 * z * z, Complex::new(-0.5, 1.0), 1e-6, 50);
+            /// assert!(root.converged);
+            /// assert!((root.root - Complex::new(-0.5, 0.8660254)).abs() < 1e-5);
+            /// ```
+            pub fn newton(
+                f: impl Fn(Complex<$t>) -> Complex<$t>, df: impl Fn(Complex<$t>) -> Complex<$t>,
+                z0: Complex<$t>, tol: $t, max_iter: usize
+            ) -> Root<$t> {
+                let zero = Complex::new(0.0, 0.0);
+                let (mut z, mut value) = (z0, f(z0));
+                for iterations in 1..=max_iter {
+                    if value == zero {
+                        return Root { root: z, value, iterations: iterations - 1, converged: true };
+                    }
+                    let slope = df(z);
+                    if slope == zero {
+                        return Root { root: z, value, iterations: iterations - 1, converged: false };
+                    }
+                    let dz = value / slope;
+                    z -= dz;
+                    value = f(z);
+                    if small_step(dz, z, tol) {
+                        return Root { root: z, value, iterations, converged: true };
+                    }
+                }
+                Root { root: z, value, iterations: max_iter, converged: value == zero }
+            }
+
+            /// The secant method from the starting points `z0` and `z1`
+            ///
+            /// Stops without converging if two iterates give the same value.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, roots::", stringify!($m), "::secant};")]
+            /// // z = i π / 2 solves e^z = i
+            /// let i = Complex::new(0.0, 1.0);
+            /// let root = secant(|z| z.exp() - i, Complex::new(0.1, 1.0), Complex::new(0.0, 1.5), 1e-6, 50);
+            /// assert!(root.converged);
+            #[doc = concat!("assert!((root.root - Complex::new(0.0, std::", stringify!($t), "::consts::FRAC_PI_2)).abs() < 1e-5);")]
+            /// ```
+            pub fn secant(
+                f: impl Fn(Complex<$t>) -> Complex<$t>, z0: Complex<$t>, z1: Complex<$t>, tol: $t, max_iter: usize
+            ) -> Root<$t> {
+                let zero = Complex::new(0.0, 0.0);
+                let (mut z0, mut f0) = (z0, f(z0));
+                let (mut z1, mut f1) = (z1, f(z1));
+                for iterations in 1..=max_iter {
+                    if f1 == zero {
+                        return Root { root: z1, value: f1, iterations: iterations - 1, converged: true };
+                    }
+                    if f1 == f0 {
+                        return Root { root: z1, value: f1, iterations: iterations - 1, converged: false };
+                    }
+                    let dz = f1 * (z1 - z0) / (f1 - f0);
+                    (z0, f0) = (z1, f1);
+                    z1 -= dz;
+                    f1 = f(z1);
+                    if small_step(dz, z1, tol) {
+                        return Root { root: z1, value: f1, iterations, converged: true };
+                    }
+                }
+                Root { root: z1, value: f1, iterations: max_iter, converged: f1 == zero }
+            }
+
+            /// Muller's method from the starting points `z0`, `z1` and `z2`
+            ///
+            /// Each step moves to the nearer root of the parabola through the
+            /// last three points, so the iterates can leave the real axis.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, roots::", stringify!($m), "::muller};")]
+            /// // z² + 1 from real starting points
+            /// let root = muller(|z| z * z + 1.0, 0.5.into(), 1.0.into(), 1.5.into(), 1e-6, 50);
+            /// assert!(root.converged);
+            /// assert!((root.root.i.abs() - 1.0).abs() < 1e-5);
+            /// ```
+            pub fn muller(
+                f: impl Fn(Complex<$t>) -> Complex<$t>, z0: Complex<$t>, z1: Complex<$t>, z2: Complex<$t>,
+                tol: $t, max_iter: usize
+            ) -> Root<$t> {
+                let zero = Complex::new(0.0, 0.0);
+                let (mut z0, mut z1, mut z2) = (z0, z1, z2);
+                let (mut f0, mut f1, mut f2) = (f(z0), f(z1), f(z2));
+                for iterations in 1..=max_iter {
+                    if f2 == zero {
+                        return Root { root: z2, value: f2, iterations: iterations - 1, converged: true };
+                    }
+                    let (h1, h2) = (z1 - z0, z2 - z1);
+                    let (d1, d2) = ((f1 - f0) / h1, (f2 - f1) / h2);
+                    let a = (d2 - d1) / (h2 + h1);
+                    let b = a * h2 + d2;
+                    let disc = (b * b - 4.0 * a * f2).sqrt();
+                    // the larger denominator gives the nearer root
+                    let den = if (b + disc).abs() >= (b - disc).abs() { b + disc } else { b - disc };
+                    if den == zero || !den.abs().is_finite() {
+                        return Root { root: z2, value: f2, iterations: iterations - 1, converged: false };
+                    }
+                    let dz = -2.0 * f2 / den;
+                    (z0, z1, f0, f1) = (z1, z2, f1, f2);
+                    z2 += dz;
+                    f2 = f(z2);
+                    if small_step(dz, z2, tol) {
+                        return Root { root: z2, value: f2, iterations, converged: true };
+                    }
+                }
+                Root { root: z2, value: f2, iterations: max_iter, converged: f2 == zero }
+            }
+
+            /// Householder's method of order `N - 1`
+            ///
+            /// The closure returns f and its first `N - 1` derivatives.  Order 1
+            /// is Newton's method and order 2 Halley's; order d converges with
+            /// order d + 1 near a simple root.  Stops without converging if the
+            /// first derivative vanishes.
+            ///
+            /// # Panics
+            /// Panics if `N < 2`.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, roots::", stringify!($m), "::householder};")]
+            /// // e^z = 1 + 2i with the fourth order method
+            /// let c = Complex::new(1.0, 2.0);
+            /// let root = householder(|z| { let e = z.exp(); [e - c, e, e, e, e] }, Complex::new(0.0, 1.0), 1e-6, 20);
+            /// assert!(root.converged);
+            /// assert!((root.root - c.ln()).abs() < 1e-5);
+            /// ```
+            pub fn householder<const N: usize>(
+                f: impl Fn(Complex<$t>) -> [Complex<$t>; N], z0: Complex<$t>, tol: $t, max_iter: usize
+            ) -> Root<$t> {
+                assert!(N >= 2, "Householder's method needs the first derivative");
+                let zero = Complex::new(0.0, 0.0);
+                let mut z = z0;
+                let mut d = f(z);
+                for iterations in 1..=max_iter {
+                    if d[0] == zero {
+                        return Root { root: z, value: d[0], iterations: iterations - 1, converged: true };
+                    }
+                    if d[1] == zero {
+                        return Root { root: z, value: d[0], iterations: iterations - 1, converged: false };
+                    }
+                    // Taylor coefficients of f(z + s t) / f(z), with the Newton
+                    // step s = f / f′ so the linear one is 1, and their reciprocal
+                    // series b; the step is s b[N - 2] / b[N - 1]
+                    let s = d[0] / d[1];
+                    let mut c = [zero; N];
+                    let (mut power, mut factorial) = (Complex::new(1.0, 0.0), 1.0);
+                    for j in 1..N {
+                        factorial *= j as $t;
+                        c[j] = d[j] / d[1] * power / factorial;
+                        power *= s;
+                    }
+                    let mut b = [zero; N];
+                    b[0] = Complex::new(1.0, 0.0);
+                    for m in 1..N {
+                        b[m] = -(1..=m).fold(zero, |sum, j| sum + c[j] * b[m - j]);
+                    }
+                    let dz = s * b[N - 2] / b[N - 1];
+                    z += dz;
+                    d = f(z);
+                    if small_step(dz, z, tol) {
+                        return Root { root: z, value: d[0], iterations, converged: true };
+                    }
+                }
+                Root { root: z, value: d[0], iterations: max_iter, converged: d[0] == zero }
+            }
+
+            /// Halley's method, the Householder method of order 2
+            ///
+            /// The closure returns f, f′ and f″.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, roots::", stringify!($m), "::halley};")]
+            /// // a root of z³ − 1 near e^(2πi/3)
+            /// let root = halley(|z| [z * z * z - 1.0, 3.0 * z * z, 6.0 * z], Complex::new(-0.5, 1.0), 1e-6, 50);
+            /// assert!(root.converged);
+            /// assert!((root.root - Complex::new(-0.5, 0.8660254)).abs() < 1e-5);
+            /// ```
+            pub fn halley(
+                f: impl Fn(Complex<$t>) -> [Complex<$t>; 3], z0: Complex<$t>, tol: $t, max_iter: usize
+            ) -> Root<$t> {
+                householder(f, z0, tol, max_iter)
+            }
+
+            /// The change in the argument of f along the segment from `a` to
+            /// `b`, bisecting until each piece turns by less than π/4
+            fn phase_change(
+                f: &impl Fn(Complex<$t>) -> Complex<$t>, a: Complex<$t>, b: Complex<$t>,
+                fa: Complex<$t>, fb: Complex<$t>, depth: u32
+            ) -> Option<$t> {
+                let zero = Complex::new(0.0, 0.0);
+                if fa == zero || fb == zero || !(fa.abs() * fb.abs()).is_finite() {
+                    return None;
+                }
+                let mid = (a + b) * 0.5;
+                let fm = f(mid);
+                if fm == zero {
+                    return None;
+                }
+                let whole = (fb * fa.conj()).angle();
+                let halves = (fm * fa.conj()).angle() + (fb * fm.conj()).angle();
+                if whole.abs() < std::$t::consts::FRAC_PI_4 && (halves - whole).abs() < 0.1 {
+                    return Some(halves);
+                }
+                if depth == 0 {
+                    return None;
+                }
+                Some(phase_change(f, a, mid, fa, fm, depth - 1)? + phase_change(f, mid, b, fm, fb, depth - 1)?)
+            }
+
+            /// The number of zeros of f in the rectangle with corners `lo` and
+            /// `hi` by the argument principle, or `None` if f comes too close
+            /// to zero on the boundary
+            fn zero_count(f: &impl Fn(Complex<$t>) -> Complex<$t>, lo: Complex<$t>, hi: Complex<$t>) -> Option<usize> {
+                const STEPS: usize = 16;
+                let corners = [lo, Complex::new(hi.r, lo.i), hi, Complex::new(lo.r, hi.i), lo];
+                let mut total = 0.0;
+                for edge in corners.windows(2) {
+                    let step = (edge[1] - edge[0]) / STEPS as $t;
+                    let mut a = edge[0];
+                    let mut fa = f(a);
+                    for k in 1..=STEPS {
+                        let b = if k == STEPS { edge[1] } else { edge[0] + step * k as $t };
+                        let fb = f(b);
+                        total += phase_change(f, a, b, fa, fb, 20)?;
+                        (a, fa) = (b, fb);
+                    }
+                }
+                let turns = total / std::$t::consts::TAU;
+                (turns.round() >= 0.0 && (turns - turns.round()).abs() < 0.25).then_some(turns.round() as usize)
+            }
+
+            /// All zeros of an analytic function in the rectangle with
+            /// opposite corners `corner0` and `corner1`
+            ///
+            /// The rectangle is split recursively, discarding pieces that the
+            /// argument principle shows to be empty, until each piece holds a
+            /// single zero for Newton's method to refine.  Zeros closer
+            /// together than `tol` are reported at the centre of their piece,
+            /// once for each multiplicity.  A piece whose count or iteration
+            /// fails gives an unconverged entry at its centre, as does each
+            /// zero of a piece split 256 times without meeting `tol`, such as
+            /// a multiple zero with `tol` zero.  The roots are not in any
+            /// particular order.
+            ///
+            /// Returns `None` if f has a zero on, or very near, the boundary
+            /// of the rectangle.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, roots::", stringify!($m), "::roots_in_rectangle};")]
+            /// // the zeros of sin z in −10 < Re z < 10 are kπ for k = −3 to 3
+            /// let roots = roots_in_rectangle(|z| z.sin(), |z| z.cos(), Complex::new(-10.0, -1.0), Complex::new(10.0, 1.0), 1e-6).unwrap();
+            /// assert_eq!(roots.len(), 7);
+            /// assert!(roots.iter().all(|root| root.converged && root.value.abs() < 1e-5));
+            /// ```
+            pub fn roots_in_rectangle(
+                f: impl Fn(Complex<$t>) -> Complex<$t>, df: impl Fn(Complex<$t>) -> Complex<$t>,
+                corner0: Complex<$t>, corner1: Complex<$t>, tol: $t
+            ) -> Option<Vec<Root<$t>>> {
+                // split points a little off centre, so that symmetric zeros
+                // do not fall on the dividing lines
+                const SPLITS: [f64; 4] = [0.5123, 0.4631, 0.5437, 0.4291];
+                // a bound on the splits, which a tolerance of zero around a
+                // multiple zero would otherwise never end
+                const MAX_DEPTH: usize = 256;
+                let lo = Complex::new(corner0.r.min(corner1.r), corner0.i.min(corner1.i));
+                let hi = Complex::new(corner0.r.max(corner1.r), corner0.i.max(corner1.i));
+                let mut boxes = vec![(lo, hi, zero_count(&f, lo, hi)?, 0)];
+                let mut roots = Vec::new();
+                while let Some((lo, hi, count, depth)) = boxes.pop() {
+                    if count == 0 {
+                        continue;
+                    }
+                    let centre = (lo + hi) * 0.5;
+                    let size = (hi - lo).abs();
+                    let small = size <= tol * centre.abs().max(1.0);
+                    if count == 1 {
+                        let root = newton(&f, &df, centre, tol, 50);
+                        let margin = tol * root.root.abs().max(1.0);
+                        let inside = root.root.r >= lo.r - margin && root.root.r <= hi.r + margin
+                            && root.root.i >= lo.i - margin && root.root.i <= hi.i + margin;
+                        if root.converged && inside {
+                            roots.push(root);
+                            continue;
+                        }
+                    }
+                    let value = f(centre);
+                    if small || depth == MAX_DEPTH {
+                        let root = Root { root: centre, value, iterations: 0, converged: small };
+                        roots.extend(std::iter::repeat_n(root, count));
+                        continue;
+                    }
+                    // halve the longer side
+                    let halves = SPLITS.iter().find_map(|&split| {
+                        let (mid0, mid1) = if hi.r - lo.r >= hi.i - lo.i {
+                            let r = lo.r + (hi.r - lo.r) * split as $t;
+                            (Complex::new(r, hi.i), Complex::new(r, lo.i))
+                        } else {
+                            let i = lo.i + (hi.i - lo.i) * split as $t;
+                            (Complex::new(hi.r, i), Complex::new(lo.r, i))
+                        };
+                        let first = zero_count(&f, lo, mid0)?;
+                        let second = zero_count(&f, mid1, hi)?;
+                        (first + second == count).then_some([(lo, mid0, first, depth + 1), (mid1, hi, second, depth + 1)])
+                    });
+                    match halves {
+                        Some(halves) => boxes.extend(halves),
+                        None => roots.push(Root { root: centre, value, iterations: 0, converged: false })
+                    }
+                }
+                Some(roots)
+            }
+
+            /// All zeros of an analytic function inside a circle, by the
+            /// Delves–Lyness method
+            ///
+            /// The moments s<sub>k</sub> = (1/2πi) ∮ w<sup>k</sup> f′/f dz,
+            /// with w the position relative to the circle scaled to the unit
+            /// disk, are the power sums of the enclosed zeros.  s<sub>0</sub>
+            /// counts them and Newton's identities turn the rest into the
+            /// polynomial with those zeros, whose roots are then polished by
+            /// Newton's method on f.  The trapezoid rule on the circle doubles
+            /// its points until the moments agree to √`tol`.  Zeros whose
+            /// polishing does not converge, such as multiple zeros, are
+            /// reported unpolished with `converged` false.
+            ///
+            /// The polynomial becomes ill-conditioned as the number of zeros
+            /// grows, so the circle should hold only a few of them; see
+            /// [`roots_in_rectangle`] for larger regions.
+            ///
+            /// Returns `None` if f vanishes on, or very near, the circle.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, roots::", stringify!($m), "::delves_lyness};")]
+            /// // the zeros of cos z − z inside |z| < 3
+            /// let roots = delves_lyness(|z| z.cos() - z, |z| -z.sin() - 1.0, Complex::new(0.0, 0.0), 3.0, 1e-6).unwrap();
+            /// assert_eq!(roots.len(), 1);
+            /// assert!((roots[0].root - Complex::new(0.7390851, 0.0)).abs() < 1e-5);
+            /// ```
+            pub fn delves_lyness(
+                f: impl Fn(Complex<$t>) -> Complex<$t>, df: impl Fn(Complex<$t>) -> Complex<$t>,
+                centre: Complex<$t>, radius: $t, tol: $t
+            ) -> Option<Vec<Root<$t>>> {
+                const MAX_POINTS: usize = 1 << 16;
+                // w and r f′/f at the points of the trapezoid rule
+                let sample = |theta: $t| {
+                    let w = Complex::<$t>::cis(theta);
+                    let z = centre + w * radius;
+                    let g = df(z) / f(z) * radius;
+                    (g.r.is_finite() && g.i.is_finite()).then_some((w, g))
+                };
+                let moments = |points: &[(Complex<$t>, Complex<$t>)], n: usize| {
+                    let mut sums = vec![Complex::new(0.0, 0.0); n + 1];
+                    for &(w, g) in points {
+                        let mut term = w * g;
+                        for sum in &mut sums {
+                            *sum += term;
+                            term *= w;
+                        }
+                    }
+                    sums.into_iter().map(|sum| sum / points.len() as $t).collect::<Vec<_>>()
+                };
+                let count = |s0: Complex<$t>| (s0.r.round() >= 0.0 && (s0.r - s0.r.round()).abs() < 0.25)
+                    .then_some(s0.r.round() as usize);
+                let accuracy = tol.sqrt();
+                let mut points = (0..16)
+                    .map(|j| sample(std::$t::consts::TAU * j as $t / 16.0))
+                    .collect::<Option<Vec<_>>>()?;
+                let mut s = moments(&points, 0);
+                let (n, s) = loop {
+                    let m = points.len();
+                    if m >= MAX_POINTS {
+                        return None;
+                    }
+                    for j in 0..m {
+                        points.push(sample(std::$t::consts::TAU * (2 * j + 1) as $t / (2 * m) as $t)?);
+                    }
+                    let n = count(moments(&points, 0)[0]).unwrap_or(0);
+                    let next = moments(&points, n.max(s.len() - 1));
+                    let agree = s.len() > n && s.iter().zip(&next)
+                        .all(|(a, b)| (*a - *b).abs() <= accuracy * b.abs().max(1.0));
+                    if agree {
+                        if let Some(n) = count(next[0]) {
+                            break (n, next);
+                        }
+                    }
+                    s = next;
+                };
+                // Newton's identities for the monic polynomial in w
+                let mut coeffs = vec![Complex::new(1.0, 0.0)];
+                for k in 1..=n {
+                    let sum = (1..k).fold(s[k], |sum, j| sum + coeffs[j] * s[k - j]);
+                    coeffs.push(-sum / k as $t);
+                }
+                let zeros = Polynomial::<$t>::new(coeffs).roots()?;
+                Some(zeros.into_iter().take(n).map(|w| {
+                    let z = centre + w * radius;
+                    let root = newton(&f, &df, z, tol, 50);
+                    let inside = (root.root - centre).abs() <= radius * (1.0 + tol);
+                    if root.converged && inside {
+                        root
+                    } else {
+                        Root { root: z, value: f(z), iterations: 0, converged: false }
+                    }
+                }).collect())
+            }
+
+            /// The sum of the squared magnitudes of `values`
+            fn norm_sqr<const N: usize>(values: &[Complex<$t>; N]) -> $t {
+                values.iter().map(|v| v.r * v.r + v.i * v.i).sum()
+            }
+
+            /// Damped Newton's method for the system f(z) = 0 of `N`
+            /// equations in `N` unknowns, with the Jacobian
+            /// `jacobian(z)[i][j]` = ∂f<sub>i</sub>/∂z<sub>j</sub>
+            ///
+            /// Each Newton step is solved by LU decomposition and then halved
+            /// until it reduces ‖f‖, so that the iteration also converges
+            /// from guesses that are not close.  Meant for a few equations:
+            /// the Jacobian is a dense `N` × `N` matrix.  Stops without
+            /// converging if the Jacobian is singular or no fraction of the
+            /// step reduces ‖f‖.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, roots::", stringify!($m), "::newton_system};")]
+            /// // z² + w² = 1 and z w = 2
+            /// let f = |&[z, w]: &[Complex<_>; 2]| [z * z + w * w - 1.0, z * w - 2.0];
+            /// let jacobian = |&[z, w]: &[Complex<_>; 2]| [[2.0 * z, 2.0 * w], [w, z]];
+            /// let root = newton_system(f, jacobian, [Complex::new(1.0, 1.0), Complex::new(1.0, -1.0)], 1e-6, 50);
+            /// assert!(root.converged);
+            /// let [z, w] = root.root;
+            /// assert!((z * w - 2.0).abs() < 1e-5 && (z * z + w * w - 1.0).abs() < 1e-5);
+            /// ```
+            pub fn newton_system<const N: usize>(
+                f: impl Fn(&[Complex<$t>; N]) -> [Complex<$t>; N],
+                jacobian: impl Fn(&[Complex<$t>; N]) -> [[Complex<$t>; N]; N],
+                z0: [Complex<$t>; N], tol: $t, max_iter: usize
+            ) -> SystemRoot<$t, N> {
+                let (mut z, mut value) = (z0, f(&z0));
+                let mut norm = norm_sqr(&value);
+                for iterations in 1..=max_iter {
+                    if norm == 0.0 {
+                        return SystemRoot { root: z, value, iterations: iterations - 1, converged: true };
+                    }
+                    let failed = SystemRoot { root: z, value, iterations: iterations - 1, converged: false };
+                    let matrix = Matrix::<$t>::new(N, jacobian(&z).iter().flatten().copied().collect());
+                    let Some(step) = matrix.lu().solve(&value) else {
+                        return failed;
+                    };
+                    // halve the step until ‖f‖ decreases
+                    let mut scale = 1.0;
+                    loop {
+                        let mut next = z;
+                        for (next, &dz) in next.iter_mut().zip(&step) {
+                            *next -= dz * scale;
+                        }
+                        let next_value = f(&next);
+                        let next_norm = norm_sqr(&next_value);
+                        if next_norm < norm || next_norm == 0.0 {
+                            let small = step.iter().zip(&next).all(|(&dz, &z)| small_step(dz * scale, z, tol));
+                            (z, value, norm) = (next, next_value, next_norm);
+                            if small {
+                                return SystemRoot { root: z, value, iterations, converged: true };
+                            }
+                            break;
+                        }
+                        scale *= 0.5;
+                        if scale < 1e-3 {
+                            // no descent, unless already at the rounding
+                            // error of a root
+                            let small = step.iter().zip(&z).all(|(&dz, &z)| small_step(dz, z, tol));
+                            return SystemRoot { converged: small, ..failed };
+                        }
+                    }
+                }
+                SystemRoot { root: z, value, iterations: max_iter, converged: norm == 0.0 }
+            }
+
+            /// Damped Newton's method for the system f(z) = 0 of `N`
+            /// analytic equations in `N` unknowns, estimating the Jacobian
+            ///
+            /// Each column of the Jacobian is estimated from f at four points
+            /// around z at the complex steps ±h and ±ih, whose average
+            /// cancels the error terms up to fourth order, for `4 N` extra
+            /// evaluations of f per iteration.  Otherwise the same as
+            /// [`newton_system`].
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, roots::", stringify!($m), "::newton_system_estimated};")]
+            /// // e^z = w and z + w = 2
+            #[doc = concat!("let f = |&[z, w]: &[Complex<", stringify!($t), ">; 2]| [z.exp() - w, z + w - 2.0];")]
+            /// let root = newton_system_estimated(f, [Complex::new(0.0, 0.0), Complex::new(1.0, 0.0)], 1e-6, 50);
+            /// assert!(root.converged);
+            /// let [z, w] = root.root;
+            /// assert!((z.exp() - w).abs() < 1e-5 && (z + w - 2.0).abs() < 1e-5);
+            /// ```
+            pub fn newton_system_estimated<const N: usize>(
+                f: impl Fn(&[Complex<$t>; N]) -> [Complex<$t>; N],
+                z0: [Complex<$t>; N], tol: $t, max_iter: usize
+            ) -> SystemRoot<$t, N> {
+                let jacobian = |z: &[Complex<$t>; N]| {
+                    let mut columns = [[Complex::new(0.0, 0.0); N]; N];
+                    for (j, column) in columns.iter_mut().enumerate() {
+                        let h = $t::EPSILON.powf(0.2) * z[j].abs().max(1.0);
+                        let mut direction = Complex::new(h, 0.0);
+                        for _ in 0..4 {
+                            let mut point = *z;
+                            point[j] += direction;
+                            for (sum, value) in column.iter_mut().zip(f(&point)) {
+                                *sum += value / (4.0 * direction);
+                            }
+                            direction = Complex::new(-direction.i, direction.r);
+                        }
+                    }
+                    let mut rows = [[Complex::new(0.0, 0.0); N]; N];
+                    for (i, row) in rows.iter_mut().enumerate() {
+                        for (j, entry) in row.iter_mut().enumerate() {
+                            *entry = columns[j][i];
+                        }
+                    }
+                    rows
+                };
+                newton_system(&f, jacobian, z0, tol, max_iter)
+            }
+
+            #[cfg(test)]
+            mod test {
+                use super::*;
+
+                #[test]
+                fn check_iterations() {
+                    let ep = 1e2 * $t::EPSILON;
+                    let f = |z: Complex<$t>| z.sin() - 2.0;
+                    let df = |z: Complex<$t>| z.cos();
+                    let guess = Complex::new(1.5, 1.0);
+                    for root in [
+                        newton(f, df, guess, ep, 50),
+                        secant(f, guess, guess + 0.1, ep, 50),
+                        muller(f, guess, guess + 0.1, guess - 0.1, ep, 50),
+                    ] {
+                        assert!(root.converged, "{root:?}");
+                        assert!(f(root.root).abs() < 10.0 * ep, "{root:?}");
+                    }
+                    // sin z = 2 at π/2 ± i ln(2 + √3)
+                    use std::$t::consts::FRAC_PI_2;
+                    let root = newton(f, df, guess, ep, 50).root;
+                    assert!((root.r - FRAC_PI_2).abs() < ep && (root.i.abs() - (2.0 + (3.0 as $t).sqrt()).ln()).abs() < ep);
+                    // Newton converges quadratically
+                    assert!(newton(f, df, guess, ep, 50).iterations < 8);
+                }
+
+                #[test]
+                fn check_failures() {
+                    let zero = Complex::new(0.0, 0.0);
+                    // a stationary point stops Newton
+                    let root = newton(|z| z * z + 1.0, |z| 2.0 * z, zero, 1e-6, 10);
+                    assert!(!root.converged && root.iterations == 0);
+                    // no root at all
+                    let root = secant(|z| z.exp(), Complex::new(0.0, 0.0), Complex::new(1.0, 0.0), 1e-6, 20);
+                    assert!(!root.converged);
+                    // an exact root is reported without iterating
+                    let root = muller(|z| z, 2.0.into(), 1.0.into(), zero, 1e-6, 10);
+                    assert_eq!((root.iterations, root.converged), (0, true));
+                }
+
+                #[test]
+                fn check_householder() {
+                    let ep = 1e2 * $t::EPSILON;
+                    // sin z = 2 again, with every order from Newton to fifth
+                    let d = |z: Complex<$t>| { let (s, c) = (z.sin(), z.cos()); [s - 2.0, c, -s, -c, s, c] };
+                    let guess = Complex::new(1.5, 1.0);
+                    let newton = newton(|z| z.sin() - 2.0, |z| z.cos(), guess, ep, 50);
+                    let orders = [
+                        householder(|z| { let [a, b, ..] = d(z); [a, b] }, guess, ep, 50),
+                        halley(|z| { let [a, b, c, ..] = d(z); [a, b, c] }, guess, ep, 50),
+                        householder(|z| { let [a, b, c, e, ..] = d(z); [a, b, c, e] }, guess, ep, 50),
+                        householder(d, guess, ep, 50),
+                    ];
+                    assert!(orders[0].converged && (orders[0].root - newton.root).abs() < ep);
+                    for pair in orders.windows(2) {
+                        assert!(pair[1].converged && (pair[1].root - newton.root).abs() < ep, "{pair:?}");
+                        assert!(pair[1].iterations <= pair[0].iterations, "{pair:?}");
+                    }
+                    assert!(orders[3].iterations < newton.iterations);
+                }
+
+                #[test]
+                fn check_rectangle() {
+                    let ep = 1e2 * $t::EPSILON;
+                    // a polynomial with a double root and a close pair
+                    let zeros = [Complex::new(0.5, 0.5), Complex::new(0.5, 0.5), Complex::new(-1.0, 0.25),
+                        Complex::new(-1.0, 0.26), Complex::new(2.0, -1.5), Complex::new(0.0, 3.0)];
+                    let f = |z: Complex<$t>| zeros.iter().fold(Complex::new(1.0, 0.0), |p, &r| p * (z - r));
+                    let df = |z: Complex<$t>| (0..zeros.len()).fold(Complex::new(0.0, 0.0), |sum, k| {
+                        sum + zeros.iter().enumerate().filter(|&(j, _)| j != k).fold(Complex::new(1.0, 0.0), |p, (_, &r)| p * (z - r))
+                    });
+                    let roots = roots_in_rectangle(f, df, Complex::new(-3.0, -2.0), Complex::new(3.0, 2.0), ep).unwrap();
+                    assert_eq!(roots.len(), 5, "{roots:?}");
+                    for zero in &zeros[..5] {
+                        let tol = if zero == &zeros[0] { ep.sqrt() } else { 1e3 * ep };
+                        assert!(roots.iter().any(|root| root.converged && (root.root - *zero).abs() < tol), "{zero:?} {roots:?}");
+                    }
+                    // no zeros, or a zero on the boundary
+                    let exp = |z: Complex<$t>| z.exp();
+                    assert_eq!(roots_in_rectangle(exp, exp, Complex::new(-1.0, -1.0), Complex::new(1.0, 1.0), ep), Some(vec![]));
+                    assert_eq!(roots_in_rectangle(f, df, Complex::new(-1.0, 0.25), Complex::new(1.0, 1.0), ep), None);
+                    // a zero tolerance still ends at a double zero
+                    let square = |z: Complex<$t>| (z - 0.5) * (z - 0.5);
+                    let roots = roots_in_rectangle(square, |z| 2.0 * (z - 0.5), Complex::new(-1.0, -1.0), Complex::new(1.0, 1.0), 0.0).unwrap();
+                    assert!(!roots.is_empty() && roots.len() <= 2, "{roots:?}");
+                    assert!(roots.iter().all(|root| !root.converged && (root.root - 0.5).abs() < ep.sqrt()), "{roots:?}");
+                }
+
+                #[test]
+                fn check_delves_lyness() {
+                    let ep = 1e2 * $t::EPSILON;
+                    // e^z (z − a)(z − b)(z − c) with one zero outside the circle
+                    let (a, b, c) = (Complex::new(0.3, -0.2), Complex::new(-0.5, 0.6), Complex::new(1.5, 1.5));
+                    let f = |z: Complex<$t>| z.exp() * (z - a) * (z - b) * (z - c);
+                    let df = |z: Complex<$t>| z.exp() * ((z - a) * (z - b) * (z - c)
+                        + (z - b) * (z - c) + (z - a) * (z - c) + (z - a) * (z - b));
+                    let roots = delves_lyness(f, df, Complex::new(0.0, 0.0), 1.0, ep).unwrap();
+                    assert_eq!(roots.len(), 2, "{roots:?}");
+                    for zero in [a, b] {
+                        assert!(roots.iter().any(|root| root.converged && (root.root - zero).abs() < 10.0 * ep), "{roots:?}");
+                    }
+                    // sin z has its zeros at −π, 0 and π inside |z| < 4
+                    let roots = delves_lyness(|z| z.sin(), |z| z.cos(), Complex::new(0.0, 0.0), 4.0, ep).unwrap();
+                    let mut zeros: Vec<$t> = roots.iter().map(|root| root.root.r).collect();
+                    zeros.sort_by(|x, y| x.partial_cmp(y).unwrap());
+                    assert_eq!(zeros.len(), 3);
+                    for (zero, k) in zeros.iter().zip([-1.0, 0.0, 1.0]) {
+                        assert!((zero - k * std::$t::consts::PI).abs() < 10.0 * ep);
+                    }
+                    // no zeros, or a zero on the circle
+                    let exp = |z: Complex<$t>| z.exp();
+                    assert_eq!(delves_lyness(exp, exp, Complex::new(0.0, 0.0), 2.0, ep), Some(vec![]));
+                    assert_eq!(delves_lyness(|z| z.sin(), |z| z.cos(), Complex::new(1.0, 0.0), 1.0, ep), None);
+                }
+
+                #[test]
+                fn check_newton_system() {
+                    let ep = 1e2 * $t::EPSILON;
+                    // z² + w² = 1, z w = 2: z² is a root of u² − u + 4
+                    let f = |&[z, w]: &[Complex<$t>; 2]| [z * z + w * w - 1.0, z * w - 2.0];
+                    let jacobian = |&[z, w]: &[Complex<$t>; 2]| [[2.0 * z, 2.0 * w], [w, z]];
+                    let guess = [Complex::new(1.0, 1.0), Complex::new(1.0, -1.0)];
+                    let exact = newton_system(f, jacobian, guess, ep, 50);
+                    let estimated = newton_system_estimated(f, guess, ep, 50);
+                    for root in [exact, estimated] {
+                        assert!(root.converged, "{root:?}");
+                        let [z, w] = root.root;
+                        let u = z * z;
+                        assert!((u * u - u + 4.0).abs() < 1e2 * ep, "{root:?}");
+                        assert!((z * w - 2.0).abs() < 10.0 * ep, "{root:?}");
+                    }
+                    assert!((exact.root[0] - estimated.root[0]).abs() < 10.0 * ep);
+                    assert!(exact.iterations < 10);
+                    // three unknowns: z = 1, w = i, v = −1
+                    let f = |&[z, w, v]: &[Complex<$t>; 3]| [z + w + v - Complex::new(0.0, 1.0), z * w * v + Complex::new(0.0, 1.0), z * z + w * w + v * v - 1.0];
+                    let root = newton_system_estimated(f, [Complex::new(1.5, 0.5), Complex::new(0.5, 1.5), Complex::new(-1.5, -0.5)], ep, 100);
+                    assert!(root.converged, "{root:?}");
+                    assert!(root.value.iter().all(|v| v.abs() < 1e2 * ep), "{root:?}");
+                }
+
+                #[test]
+                fn check_newton_system_failures() {
+                    let zero = Complex::new(0.0, 0.0);
+                    // a singular Jacobian
+                    let f = |&[z, w]: &[Complex<$t>; 2]| [z + w - 1.0, z + w + 1.0];
+                    let root = newton_system(f, |_| [[Complex::new(1.0, 0.0); 2]; 2], [zero; 2], 1e-6, 10);
+                    assert!(!root.converged && root.iterations == 0);
+                    // an exact root is reported without iterating
+                    let f = |&[z, w]: &[Complex<$t>; 2]| [z - w, z + w];
+                    let root = newton_system_estimated(f, [zero; 2], 1e-6, 10);
+                    assert_eq!((root.iterations, root.converged), (0, true));
+                }
+            }
+        }
+    }
+}
+roots_mod!(c32 for f32);
+roots_mod!(c64 for f64);
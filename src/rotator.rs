@@ -0,0 +1,193 @@
+//! Repeated multiplication by a unit complex number
+//!
+//! A [`Rotator`] is a numerically controlled oscillator: each sample it
+//! advances its state by a fixed phase step with a single complex multiply.
+//! Rounding errors make the state drift off the unit circle, so its
+//! magnitude is pulled back to 1 every few samples.
+
+use crate::Complex;
+
+/// Multiplication by a fixed unit complex number, e<sup>iω</sup> per sample
+///
+/// # Example
+/// ```
+/// use imaginary::{Complex, rotator::Rotator};
+/// let mut nco = Rotator::<f64>::new(0.1);
+/// let mut buf = vec![Complex::new(1.0, 0.0); 1000];
+/// nco.rotate(&mut buf);
+/// assert!((buf[999] - Complex::<f64>::cis(99.9)).abs() < 1e-12);
+/// assert!((nco.phase() - 100.0).abs() < 1e-12);
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Rotator<T: Copy> {
+    step: Complex<T>,
+    state: Complex<T>,
+    angle: T,
+    start: T,
+    count: u64,
+    interval: u32,
+    since: u32
+}
+
+impl<T: Copy> Rotator<T> {
+    /// The current state, e<sup>i(ωn + φ)</sup> after `n` samples
+    pub fn state(&self) -> Complex<T> {
+        self.state
+    }
+
+    /// Number of samples rotated so far
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Sets the number of samples between renormalizations, 256 by default
+    ///
+    /// An interval of 0 disables renormalization.  The count towards the
+    /// next renormalization starts again from the current sample.
+    pub fn with_interval(mut self, interval: u32) -> Rotator<T> {
+        self.interval = interval;
+        self.since = 0;
+        self
+    }
+}
+
+macro_rules! impl_rotator {
+    ($t: ident) => {
+        impl Rotator<$t> {
+            /// Creates a rotator stepping by `angle` radians per sample
+            pub fn new(angle: $t) -> Rotator<$t> {
+                Rotator {
+                    step: Complex::<$t>::cis(angle),
+                    state: Complex::new(1.0, 0.0),
+                    angle,
+                    start: 0.0,
+                    count: 0,
+                    interval: 256,
+                    since: 0
+                }
+            }
+
+            /// Sets the initial phase φ in radians, so the state after `n`
+            /// samples is e<sup>i(ωn + φ)</sup>
+            pub fn with_phase(mut self, phase: $t) -> Rotator<$t> {
+                self.start = phase;
+                self.reset();
+                self
+            }
+
+            /// The accumulated phase ωn + φ in radians, not wrapped
+            pub fn phase(&self) -> $t {
+                (self.start as f64 + self.angle as f64 * self.count as f64) as $t
+            }
+
+            /// Returns the current state and advances by one sample
+            pub fn advance(&mut self) -> Complex<$t> {
+                let out = self.state;
+                self.state *= self.step;
+                self.count += 1;
+                if self.interval != 0 {
+                    self.since += 1;
+                    if self.since >= self.interval {
+                        self.renormalize();
+                    }
+                }
+                out
+            }
+
+            /// Multiplies each sample by the advancing state
+            pub fn rotate(&mut self, buf: &mut [Complex<$t>]) {
+                for z in buf.iter_mut() {
+                    *z *= self.advance();
+                }
+            }
+
+            /// Fills `buf` with the advancing state
+            pub fn fill(&mut self, buf: &mut [Complex<$t>]) {
+                for z in buf.iter_mut() {
+                    *z = self.advance();
+                }
+            }
+
+            /// Resets the state to the initial phase
+            pub fn reset(&mut self) {
+                self.state = Complex::<$t>::cis(self.start);
+                self.count = 0;
+                self.since = 0;
+            }
+
+            /// Pulls the state back onto the unit circle
+            ///
+            /// One Newton step for 1/|s|, s(3 − |s|²)/2, is enough since the
+            /// drift is tiny, and avoids a square root.
+            fn renormalize(&mut self) {
+                let s = self.state;
+                self.state = s * (1.5 - 0.5 * (s.r * s.r + s.i * s.i));
+                self.since = 0;
+            }
+        }
+    }
+}
+impl_rotator!(f32);
+impl_rotator!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renormalization() {
+        let n = 10_000_000;
+        let mut fixed = Rotator::<f32>::new(0.01);
+        let mut drifting = Rotator::<f32>::new(0.01).with_interval(0);
+        for _ in 0..n {
+            fixed.advance();
+            drifting.advance();
+        }
+        assert!((fixed.state().abs() - 1.0).abs() < 1e-5, "{}", fixed.state().abs());
+        assert!((drifting.state().abs() - 1.0).abs() > 1e-4, "{}", drifting.state().abs());
+        assert_eq!(fixed.count(), n);
+    }
+
+    #[test]
+    fn interval_zero_never_counts() {
+        let mut r = Rotator::<f64>::new(0.1).with_interval(0);
+        r.since = u32::MAX;
+        r.advance();
+        assert_eq!(r.since, u32::MAX);
+    }
+
+    #[test]
+    fn lowered_interval() {
+        let mut r = Rotator::<f64>::new(0.1);
+        for _ in 0..100 {
+            r.advance();
+        }
+        let mut r = r.with_interval(10);
+        assert_eq!(r.since, 0);
+        for _ in 0..9 {
+            r.advance();
+        }
+        assert_eq!(r.since, 9);
+        r.advance();
+        assert_eq!(r.since, 0);
+    }
+
+    #[test]
+    fn phase() {
+        let mut r = Rotator::<f64>::new(-0.3);
+        let mut buf = [Complex::new(0.0, 0.0); 50];
+        r.fill(&mut buf);
+        for (k, &z) in buf.iter().enumerate() {
+            assert!((z - Complex::<f64>::cis(-0.3 * k as f64)).abs() < 1e-13);
+        }
+        assert!((r.phase() + 15.0).abs() < 1e-12);
+        r.reset();
+        assert_eq!((r.state(), r.phase()), (Complex::new(1.0, 0.0), 0.0));
+        let mut r = Rotator::<f64>::new(0.2).with_phase(1.0);
+        assert_eq!(r.advance(), Complex::<f64>::cis(1.0));
+        r.advance();
+        assert!((r.phase() - 1.4).abs() < 1e-15);
+        r.reset();
+        assert_eq!(r.state(), Complex::<f64>::cis(1.0));
+    }
+}
@@ -0,0 +1,166 @@
+//! Representations of complex numbers for serde
+//!
+//! With the `serde` feature, [`Complex`] implements `Serialize` and
+//! `Deserialize` as a struct, `{"r": 3.0, "i": 4.0}` in JSON.  The
+//! [`tuple`](mod@tuple) and [`string`](mod@string) modules give the forms
+//! `[3.0, 4.0]` and `"3 + 4*i"` instead, for use with
+//! `#[serde(with = ...)]` on a field, and the wrappers [`Tuple`] and
+//! [`Text`] do the same inside other types, such as vectors.  Strings are read by [`FromStr`](core::str::FromStr), so
+//! `"3+4i"` and `"3 + 4j"` are accepted too.
+//!
+//! # Example
+//! ```
+//! use imaginary::Complex;
+//! use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize, Debug, PartialEq)]
+//! struct Config {
+//!     gain: Complex<f64>,
+//!     #[serde(with = "imaginary::serde::tuple")]
+//!     offset: Complex<f64>,
+//!     #[serde(with = "imaginary::serde::string")]
+//!     load: Complex<f64>
+//! }
+//! let config = Config {
+//!     gain: Complex::new(0.5, 1.0),
+//!     offset: Complex::new(-1.0, 0.0),
+//!     load: Complex::new(50.0, -25.0)
+//! };
+//! let json = serde_json::to_string(&config).unwrap();
+//! assert_eq!(json, r#"{"gain":{"r":0.5,"i":1.0},"offset":[-1.0,0.0],"load":"50 - 25*i"}"#);
+//! assert_eq!(serde_json::from_str::<Config>(&json).unwrap(), config);
+//! ```
+
+use crate::Complex;
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// A complex number as the pair `[r, i]`, for `#[serde(with = "imaginary::serde::tuple")]`
+pub mod tuple {
+    use super::*;
+
+    /// Writes `z` as the pair `[r, i]`
+    pub fn serialize<T, S>(z: &Complex<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where T: Serialize + Copy, S: Serializer {
+        (z.r, z.i).serialize(serializer)
+    }
+
+    /// Reads a complex number from the pair `[r, i]`
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Complex<T>, D::Error>
+    where T: Deserialize<'de> + Copy, D: Deserializer<'de> {
+        let (r, i) = <(T, T)>::deserialize(deserializer)?;
+        Ok(Complex::new(r, i))
+    }
+}
+
+/// A complex number as a string such as `"3 + 4*i"`, for
+/// `#[serde(with = "imaginary::serde::string")]`
+pub mod string {
+    use super::*;
+    use core::fmt::Display;
+    use core::marker::PhantomData;
+    use core::str::FromStr;
+
+    /// Writes `z` as [`Display`] does
+    pub fn serialize<T, S>(z: &Complex<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where T: Copy, Complex<T>: Display, S: Serializer {
+        serializer.collect_str(z)
+    }
+
+    /// Reads a complex number from a string, as [`FromStr`] does
+    pub fn deserialize<'de, T, D>(deserializer: D) -> Result<Complex<T>, D::Error>
+    where T: Copy, Complex<T>: FromStr, <Complex<T> as FromStr>::Err: Display, D: Deserializer<'de> {
+        deserializer.deserialize_str(Visitor(PhantomData))
+    }
+
+    struct Visitor<T>(PhantomData<T>);
+
+    impl<T> ::serde::de::Visitor<'_> for Visitor<T>
+    where T: Copy, Complex<T>: FromStr, <Complex<T> as FromStr>::Err: Display {
+        type Value = Complex<T>;
+
+        fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str("a complex number such as \"3 + 4*i\"")
+        }
+
+        fn visit_str<E: ::serde::de::Error>(self, s: &str) -> Result<Complex<T>, E> {
+            s.parse().map_err(E::custom)
+        }
+    }
+}
+
+/// A complex number serialized as the pair `[r, i]`
+///
+/// # Example
+/// ```
+/// use imaginary::{Complex, serde::Tuple};
+/// let samples = vec![Tuple(Complex::new(1.0, -1.0)), Tuple(Complex::new(0.0, 2.0))];
+/// assert_eq!(serde_json::to_string(&samples).unwrap(), "[[1.0,-1.0],[0.0,2.0]]");
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Tuple<T: Copy>(pub Complex<T>);
+
+impl<T: Serialize + Copy> Serialize for Tuple<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        tuple::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de> + Copy> Deserialize<'de> for Tuple<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Tuple<T>, D::Error> {
+        tuple::deserialize(deserializer).map(Tuple)
+    }
+}
+
+/// A complex number serialized as a string such as `"3 + 4*i"`
+///
+/// # Example
+/// ```
+/// use imaginary::{Complex, serde::Text};
+/// let z: Vec<Text<f64>> = serde_json::from_str(r#"["1 - i", "2j", "-0.5"]"#).unwrap();
+/// assert_eq!(z[1], Text(Complex::new(0.0, 2.0)));
+/// ```
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Text<T: Copy>(pub Complex<T>);
+
+impl<T: Copy> Serialize for Text<T> where Complex<T>: core::fmt::Display {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        string::serialize(&self.0, serializer)
+    }
+}
+
+impl<'de, T: Copy> Deserialize<'de> for Text<T>
+where Complex<T>: core::str::FromStr, <Complex<T> as core::str::FromStr>::Err: core::fmt::Display {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Text<T>, D::Error> {
+        string::deserialize(deserializer).map(Text)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn representations() {
+        let z = Complex::new(1.5f32, -0.25);
+        assert_eq!(serde_json::to_string(&z).unwrap(), r#"{"r":1.5,"i":-0.25}"#);
+        assert_eq!(serde_json::from_str::<Complex<f32>>(r#"{"i":-0.25,"r":1.5}"#).unwrap(), z);
+        assert_eq!(serde_json::from_str::<Complex<i32>>(r#"{"r":3,"i":4}"#).unwrap(), Complex::new(3, 4));
+        assert_eq!(serde_json::to_string(&Tuple(z)).unwrap(), "[1.5,-0.25]");
+        assert_eq!(serde_json::from_str::<Tuple<f32>>("[1.5,-0.25]").unwrap(), Tuple(z));
+        assert_eq!(serde_json::to_string(&Text(z)).unwrap(), r#""1.5 - 0.25*i""#);
+        assert_eq!(serde_json::from_str::<Text<f32>>(r#""1.5-0.25i""#).unwrap(), Text(z));
+        assert_eq!(serde_json::from_str::<Text<i64>>(r#""-i""#).unwrap(), Text(Complex::new(0, -1)));
+        // round trips keep every bit
+        let z = Complex::new(0.1f64, -1.0 / 3.0);
+        assert_eq!(serde_json::from_str::<Text<f64>>(&serde_json::to_string(&Text(z)).unwrap()).unwrap(), Text(z));
+    }
+
+    #[test]
+    fn errors() {
+        assert!(serde_json::from_str::<Complex<f64>>(r#"{"r":1.0}"#).is_err());
+        assert!(serde_json::from_str::<Tuple<f64>>("[1.0]").is_err());
+        assert!(serde_json::from_str::<Tuple<f64>>("[1.0, 2.0, 3.0]").is_err());
+        let error = serde_json::from_str::<Text<f64>>(r#""3 + 4""#).unwrap_err();
+        assert!(error.to_string().contains("invalid complex number syntax at byte 5"), "{error}");
+        assert!(serde_json::from_str::<Text<f64>>("3").is_err());
+    }
+}
@@ -0,0 +1,881 @@
+//! Analysis of uniformly sampled complex signals
+
+use crate::Complex;
+
+/// A damped complex exponential `amplitude · poleⁿ` at sample `n`
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Exponential<T: Copy> {
+    /// ratio between consecutive samples, inside the unit circle when damped
+    pub pole: Complex<T>,
+    /// value at sample 0
+    pub amplitude: Complex<T>
+}
+
+/// DC offset and gain and phase imbalance between the I and Q channels of
+/// a receiver
+///
+/// A sample x = I + iQ is received as dc + I' + iQ', with I' = I and
+/// Q' = gain (Q cos phase + I sin phase), so that `gain` = 1 and `phase` = 0
+/// describe a balanced receiver.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct IqImbalance<T: Copy> {
+    /// offset added to every sample
+    pub dc: Complex<T>,
+    /// gain of the Q channel relative to the I channel
+    pub gain: T,
+    /// phase error of the Q channel in radians, positive when it leans
+    /// towards I
+    pub phase: T
+}
+
+/// A second-order section, the digital filter
+///
+/// H(z) = (b<sub>0</sub> + b<sub>1</sub> z<sup>−1</sup> + b<sub>2</sub>
+/// z<sup>−2</sup>) / (a<sub>0</sub> + a<sub>1</sub> z<sup>−1</sup> +
+/// a<sub>2</sub> z<sup>−2</sup>)
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Biquad<T: Copy> {
+    /// numerator coefficients
+    pub b: [T; 3],
+    /// denominator coefficients
+    pub a: [T; 3]
+}
+
+/// A cascade of second-order sections
+///
+/// The response is the product of the responses of the sections.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Sos<T: Copy> {
+    /// sections, in the order the signal passes through them
+    pub sections: Vec<Biquad<T>>
+}
+
+impl<T: Copy> Biquad<T> {
+    /// Creates a section from its numerator and denominator coefficients
+    pub fn new(b: [T; 3], a: [T; 3]) -> Biquad<T> {
+        Biquad { b, a }
+    }
+
+    /// Cascades `self` followed by `next`
+    pub fn cascade(self, next: Biquad<T>) -> Sos<T> {
+        Sos { sections: vec![self, next] }
+    }
+}
+
+impl<T: Copy> Sos<T> {
+    /// Creates a cascade from its sections
+    pub fn new(sections: Vec<Biquad<T>>) -> Sos<T> {
+        Sos { sections }
+    }
+
+    /// Cascades `self` followed by `next`
+    pub fn cascade(mut self, next: impl Into<Sos<T>>) -> Sos<T> {
+        self.sections.extend(next.into().sections);
+        self
+    }
+}
+
+impl<T: Copy> From<Biquad<T>> for Sos<T> {
+    fn from(section: Biquad<T>) -> Sos<T> {
+        Sos { sections: vec![section] }
+    }
+}
+
+/// A finite impulse response filter with complex taps, carrying its state
+/// from one block of a stream to the next
+///
+/// y<sub>n</sub> = Σ h<sub>k</sub> x<sub>n−k</sub>, with the samples before
+/// the first block taken as zero.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fir<T: Copy> {
+    taps: Vec<Complex<T>>,
+    history: Vec<Complex<T>>
+}
+
+impl<T: Copy> Fir<T> {
+    /// The taps h<sub>k</sub>, the impulse response of the filter
+    pub fn taps(&self) -> &[Complex<T>] {
+        &self.taps
+    }
+}
+
+macro_rules! impl_biquad {
+    ($m: ident for $t: ident) => {
+        impl Biquad<$t> {
+            /// Evaluates the transfer function at `z`
+            pub fn eval(&self, z: Complex<$t>) -> Complex<$t> {
+                let w = 1.0 / z;
+                let [b0, b1, b2] = self.b;
+                let [a0, a1, a2] = self.a;
+                ((b2 * w + b1) * w + b0) / ((a2 * w + a1) * w + a0)
+            }
+
+            /// Frequency response at `w` radians per sample
+            ///
+            /// # Example
+            /// ```
+            /// use imaginary::signal::Biquad;
+            /// // zeros at z = ±i notch out a quarter of the sample rate
+            /// let notch = Biquad::<f64>::new([1.0, 0.0, 1.0], [1.0, 0.0, 0.81]);
+            /// assert!(notch.response(std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+            /// ```
+            pub fn response(&self, w: $t) -> Complex<$t> {
+                self.eval(Complex::<$t>::cis(w))
+            }
+
+            /// Zeros of the section in the z-plane
+            ///
+            /// A section with b<sub>0</sub> = 0 has fewer than two finite
+            /// zeros.
+            pub fn zeros(&self) -> Vec<Complex<$t>> {
+                Self::roots(self.b)
+            }
+
+            /// Poles of the section in the z-plane
+            ///
+            /// A section with a<sub>0</sub> = 0 has fewer than two finite
+            /// poles.
+            pub fn poles(&self) -> Vec<Complex<$t>> {
+                Self::roots(self.a)
+            }
+
+            /// Roots of c<sub>0</sub> z² + c<sub>1</sub> z + c<sub>2</sub>
+            fn roots([c0, c1, c2]: [$t; 3]) -> Vec<Complex<$t>> {
+                if c0 != 0.0 {
+                    crate::$m::quad(c0, c1, c2).to_vec()
+                } else if c1 != 0.0 {
+                    vec![Complex::new(-c2 / c1, 0.0)]
+                } else {
+                    Vec::new()
+                }
+            }
+        }
+
+        impl Sos<$t> {
+            /// Evaluates the transfer function at `z`
+            pub fn eval(&self, z: Complex<$t>) -> Complex<$t> {
+                self.sections.iter().fold(Complex::new(1.0, 0.0), |h, s| h * s.eval(z))
+            }
+
+            /// Frequency response at `w` radians per sample
+            pub fn response(&self, w: $t) -> Complex<$t> {
+                self.eval(Complex::<$t>::cis(w))
+            }
+
+            /// Zeros of all sections in the z-plane
+            pub fn zeros(&self) -> Vec<Complex<$t>> {
+                self.sections.iter().flat_map(|s| s.zeros()).collect()
+            }
+
+            /// Poles of all sections in the z-plane
+            pub fn poles(&self) -> Vec<Complex<$t>> {
+                self.sections.iter().flat_map(|s| s.poles()).collect()
+            }
+        }
+    }
+}
+impl_biquad!(c32 for f32);
+impl_biquad!(c64 for f64);
+
+macro_rules! impl_fir {
+    ($m: ident for $t: ident) => {
+        impl Fir<$t> {
+            /// Creates a filter with the impulse response `taps` and a zero
+            /// initial state
+            pub fn new(taps: Vec<Complex<$t>>) -> Fir<$t> {
+                let history = vec![Complex::new(0.0, 0.0); taps.len().saturating_sub(1)];
+                Fir { taps, history }
+            }
+
+            /// Filters the next block of the stream, `input`, into `output`
+            ///
+            /// Blocks can be of any length, and filtering a stream block by
+            /// block gives the same output, up to rounding, as filtering it
+            /// all at once.
+            ///
+            /// # Panics
+            /// Panics if `input` and `output` differ in length.
+            ///
+            /// # Example
+            /// ```
+            /// use imaginary::{Complex, signal::Fir};
+            /// // a two sample moving sum
+            /// let mut fir = Fir::<f64>::new(vec![Complex::new(1.0, 0.0); 2]);
+            /// let mut y = [Complex::new(0.0, 0.0); 2];
+            /// fir.process(&[Complex::new(1.0, 0.0), Complex::new(2.0, 0.0)], &mut y);
+            /// assert_eq!(y, [Complex::new(1.0, 0.0), Complex::new(3.0, 0.0)]);
+            /// fir.process(&[Complex::new(0.0, 1.0), Complex::new(0.0, 0.0)], &mut y);
+            /// assert_eq!(y, [Complex::new(2.0, 1.0), Complex::new(0.0, 1.0)]);
+            /// ```
+            pub fn process(&mut self, input: &[Complex<$t>], output: &mut [Complex<$t>]) {
+                assert_eq!(input.len(), output.len(), "input and output lengths differ");
+                self.history.extend_from_slice(input);
+                Self::convolve(&self.taps, &self.history, output);
+                self.history.drain(..input.len());
+            }
+
+            /// Clears the state, as if the filter had only seen zeros
+            pub fn reset(&mut self) {
+                self.history.fill(Complex::new(0.0, 0.0));
+            }
+
+            /// Writes to each `out[j]` the sum of `taps[k] * x[j + taps.len() - 1 - k]`
+            ///
+            /// Each tap is broadcast into a block, so the products are
+            /// accumulated by the vectorized multiply-accumulate kernel.
+            fn convolve(taps: &[Complex<$t>], x: &[Complex<$t>], out: &mut [Complex<$t>]) {
+                const BLOCK: usize = 256;
+                out.fill(Complex::new(0.0, 0.0));
+                let (n, delay) = (out.len(), taps.len().saturating_sub(1));
+                let mut splat = [Complex::new(0.0, 0.0); BLOCK];
+                for (k, &h) in taps.iter().enumerate() {
+                    splat.fill(h);
+                    let x = &x[delay - k..delay - k + n];
+                    for (acc, x) in out.chunks_mut(BLOCK).zip(x.chunks(BLOCK)) {
+                        crate::slice::$m::mul_acc(acc, x, &splat[..acc.len()]);
+                    }
+                }
+            }
+        }
+    }
+}
+impl_fir!(c32 for f32);
+impl_fir!(c64 for f64);
+
+macro_rules! signal_mod {
+    ($m: ident for $t: ident) => {
+        /// Analysis of uniformly sampled complex signals
+        pub mod $m {
+            use crate::Complex;
+            use crate::poly::Polynomial;
+            use crate::linalg::$m::least_squares;
+            use super::{Exponential, Fir, IqImbalance};
+
+            /// Evaluates the Z-transform of a finite sequence at `z`
+            ///
+            /// X(z) = Σ x<sub>n</sub> z<sup>−n</sup>, with `x[0]` the sample
+            /// at n = 0, computed by Horner's method in z<sup>−1</sup>.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, signal::", stringify!($m), "::eval_z};")]
+            /// let x = [Complex::new(1.0, 0.0), Complex::new(2.0, 0.0)];
+            /// assert_eq!(eval_z(&x, Complex::new(2.0, 0.0)), Complex::new(2.0, 0.0));
+            /// ```
+            pub fn eval_z(x: &[Complex<$t>], z: Complex<$t>) -> Complex<$t> {
+                let w = 1.0 / z;
+                x.iter().rev().fold(Complex::new(0.0, 0.0), |sum, &x| sum * w + x)
+            }
+
+            /// Samples the Z-transform of a finite sequence at `n_points`
+            /// evenly spaced points around the unit circle
+            ///
+            /// Returns X(e<sup>iω<sub>k</sub></sup>) for
+            /// ω<sub>k</sub> = 2πk / `n_points`, k = 0, 1, …, `n_points` − 1.
+            /// The sequence is folded modulo `n_points` and transformed with
+            /// an FFT, which gives exact samples for any sequence length.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, signal::", stringify!($m), "::eval_on_unit_circle};")]
+            /// let x = [Complex::new(1.0, 0.0), Complex::new(1.0, 0.0)];
+            /// let h = eval_on_unit_circle(&x, 4);
+            /// assert!((h[0] - 2.0).abs() < 1e-6);
+            /// assert!((h[2] - 0.0).abs() < 1e-6);
+            /// ```
+            pub fn eval_on_unit_circle(x: &[Complex<$t>], n_points: usize) -> Vec<Complex<$t>> {
+                if n_points == 0 {
+                    return Vec::new();
+                }
+                let mut buf = vec![Complex::new(0.0, 0.0); n_points];
+                for (k, &x) in x.iter().enumerate() {
+                    buf[k % n_points] += x;
+                }
+                crate::fft::$m::fft(&mut buf);
+                buf
+            }
+
+            /// Frequency response of the digital filter `b / a` at `n`
+            /// frequencies evenly spaced over [0, π)
+            ///
+            /// H(e<sup>iω</sup>) = Σ b<sub>k</sub> e<sup>−iωk</sup> /
+            /// Σ a<sub>k</sub> e<sup>−iωk</sup>
+            ///
+            /// Returns the frequencies ω<sub>k</sub> = πk / `n` in radians per
+            /// sample and the response at each.  Use [`freqz_at`] for other
+            /// frequencies.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::signal::", stringify!($m), "::freqz;")]
+            /// // two point moving average
+            /// let (w, h) = freqz(&[0.5, 0.5], &[1.0], 4);
+            /// assert_eq!(w.len(), 4);
+            /// assert!((h[0] - 1.0).abs() < 1e-6);
+            /// assert!((h[2].abs().powi(2) - 0.5).abs() < 1e-6);
+            /// ```
+            pub fn freqz(b: &[$t], a: &[$t], n: usize) -> (Vec<$t>, Vec<Complex<$t>>) {
+                use std::$t::consts::PI;
+                let to_complex = |c: &[$t]| -> Vec<Complex<$t>> { c.iter().map(|&c| c.into()).collect() };
+                let mut num = eval_on_unit_circle(&to_complex(b), 2 * n);
+                let den = eval_on_unit_circle(&to_complex(a), 2 * n);
+                num.truncate(n);
+                for (h, &d) in num.iter_mut().zip(&den) {
+                    *h /= d;
+                }
+                let w = (0..n).map(|k| PI * k as $t / n as $t).collect();
+                (w, num)
+            }
+
+            /// Frequency response of the digital filter `b / a` at the given
+            /// frequencies in radians per sample
+            ///
+            /// See [`freqz`].
+            pub fn freqz_at(b: &[$t], a: &[$t], w: &[$t]) -> Vec<Complex<$t>> {
+                w.iter().map(|&w| {
+                    let z = Complex::<$t>::cis(w);
+                    let num = b.iter().rev().fold(Complex::new(0.0, 0.0), |sum, &b| sum / z + b);
+                    let den = a.iter().rev().fold(Complex::new(0.0, 0.0), |sum, &a| sum / z + a);
+                    num / den
+                }).collect()
+            }
+
+            /// Group delay of the digital filter `b / a` at the given
+            /// frequencies in radians per sample
+            ///
+            /// τ(ω) = −dφ/dω is computed analytically from the response of
+            /// each polynomial and of its ramped coefficients k c<sub>k</sub>,
+            /// as Re(Σ k c<sub>k</sub> e<sup>−iωk</sup> / Σ c<sub>k</sub>
+            /// e<sup>−iωk</sup>), so no phase unwrapping is needed.  The delay
+            /// is `NAN` at frequencies where the response is zero.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::signal::", stringify!($m), "::group_delay;")]
+            /// // symmetric FIR filters have constant delay
+            /// let tau = group_delay(&[1.0, 2.0, 3.0, 2.0, 1.0], &[1.0], &[0.0, 0.5, 1.0]);
+            /// for tau in tau {
+            ///     assert!((tau - 2.0).abs() < 1e-5);
+            /// }
+            /// ```
+            pub fn group_delay(b: &[$t], a: &[$t], w: &[$t]) -> Vec<$t> {
+                // Re(Σ k c_k z⁻ᵏ / Σ c_k z⁻ᵏ)
+                fn delay(c: &[$t], z_inv: Complex<$t>) -> $t {
+                    let zero = Complex::new(0.0, 0.0);
+                    let (sum, ramp) = c.iter().enumerate().rev().fold((zero, zero), |(sum, ramp), (k, &c)| {
+                        (sum * z_inv + c, ramp * z_inv + k as $t * c)
+                    });
+                    if sum == zero {
+                        $t::NAN
+                    } else {
+                        (ramp / sum).r
+                    }
+                }
+                w.iter().map(|&w| {
+                    let z_inv = Complex::<$t>::cis(-w);
+                    delay(b, z_inv) - delay(a, z_inv)
+                }).collect()
+            }
+
+            /// Unwrapped phase of a sequence of complex values
+            ///
+            /// Equivalent to [`unwrap_angles`] applied to the
+            /// [`angle`](crate::Complex::angle) of each value.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, signal::", stringify!($m), "::unwrap_phase};")]
+            #[doc = concat!("use std::", stringify!($t), "::consts::PI;")]
+            #[doc = concat!("let z: Vec<_> = (0..8).map(|k| Complex::<", stringify!($t), ">::cis(k as _)).collect();")]
+            /// let phase = unwrap_phase(&z, PI);
+            /// for (k, phase) in phase.iter().enumerate() {
+            #[doc = concat!("    assert!((phase - k as ", stringify!($t), ").abs() < 1e-5);")]
+            /// }
+            /// ```
+            pub fn unwrap_phase(z: &[Complex<$t>], discont: $t) -> Vec<$t> {
+                let angles: Vec<$t> = z.iter().map(|z| z.angle()).collect();
+                unwrap_angles(&angles, discont)
+            }
+
+            /// Removes jumps of 2π from a sequence of angles in radians
+            ///
+            /// Wherever consecutive angles differ by at least `discont`, a
+            /// multiple of 2π is added to the rest of the sequence to bring the
+            /// difference into [−π, π].  A `discont` below π behaves as π.
+            pub fn unwrap_angles(angles: &[$t], discont: $t) -> Vec<$t> {
+                use std::$t::consts::{PI, TAU};
+                let discont = discont.max(PI);
+                let mut out = Vec::with_capacity(angles.len());
+                let mut offset = 0.0;
+                for (k, &angle) in angles.iter().enumerate() {
+                    if k > 0 {
+                        let d = angle - angles[k - 1];
+                        if d.abs() >= discont {
+                            let mut wrapped = (d + PI).rem_euclid(TAU) - PI;
+                            if wrapped == -PI && d > 0.0 {
+                                wrapped = PI;
+                            }
+                            offset += wrapped - d;
+                        }
+                    }
+                    out.push(angle + offset);
+                }
+                out
+            }
+
+            /// Magnitude in decibels and phase in degrees of a frequency
+            /// response
+            ///
+            /// The magnitude is 20 log<sub>10</sub>|h| and the phase is the
+            /// wrapped [`angle`](crate::Complex::angle) in (−180°, 180°]; see
+            /// [`unwrap_phase`] for a continuous phase.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, signal::", stringify!($m), "::to_bode};")]
+            /// let (mag, phase) = to_bode(&[Complex::new(0.0, 10.0)]);
+            /// assert!((mag[0] - 20.0).abs() < 1e-5);
+            /// assert!((phase[0] - 90.0).abs() < 1e-5);
+            /// ```
+            pub fn to_bode(h: &[Complex<$t>]) -> (Vec<$t>, Vec<$t>) {
+                let mut mag = Vec::with_capacity(h.len());
+                let mut phase = Vec::with_capacity(h.len());
+                for h in h {
+                    mag.push(20.0 * crate::math::$m::log10(h.abs()));
+                    phase.push(h.angle().to_degrees());
+                }
+                (mag, phase)
+            }
+
+            /// Fits a sum of `order` damped complex exponentials to `samples`
+            /// with Prony's method
+            ///
+            /// The poles are the roots of the least squares linear prediction
+            /// polynomial, x<sub>n</sub> + Σ c<sub>j</sub> x<sub>n−j</sub> = 0,
+            /// and the amplitudes are then fitted by least squares.  Returns
+            /// `None` if there are fewer than `2 · order` samples or the root
+            /// finder fails.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, signal::", stringify!($m), "::prony};")]
+            #[doc = concat!("let pole = Complex::<", stringify!($t), ">::new(0.6, 0.7);")]
+            /// let samples: Vec<_> = (0..8).map(|n| 3.0 * pole.powf(n as _)).collect();
+            /// let fit = prony(&samples, 1).unwrap();
+            /// assert!((fit[0].pole - pole).abs() < 1e-4);
+            /// assert!((fit[0].amplitude - 3.0).abs() < 1e-4);
+            /// ```
+            pub fn prony(samples: &[Complex<$t>], order: usize) -> Option<Vec<Exponential<$t>>> {
+                let n = samples.len();
+                if n < 2 * order {
+                    return None;
+                }
+                if order == 0 {
+                    return Some(Vec::new());
+                }
+                let prediction = (1..=order)
+                    .map(|j| samples[order - j..n - j].to_vec())
+                    .collect();
+                let rhs: Vec<Complex<$t>> = samples[order..].iter().map(|&x| -x).collect();
+                let mut coeffs = vec![Complex::new(1.0, 0.0)];
+                coeffs.extend(least_squares(prediction, &rhs));
+                let poles = Polynomial::new(coeffs).roots()?;
+                let vandermonde = poles.iter().map(|&pole| {
+                    let mut power = Complex::new(1.0, 0.0);
+                    (0..n).map(|_| {
+                        let value = power;
+                        power *= pole;
+                        value
+                    }).collect()
+                }).collect();
+                let amplitudes = least_squares(vandermonde, samples);
+                Some(poles.into_iter().zip(amplitudes)
+                    .map(|(pole, amplitude)| Exponential { pole, amplitude })
+                    .collect())
+            }
+
+            /// Estimates the DC offset and IQ imbalance of `samples` blindly,
+            /// from their second order statistics
+            ///
+            /// The DC offset is the mean.  Once it is removed, a balanced
+            /// signal is assumed to be circular, with I and Q uncorrelated and
+            /// of equal power, as for most modulations and noise, and the gain
+            /// and phase are fitted to the powers and the correlation of the
+            /// received channels.  Returns a balanced estimate with zero
+            /// offset for an empty buffer.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, signal::", stringify!($m), "::{correct_imbalance, estimate_imbalance}};")]
+            /// // a tone received with the Q channel 10 % hot and an offset
+            #[doc = concat!("use std::", stringify!($t), "::consts::FRAC_PI_4;")]
+            #[doc = concat!("let mut x: Vec<_> = (0..1000).map(|n| Complex::<", stringify!($t), ">::cis(FRAC_PI_4 * n as ", stringify!($t), ")).collect();")]
+            /// for z in &mut x {
+            ///     *z = Complex::new(z.r + 0.2, 1.1 * z.i);
+            /// }
+            /// let imbalance = estimate_imbalance(&x);
+            /// assert!((imbalance.gain - 1.1).abs() < 1e-3 && (imbalance.dc.r - 0.2).abs() < 1e-3);
+            /// correct_imbalance(&mut x, &imbalance);
+            /// assert!((x[0] - Complex::new(1.0, 0.0)).abs() < 1e-2);
+            /// ```
+            pub fn estimate_imbalance(samples: &[Complex<$t>]) -> IqImbalance<$t> {
+                if samples.is_empty() {
+                    return IqImbalance { dc: Complex::new(0.0, 0.0), gain: 1.0, phase: 0.0 };
+                }
+                let n = samples.len() as $t;
+                let dc = samples.iter().fold(Complex::new(0.0, 0.0), |sum, &z| sum + z) / n;
+                let (mut ii, mut qq, mut iq) = (0.0, 0.0, 0.0);
+                for &z in samples {
+                    let z = z - dc;
+                    ii += z.r * z.r;
+                    qq += z.i * z.i;
+                    iq += z.r * z.i;
+                }
+                if ii == 0.0 || qq == 0.0 {
+                    return IqImbalance { dc, gain: 1.0, phase: 0.0 };
+                }
+                let gain = (qq / ii).sqrt();
+                let phase = crate::math::$m::asin((iq / (ii * qq).sqrt()).clamp(-1.0, 1.0));
+                IqImbalance { dc, gain, phase }
+            }
+
+            /// Removes the DC offset and IQ imbalance from `samples` in place
+            ///
+            /// Each sample has `imbalance.dc` subtracted, and then
+            /// Q = (Q' / gain − I sin phase) / cos phase.
+            pub fn correct_imbalance(samples: &mut [Complex<$t>], imbalance: &IqImbalance<$t>) {
+                let (sin, cos) = (crate::math::$m::sin(imbalance.phase), crate::math::$m::cos(imbalance.phase));
+                let scale = 1.0 / (imbalance.gain * cos);
+                let lean = sin / cos;
+                for z in samples {
+                    let (i, q) = (z.r - imbalance.dc.r, z.i - imbalance.dc.i);
+                    *z = Complex::new(i, q * scale - i * lean);
+                }
+            }
+
+            /// Estimates the frequency of a complex exponential in noise from
+            /// the phase progression between consecutive samples, in radians
+            /// per sample
+            ///
+            /// This is Kay's weighted phase difference estimator, which
+            /// averages the angles of x<sub>n+1</sub> conj(x<sub>n</sub>) with
+            /// parabolic weights and reaches the Cramér–Rao bound at high
+            /// signal to noise ratios.  It needs no FFT and works for any
+            /// frequency in (−π, π), but breaks down abruptly at low SNR, where
+            /// [`estimate_frequency_fft`] should be used instead.  Returns 0
+            /// for fewer than 2 samples.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, signal::", stringify!($m), "::estimate_frequency};")]
+            #[doc = concat!("let x: Vec<_> = (0..64).map(|n| Complex::<", stringify!($t), ">::cis(-2.5 * n as ", stringify!($t), " + 1.0)).collect();")]
+            /// assert!((estimate_frequency(&x) + 2.5).abs() < 1e-4);
+            /// ```
+            pub fn estimate_frequency(samples: &[Complex<$t>]) -> $t {
+                let n = samples.len();
+                if n < 2 {
+                    return 0.0;
+                }
+                let half = n as $t / 2.0;
+                let scale = 1.5 * n as $t / ((n * n - 1) as $t);
+                samples.windows(2).enumerate().map(|(t, pair)| {
+                    let u = (t as $t + 1.0 - half) / half;
+                    scale * (1.0 - u * u) * (pair[1] * pair[0].conj()).angle()
+                }).sum()
+            }
+
+            /// Estimates the frequency of a complex exponential in noise from
+            /// the peak of its spectrum, in radians per sample
+            ///
+            /// The largest bin of the FFT of `samples` is refined by
+            /// interpolating between it and its neighbours, using Jacobsen's
+            /// estimator with Candan's bias correction, which is accurate to a
+            /// small fraction of a bin and holds up to much lower signal to
+            /// noise ratios than [`estimate_frequency`].  The result lies in
+            /// [−π, π).  Returns 0 for fewer than 3 samples.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, signal::", stringify!($m), "::estimate_frequency_fft};")]
+            #[doc = concat!("let x: Vec<_> = (0..100).map(|n| Complex::<", stringify!($t), ">::cis(0.123 * n as ", stringify!($t), ")).collect();")]
+            /// assert!((estimate_frequency_fft(&x) - 0.123).abs() < 1e-4);
+            /// ```
+            pub fn estimate_frequency_fft(samples: &[Complex<$t>]) -> $t {
+                use std::$t::consts::{PI, TAU};
+                let n = samples.len();
+                if n < 3 {
+                    return 0.0;
+                }
+                let mut spectrum = samples.to_vec();
+                crate::fft::$m::fft(&mut spectrum);
+                let (peak, _) = spectrum.iter().enumerate().fold((0, -1.0), |(best, max), (k, z)| {
+                    let power = z.r * z.r + z.i * z.i;
+                    if power > max { (k, power) } else { (best, max) }
+                });
+                let (before, at, after) = (spectrum[(peak + n - 1) % n], spectrum[peak], spectrum[(peak + 1) % n]);
+                let denominator = at * 2.0 - before - after;
+                let mut delta = if denominator.r == 0.0 && denominator.i == 0.0 {
+                    0.0
+                } else {
+                    -((after - before) / denominator).r
+                };
+                let bin = PI / n as $t;
+                delta *= crate::math::$m::tan(bin) / bin;
+                let w = TAU * (peak as $t + delta.clamp(-0.5, 0.5)) / n as $t;
+                if w >= PI { w - TAU } else { w }
+            }
+
+            /// Filters `signal` with the complex `taps`, starting from a zero
+            /// state
+            ///
+            /// y<sub>n</sub> = Σ h<sub>k</sub> x<sub>n−k</sub> for each sample
+            /// of `signal`, so the output has the length of the input.  Use a
+            /// [`Fir`] to filter a stream block by block.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, signal::", stringify!($m), "::fir_filter};")]
+            /// // the taps come out of an impulse
+            /// let taps = [Complex::new(0.5, 0.5), Complex::new(0.0, -1.0)];
+            /// let mut impulse = [Complex::new(0.0, 0.0); 3];
+            /// impulse[0] = Complex::new(1.0, 0.0);
+            /// assert_eq!(fir_filter(&taps, &impulse), [taps[0], taps[1], Complex::new(0.0, 0.0)]);
+            /// ```
+            pub fn fir_filter(taps: &[Complex<$t>], signal: &[Complex<$t>]) -> Vec<Complex<$t>> {
+                let mut out = vec![Complex::new(0.0, 0.0); signal.len()];
+                Fir::<$t>::new(taps.to_vec()).process(signal, &mut out);
+                out
+            }
+
+            #[cfg(test)]
+            mod test {
+                use super::*;
+
+                #[test]
+                fn check_eval_on_unit_circle() {
+                    use std::$t::consts::TAU;
+                    let x: Vec<Complex<$t>> = (0..11)
+                        .map(|k| Complex::new((k as $t * 0.4).cos(), k as $t * 0.1))
+                        .collect();
+                    for n in [1, 4, 7, 11, 16] {
+                        let h = eval_on_unit_circle(&x, n);
+                        assert_eq!(h.len(), n);
+                        for (k, &h) in h.iter().enumerate() {
+                            let z = Complex::<$t>::cis(TAU * k as $t / n as $t);
+                            let e = eval_z(&x, z);
+                            assert!((h - e).abs() < 1e3 * $t::EPSILON, "n = {n}, {h} != {e}");
+                        }
+                    }
+                    assert!(eval_on_unit_circle(&x, 0).is_empty());
+                }
+
+                #[test]
+                fn check_freqz() {
+                    let b = [0.2, 0.3, -0.1];
+                    let a = [1.0, -0.5, 0.25];
+                    let (w, h) = freqz(&b, &a, 9);
+                    assert_eq!((w.len(), h.len()), (9, 9));
+                    let expected = freqz_at(&b, &a, &w);
+                    for (&h, &e) in h.iter().zip(&expected) {
+                        assert!((h - e).abs() < 1e2 * $t::EPSILON, "{h} != {e}");
+                    }
+                    // DC gain is sum(b) / sum(a)
+                    assert!((h[0] - 0.4 / 0.75).abs() < 1e2 * $t::EPSILON);
+                    assert!(freqz(&b, &a, 0).1.is_empty());
+                }
+
+                #[test]
+                fn check_group_delay() {
+                    let b = [0.2, 0.3, -0.1];
+                    let a = [1.0, -0.5, 0.25];
+                    let w: Vec<$t> = (1..30).map(|k| 0.1 * k as $t).collect();
+                    let tau = group_delay(&b, &a, &w);
+                    // central difference of the phase
+                    let h = 1e-3;
+                    for (&w, &tau) in w.iter().zip(&tau) {
+                        let ratio = freqz_at(&b, &a, &[w + h])[0] / freqz_at(&b, &a, &[w - h])[0];
+                        let numeric = -ratio.angle() / (2.0 * h);
+                        assert!((tau - numeric).abs() < 1e-2, "{tau} != {numeric}");
+                    }
+                    assert_eq!(group_delay(&[0.0, 0.0, 1.0], &[1.0], &[0.7]), vec![2.0]);
+                    assert!(group_delay(&[0.0], &[1.0], &[0.3])[0].is_nan());
+                }
+
+                #[test]
+                fn check_unwrap() {
+                    use std::$t::consts::{PI, TAU};
+                    let truth: Vec<$t> = (0..40).map(|k| 0.9 * k as $t - 0.02 * (k * k) as $t).collect();
+                    let wrapped: Vec<$t> = truth.iter().map(|&a| (a + PI).rem_euclid(TAU) - PI).collect();
+                    let unwrapped = unwrap_angles(&wrapped, PI);
+                    for (&u, &t) in unwrapped.iter().zip(&truth) {
+                        assert!((u - t).abs() < 1e-4, "{u} != {t}");
+                    }
+                    // a larger threshold leaves smaller jumps alone
+                    let jumps = [0.0, 4.0, 0.0];
+                    assert_eq!(unwrap_angles(&jumps, 5.0), jumps.to_vec());
+                    let fixed = unwrap_angles(&jumps, PI);
+                    assert!((fixed[1] - (4.0 - TAU)).abs() < 1e-6);
+                    assert!(fixed[2].abs() < 1e-6);
+                    assert!(unwrap_angles(&[], PI).is_empty());
+                }
+
+                #[test]
+                fn check_to_bode() {
+                    let h = [Complex::new(1.0, 0.0), Complex::new(-0.1, 0.0), Complex::new(1.0, -1.0), Complex::new(0.0, 0.0)];
+                    let (mag, phase) = to_bode(&h);
+                    let ep = 1e2 * $t::EPSILON;
+                    assert!(mag[0].abs() < ep && phase[0].abs() < ep);
+                    assert!((mag[1] + 20.0).abs() < 1e2 * ep && (phase[1] - 180.0).abs() < 1e2 * ep);
+                    assert!((mag[2] - 20.0 * (2.0 as $t).sqrt().log10()).abs() < ep);
+                    assert!((phase[2] + 45.0).abs() < 1e2 * ep);
+                    assert_eq!(mag[3], $t::NEG_INFINITY);
+                }
+
+                #[test]
+                fn check_prony() {
+                    let terms = [
+                        Exponential { pole: Complex::new(0.9, 0.0) * Complex::<$t>::cis(0.3), amplitude: Complex::new(2.0, 0.0) },
+                        Exponential { pole: Complex::new(0.8, 0.0) * Complex::<$t>::cis(-1.1), amplitude: Complex::new(0.5, -1.0) },
+                        Exponential { pole: Complex::new(1.0, 0.0), amplitude: Complex::new(0.0, 0.25) }
+                    ];
+                    let samples: Vec<Complex<$t>> = (0..16).map(|n| {
+                        terms.iter().fold(Complex::new(0.0, 0.0), |sum, t| {
+                            sum + t.amplitude * t.pole.powf(n as $t)
+                        })
+                    }).collect();
+                    let fit = prony(&samples, 3).unwrap();
+                    assert_eq!(fit.len(), 3);
+                    let ep = 1e4 * $t::EPSILON;
+                    for term in &terms {
+                        let found = fit.iter()
+                            .find(|f| (f.pole - term.pole).abs() < ep)
+                            .unwrap_or_else(|| panic!("{:?} not in {:?}", term, fit));
+                        assert!((found.amplitude - term.amplitude).abs() < ep, "{:?}", found);
+                    }
+                    assert!(prony(&samples[..5], 3).is_none());
+                    assert_eq!(prony(&samples, 0), Some(Vec::new()));
+                }
+
+                #[test]
+                fn check_imbalance() {
+                    // two tones and their imbalanced, offset reception
+                    // whole numbers of cycles, so the statistics are exact
+                    let pi = std::$t::consts::PI;
+                    let x: Vec<Complex<$t>> = (0..2000).map(|n| {
+                        Complex::<$t>::cis(pi / 8.0 * n as $t) * 0.7 + Complex::<$t>::cis(-0.3 * pi * n as $t) * 0.3
+                    }).collect();
+                    let truth = IqImbalance::<$t> { dc: Complex::new(-0.05, 0.1), gain: 0.9, phase: 0.08 };
+                    let y: Vec<Complex<$t>> = x.iter().map(|z| {
+                        let q = truth.gain * (z.i * truth.phase.cos() + z.r * truth.phase.sin());
+                        truth.dc + Complex::new(z.r, q)
+                    }).collect();
+                    let found = estimate_imbalance(&y);
+                    assert!((found.dc - truth.dc).abs() < 1e-3, "{found:?}");
+                    assert!((found.gain - truth.gain).abs() < 1e-3 && (found.phase - truth.phase).abs() < 1e-3, "{found:?}");
+                    // the exact parameters undo the imbalance
+                    let mut z = y.clone();
+                    correct_imbalance(&mut z, &truth);
+                    for (z, x) in z.iter().zip(&x) {
+                        assert!((*z - *x).abs() < 1e2 * $t::EPSILON, "{z:?} {x:?}");
+                    }
+                    // and a balanced signal is left alone
+                    let balanced = estimate_imbalance(&x);
+                    assert!(balanced.dc.abs() < 1e-3 && (balanced.gain - 1.0).abs() < 1e-3 && balanced.phase.abs() < 1e-3);
+                    assert_eq!(estimate_imbalance(&[]), IqImbalance { dc: Complex::new(0.0, 0.0), gain: 1.0, phase: 0.0 });
+                }
+
+                #[test]
+                fn check_estimate_frequency() {
+                    use std::$t::consts::PI;
+                    assert_eq!((estimate_frequency(&[]), estimate_frequency_fft(&[Complex::new(1.0, 0.0); 2])), (0.0, 0.0));
+                    for w in [0.0, 0.01, 0.7, -1.3, 3.0, -3.1] {
+                        for n in [16, 100, 257] {
+                            let x: Vec<Complex<$t>> = (0..n).map(|k| Complex::<$t>::cis(w * k as $t + 0.3) * 2.0).collect();
+                            let kay = estimate_frequency(&x);
+                            assert!((kay - w).abs() < 1e3 * $t::EPSILON, "{w} {n} {kay}");
+                            // within a hundredth of a bin
+                            let fft = estimate_frequency_fft(&x);
+                            assert!((fft - w).abs() < 1e-2 * PI / n as $t + 1e3 * $t::EPSILON, "{w} {n} {fft}");
+                        }
+                    }
+                    // a weak tone among a deterministic pseudo-noise
+                    let mut state = 1u32;
+                    let x: Vec<Complex<$t>> = (0..512).map(|k| {
+                        let mut noise = || {
+                            state = state.wrapping_mul(1664525).wrapping_add(1013904223);
+                            (state >> 8) as $t / (1u32 << 24) as $t - 0.5
+                        };
+                        Complex::<$t>::cis(1.1 * k as $t) * 0.5 + Complex::new(noise(), noise())
+                    }).collect();
+                    assert!((estimate_frequency_fft(&x) - 1.1).abs() < 2e-3);
+                }
+
+                #[test]
+                fn check_fir_filter() {
+                    let taps: Vec<Complex<$t>> = (0..13).map(|k| Complex::new(0.1 * k as $t, 1.0 / (k + 1) as $t)).collect();
+                    let x: Vec<Complex<$t>> = (0..700).map(|n| Complex::new((0.3 * n as $t).sin(), (0.07 * n as $t).cos())).collect();
+                    let y = fir_filter(&taps, &x);
+                    for (n, &y) in y.iter().enumerate() {
+                        let direct = (0..=n.min(taps.len() - 1)).fold(Complex::new(0.0, 0.0), |sum, k| sum + taps[k] * x[n - k]);
+                        assert!((y - direct).abs() < 1e2 * $t::EPSILON, "{n} {y:?} {direct:?}");
+                    }
+                    // block by block, in blocks shorter and longer than the taps
+                    let mut fir = Fir::<$t>::new(taps.clone());
+                    let mut streamed = vec![Complex::new(0.0, 0.0); x.len()];
+                    let mut start = 0;
+                    for len in [0, 1, 5, 300, 13, 381] {
+                        fir.process(&x[start..start + len], &mut streamed[start..start + len]);
+                        start += len;
+                    }
+                    let close = |a: &[Complex<$t>], b: &[Complex<$t>]| a.iter().zip(b).all(|(a, b)| (*a - *b).abs() < 1e2 * $t::EPSILON);
+                    assert!(close(&streamed, &y));
+                    fir.reset();
+                    fir.process(&x[..100], &mut streamed[..100]);
+                    assert!(close(&streamed[..100], &y[..100]));
+                    assert_eq!(fir_filter(&[], &x[..3]), [Complex::new(0.0, 0.0); 3]);
+                    assert_eq!(fir_filter(&taps, &[]), []);
+                }
+            }
+        }
+    }
+}
+signal_mod!(c32 for f32);
+signal_mod!(c64 for f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::signal::c64::freqz_at;
+
+    #[test]
+    fn biquad_response() {
+        let low = Biquad::<f64>::new([0.2, 0.4, 0.2], [1.0, -0.6, 0.3]);
+        let high = Biquad::<f64>::new([0.5, -1.0, 0.5], [1.0, 0.2, 0.1]);
+        let sos = low.cascade(high).cascade(Biquad::new([1.0, 0.5, 0.0], [2.0, 0.0, 0.0]));
+        assert_eq!(sos.sections.len(), 3);
+        for k in 0..10 {
+            let w = 0.3 * k as f64;
+            let expected = freqz_at(&low.b, &low.a, &[w])[0]
+                * freqz_at(&high.b, &high.a, &[w])[0]
+                * freqz_at(&[1.0, 0.5], &[2.0], &[w])[0];
+            assert!((sos.response(w) - expected).abs() < 1e-14);
+        }
+    }
+
+    #[test]
+    fn biquad_poles_zeros() {
+        let section = Biquad::<f64>::new([1.0, 0.0, -0.25], [1.0, -1.0, 0.5]);
+        let mut zeros = section.zeros();
+        zeros.sort_by(|a, b| a.r.total_cmp(&b.r));
+        assert_eq!(zeros, vec![Complex::new(-0.5, 0.0), Complex::new(0.5, 0.0)]);
+        for p in section.poles() {
+            assert!((p.abs() - 0.5f64.sqrt()).abs() < 1e-15);
+            assert!((p * p - p + 0.5).abs() < 1e-15);
+        }
+        let first_order = Biquad::<f64>::new([0.0, 1.0, 0.5], [1.0, -0.5, 0.0]);
+        assert_eq!(first_order.zeros(), vec![Complex::new(-0.5, 0.0)]);
+        let sos = Sos::from(section).cascade(first_order);
+        assert_eq!(sos.zeros().len(), 3);
+        assert_eq!(sos.poles().len(), 4);
+    }
+}
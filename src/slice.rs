@@ -0,0 +1,847 @@
+//! Bulk operations on slices of complex numbers
+//!
+//! The interleaved real and imaginary parts of complex slices do not
+//! auto-vectorize well, so the element-wise products here use explicit SIMD
+//! kernels chosen at runtime: AVX with FMA on x86-64 and NEON on AArch64.
+//! WebAssembly has no runtime feature detection, so its simd128 kernels are
+//! used whenever the crate is built with that feature enabled, as by
+//! `RUSTFLAGS="-C target-feature=+simd128"`, which every current browser
+//! supports.  Other targets, and the tails of slices, use scalar code, as
+//! does everything with the `deterministic` feature, so that the results are
+//! the same on every platform.
+//!
+//! A [`Histogram2d`] counts samples into a grid of bins over a rectangle of
+//! the plane, for constellation density plots of IQ captures and phase
+//! portraits.
+
+use crate::Complex;
+
+/// Counts of complex samples in a grid of equal rectangular bins
+///
+/// The bins cover a rectangle of the plane in `nx` columns along the real
+/// axis and `ny` rows along the imaginary axis, stored in row-major order
+/// like the points of a [`grid`](crate::grid::c64::grid): the count for
+/// column `k` of row `j` is at index `j * nx + k` of
+/// [`counts`](Histogram2d::counts), with row 0 at the least imaginary part.
+/// Each bin includes its lower edges, and the last column and row also
+/// include the upper edges of the rectangle.  Samples outside the rectangle,
+/// and NaNs, are tallied separately.
+///
+/// # Example
+/// ```
+/// use imaginary::Complex;
+/// use imaginary::slice::Histogram2d;
+/// // a noisy QPSK constellation
+/// let samples: Vec<_> = (0..1000)
+///     .map(|k| Complex::<f64>::new(if k % 2 == 0 { 1.0 } else { -1.0 }, if k % 4 < 2 { 1.0 } else { -1.0 })
+///         + Complex::<f64>::cis(k as f64) * 0.1)
+///     .collect();
+/// let mut histogram = Histogram2d::<f64>::new(Complex::new(-2.0, -2.0), Complex::new(2.0, 2.0), 8, 8);
+/// histogram.add_slice(&samples);
+/// // the bins either side of 1 + i
+/// let near = (5..7).flat_map(|j| (5..7).map(move |k| (k, j)));
+/// assert_eq!(near.map(|(k, j)| histogram.count(k, j)).sum::<u64>(), 250);
+/// assert_eq!(histogram.count(0, 0), 0);
+/// assert_eq!(histogram.total(), 1000);
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct Histogram2d<T: Copy> {
+    lo: Complex<T>,
+    hi: Complex<T>,
+    scale: Complex<T>,
+    nx: usize,
+    ny: usize,
+    counts: Vec<u64>,
+    outside: u64
+}
+
+impl<T: Copy> Histogram2d<T> {
+    /// Number of columns and rows, `(nx, ny)`
+    pub fn bins(&self) -> (usize, usize) {
+        (self.nx, self.ny)
+    }
+
+    /// The counts of all the bins, in row-major order
+    pub fn counts(&self) -> &[u64] {
+        &self.counts
+    }
+
+    /// The count of column `k` of row `j`
+    ///
+    /// # Panics
+    /// Panics if `k` ≥ `nx` or `j` ≥ `ny`.
+    pub fn count(&self, k: usize, j: usize) -> u64 {
+        assert!(k < self.nx && j < self.ny, "bin ({k}, {j}) out of range for {} by {} bins", self.nx, self.ny);
+        self.counts[j * self.nx + k]
+    }
+
+    /// The largest count of any bin, for scaling a density plot
+    pub fn max_count(&self) -> u64 {
+        self.counts.iter().copied().max().unwrap_or(0)
+    }
+
+    /// Number of samples counted in the bins
+    pub fn total(&self) -> u64 {
+        self.counts.iter().sum()
+    }
+
+    /// Number of samples outside the rectangle, or NaN
+    pub fn outside(&self) -> u64 {
+        self.outside
+    }
+
+    /// Resets every count to zero
+    pub fn clear(&mut self) {
+        self.counts.fill(0);
+        self.outside = 0;
+    }
+}
+
+macro_rules! impl_histogram {
+    ($t: ident) => {
+        impl Histogram2d<$t> {
+            /// Empty histogram of `nx` by `ny` bins over the rectangle with
+            /// opposite corners `corner0` and `corner1`
+            ///
+            /// # Panics
+            /// Panics if `nx` or `ny` is zero, or the rectangle has no area
+            /// or is not finite.
+            pub fn new(corner0: Complex<$t>, corner1: Complex<$t>, nx: usize, ny: usize) -> Histogram2d<$t> {
+                let lo = Complex::new(corner0.r.min(corner1.r), corner0.i.min(corner1.i));
+                let hi = Complex::new(corner0.r.max(corner1.r), corner0.i.max(corner1.i));
+                assert!(nx > 0 && ny > 0, "a histogram needs at least one bin in each direction");
+                assert!(lo.r < hi.r && lo.i < hi.i && (hi.r - lo.r).is_finite() && (hi.i - lo.i).is_finite(),
+                        "the histogram rectangle must be finite with nonzero area, corners {corner0} and {corner1}");
+                let scale = Complex::new(nx as $t / (hi.r - lo.r), ny as $t / (hi.i - lo.i));
+                Histogram2d { lo, hi, scale, nx, ny, counts: vec![0; nx * ny], outside: 0 }
+            }
+
+            /// The bin, as `(k, j)` for column `k` of row `j`, that counts
+            /// `z`, or `None` outside the rectangle
+            pub fn bin_of(&self, z: Complex<$t>) -> Option<(usize, usize)> {
+                // written to be false for NaN
+                if !(self.lo.r <= z.r && z.r <= self.hi.r && self.lo.i <= z.i && z.i <= self.hi.i) {
+                    return None;
+                }
+                // rounding can put points just inside the upper edges, as
+                // well as those on them, one past the last bin
+                let k = (((z.r - self.lo.r) * self.scale.r) as usize).min(self.nx - 1);
+                let j = (((z.i - self.lo.i) * self.scale.i) as usize).min(self.ny - 1);
+                Some((k, j))
+            }
+
+            /// The centre of column `k` of row `j`
+            pub fn bin_centre(&self, k: usize, j: usize) -> Complex<$t> {
+                Complex::new(self.lo.r + (k as $t + 0.5) / self.scale.r, self.lo.i + (j as $t + 0.5) / self.scale.i)
+            }
+
+            /// Counts the sample `z`
+            pub fn add(&mut self, z: Complex<$t>) {
+                match self.bin_of(z) {
+                    Some((k, j)) => self.counts[j * self.nx + k] += 1,
+                    None => self.outside += 1
+                }
+            }
+
+            /// Counts each of the samples `z`
+            pub fn add_slice(&mut self, z: &[Complex<$t>]) {
+                for &z in z {
+                    self.add(z);
+                }
+            }
+        }
+    }
+}
+impl_histogram!(f32);
+impl_histogram!(f64);
+
+macro_rules! slice_mod {
+    ($m: ident for $t: ident, $mul: ident, $mul_acc: ident, $axpy: ident, $abs: ident) => {
+        /// Bulk operations on slices of complex numbers
+        pub mod $m {
+            use crate::Complex;
+
+            /// Element-wise product, `out[k] = a[k] * b[k]`
+            ///
+            /// # Panics
+            /// Panics if the slices differ in length.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, slice::", stringify!($m), "::mul};")]
+            /// let a = [Complex::new(1.0, 2.0); 5];
+            /// let b = [Complex::new(0.0, 1.0); 5];
+            /// let mut out = [Complex::new(0.0, 0.0); 5];
+            /// mul(&a, &b, &mut out);
+            /// assert_eq!(out, [Complex::new(-2.0, 1.0); 5]);
+            /// ```
+            pub fn mul(a: &[Complex<$t>], b: &[Complex<$t>], out: &mut [Complex<$t>]) {
+                assert!(a.len() == b.len() && a.len() == out.len(), "slice lengths differ");
+                let n = out.len();
+                // SAFETY: all three slices hold n elements
+                let done = unsafe { super::$mul(a.as_ptr(), b.as_ptr(), out.as_mut_ptr(), n) };
+                for k in done..n {
+                    out[k] = a[k] * b[k];
+                }
+            }
+
+            /// Element-wise product in place, `a[k] *= b[k]`
+            ///
+            /// # Panics
+            /// Panics if the slices differ in length.
+            pub fn mul_in_place(a: &mut [Complex<$t>], b: &[Complex<$t>]) {
+                assert_eq!(a.len(), b.len(), "slice lengths differ");
+                let n = a.len();
+                let ptr = a.as_mut_ptr();
+                // SAFETY: both slices hold n elements, and the kernels read
+                // each chunk of `a` before writing it
+                let done = unsafe { super::$mul(ptr, b.as_ptr(), ptr, n) };
+                for k in done..n {
+                    a[k] *= b[k];
+                }
+            }
+
+            /// Element-wise multiply-accumulate, `acc[k] += a[k] * b[k]`
+            ///
+            /// # Panics
+            /// Panics if the slices differ in length.
+            pub fn mul_acc(acc: &mut [Complex<$t>], a: &[Complex<$t>], b: &[Complex<$t>]) {
+                assert!(a.len() == b.len() && a.len() == acc.len(), "slice lengths differ");
+                let n = acc.len();
+                // SAFETY: all three slices hold n elements
+                let done = unsafe { super::$mul_acc(acc.as_mut_ptr(), a.as_ptr(), b.as_ptr(), n) };
+                for k in done..n {
+                    acc[k] += a[k] * b[k];
+                }
+            }
+
+            /// Multiplies `buf` by e<sup>i(ωn + φ)</sup>, shifting its
+            /// frequency by `w` radians per sample
+            ///
+            /// Uses the [`Rotator`](crate::rotator::Rotator) recurrence, one
+            /// complex multiply per sample rather than a sine and cosine.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, slice::", stringify!($m), "::freq_shift};")]
+            /// let mut buf = vec![Complex::new(2.0, 0.0); 100];
+            /// freq_shift(&mut buf, 0.25, 1.0);
+            /// for (n, &z) in buf.iter().enumerate() {
+            #[doc = concat!("    let expected = 2.0 * Complex::<", stringify!($t), ">::cis(0.25 * n as ", stringify!($t), " + 1.0);")]
+            ///     assert!((z - expected).abs() < 1e-4);
+            /// }
+            /// ```
+            pub fn freq_shift(buf: &mut [Complex<$t>], w: $t, phase0: $t) {
+                crate::rotator::Rotator::<$t>::new(w).with_phase(phase0).rotate(buf);
+            }
+
+            /// Scaled accumulation, `y[k] += alpha * x[k]`
+            ///
+            /// # Panics
+            /// Panics if the slices differ in length.
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, slice::", stringify!($m), "::axpy};")]
+            /// let x = [Complex::new(1.0, 0.0), Complex::new(0.0, 1.0)];
+            /// let mut y = [Complex::new(1.0, 1.0); 2];
+            /// axpy(Complex::new(0.0, 2.0), &x, &mut y);
+            /// assert_eq!(y, [Complex::new(1.0, 3.0), Complex::new(-1.0, 1.0)]);
+            /// ```
+            pub fn axpy(alpha: Complex<$t>, x: &[Complex<$t>], y: &mut [Complex<$t>]) {
+                assert_eq!(x.len(), y.len(), "slice lengths differ");
+                let n = y.len();
+                // SAFETY: both slices hold n elements
+                let done = unsafe { super::$axpy(alpha, x.as_ptr(), y.as_mut_ptr(), n) };
+                for (y, x) in y[done..].iter_mut().zip(&x[done..]) {
+                    y.r += alpha.r * x.r - alpha.i * x.i;
+                    y.i += alpha.r * x.i + alpha.i * x.r;
+                }
+            }
+
+            /// Conjugated dot product Σ conj(x<sub>k</sub>) y<sub>k</sub>
+            ///
+            /// # Panics
+            /// Panics if the slices differ in length.
+            pub fn dotc(x: &[Complex<$t>], y: &[Complex<$t>]) -> Complex<$t> {
+                assert_eq!(x.len(), y.len(), "slice lengths differ");
+                sum4(x.iter().zip(y).map(|(x, y)| x.conj() * *y))
+            }
+
+            /// Unconjugated dot product Σ x<sub>k</sub> y<sub>k</sub>
+            ///
+            /// # Panics
+            /// Panics if the slices differ in length.
+            pub fn dotu(x: &[Complex<$t>], y: &[Complex<$t>]) -> Complex<$t> {
+                assert_eq!(x.len(), y.len(), "slice lengths differ");
+                sum4(x.iter().zip(y).map(|(x, y)| *x * *y))
+            }
+
+            /// Fused `y[k] += alpha * x[k]` followed by the conjugated dot
+            /// product of `z` with the updated `y`, in a single pass
+            ///
+            /// This is the inner step of modified Gram–Schmidt, where the
+            /// projection onto the next basis vector is accumulated while the
+            /// previous one is removed.
+            ///
+            /// # Panics
+            /// Panics if the slices differ in length.
+            pub fn axpy_dotc(alpha: Complex<$t>, x: &[Complex<$t>], y: &mut [Complex<$t>],
+                             z: &[Complex<$t>]) -> Complex<$t> {
+                assert!(x.len() == y.len() && x.len() == z.len(), "slice lengths differ");
+                sum4(y.iter_mut().zip(x).zip(z).map(|((y, x), z)| {
+                    y.r += alpha.r * x.r - alpha.i * x.i;
+                    y.i += alpha.r * x.i + alpha.i * x.r;
+                    z.conj() * *y
+                }))
+            }
+
+            /// e<sup>iθ</sup> for each angle θ in `angles`
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, slice::", stringify!($m), "::cis_slice};")]
+            #[doc = concat!("use std::", stringify!($t), "::consts::PI;")]
+            /// let z = cis_slice(&[0.0, PI / 2.0, PI]);
+            /// assert_eq!(z[0], Complex::new(1.0, 0.0));
+            /// assert!((z[1] - Complex::new(0.0, 1.0)).abs() < 1e-6);
+            /// assert!((z[2] - Complex::new(-1.0, 0.0)).abs() < 1e-6);
+            /// ```
+            pub fn cis_slice(angles: &[$t]) -> Vec<Complex<$t>> {
+                let mut out = vec![Complex::new(0.0, 0.0); angles.len()];
+                cis_into(angles, &mut out);
+                out
+            }
+
+            /// Writes e<sup>iθ</sup> for each angle θ in `angles` to `out`
+            ///
+            /// # Panics
+            /// Panics if the slices differ in length.
+            pub fn cis_into(angles: &[$t], out: &mut [Complex<$t>]) {
+                assert_eq!(angles.len(), out.len(), "slice lengths differ");
+                for (z, &theta) in out.iter_mut().zip(angles) {
+                    *z = Complex::new(crate::math::$m::cos(theta), crate::math::$m::sin(theta));
+                }
+            }
+
+            /// The absolute value of each element
+            ///
+            /// # Example
+            /// ```
+            #[doc = concat!("use imaginary::{Complex, slice::", stringify!($m), "::abs_slice};")]
+            /// // the squares of the last element overflow an f32
+            /// let z = [Complex::new(3.0, 4.0), Complex::new(0.0, -2.0), Complex::new(3e30, 4e30)];
+            /// let abs = abs_slice(&z);
+            /// assert_eq!(abs[..2], [5.0, 2.0]);
+            /// assert!((abs[2] / 5e30 - 1.0).abs() < 1e-6);
+            /// ```
+            pub fn abs_slice(z: &[Complex<$t>]) -> Vec<$t> {
+                let mut out = vec![0.0; z.len()];
+                abs_into(z, &mut out);
+                out
+            }
+
+            /// Writes the absolute value of each element of `z` to `out`
+            ///
+            /// The square root of the sum of squares is computed in a first
+            /// pass that vectorizes, and the rare elements where the squares
+            /// overflowed or underflowed are redone with `hypot`.
+            ///
+            /// # Panics
+            /// Panics if the slices differ in length.
+            pub fn abs_into(z: &[Complex<$t>], out: &mut [$t]) {
+                assert_eq!(z.len(), out.len(), "slice lengths differ");
+                let n = z.len();
+                // SAFETY: both slices hold n elements
+                let done = unsafe { super::$abs(z.as_ptr(), out.as_mut_ptr(), n) };
+                for (a, z) in out[done..].iter_mut().zip(&z[done..]) {
+                    *a = (z.r * z.r + z.i * z.i).sqrt();
+                }
+                for (a, z) in out.iter_mut().zip(z) {
+                    if !(*a >= $t::MIN_POSITIVE.sqrt() && *a < $t::INFINITY) {
+                        *a = crate::math::$m::hypot(z.r, z.i);
+                    }
+                }
+            }
+
+            /// The phase angle of each element
+            pub fn angle_slice(z: &[Complex<$t>]) -> Vec<$t> {
+                let mut out = vec![0.0; z.len()];
+                angle_into(z, &mut out);
+                out
+            }
+
+            /// Writes the phase angle of each element of `z` to `out`
+            ///
+            /// # Panics
+            /// Panics if the slices differ in length.
+            pub fn angle_into(z: &[Complex<$t>], out: &mut [$t]) {
+                assert_eq!(z.len(), out.len(), "slice lengths differ");
+                for (a, z) in out.iter_mut().zip(z) {
+                    *a = crate::math::$m::atan2(z.i, z.r);
+                }
+            }
+
+            /// Sums with four independent accumulators, so the additions can
+            /// be vectorized and pipelined
+            fn sum4(terms: impl Iterator<Item = Complex<$t>>) -> Complex<$t> {
+                let zero = Complex::new(0.0, 0.0);
+                let mut acc = [zero; 4];
+                for (k, t) in terms.enumerate() {
+                    acc[k % 4] += t;
+                }
+                (acc[0] + acc[2]) + (acc[1] + acc[3])
+            }
+
+            #[cfg(test)]
+            mod test {
+                use super::*;
+
+                fn data(n: usize, seed: $t) -> Vec<Complex<$t>> {
+                    (0..n).map(|k| Complex::new((k as $t * seed).sin(), (k as $t * 0.7 + seed).cos())).collect()
+                }
+
+                #[test]
+                fn check_blas() {
+                    let alpha = Complex::new(0.5, -1.5);
+                    for n in [0, 1, 5, 17] {
+                        let (x, y0, z) = (data(n, 0.3), data(n, 1.1), data(n, 2.3));
+                        let mut y = y0.clone();
+                        axpy(alpha, &x, &mut y);
+                        let ep = 8.0 * n as $t * $t::EPSILON;
+                        for k in 0..n {
+                            assert!((y[k] - (y0[k] + alpha * x[k])).abs() <= 4.0 * $t::EPSILON);
+                        }
+                        let expected = (0..n).fold(Complex::new(0.0, 0.0), |s, k| s + z[k].conj() * y[k]);
+                        assert!((dotc(&z, &y) - expected).abs() <= ep);
+                        let expected_u = (0..n).fold(Complex::new(0.0, 0.0), |s, k| s + z[k] * y[k]);
+                        assert!((dotu(&z, &y) - expected_u).abs() <= ep);
+                        let mut fused = y0.clone();
+                        let d = axpy_dotc(alpha, &x, &mut fused, &z);
+                        assert_eq!(fused, y);
+                        assert!((d - expected).abs() <= ep);
+                    }
+                }
+
+                #[test]
+                fn check_polar() {
+                    let z = data(33, 0.9);
+                    let abs = abs_slice(&z);
+                    let angle = angle_slice(&z);
+                    let back = cis_slice(&angle);
+                    for k in 0..z.len() {
+                        assert!((abs[k] - z[k].abs()).abs() <= 2.0 * $t::EPSILON);
+                        assert_eq!(angle[k], z[k].angle());
+                        assert!((back[k] * abs[k] - z[k]).abs() <= 8.0 * $t::EPSILON);
+                    }
+                    let big = $t::MAX / 2.0;
+                    let small = $t::MIN_POSITIVE * 4.0;
+                    let edge = [Complex::new(big, big), Complex::new(small, -small), Complex::new(0.0, 0.0),
+                                Complex::new($t::INFINITY, $t::NAN), Complex::new($t::NAN, 1.0)];
+                    let abs = abs_slice(&edge);
+                    for (a, z) in abs.iter().zip(&edge) {
+                        assert!(*a == z.abs() || (a.is_nan() && z.abs().is_nan()), "{a} {z}");
+                    }
+                }
+
+                #[test]
+                fn check_mul() {
+                    for n in 0..40 {
+                        let (a, b) = (data(n, 0.3), data(n, 1.1));
+                        let mut out = vec![Complex::new(0.0, 0.0); n];
+                        mul(&a, &b, &mut out);
+                        let mut in_place = a.clone();
+                        mul_in_place(&mut in_place, &b);
+                        let mut acc = data(n, 2.0);
+                        let start = acc.clone();
+                        mul_acc(&mut acc, &a, &b);
+                        for k in 0..n {
+                            let p = a[k] * b[k];
+                            let ep = 4.0 * $t::EPSILON;
+                            assert!((out[k] - p).abs() <= ep, "n = {n}, k = {k}");
+                            assert_eq!(in_place[k], out[k]);
+                            assert!((acc[k] - (start[k] + p)).abs() <= ep, "n = {n}, k = {k}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+slice_mod!(c32 for f32, mul_f32, mul_acc_f32, axpy_f32, abs_f32);
+slice_mod!(c64 for f64, mul_f64, mul_acc_f64, axpy_f64, abs_f64);
+
+/// Defines a dispatching kernel, returning the number of leading elements it
+/// processed, that tries each of the listed SIMD implementations available
+/// on the target
+///
+/// With the `deterministic` feature none are tried, as the fused
+/// multiply-adds and the order of the sums differ between them, and the
+/// scalar code does the whole slice.
+macro_rules! dispatch {
+    ([$($backend: ident),*] $name: ident($($arg: ident: $ty: ty),*)) => {
+        #[allow(unused_variables, unreachable_code)]
+        unsafe fn $name($($arg: $ty),*) -> usize {
+            #[cfg(not(feature = "deterministic"))]
+            dispatch!(@tries [$($backend),*] ($name($($arg),*)));
+            0
+        }
+    };
+    (@tries [$($backend: ident),*] $call: tt) => {
+        $(dispatch!(@try $backend, $call);)*
+    };
+    (@try x86, ($name: ident($($arg: ident),*))) => {
+        #[cfg(target_arch = "x86_64")]
+        if std::is_x86_feature_detected!("avx") && std::is_x86_feature_detected!("fma") {
+            return x86::$name($($arg),*);
+        }
+    };
+    (@try neon, ($name: ident($($arg: ident),*))) => {
+        #[cfg(target_arch = "aarch64")]
+        if std::arch::is_aarch64_feature_detected!("neon") {
+            return neon::$name($($arg),*);
+        }
+    };
+    (@try wasm, ($name: ident($($arg: ident),*))) => {
+        #[cfg(all(target_arch = "wasm32", target_feature = "simd128"))]
+        return wasm::$name($($arg),*);
+    };
+}
+dispatch!([x86, neon, wasm] mul_f32(a: *const Complex<f32>, b: *const Complex<f32>, out: *mut Complex<f32>, n: usize));
+dispatch!([x86, neon, wasm] mul_f64(a: *const Complex<f64>, b: *const Complex<f64>, out: *mut Complex<f64>, n: usize));
+dispatch!([x86, neon, wasm] mul_acc_f32(acc: *mut Complex<f32>, a: *const Complex<f32>, b: *const Complex<f32>, n: usize));
+dispatch!([x86, neon, wasm] mul_acc_f64(acc: *mut Complex<f64>, a: *const Complex<f64>, b: *const Complex<f64>, n: usize));
+// the scalar loops of these vectorize well enough on x86-64 and AArch64
+dispatch!([wasm] axpy_f32(alpha: Complex<f32>, x: *const Complex<f32>, y: *mut Complex<f32>, n: usize));
+dispatch!([wasm] axpy_f64(alpha: Complex<f64>, x: *const Complex<f64>, y: *mut Complex<f64>, n: usize));
+dispatch!([wasm] abs_f32(z: *const Complex<f32>, out: *mut f32, n: usize));
+dispatch!([wasm] abs_f64(z: *const Complex<f64>, out: *mut f64, n: usize));
+
+/// AVX and FMA kernels
+///
+/// With interleaved data, `moveldup`/`movehdup` broadcast the real and
+/// imaginary parts of `b`, and `fmaddsub` combines
+/// `a · re(b) ∓ swap(a) · im(b)` into the product in one step.
+#[cfg(all(target_arch = "x86_64", not(feature = "deterministic")))]
+mod x86 {
+    use crate::Complex;
+    use core::arch::x86_64::*;
+
+    #[inline]
+    #[target_feature(enable = "avx,fma")]
+    unsafe fn product_ps(a: __m256, b: __m256) -> __m256 {
+        let re = _mm256_moveldup_ps(b);
+        let im = _mm256_movehdup_ps(b);
+        let swap = _mm256_permute_ps(a, 0b1011_0001);
+        _mm256_fmaddsub_ps(a, re, _mm256_mul_ps(swap, im))
+    }
+
+    #[inline]
+    #[target_feature(enable = "avx,fma")]
+    unsafe fn product_pd(a: __m256d, b: __m256d) -> __m256d {
+        let re = _mm256_movedup_pd(b);
+        let im = _mm256_permute_pd(b, 0b1111);
+        let swap = _mm256_permute_pd(a, 0b0101);
+        _mm256_fmaddsub_pd(a, re, _mm256_mul_pd(swap, im))
+    }
+
+    #[target_feature(enable = "avx,fma")]
+    pub(super) unsafe fn mul_f32(a: *const Complex<f32>, b: *const Complex<f32>,
+                                 out: *mut Complex<f32>, n: usize) -> usize {
+        let chunks = n / 4;
+        for k in (0..chunks).map(|k| 4 * k) {
+            let x = _mm256_loadu_ps(a.add(k) as *const f32);
+            let y = _mm256_loadu_ps(b.add(k) as *const f32);
+            _mm256_storeu_ps(out.add(k) as *mut f32, product_ps(x, y));
+        }
+        4 * chunks
+    }
+
+    #[target_feature(enable = "avx,fma")]
+    pub(super) unsafe fn mul_f64(a: *const Complex<f64>, b: *const Complex<f64>,
+                                 out: *mut Complex<f64>, n: usize) -> usize {
+        let chunks = n / 2;
+        for k in (0..chunks).map(|k| 2 * k) {
+            let x = _mm256_loadu_pd(a.add(k) as *const f64);
+            let y = _mm256_loadu_pd(b.add(k) as *const f64);
+            _mm256_storeu_pd(out.add(k) as *mut f64, product_pd(x, y));
+        }
+        2 * chunks
+    }
+
+    #[target_feature(enable = "avx,fma")]
+    pub(super) unsafe fn mul_acc_f32(acc: *mut Complex<f32>, a: *const Complex<f32>,
+                                     b: *const Complex<f32>, n: usize) -> usize {
+        let chunks = n / 4;
+        for k in (0..chunks).map(|k| 4 * k) {
+            let x = _mm256_loadu_ps(a.add(k) as *const f32);
+            let y = _mm256_loadu_ps(b.add(k) as *const f32);
+            let sum = _mm256_loadu_ps(acc.add(k) as *const f32);
+            _mm256_storeu_ps(acc.add(k) as *mut f32, _mm256_add_ps(sum, product_ps(x, y)));
+        }
+        4 * chunks
+    }
+
+    #[target_feature(enable = "avx,fma")]
+    pub(super) unsafe fn mul_acc_f64(acc: *mut Complex<f64>, a: *const Complex<f64>,
+                                     b: *const Complex<f64>, n: usize) -> usize {
+        let chunks = n / 2;
+        for k in (0..chunks).map(|k| 2 * k) {
+            let x = _mm256_loadu_pd(a.add(k) as *const f64);
+            let y = _mm256_loadu_pd(b.add(k) as *const f64);
+            let sum = _mm256_loadu_pd(acc.add(k) as *const f64);
+            _mm256_storeu_pd(acc.add(k) as *mut f64, _mm256_add_pd(sum, product_pd(x, y)));
+        }
+        2 * chunks
+    }
+}
+
+/// NEON kernels
+///
+/// `vld2q` deinterleaves the real and imaginary parts into separate
+/// registers, so the product is plain fused multiply-adds.
+#[cfg(all(target_arch = "aarch64", not(feature = "deterministic")))]
+mod neon {
+    use crate::Complex;
+    use core::arch::aarch64::*;
+
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn mul_f32(a: *const Complex<f32>, b: *const Complex<f32>,
+                                 out: *mut Complex<f32>, n: usize) -> usize {
+        let chunks = n / 4;
+        for k in (0..chunks).map(|k| 4 * k) {
+            let x = vld2q_f32(a.add(k) as *const f32);
+            let y = vld2q_f32(b.add(k) as *const f32);
+            let re = vfmsq_f32(vmulq_f32(x.0, y.0), x.1, y.1);
+            let im = vfmaq_f32(vmulq_f32(x.0, y.1), x.1, y.0);
+            vst2q_f32(out.add(k) as *mut f32, float32x4x2_t(re, im));
+        }
+        4 * chunks
+    }
+
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn mul_f64(a: *const Complex<f64>, b: *const Complex<f64>,
+                                 out: *mut Complex<f64>, n: usize) -> usize {
+        let chunks = n / 2;
+        for k in (0..chunks).map(|k| 2 * k) {
+            let x = vld2q_f64(a.add(k) as *const f64);
+            let y = vld2q_f64(b.add(k) as *const f64);
+            let re = vfmsq_f64(vmulq_f64(x.0, y.0), x.1, y.1);
+            let im = vfmaq_f64(vmulq_f64(x.0, y.1), x.1, y.0);
+            vst2q_f64(out.add(k) as *mut f64, float64x2x2_t(re, im));
+        }
+        2 * chunks
+    }
+
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn mul_acc_f32(acc: *mut Complex<f32>, a: *const Complex<f32>,
+                                     b: *const Complex<f32>, n: usize) -> usize {
+        let chunks = n / 4;
+        for k in (0..chunks).map(|k| 4 * k) {
+            let x = vld2q_f32(a.add(k) as *const f32);
+            let y = vld2q_f32(b.add(k) as *const f32);
+            let sum = vld2q_f32(acc.add(k) as *const f32);
+            let re = vfmsq_f32(vfmaq_f32(sum.0, x.0, y.0), x.1, y.1);
+            let im = vfmaq_f32(vfmaq_f32(sum.1, x.0, y.1), x.1, y.0);
+            vst2q_f32(acc.add(k) as *mut f32, float32x4x2_t(re, im));
+        }
+        4 * chunks
+    }
+
+    #[target_feature(enable = "neon")]
+    pub(super) unsafe fn mul_acc_f64(acc: *mut Complex<f64>, a: *const Complex<f64>,
+                                     b: *const Complex<f64>, n: usize) -> usize {
+        let chunks = n / 2;
+        for k in (0..chunks).map(|k| 2 * k) {
+            let x = vld2q_f64(a.add(k) as *const f64);
+            let y = vld2q_f64(b.add(k) as *const f64);
+            let sum = vld2q_f64(acc.add(k) as *const f64);
+            let re = vfmsq_f64(vfmaq_f64(sum.0, x.0, y.0), x.1, y.1);
+            let im = vfmaq_f64(vfmaq_f64(sum.1, x.0, y.1), x.1, y.0);
+            vst2q_f64(acc.add(k) as *mut f64, float64x2x2_t(re, im));
+        }
+        2 * chunks
+    }
+}
+
+/// WebAssembly simd128 kernels
+///
+/// Shuffles broadcast the real and imaginary parts of `b` and swap those of
+/// `a`, and flipping the sign bit of alternate lanes turns the sum
+/// `a · re(b) + swap(a) · im(b)` into the product.
+#[cfg(all(target_arch = "wasm32", target_feature = "simd128", not(feature = "deterministic")))]
+mod wasm {
+    use crate::Complex;
+    use core::arch::wasm32::*;
+
+    /// The sign bits of the real lanes of two interleaved `f32` pairs
+    const SIGN_F32: v128 = u32x4(0x8000_0000, 0, 0x8000_0000, 0);
+
+    /// The sign bit of the real lane of an interleaved `f64` pair
+    const SIGN_F64: v128 = u64x2(0x8000_0000_0000_0000, 0);
+
+    #[inline]
+    #[target_feature(enable = "simd128")]
+    fn product_f32x4(a: v128, b: v128) -> v128 {
+        let re = i32x4_shuffle::<0, 0, 2, 2>(b, b);
+        let im = i32x4_shuffle::<1, 1, 3, 3>(b, b);
+        let swap = i32x4_shuffle::<1, 0, 3, 2>(a, a);
+        f32x4_add(f32x4_mul(a, re), v128_xor(f32x4_mul(swap, im), SIGN_F32))
+    }
+
+    #[inline]
+    #[target_feature(enable = "simd128")]
+    fn product_f64x2(a: v128, b: v128) -> v128 {
+        let re = i64x2_shuffle::<0, 0>(b, b);
+        let im = i64x2_shuffle::<1, 1>(b, b);
+        let swap = i64x2_shuffle::<1, 0>(a, a);
+        f64x2_add(f64x2_mul(a, re), v128_xor(f64x2_mul(swap, im), SIGN_F64))
+    }
+
+    #[target_feature(enable = "simd128")]
+    pub(super) unsafe fn mul_f32(a: *const Complex<f32>, b: *const Complex<f32>,
+                                 out: *mut Complex<f32>, n: usize) -> usize {
+        let chunks = n / 2;
+        for k in (0..chunks).map(|k| 2 * k) {
+            let x = v128_load(a.add(k) as *const v128);
+            let y = v128_load(b.add(k) as *const v128);
+            v128_store(out.add(k) as *mut v128, product_f32x4(x, y));
+        }
+        2 * chunks
+    }
+
+    #[target_feature(enable = "simd128")]
+    pub(super) unsafe fn mul_f64(a: *const Complex<f64>, b: *const Complex<f64>,
+                                 out: *mut Complex<f64>, n: usize) -> usize {
+        for k in 0..n {
+            let x = v128_load(a.add(k) as *const v128);
+            let y = v128_load(b.add(k) as *const v128);
+            v128_store(out.add(k) as *mut v128, product_f64x2(x, y));
+        }
+        n
+    }
+
+    #[target_feature(enable = "simd128")]
+    pub(super) unsafe fn mul_acc_f32(acc: *mut Complex<f32>, a: *const Complex<f32>,
+                                     b: *const Complex<f32>, n: usize) -> usize {
+        let chunks = n / 2;
+        for k in (0..chunks).map(|k| 2 * k) {
+            let x = v128_load(a.add(k) as *const v128);
+            let y = v128_load(b.add(k) as *const v128);
+            let sum = v128_load(acc.add(k) as *const v128);
+            v128_store(acc.add(k) as *mut v128, f32x4_add(sum, product_f32x4(x, y)));
+        }
+        2 * chunks
+    }
+
+    #[target_feature(enable = "simd128")]
+    pub(super) unsafe fn mul_acc_f64(acc: *mut Complex<f64>, a: *const Complex<f64>,
+                                     b: *const Complex<f64>, n: usize) -> usize {
+        for k in 0..n {
+            let x = v128_load(a.add(k) as *const v128);
+            let y = v128_load(b.add(k) as *const v128);
+            let sum = v128_load(acc.add(k) as *const v128);
+            v128_store(acc.add(k) as *mut v128, f64x2_add(sum, product_f64x2(x, y)));
+        }
+        n
+    }
+
+    #[target_feature(enable = "simd128")]
+    pub(super) unsafe fn axpy_f32(alpha: Complex<f32>, x: *const Complex<f32>,
+                                  y: *mut Complex<f32>, n: usize) -> usize {
+        let a = f32x4(alpha.r, alpha.i, alpha.r, alpha.i);
+        let chunks = n / 2;
+        for k in (0..chunks).map(|k| 2 * k) {
+            let v = v128_load(x.add(k) as *const v128);
+            let sum = v128_load(y.add(k) as *const v128);
+            v128_store(y.add(k) as *mut v128, f32x4_add(sum, product_f32x4(v, a)));
+        }
+        2 * chunks
+    }
+
+    #[target_feature(enable = "simd128")]
+    pub(super) unsafe fn axpy_f64(alpha: Complex<f64>, x: *const Complex<f64>,
+                                  y: *mut Complex<f64>, n: usize) -> usize {
+        let a = f64x2(alpha.r, alpha.i);
+        for k in 0..n {
+            let v = v128_load(x.add(k) as *const v128);
+            let sum = v128_load(y.add(k) as *const v128);
+            v128_store(y.add(k) as *mut v128, f64x2_add(sum, product_f64x2(v, a)));
+        }
+        n
+    }
+
+    #[target_feature(enable = "simd128")]
+    pub(super) unsafe fn abs_f32(z: *const Complex<f32>, out: *mut f32, n: usize) -> usize {
+        let chunks = n / 4;
+        for k in (0..chunks).map(|k| 4 * k) {
+            let lo = v128_load(z.add(k) as *const v128);
+            let hi = v128_load(z.add(k + 2) as *const v128);
+            let re = i32x4_shuffle::<0, 2, 4, 6>(lo, hi);
+            let im = i32x4_shuffle::<1, 3, 5, 7>(lo, hi);
+            let sum = f32x4_add(f32x4_mul(re, re), f32x4_mul(im, im));
+            v128_store(out.add(k) as *mut v128, f32x4_sqrt(sum));
+        }
+        4 * chunks
+    }
+
+    #[target_feature(enable = "simd128")]
+    pub(super) unsafe fn abs_f64(z: *const Complex<f64>, out: *mut f64, n: usize) -> usize {
+        let chunks = n / 2;
+        for k in (0..chunks).map(|k| 2 * k) {
+            let lo = v128_load(z.add(k) as *const v128);
+            let hi = v128_load(z.add(k + 1) as *const v128);
+            let re = i64x2_shuffle::<0, 2>(lo, hi);
+            let im = i64x2_shuffle::<1, 3>(lo, hi);
+            let sum = f64x2_add(f64x2_mul(re, re), f64x2_mul(im, im));
+            v128_store(out.add(k) as *mut v128, f64x2_sqrt(sum));
+        }
+        2 * chunks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn histogram() {
+        let mut h = Histogram2d::<f64>::new(Complex::new(1.0, 2.0), Complex::new(-1.0, 0.0), 4, 2);
+        assert_eq!(h.bins(), (4, 2));
+        assert_eq!(h.bin_of(Complex::new(-1.0, 0.0)), Some((0, 0)));
+        assert_eq!(h.bin_of(Complex::new(1.0, 2.0)), Some((3, 1)));
+        assert_eq!(h.bin_of(Complex::new(-0.5, 1.0)), Some((1, 1)));
+        assert_eq!(h.bin_of(Complex::new(1.0 - 1e-17, 1.999)), Some((3, 1)));
+        assert_eq!(h.bin_of(Complex::new(1.0 + 1e-15, 1.0)), None);
+        assert_eq!(h.bin_of(Complex::new(f64::NAN, 1.0)), None);
+        assert_eq!(h.bin_centre(1, 0), Complex::new(-0.25, 0.5));
+        let samples: Vec<_> = (0..400).map(|k| Complex::new((k as f64 * 0.37).sin() * 1.5, (k as f64 * 0.11).cos() + 1.0)).collect();
+        h.add_slice(&samples);
+        h.add(Complex::new(f64::INFINITY, 0.0));
+        let inside = samples.iter().filter(|z| z.r.abs() <= 1.0).count() as u64;
+        assert_eq!((h.total(), h.outside()), (inside, 401 - inside));
+        for j in 0..2 {
+            for k in 0..4 {
+                let expected = samples.iter().filter(|&&z| h.bin_of(z) == Some((k, j))).count() as u64;
+                assert_eq!(h.count(k, j), expected);
+                assert_eq!(h.counts()[j * 4 + k], expected);
+            }
+        }
+        assert_eq!(h.max_count(), *h.counts().iter().max().unwrap());
+        h.clear();
+        assert_eq!((h.total(), h.outside(), h.max_count()), (0, 0, 0));
+        let mut h = Histogram2d::<f32>::new(Complex::new(0.0, 0.0), Complex::new(0.3, 0.7), 3, 7);
+        for j in 0..7 {
+            for k in 0..3 {
+                h.add(h.bin_centre(k, j));
+            }
+        }
+        assert!(h.counts().iter().all(|&c| c == 1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn empty_histogram() {
+        Histogram2d::<f64>::new(Complex::new(0.0, 0.0), Complex::new(1.0, 0.0), 4, 4);
+    }
+}